@@ -2,7 +2,7 @@ import { useState, useEffect } from 'react';
 import ReactMarkdown from 'react-markdown';
 import { useAnalysis, useBackendHealth, useEventStream, useMetrics, useSimulation, useSystemHealth, useTransactionDetail, useTransactions } from './hooks';
 import type { CrossChainMessage, GasInfo, LifecycleEvent, SubsystemHealth, SubsystemStatus } from './types';
-import { ACTOR_COLORS, PIPELINE_STEPS, STATUS_COLORS, STEP_ACTORS } from './types';
+import { actorColor, PIPELINE_STEPS, STATUS_COLORS, STEP_ACTORS } from './types';
 
 // ──────────────────────────────────────────────
 // Cold-start loading screen
@@ -303,7 +303,7 @@ function TransactionRow({
   tx, selected, onClick,
 }: { tx: CrossChainMessage; selected: boolean; onClick: () => void }) {
   const stateColor = tx.state === 'settled' ? '#22c55e'
-    : tx.state === 'failed' || tx.state === 'rolled_back' ? '#ef4444'
+    : tx.state === 'failed' || tx.state === 'rolled_back' || tx.state === 'refunded' ? '#ef4444'
     : '#f59e0b';
 
   return (
@@ -345,7 +345,7 @@ function PipelineView({ events, currentState }: { events: LifecycleEvent[]; curr
         {PIPELINE_STEPS.map((step, i) => {
           const done = completedSteps.has(step);
           const actor = STEP_ACTORS[step];
-          const color = ACTOR_COLORS[actor];
+          const color = actorColor(actor);
           const isLast = i === PIPELINE_STEPS.length - 1;
 
           return (
@@ -500,7 +500,7 @@ function EventTimeline({ events, nonce, analysis }: {
               <div className="flex items-center gap-2 mb-1 flex-wrap">
                 <span
                   className="text-xs font-bold uppercase"
-                  style={{ color: ACTOR_COLORS[ev.actor] }}
+                  style={{ color: actorColor(ev.actor) }}
                 >
                   {ev.actor}
                 </span>
@@ -576,7 +576,7 @@ function EventCard({ event }: { event: LifecycleEvent }) {
           className="w-1.5 h-1.5 rounded-full"
           style={{ background: STATUS_COLORS[event.status] }}
         />
-        <span className="text-[10px] font-bold uppercase" style={{ color: ACTOR_COLORS[event.actor] }}>
+        <span className="text-[10px] font-bold uppercase" style={{ color: actorColor(event.actor) }}>
           {event.actor}
         </span>
         <span className="text-[10px] text-gray-400 capitalize">{event.step}</span>
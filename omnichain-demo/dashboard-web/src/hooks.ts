@@ -2,8 +2,13 @@ import { useCallback, useEffect, useRef, useState } from 'react';
 import type {
   CrossChainMessage,
   GasInfo,
+  GasResponse,
+  InjectBehavior,
   LifecycleEvent,
   MetricsResponse,
+  RelayerStatus,
+  RunComparison,
+  Scenario,
   SubsystemHealth,
   SystemHealthResponse,
   TransactionDetailResponse,
@@ -111,6 +116,54 @@ export function useSystemHealth(pollMs = 5000) {
 // REST hooks
 // ──────────────────────────────────────────────
 
+export function useGas(pollMs = 15000) {
+  const [gas, setGas] = useState<GasResponse>({ current: null, history: [], settlements: [] });
+
+  useEffect(() => {
+    let active = true;
+    const poll = async () => {
+      try {
+        const res = await fetch(`${API_BASE}/gas`);
+        if (res.ok) {
+          const data: GasResponse = await res.json();
+          if (active) setGas(data);
+        }
+      } catch {
+        if (active) setGas({ current: null, history: [], settlements: [] });
+      }
+    };
+    poll();
+    const id = setInterval(poll, pollMs);
+    return () => { active = false; clearInterval(id); };
+  }, [pollMs]);
+
+  return gas;
+}
+
+export function useRelayerStatus(pollMs = 5000) {
+  const [status, setStatus] = useState<RelayerStatus | null>(null);
+
+  useEffect(() => {
+    let active = true;
+    const poll = async () => {
+      try {
+        const res = await fetch(`${API_BASE}/relayer/status`);
+        if (res.ok) {
+          const data: RelayerStatus = await res.json();
+          if (active) setStatus(data);
+        }
+      } catch {
+        if (active) setStatus(null);
+      }
+    };
+    poll();
+    const id = setInterval(poll, pollMs);
+    return () => { active = false; clearInterval(id); };
+  }, [pollMs]);
+
+  return status;
+}
+
 export function useTransactions(pollMs = 3000) {
   const [transactions, setTransactions] = useState<CrossChainMessage[]>([]);
   const [total, setTotal] = useState(0);
@@ -166,6 +219,29 @@ export function useTransactionDetail(nonce: number | null) {
   return detail;
 }
 
+/** Side-by-side metrics of two runs, `a` being the baseline. */
+export function useRunComparison(a: string | null, b: string | null) {
+  const [comparison, setComparison] = useState<RunComparison | null>(null);
+
+  useEffect(() => {
+    if (!a || !b) { setComparison(null); return; }
+    let active = true;
+    const load = async () => {
+      try {
+        const params = new URLSearchParams({ a, b });
+        const res = await fetch(`${API_BASE}/runs/compare?${params}`);
+        if (active) setComparison(res.ok ? await res.json() : null);
+      } catch {
+        if (active) setComparison(null);
+      }
+    };
+    load();
+    return () => { active = false; };
+  }, [a, b]);
+
+  return comparison;
+}
+
 export function useMetrics(pollMs = 3000) {
   const [metrics, setMetrics] = useState<MetricsResponse>({
     total_transactions: 0, settled: 0, failed: 0, pending: 0,
@@ -215,7 +291,11 @@ export function useEventStream() {
       const ws = new WebSocket(WS_URL);
       wsRef.current = ws;
 
-      ws.onopen = () => setConnected(true);
+      ws.onopen = () => {
+        setConnected(true);
+        // Live events arrive as arrays coalesced over 100ms
+        ws.send(JSON.stringify({ type: 'subscribe', batch_ms: 100 }));
+      };
       ws.onclose = () => {
         setConnected(false);
         reconnectTimer = setTimeout(connect, 3000);
@@ -223,9 +303,10 @@ export function useEventStream() {
       ws.onerror = () => ws.close();
       ws.onmessage = (msg) => {
         try {
-          const event: LifecycleEvent = JSON.parse(msg.data);
+          const data: LifecycleEvent | LifecycleEvent[] = JSON.parse(msg.data);
+          const batch = Array.isArray(data) ? [...data].reverse() : [data];
           setEvents((prev) => {
-            const next = [event, ...prev];
+            const next = [...batch, ...prev];
             return next.length > 500 ? next.slice(0, 500) : next;
           });
         } catch { /* ignore non-JSON */ }
@@ -272,12 +353,12 @@ export function useSimulation() {
     return () => { active = false; clearInterval(id); };
   }, []);
 
-  const startSimulation = useCallback(async (durationMinutes = 60) => {
+  const startSimulation = useCallback(async (durationMinutes = 60, scenario: Scenario = 'steady') => {
     try {
       await fetch(`${API_BASE}/control/start-simulation`, {
         method: 'POST',
         headers: { 'Content-Type': 'application/json' },
-        body: JSON.stringify({ duration_minutes: durationMinutes }),
+        body: JSON.stringify({ duration_minutes: durationMinutes, scenario }),
       });
       setRunning(true);
     } catch { /* ignore */ }
@@ -297,7 +378,22 @@ export function useSimulation() {
     } catch { /* ignore */ }
   }, []);
 
-  return { running, remainingSeconds, startSimulation, stopSimulation, clearData };
+  const injectMessage = useCallback(async (amount: number, description?: string, behavior: InjectBehavior = {}) => {
+    try {
+      const res = await fetch(`${API_BASE}/control/inject`, {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify({ amount, description, behavior }),
+      });
+      if (res.ok) {
+        const msg: CrossChainMessage = await res.json();
+        return msg;
+      }
+    } catch { /* ignore */ }
+    return null;
+  }, []);
+
+  return { running, remainingSeconds, startSimulation, stopSimulation, clearData, injectMessage };
 }
 
 // ──────────────────────────────────────────────
@@ -1,11 +1,43 @@
 export interface LifecycleEvent {
+  /** Absent from relayers that predate versioning (version 1) */
+  schema_version?: number;
   trace_id: string;
   nonce: number;
-  actor: 'ethereum' | 'relayer' | 'solana' | 'dashboard';
-  step: 'locked' | 'observed' | 'verified' | 'executed' | 'minted' | 'burned' | 'rollback' | 'settled';
+  /** 'relayer', 'dashboard', or a chain's registry name ('ethereum', 'solana', ...) */
+  actor: string;
+  /** Set when the actor is a chain */
+  actor_chain_id?: number;
+  step: 'locked' | 'observed' | 'verified' | 'executed' | 'minted' | 'burned' | 'rollback' | 'settled'
+    | 'refunded' | 'expired' | 'validator-misbehavior' | 'duplicate-trace'
+    | 'integrity-mismatch' | 'attack-detected';
   status: 'success' | 'failure' | 'retry';
   timestamp: string;
   detail?: string;
+  seq?: number;
+  src_chain_id?: number;
+  dst_chain_id?: number;
+  /** Decimal string: fees can exceed Number.MAX_SAFE_INTEGER */
+  fee_wei?: string;
+  fee_lamports?: number;
+  /** Gas on Ethereum steps, compute units on Solana steps */
+  gas_used?: number;
+  /** Run the event was replayed from; absent on live events */
+  replay_run_id?: string;
+  /** HMAC-SHA256 of the canonical event, on /ws when EVENT_SIGNING_KEY is set */
+  signature?: string;
+}
+
+export type Scenario = 'steady' | 'burst' | 'failures' | 'stress';
+
+export interface ChainConfig {
+  chain_id: number;
+  name: string;
+  kind: 'evm' | 'solana' | 'cosmos';
+  rpc_url: string | null;
+  executor: unknown;
+  enabled: boolean;
+  created_at: string;
+  updated_at: string;
 }
 
 export interface CrossChainMessage {
@@ -21,12 +53,33 @@ export interface CrossChainMessage {
   result: string | null;
   solana_signature: string | null;
   eth_settle_tx: string | null;
+  refund_tx: string | null;
   retry_count: number;
   error_message: string | null;
+  run_id: string | null;
+  src_chain_id: number;
+  dst_chain_id: number;
+  payload_type: number;
+  computation: 'double' | 'identity';
+  /** JSON InjectBehavior; set only on messages from POST /control/inject */
+  behavior_json: string | null;
+  /** Settlement receipt figures; null until settled */
+  settle_gas_used: number | null;
+  settle_gas_price_wei: string | null;
+  settle_block_number: number | null;
+  settle_cost_wei: string | null;
   created_at: string;
   updated_at: string;
 }
 
+/** Edge cases a message injected via POST /control/inject forces */
+export interface InjectBehavior {
+  fail_verification?: boolean;
+  fail_execution?: boolean;
+  fail_settlement?: boolean;
+  expired?: boolean;
+}
+
 export interface TransactionListResponse {
   transactions: CrossChainMessage[];
   total: number;
@@ -36,6 +89,18 @@ export interface TransactionDetailResponse {
   transaction: CrossChainMessage;
   events: LifecycleEvent[];
   proof: ProofBundle | null;
+  transitions: StateTransition[];
+}
+
+export interface StateTransition {
+  id: number;
+  nonce: number;
+  from_state: string | null;
+  to_state: string;
+  reason: string;
+  /** false for an update that lost a race and changed nothing */
+  applied: boolean;
+  at: string;
 }
 
 export interface ProofBundle {
@@ -77,6 +142,87 @@ export interface GasInfo {
   is_low: boolean;
 }
 
+export interface GasReading {
+  source: 'fee_history' | 'oracle' | 'gas_price' | 'simulated';
+  base_fee_gwei: number;
+  priority_fee_gwei: number;
+  max_fee_gwei: number;
+  block_number: number | null;
+  nonce: number | null;
+  observed_at: string;
+}
+
+export interface GasResponse {
+  current: GasReading | null;
+  history: GasReading[];
+  settlements: GasReading[];
+}
+
+/** Wei amounts are decimal strings */
+export interface EscrowSnapshot {
+  balance_wei: string;
+  locked_wei: string;
+  locked_escrows: number;
+  /** Open escrows not read from the contract yet */
+  unread_escrows: number;
+  db_pending_wei: string;
+  db_pending_messages: number;
+  block_number: number | null;
+  observed_at: string;
+}
+
+export interface LockedEscrow {
+  nonce: number;
+  amount_wei: string;
+  state: string;
+}
+
+export interface EscrowBalanceResponse {
+  current: EscrowSnapshot | null;
+  locked: LockedEscrow[];
+  history: EscrowSnapshot[];
+}
+
+export interface CircuitBreaker {
+  /** 'processor', 'traffic_backlog', 'validator:<address>' or 'chain:<id>' */
+  name: string;
+  /** true = blocking traffic */
+  open: boolean;
+  detail: string | null;
+}
+
+export interface SignerKey {
+  /** eth-settlement, proof-validator, solana-payer or evm-chain-<id> */
+  purpose: string;
+  address: string | null;
+  /** false when the purpose falls back to the relayer key */
+  dedicated: boolean;
+}
+
+export interface RelayerStatus {
+  started_at: string;
+  uptime_secs: number;
+  ethereum: {
+    address: string;
+    balance_wei: string | null;
+    block_height: number | null;
+    last_processed_block: number;
+    blocks_behind: number | null;
+    error: string | null;
+  };
+  solana: {
+    mode: 'sim' | 'rpc';
+    slot: number | null;
+    payer: string | null;
+    balance_lamports: number | null;
+    error: string | null;
+  };
+  keys: SignerKey[];
+  /** Message count per state */
+  backlog: Record<string, number>;
+  circuit_breakers: CircuitBreaker[];
+}
+
 export interface SystemHealthResponse {
   systems: SubsystemHealth[];
   gas: GasInfo;
@@ -86,13 +232,18 @@ export const PIPELINE_STEPS: LifecycleEvent['step'][] = [
   'locked', 'observed', 'verified', 'executed', 'minted', 'burned', 'rollback', 'settled',
 ];
 
-export const ACTOR_COLORS: Record<LifecycleEvent['actor'], string> = {
+export const ACTOR_COLORS: Record<string, string> = {
   ethereum: '#627EEA',
   relayer: '#14F195',
   solana: '#9945FF',
   dashboard: '#64748b',
 };
 
+/** Chains registered at runtime have no assigned color */
+export function actorColor(actor: string): string {
+  return ACTOR_COLORS[actor] ?? '#94a3b8';
+}
+
 export const STEP_ACTORS: Record<LifecycleEvent['step'], LifecycleEvent['actor']> = {
   locked: 'ethereum',
   observed: 'relayer',
@@ -102,6 +253,12 @@ export const STEP_ACTORS: Record<LifecycleEvent['step'], LifecycleEvent['actor']
   burned: 'solana',
   rollback: 'relayer',
   settled: 'ethereum',
+  refunded: 'ethereum',
+  expired: 'ethereum',
+  'validator-misbehavior': 'relayer',
+  'duplicate-trace': 'relayer',
+  'integrity-mismatch': 'relayer',
+  'attack-detected': 'relayer',
 };
 
 export const STATUS_COLORS: Record<LifecycleEvent['status'], string> = {
@@ -109,3 +266,74 @@ export const STATUS_COLORS: Record<LifecycleEvent['status'], string> = {
   failure: '#ef4444',
   retry: '#f59e0b',
 };
+
+export interface SimulationRun {
+  run_id: string;
+  scenario: string;
+  parameters: Record<string, unknown>;
+  started_at: string;
+  ended_at: string | null;
+  message_count: number;
+}
+
+export interface StageLatency {
+  /** verify, execute, settle or end_to_end */
+  stage: string;
+  samples: number;
+  p50_ms: number;
+  p90_ms: number;
+  p99_ms: number;
+  max_ms: number;
+}
+
+export interface RunStats {
+  run: SimulationRun;
+  messages: number;
+  settled: number;
+  failed: number;
+  rolled_back: number;
+  pending: number;
+  retries: number;
+  active_secs: number;
+  throughput_per_sec: number;
+  rollback_rate: number;
+  retry_rate: number;
+  latency: StageLatency[];
+  /** Decimal strings: wei can exceed Number.MAX_SAFE_INTEGER */
+  fee_wei: string;
+  fee_lamports: number;
+  fee_wei_per_settled: string;
+}
+
+/** b minus a */
+export interface RunDelta {
+  throughput_per_sec: number;
+  rollback_rate: number;
+  retry_rate: number;
+  latency: { stage: string; p50_ms: number; p90_ms: number; p99_ms: number }[];
+  fee_wei: string;
+  fee_lamports: number;
+  fee_wei_per_settled: string;
+}
+
+export interface RunComparison {
+  a: RunStats;
+  b: RunStats;
+  delta: RunDelta;
+}
+
+/** Merkle roots of one UTC day, anchored on Ethereum */
+export interface Notarization {
+  id: number;
+  day: string;
+  messages: number;
+  events: number;
+  messages_root: string;
+  events_root: string;
+  root: string;
+  anchor_tx: string | null;
+  anchor_block: number | null;
+  anchor_error: string | null;
+  created_at: string;
+  anchored_at: string | null;
+}
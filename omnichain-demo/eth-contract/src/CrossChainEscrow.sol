@@ -183,7 +183,7 @@ contract CrossChainEscrow {
     }
 
     // ──────────────────────────────────────────────
-    // External — Reclaim (timeout)
+    // External — Reclaim / refund (timeout)
     // ──────────────────────────────────────────────
 
     /**
@@ -206,6 +206,26 @@ contract CrossChainEscrow {
         emit Reclaimed(_nonce, escrow.sender, escrow.amount);
     }
 
+    /**
+     * @notice Refund an expired escrow to its sender on the sender's behalf,
+     *         so funds do not sit locked until the sender reclaims them.
+     * @param _nonce Nonce of the escrow to refund
+     */
+    function refund(uint64 _nonce) external {
+        if (msg.sender != relayer) revert OnlyRelayer();
+        Escrow storage escrow = escrows[_nonce];
+        if (escrow.sender == address(0)) revert EscrowNotFound();
+        if (escrow.executed) revert AlreadyExecuted();
+        if (block.timestamp < escrow.deadline) revert DeadlineNotReached();
+
+        escrow.executed = true;
+
+        (bool success,) = escrow.sender.call{value: escrow.amount}("");
+        if (!success) revert TransferFailed();
+
+        emit Reclaimed(_nonce, escrow.sender, escrow.amount);
+    }
+
     // ──────────────────────────────────────────────
     // View
     // ──────────────────────────────────────────────
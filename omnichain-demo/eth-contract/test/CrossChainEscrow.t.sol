@@ -299,6 +299,60 @@ contract CrossChainEscrowTest is Test {
         escrow.reclaim(1);
     }
 
+    // ──────────────────────────────────────────────
+    // refund tests
+    // ──────────────────────────────────────────────
+
+    function test_refund_afterDeadline() public {
+        vm.prank(user1);
+        escrow.lockFunds{value: LOCK_AMOUNT}(PAYLOAD);
+
+        uint256 balanceBefore = user1.balance;
+
+        vm.warp(block.timestamp + TIMEOUT + 1);
+
+        vm.prank(relayer);
+        escrow.refund(1);
+
+        assertEq(user1.balance, balanceBefore + LOCK_AMOUNT);
+        (,,, bool executed,,) = escrow.getEscrow(1);
+        assertTrue(executed);
+        assertFalse(escrow.settled(1));
+    }
+
+    function test_refund_revertsForNonRelayer() public {
+        vm.prank(user1);
+        escrow.lockFunds{value: LOCK_AMOUNT}(PAYLOAD);
+
+        vm.warp(block.timestamp + TIMEOUT + 1);
+
+        vm.prank(user2);
+        vm.expectRevert(CrossChainEscrow.OnlyRelayer.selector);
+        escrow.refund(1);
+    }
+
+    function test_refund_revertsBeforeDeadline() public {
+        vm.prank(user1);
+        escrow.lockFunds{value: LOCK_AMOUNT}(PAYLOAD);
+
+        vm.prank(relayer);
+        vm.expectRevert(CrossChainEscrow.DeadlineNotReached.selector);
+        escrow.refund(1);
+    }
+
+    function test_refund_revertsIfReclaimed() public {
+        vm.prank(user1);
+        escrow.lockFunds{value: LOCK_AMOUNT}(PAYLOAD);
+
+        vm.warp(block.timestamp + TIMEOUT + 1);
+        vm.prank(user1);
+        escrow.reclaim(1);
+
+        vm.prank(relayer);
+        vm.expectRevert(CrossChainEscrow.AlreadyExecuted.selector);
+        escrow.refund(1);
+    }
+
     // ──────────────────────────────────────────────
     // Edge cases
     // ──────────────────────────────────────────────
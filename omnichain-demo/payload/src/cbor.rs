@@ -0,0 +1,150 @@
+//! Version 2: a CBOR map (RFC 8949) with text keys. Only definite lengths
+//! are accepted; keys other than the payload fields are skipped.
+
+use crate::{trace_id, Error, Payload};
+
+const BYTES: u8 = 2;
+const TEXT: u8 = 3;
+const ARRAY: u8 = 4;
+const MAP: u8 = 5;
+const TAG: u8 = 6;
+
+/// How deep skipped values may nest.
+const MAX_DEPTH: usize = 16;
+
+pub fn encode(payload: &Payload, out: &mut Vec<u8>) {
+    head(out, MAP, 3);
+    text(out, "trace_id");
+    bytes(out, &payload.trace_id);
+    text(out, "description");
+    text(out, &payload.description);
+    text(out, "data");
+    bytes(out, &payload.data);
+}
+
+pub fn decode(encoded: &[u8]) -> Result<Payload, Error> {
+    let mut reader = Reader { buf: encoded, pos: 0 };
+    let (major, entries) = reader.head()?;
+    if major != MAP {
+        return Err(Error::Malformed("CBOR payload is not a map"));
+    }
+
+    let mut trace = None;
+    let mut payload = Payload::default();
+    for _ in 0..entries {
+        let key = match reader.head()? {
+            (TEXT, len) => reader.take(len)?,
+            _ => return Err(Error::Malformed("CBOR map key is not text")),
+        };
+        match key {
+            b"trace_id" => trace = Some(trace_id(reader.string(BYTES)?)?),
+            b"description" => {
+                payload.description = String::from_utf8(reader.string(TEXT)?.to_vec())
+                    .map_err(|_| Error::Malformed("description is not UTF-8"))?;
+            }
+            b"data" => payload.data = reader.string(BYTES)?.to_vec(),
+            _ => reader.skip(0)?,
+        }
+    }
+    if reader.pos != encoded.len() {
+        return Err(Error::Malformed("trailing bytes after the CBOR map"));
+    }
+    payload.trace_id = trace.ok_or(Error::Malformed("CBOR payload has no trace_id"))?;
+    Ok(payload)
+}
+
+fn head(out: &mut Vec<u8>, major: u8, n: u64) {
+    let major = major << 5;
+    match n {
+        0..=23 => out.push(major | n as u8),
+        24..=0xff => out.extend_from_slice(&[major | 24, n as u8]),
+        0x100..=0xffff => {
+            out.push(major | 25);
+            out.extend_from_slice(&(n as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            out.push(major | 26);
+            out.extend_from_slice(&(n as u32).to_be_bytes());
+        }
+        _ => {
+            out.push(major | 27);
+            out.extend_from_slice(&n.to_be_bytes());
+        }
+    }
+}
+
+fn text(out: &mut Vec<u8>, s: &str) {
+    head(out, TEXT, s.len() as u64);
+    out.extend_from_slice(s.as_bytes());
+}
+
+fn bytes(out: &mut Vec<u8>, b: &[u8]) {
+    head(out, BYTES, b.len() as u64);
+    out.extend_from_slice(b);
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: u64) -> Result<&'a [u8], Error> {
+        let end = usize::try_from(n)
+            .ok()
+            .and_then(|n| self.pos.checked_add(n))
+            .filter(|&end| end <= self.buf.len())
+            .ok_or(Error::Truncated)?;
+        let taken = &self.buf[self.pos..end];
+        self.pos = end;
+        Ok(taken)
+    }
+
+    /// Major type and argument of the next data item.
+    fn head(&mut self) -> Result<(u8, u64), Error> {
+        let initial = self.take(1)?[0];
+        let argument = match initial & 0x1f {
+            n @ 0..=23 => n as u64,
+            24 => self.take(1)?[0] as u64,
+            25 => u16::from_be_bytes(self.take(2)?.try_into().unwrap()) as u64,
+            26 => u32::from_be_bytes(self.take(4)?.try_into().unwrap()) as u64,
+            27 => u64::from_be_bytes(self.take(8)?.try_into().unwrap()),
+            _ => return Err(Error::Malformed("indefinite or reserved CBOR length")),
+        };
+        Ok((initial >> 5, argument))
+    }
+
+    /// A byte or text string of the given major type.
+    fn string(&mut self, major: u8) -> Result<&'a [u8], Error> {
+        match self.head()? {
+            (m, len) if m == major => self.take(len),
+            _ => Err(Error::Malformed("CBOR field has the wrong type")),
+        }
+    }
+
+    fn skip(&mut self, depth: usize) -> Result<(), Error> {
+        if depth > MAX_DEPTH {
+            return Err(Error::Malformed("CBOR value nests too deep"));
+        }
+        match self.head()? {
+            (BYTES | TEXT, len) => {
+                self.take(len)?;
+            }
+            (ARRAY, items) => {
+                for _ in 0..items {
+                    self.skip(depth + 1)?;
+                }
+            }
+            (MAP, entries) => {
+                for _ in 0..entries {
+                    self.skip(depth + 1)?;
+                    self.skip(depth + 1)?;
+                }
+            }
+            (TAG, _) => self.skip(depth + 1)?,
+            // Integers, simple values and floats are all in the head
+            _ => {}
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,258 @@
+//! Versioned message payload codecs, shared by the traffic generators and
+//! the relayer.
+//!
+//! A payload body (after the optional `"OMNI"` routing header) is either a
+//! codec frame or the legacy layout:
+//!
+//! ```text
+//! "OMNP" | version: u8 | encoded payload
+//! trace_id: 16 | desc_len: u16 BE | description | data      (legacy, unframed)
+//! ```
+//!
+//! The version byte selects the decoder: 1 is the legacy layout, 2 a CBOR
+//! map and 3 a protobuf message. Bodies without the marker are decoded as
+//! the legacy layout, so traffic from before the frame keeps working.
+
+mod cbor;
+mod protobuf;
+
+use std::fmt;
+
+/// Marker in front of the version byte.
+pub const CODEC_MAGIC: &[u8; 4] = b"OMNP";
+
+/// The fields every codec carries.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Payload {
+    pub trace_id: [u8; 16],
+    /// Human-readable description; empty when there is none
+    pub description: String,
+    /// Opaque application data
+    pub data: Vec<u8>,
+}
+
+/// How a payload body is encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Codec {
+    /// The raw layout without a frame, as sent before versioning
+    Legacy,
+    /// Version 1: the raw layout in a frame
+    #[default]
+    Raw,
+    /// Version 2: CBOR map with text keys `trace_id`, `description`, `data`
+    Cbor,
+    /// Version 3: protobuf message `{ bytes trace_id = 1; string description = 2; bytes data = 3; }`
+    Protobuf,
+}
+
+impl Codec {
+    /// Version byte written after the marker; `None` for unframed payloads.
+    pub fn version(self) -> Option<u8> {
+        match self {
+            Self::Legacy => None,
+            Self::Raw => Some(1),
+            Self::Cbor => Some(2),
+            Self::Protobuf => Some(3),
+        }
+    }
+
+    fn from_version(version: u8) -> Result<Self, Error> {
+        match version {
+            1 => Ok(Self::Raw),
+            2 => Ok(Self::Cbor),
+            3 => Ok(Self::Protobuf),
+            other => Err(Error::UnknownVersion(other)),
+        }
+    }
+}
+
+impl fmt::Display for Codec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Legacy => write!(f, "legacy"),
+            Self::Raw => write!(f, "raw"),
+            Self::Cbor => write!(f, "cbor"),
+            Self::Protobuf => write!(f, "protobuf"),
+        }
+    }
+}
+
+impl std::str::FromStr for Codec {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "legacy" => Ok(Self::Legacy),
+            "raw" => Ok(Self::Raw),
+            "cbor" => Ok(Self::Cbor),
+            "protobuf" => Ok(Self::Protobuf),
+            other => Err(Error::UnknownCodec(other.to_string())),
+        }
+    }
+}
+
+/// Why a payload body could not be decoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The version byte names no known codec
+    UnknownVersion(u8),
+    /// A codec name that is not `legacy`, `raw`, `cbor` or `protobuf`
+    UnknownCodec(String),
+    /// The body ends before the encoding does
+    Truncated,
+    /// The body does not follow its codec's layout
+    Malformed(&'static str),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownVersion(v) => write!(f, "unknown payload codec version {}", v),
+            Self::UnknownCodec(name) => write!(f, "unknown payload codec: {}", name),
+            Self::Truncated => write!(f, "payload is truncated"),
+            Self::Malformed(what) => write!(f, "malformed payload: {}", what),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Payload {
+    /// Encode as a payload body (without a routing header).
+    ///
+    /// Descriptions longer than `u16::MAX` bytes don't fit the raw layouts
+    /// and are truncated there.
+    pub fn encode(&self, codec: Codec) -> Vec<u8> {
+        let mut out = Vec::new();
+        if let Some(version) = codec.version() {
+            out.extend_from_slice(CODEC_MAGIC);
+            out.push(version);
+        }
+        match codec {
+            Codec::Legacy | Codec::Raw => self.encode_raw(&mut out),
+            Codec::Cbor => cbor::encode(self, &mut out),
+            Codec::Protobuf => protobuf::encode(self, &mut out),
+        }
+        out
+    }
+
+    /// Decode a payload body, returning the codec it was encoded with.
+    pub fn decode(body: &[u8]) -> Result<(Self, Codec), Error> {
+        let Some(framed) = body.strip_prefix(CODEC_MAGIC) else {
+            return Ok((Self::decode_raw(body)?, Codec::Legacy));
+        };
+        let (&version, encoded) = framed.split_first().ok_or(Error::Truncated)?;
+        let codec = Codec::from_version(version)?;
+        let payload = match codec {
+            Codec::Legacy | Codec::Raw => Self::decode_raw(encoded)?,
+            Codec::Cbor => cbor::decode(encoded)?,
+            Codec::Protobuf => protobuf::decode(encoded)?,
+        };
+        Ok((payload, codec))
+    }
+
+    fn encode_raw(&self, out: &mut Vec<u8>) {
+        let description = truncate(&self.description, u16::MAX as usize);
+        out.extend_from_slice(&self.trace_id);
+        out.extend_from_slice(&(description.len() as u16).to_be_bytes());
+        out.extend_from_slice(description.as_bytes());
+        out.extend_from_slice(&self.data);
+    }
+
+    fn decode_raw(body: &[u8]) -> Result<Self, Error> {
+        if body.len() < 18 {
+            return Err(Error::Truncated);
+        }
+        let desc_len = u16::from_be_bytes([body[16], body[17]]) as usize;
+        let description = body.get(18..18 + desc_len).ok_or(Error::Truncated)?;
+        Ok(Self {
+            trace_id: trace_id(&body[..16])?,
+            description: String::from_utf8(description.to_vec())
+                .map_err(|_| Error::Malformed("description is not UTF-8"))?,
+            data: body[18 + desc_len..].to_vec(),
+        })
+    }
+}
+
+fn trace_id(bytes: &[u8]) -> Result<[u8; 16], Error> {
+    bytes.try_into().map_err(|_| Error::Malformed("trace id is not 16 bytes"))
+}
+
+/// Longest prefix of `s` within `max` bytes that ends on a char boundary.
+fn truncate(s: &str, max: usize) -> &str {
+    if s.len() <= max {
+        return s;
+    }
+    let end = (0..=max).rev().find(|&i| s.is_char_boundary(i)).unwrap_or(0);
+    &s[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Payload {
+        Payload {
+            trace_id: *b"0123456789abcdef",
+            description: "Alice's payment to Bob for piano tuning".into(),
+            data: vec![0xde, 0xad, 0xbe, 0xef],
+        }
+    }
+
+    #[test]
+    fn every_codec_round_trips() {
+        for codec in [Codec::Legacy, Codec::Raw, Codec::Cbor, Codec::Protobuf] {
+            let encoded = sample().encode(codec);
+            assert_eq!(Payload::decode(&encoded), Ok((sample(), codec)), "{}", codec);
+        }
+    }
+
+    #[test]
+    fn legacy_layout_is_unchanged() {
+        let mut expected = b"0123456789abcdef".to_vec();
+        expected.extend_from_slice(&39u16.to_be_bytes());
+        expected.extend_from_slice(b"Alice's payment to Bob for piano tuning");
+        expected.extend_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(sample().encode(Codec::Legacy), expected);
+    }
+
+    #[test]
+    fn unknown_fields_are_skipped() {
+        // {"trace_id": h'..', "priority": [1, {"x": -2}], "description": "hi"}
+        let mut cbor = vec![0xa3, 0x68];
+        cbor.extend_from_slice(b"trace_id");
+        cbor.push(0x50);
+        cbor.extend_from_slice(b"0123456789abcdef");
+        cbor.push(0x68);
+        cbor.extend_from_slice(b"priority");
+        cbor.extend_from_slice(&[0x82, 0x01, 0xa1, 0x61, b'x', 0x21]);
+        cbor.push(0x6b);
+        cbor.extend_from_slice(b"description");
+        cbor.extend_from_slice(&[0x62, b'h', b'i']);
+
+        // trace_id = 1, an unknown varint field 7 = 300, description = 2
+        let mut proto = vec![0x0a, 0x10];
+        proto.extend_from_slice(b"0123456789abcdef");
+        proto.extend_from_slice(&[0x38, 0xac, 0x02, 0x12, 0x02, b'h', b'i']);
+
+        for (version, encoded) in [(2, cbor), (3, proto)] {
+            let mut body = CODEC_MAGIC.to_vec();
+            body.push(version);
+            body.extend_from_slice(&encoded);
+            let (payload, _) = Payload::decode(&body).unwrap();
+            assert_eq!(payload.trace_id, *b"0123456789abcdef");
+            assert_eq!(payload.description, "hi");
+            assert!(payload.data.is_empty());
+        }
+    }
+
+    #[test]
+    fn bad_bodies_are_rejected() {
+        assert_eq!(Payload::decode(b"short"), Err(Error::Truncated));
+        assert_eq!(Payload::decode(b"OMNP"), Err(Error::Truncated));
+        assert_eq!(Payload::decode(b"OMNP\x09"), Err(Error::UnknownVersion(9)));
+        let truncated = sample().encode(Codec::Cbor);
+        assert_eq!(Payload::decode(&truncated[..truncated.len() - 1]), Err(Error::Truncated));
+        assert!(Payload::decode(b"OMNP\x03\x12\x01x").is_err(), "protobuf without a trace id");
+    }
+}
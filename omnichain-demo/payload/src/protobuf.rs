@@ -0,0 +1,98 @@
+//! Version 3: a protobuf message. Unknown fields are skipped, as protobuf
+//! readers do.
+//!
+//! ```proto
+//! message Payload {
+//!   bytes trace_id = 1;
+//!   string description = 2;
+//!   bytes data = 3;
+//! }
+//! ```
+
+use crate::{trace_id, Error, Payload};
+
+const VARINT: u64 = 0;
+const FIXED64: u64 = 1;
+const LEN: u64 = 2;
+const FIXED32: u64 = 5;
+
+pub fn encode(payload: &Payload, out: &mut Vec<u8>) {
+    field(out, 1, &payload.trace_id);
+    // proto3 leaves empty fields out
+    if !payload.description.is_empty() {
+        field(out, 2, payload.description.as_bytes());
+    }
+    if !payload.data.is_empty() {
+        field(out, 3, &payload.data);
+    }
+}
+
+pub fn decode(mut encoded: &[u8]) -> Result<Payload, Error> {
+    let mut trace = None;
+    let mut payload = Payload::default();
+    while !encoded.is_empty() {
+        let key = varint(&mut encoded)?;
+        let (number, wire_type) = (key >> 3, key & 0x7);
+        let value = match wire_type {
+            VARINT => {
+                varint(&mut encoded)?;
+                continue;
+            }
+            FIXED64 => take(&mut encoded, 8)?,
+            FIXED32 => take(&mut encoded, 4)?,
+            LEN => {
+                let len = varint(&mut encoded)?;
+                take(&mut encoded, len)?
+            }
+            _ => return Err(Error::Malformed("unsupported protobuf wire type")),
+        };
+        if wire_type != LEN {
+            continue;
+        }
+        match number {
+            1 => trace = Some(trace_id(value)?),
+            2 => {
+                payload.description = String::from_utf8(value.to_vec())
+                    .map_err(|_| Error::Malformed("description is not UTF-8"))?;
+            }
+            3 => payload.data = value.to_vec(),
+            _ => {}
+        }
+    }
+    payload.trace_id = trace.ok_or(Error::Malformed("protobuf payload has no trace_id"))?;
+    Ok(payload)
+}
+
+fn field(out: &mut Vec<u8>, number: u64, value: &[u8]) {
+    put_varint(out, number << 3 | LEN);
+    put_varint(out, value.len() as u64);
+    out.extend_from_slice(value);
+}
+
+fn put_varint(out: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        out.push(n as u8 | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+fn varint(buf: &mut &[u8]) -> Result<u64, Error> {
+    let mut n = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = buf.split_first().ok_or(Error::Truncated)?;
+        *buf = rest;
+        n |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(n);
+        }
+    }
+    Err(Error::Malformed("protobuf varint is too long"))
+}
+
+fn take<'a>(buf: &mut &'a [u8], n: u64) -> Result<&'a [u8], Error> {
+    let n = usize::try_from(n).ok().filter(|&n| n <= buf.len()).ok_or(Error::Truncated)?;
+    let (taken, rest) = buf.split_at(n);
+    *buf = rest;
+    Ok(taken)
+}
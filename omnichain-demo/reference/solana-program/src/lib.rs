@@ -4,7 +4,7 @@ use solana_program::{
     entrypoint,
     entrypoint::ProgramResult,
     msg,
-    program::invoke,
+    program::{invoke, invoke_signed},
     program_error::ProgramError,
     pubkey::Pubkey,
     rent::Rent,
@@ -49,6 +49,7 @@ pub enum CrossChainInstruction {
     /// 0. `[signer, writable]` Payer (relayer)
     /// 1. `[writable]` Receipt PDA account
     /// 2. `[]` System program
+    /// 3. `[writable]` Stats PDA account (created on first execution)
     ExecuteCrossChain {
         nonce: u64,
         sender: [u8; 20], // Ethereum address
@@ -84,16 +85,44 @@ impl ExecutionReceipt {
     pub const SIZE: usize = 1 + 8 + 8 + 20 + 32 + 8; // 77 bytes
 }
 
+// ──────────────────────────────────────────────
+// Stats account data
+// ──────────────────────────────────────────────
+
+/// Program-wide execution counters, kept in a singleton PDA so off-chain
+/// counters can be reconciled against what actually executed.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default)]
+pub struct ProgramStats {
+    /// Marks this account as initialized
+    pub is_initialized: bool,
+    /// Executions that created a receipt (idempotent skips excluded)
+    pub total_executions: u64,
+    /// Sum of executed amounts
+    pub total_amount: u128,
+    /// Slot of the most recent execution
+    pub last_execution_slot: u64,
+}
+
+impl ProgramStats {
+    pub const SIZE: usize = 1 + 8 + 16 + 8; // 33 bytes
+}
+
 // ──────────────────────────────────────────────
 // Seeds for PDA derivation
 // ──────────────────────────────────────────────
 
 pub const RECEIPT_SEED: &[u8] = b"receipt";
 
+pub const STATS_SEED: &[u8] = b"stats";
+
 pub fn find_receipt_pda(program_id: &Pubkey, nonce: u64) -> (Pubkey, u8) {
     Pubkey::find_program_address(&[RECEIPT_SEED, &nonce.to_le_bytes()], program_id)
 }
 
+pub fn find_stats_pda(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[STATS_SEED], program_id)
+}
+
 // ──────────────────────────────────────────────
 // Instruction handler
 // ──────────────────────────────────────────────
@@ -111,6 +140,7 @@ fn execute_cross_chain(
     let payer = next_account_info(accounts_iter)?;
     let receipt_account = next_account_info(accounts_iter)?;
     let system_program = next_account_info(accounts_iter)?;
+    let stats_account = next_account_info(accounts_iter)?;
 
     // Verify payer is signer
     if !payer.is_signer {
@@ -125,6 +155,13 @@ fn execute_cross_chain(
         return Err(ProgramError::InvalidArgument);
     }
 
+    // Derive and verify stats PDA
+    let (expected_stats, stats_bump) = find_stats_pda(program_id);
+    if *stats_account.key != expected_stats {
+        msg!("ERROR: Invalid stats PDA");
+        return Err(ProgramError::InvalidArgument);
+    }
+
     // Check if receipt already exists (idempotency / replay protection)
     if receipt_account.data_len() > 0 && receipt_account.lamports() > 0 {
         let existing = ExecutionReceipt::try_from_slice(&receipt_account.data.borrow())
@@ -154,7 +191,8 @@ fn execute_cross_chain(
 
     let seeds: &[&[u8]] = &[RECEIPT_SEED, &nonce.to_le_bytes(), &[bump]];
 
-    invoke(
+    // The PDA has no private key; the program signs for it with its seeds
+    invoke_signed(
         &system_instruction::create_account(
             payer.key,
             receipt_account.key,
@@ -163,6 +201,7 @@ fn execute_cross_chain(
             program_id,
         ),
         &[payer.clone(), receipt_account.clone(), system_program.clone()],
+        &[seeds],
     )?;
 
     // Write receipt data
@@ -180,6 +219,62 @@ fn execute_cross_chain(
 
     receipt.serialize(&mut &mut receipt_account.data.borrow_mut()[..])?;
 
+    // ── Update program-wide stats ──
+    if stats_account.data_len() == 0 {
+        let stats_seeds: &[&[u8]] = &[STATS_SEED, &[stats_bump]];
+        let stats_rent = rent.minimum_balance(ProgramStats::SIZE);
+        if stats_account.lamports() == 0 {
+            invoke_signed(
+                &system_instruction::create_account(
+                    payer.key,
+                    stats_account.key,
+                    stats_rent,
+                    ProgramStats::SIZE as u64,
+                    program_id,
+                ),
+                &[payer.clone(), stats_account.clone(), system_program.clone()],
+                &[stats_seeds],
+            )?;
+        } else {
+            // Anyone can send lamports to the PDA before it exists, which makes
+            // create_account fail; top it up and allocate it in place instead
+            let top_up = stats_rent.saturating_sub(stats_account.lamports());
+            if top_up > 0 {
+                invoke(
+                    &system_instruction::transfer(payer.key, stats_account.key, top_up),
+                    &[payer.clone(), stats_account.clone(), system_program.clone()],
+                )?;
+            }
+            invoke_signed(
+                &system_instruction::allocate(stats_account.key, ProgramStats::SIZE as u64),
+                &[stats_account.clone(), system_program.clone()],
+                &[stats_seeds],
+            )?;
+            invoke_signed(
+                &system_instruction::assign(stats_account.key, program_id),
+                &[stats_account.clone(), system_program.clone()],
+                &[stats_seeds],
+            )?;
+        }
+    }
+    if stats_account.owner != program_id {
+        msg!("ERROR: Stats PDA is not owned by this program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let mut stats = ProgramStats::try_from_slice(&stats_account.data.borrow())
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+    stats.is_initialized = true;
+    stats.total_executions = stats
+        .total_executions
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    stats.total_amount = stats
+        .total_amount
+        .checked_add(amount as u128)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    stats.last_execution_slot = clock.slot;
+    stats.serialize(&mut &mut stats_account.data.borrow_mut()[..])?;
+
     // ── Emit structured execution log ──
     emit_event_log(&trace_id, nonce, "executed", "success", "receipt-created");
 
@@ -0,0 +1,311 @@
+use anyhow::Result;
+use ethers::signers::LocalWallet;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::time::{sleep, Duration};
+use tracing::{error, info, warn};
+
+use crate::db;
+use crate::eth;
+use crate::executor_error::ExecutorError;
+use crate::event::{Actor, LifecycleEvent, Status, Step};
+use crate::solana::ExecutionRequest;
+use crate::state_machine::{self, emit_and_persist};
+use crate::types::{AppState, CrossChainMessage, MessageState, ProofBundle};
+use crate::verification;
+
+/// An abuse the adversary attempts against the relayer or the programs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AttackKind {
+    /// Another message's proof bundle relabelled to a new nonce
+    ProofReplay,
+    /// settle() called again for a message that was already settled
+    DoubleSettle,
+    /// A proof signed by an outside key but claiming an active validator
+    ForgedSignature,
+    /// An executed nonce submitted to Solana again, out of band
+    SolanaReplay,
+}
+
+impl AttackKind {
+    pub const ALL: [AttackKind; 4] = [
+        Self::ProofReplay,
+        Self::DoubleSettle,
+        Self::ForgedSignature,
+        Self::SolanaReplay,
+    ];
+}
+
+impl std::fmt::Display for AttackKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ProofReplay => write!(f, "proof-replay"),
+            Self::DoubleSettle => write!(f, "double-settle"),
+            Self::ForgedSignature => write!(f, "forged-signature"),
+            Self::SolanaReplay => write!(f, "solana-replay"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttackResult {
+    /// Rejected, and reported as an `attack-detected` event
+    Detected,
+    /// Accepted: a check is missing
+    Undetected,
+    /// No suitable target yet (e.g. nothing settled), or a chain was unreachable
+    Skipped,
+    /// Part of the attempt could not be checked (e.g. the escrow dry run failed)
+    Inconclusive,
+}
+
+/// Outcome of one attempt, for POST /control/attack.
+#[derive(Debug, Clone, Serialize)]
+pub struct AttackOutcome {
+    pub kind: AttackKind,
+    /// Message the attack was aimed at
+    pub nonce: Option<u64>,
+    pub result: AttackResult,
+    pub detail: String,
+}
+
+/// Request body for POST /control/attack; every attack runs when `kind` is absent.
+#[derive(Debug, Default, Deserialize)]
+pub struct AttackRequest {
+    pub kind: Option<AttackKind>,
+}
+
+/// What an attempt found, before it is reported.
+enum Attempt {
+    Rejected(String),
+    Accepted(String),
+    Skipped(String),
+    Inconclusive(String),
+}
+
+/// Run one attack and report it: detected attacks become `attack-detected`
+/// events on the target message, undetected ones are logged as errors.
+pub async fn run_attack(state: &Arc<AppState>, kind: AttackKind) -> Result<AttackOutcome> {
+    let (target, attempt) = match kind {
+        AttackKind::ProofReplay => proof_replay(state).await?,
+        AttackKind::DoubleSettle => double_settle(state).await?,
+        AttackKind::ForgedSignature => forged_signature(state).await?,
+        AttackKind::SolanaReplay => solana_replay(state).await?,
+    };
+    let nonce = target.as_ref().map(|m| m.nonce as u64);
+
+    let (result, detail) = match attempt {
+        Attempt::Rejected(detail) => {
+            if let Some(msg) = &target {
+                let event = LifecycleEvent::new(
+                    &msg.trace_id,
+                    msg.nonce as u64,
+                    Actor::relayer(),
+                    Step::AttackDetected,
+                    Status::Failure,
+                )
+                .with_detail(format!("{}: {}", kind, detail));
+                emit_and_persist(state, &event).await?;
+            }
+            info!(%kind, ?nonce, %detail, "Simulated attack detected");
+            (AttackResult::Detected, detail)
+        }
+        Attempt::Accepted(detail) => {
+            error!(%kind, ?nonce, %detail, "Simulated attack was NOT detected");
+            (AttackResult::Undetected, detail)
+        }
+        Attempt::Skipped(detail) => (AttackResult::Skipped, detail),
+        Attempt::Inconclusive(detail) => {
+            warn!(%kind, ?nonce, %detail, "Simulated attack was inconclusive");
+            (AttackResult::Inconclusive, detail)
+        }
+    };
+
+    Ok(AttackOutcome {
+        kind,
+        nonce,
+        result,
+        detail,
+    })
+}
+
+/// Run every attack in turn every `ADVERSARY_INTERVAL_SECS`.
+pub async fn run_adversary(state: Arc<AppState>) {
+    let interval_secs = state.config.adversary_interval_secs;
+    if interval_secs == 0 {
+        return;
+    }
+
+    warn!(interval_secs, "Adversary enabled: simulated attacks will be attempted");
+    for kind in AttackKind::ALL.iter().cycle() {
+        sleep(Duration::from_secs(interval_secs)).await;
+        if state.paused.load(std::sync::atomic::Ordering::Relaxed) || crate::chaos::faults().suspended() {
+            continue;
+        }
+        if let Err(e) = run_attack(&state, *kind).await {
+            warn!(%kind, error = %e, "Simulated attack failed to run");
+        }
+    }
+}
+
+/// Newest messages in `state`, newest first.
+async fn newest(state: &AppState, message_state: MessageState) -> Result<Vec<CrossChainMessage>> {
+    let mut messages = db::get_messages_by_state(&state.pool, message_state).await?;
+    messages.reverse();
+    Ok(messages)
+}
+
+/// Put a proof through the same checks the state machine applies before
+/// accepting one (`verification::check_proof`).
+fn audit(state: &AppState, proof: &ProofBundle, msg: &CrossChainMessage) -> Attempt {
+    match verification::check_proof(proof, msg.payload.as_bytes(), &state.validators) {
+        Ok(()) => Attempt::Accepted("proof passed the pipeline's checks".into()),
+        Err(rejection) => Attempt::Rejected(format!("proof rejected: {}", rejection)),
+    }
+}
+
+/// Present message B with message A's proof.
+async fn proof_replay(state: &Arc<AppState>) -> Result<(Option<CrossChainMessage>, Attempt)> {
+    let settled = newest(state, MessageState::Settled).await?;
+    let Some((source, proof)) = settled.iter().find_map(|m| {
+        let proof: ProofBundle = serde_json::from_str(m.proof_json.as_deref()?).ok()?;
+        Some((m, proof))
+    }) else {
+        return Ok((None, Attempt::Skipped("no settled message with a proof".into())));
+    };
+    let Some(target) = settled.iter().find(|m| m.nonce != source.nonce).cloned() else {
+        return Ok((None, Attempt::Skipped("needs two settled messages".into())));
+    };
+
+    let replayed = ProofBundle {
+        nonce: target.nonce as u64,
+        ..proof
+    };
+    let attempt = match audit(state, &replayed, &target) {
+        Attempt::Rejected(detail) => Attempt::Rejected(format!("proof of nonce {} replayed; {}", source.nonce, detail)),
+        other => other,
+    };
+    Ok((Some(target), attempt))
+}
+
+/// Sign a proof with a key outside the validator set, claiming an active validator.
+async fn forged_signature(state: &Arc<AppState>) -> Result<(Option<CrossChainMessage>, Attempt)> {
+    let Some(target) = newest(state, MessageState::Settled).await?.into_iter().next() else {
+        return Ok((None, Attempt::Skipped("no settled message".into())));
+    };
+    let nonce = target.nonce as u64;
+    let Some((_, _, claimed)) = state.validators.select(nonce) else {
+        return Ok((None, Attempt::Skipped("no active validator to impersonate".into())));
+    };
+    let claimed = claimed.to_string();
+
+    let forger = LocalWallet::new(&mut rand::thread_rng());
+    let forger_key = hex::encode(forger.signer().to_bytes());
+    let mut proof = verification::generate_proof_bundle(
+        nonce,
+        0,
+        &target.trace_id,
+        target.payload.as_bytes(),
+        &forger_key,
+    )?;
+    proof.relayer_address = claimed;
+
+    Ok((Some(target.clone()), audit(state, &proof, &target)))
+}
+
+/// Settle a settled message again: the relayer's guard must refuse, and the
+/// escrow must revert when the settlement went to Ethereum.
+async fn double_settle(state: &Arc<AppState>) -> Result<(Option<CrossChainMessage>, Attempt)> {
+    let Some(target) = newest(state, MessageState::Settled).await?.into_iter().next() else {
+        return Ok((None, Attempt::Skipped("no settled message".into())));
+    };
+    let nonce = target.nonce as u64;
+
+    let guard = match state_machine::ensure_settleable(&target) {
+        Ok(()) => return Ok((Some(target), Attempt::Accepted("relayer would settle again".into()))),
+        Err(e) => format!("relayer refused: {}", e),
+    };
+
+    let on_chain = target
+        .eth_settle_tx
+        .as_deref()
+        .is_some_and(|tx| !tx.starts_with("0xsim_"));
+    if !on_chain || target.behavior().is_some() {
+        return Ok((Some(target), Attempt::Rejected(format!("{} (settlement was simulated)", guard))));
+    }
+
+    let cfg = &state.config;
+    let result_value: u64 = target.result.as_deref().unwrap_or("0").parse().unwrap_or(0);
+    let result = eth::encode_settlement_result(result_value);
+    let signature = eth::sign_settlement(cfg.keyring.eth_settlement(), nonce, &result)?;
+    let attempt = match eth::simulate_settle(
+        &cfg.eth_rpc_url,
+        cfg.keyring.eth_settlement(),
+        &cfg.escrow_address,
+        nonce,
+        &result,
+        &signature,
+    )
+    .await
+    {
+        Ok(Some(revert)) => Attempt::Rejected(format!("{}; escrow reverted {}", guard, revert)),
+        Ok(None) => Attempt::Accepted(format!("{}; but the escrow would accept a second settle()", guard)),
+        Err(e) => Attempt::Inconclusive(format!("{}; escrow not checked: {}", guard, e)),
+    };
+    Ok((Some(target), attempt))
+}
+
+/// Execute an executed nonce again with another amount and payload: the
+/// program must leave the first receipt untouched.
+async fn solana_replay(state: &Arc<AppState>) -> Result<(Option<CrossChainMessage>, Attempt)> {
+    let mut candidates = newest(state, MessageState::Settled).await?;
+    candidates.extend(newest(state, MessageState::Executed).await?);
+
+    for target in candidates {
+        let nonce = target.nonce as u64;
+        let before = match state.solana.receipt(nonce).await {
+            Ok(Some(receipt)) => receipt,
+            Ok(None) => continue,
+            Err(e) => return Ok((None, Attempt::Skipped(format!("Solana unreachable: {}", e.message())))),
+        };
+
+        let replay = ExecutionRequest {
+            nonce,
+            sender: before.sender,
+            amount: before.result.saturating_add(1_000_000),
+            payload: b"adversary replay".to_vec(),
+            trace_id: before.trace_id,
+            computation: target.computation.parse().unwrap_or_default(),
+            priority_fee_micro_lamports: 0,
+        };
+        let signature = match state.solana.execute(&replay).await {
+            Ok(signature) => signature,
+            Err(ExecutorError::Reverted(e)) => {
+                return Ok((Some(target), Attempt::Rejected(format!("program rejected the replay: {}", e))));
+            }
+            Err(e) => return Ok((Some(target), Attempt::Skipped(format!("Solana unavailable: {}", e.message())))),
+        };
+        let after = match state.solana.receipt(nonce).await {
+            Ok(after) => after,
+            Err(e) => return Ok((Some(target), Attempt::Skipped(format!("receipt not re-read: {}", e.message())))),
+        };
+
+        let attempt = if after.as_ref() == Some(&before) {
+            Attempt::Rejected(format!(
+                "replay {} left the receipt untouched (result {})",
+                signature, before.result
+            ))
+        } else {
+            Attempt::Accepted(format!(
+                "replay {} changed the receipt: result {} -> {:?}",
+                signature,
+                before.result,
+                after.map(|r| r.result)
+            ))
+        };
+        return Ok((Some(target), attempt));
+    }
+    Ok((None, Attempt::Skipped("no executed nonce with a receipt".into())))
+}
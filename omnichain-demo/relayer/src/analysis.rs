@@ -0,0 +1,440 @@
+use anyhow::Result;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tokio::time::{sleep, Duration};
+use tracing::{error, info, warn};
+
+use crate::db;
+use crate::event::{LifecycleEvent, Status};
+use crate::types::{
+    AppState, CrossChainMessage, FleetReport, SimilarIncident, StructuredAnalysis,
+};
+
+/// Model used for all analysis requests.
+pub const OPENAI_MODEL: &str = "gpt-4o";
+
+/// Model used for incident similarity embeddings.
+pub const EMBEDDING_MODEL: &str = "text-embedding-3-small";
+
+/// USD price per 1M (prompt, completion) tokens, used for cost estimates.
+/// Dated snapshots (`gpt-4o-2024-08-06`) are priced as their model; unknown
+/// models are recorded with zero cost.
+fn model_pricing(model: &str) -> (f64, f64) {
+    // Longer names first, so `gpt-4o-mini` is not taken for a `gpt-4o` snapshot
+    const PRICES: [(&str, (f64, f64)); 4] = [
+        ("gpt-4o-mini", (0.15, 0.60)),
+        ("gpt-4o", (2.50, 10.00)),
+        ("text-embedding-3-small", (0.02, 0.0)),
+        ("text-embedding-3-large", (0.13, 0.0)),
+    ];
+    PRICES
+        .iter()
+        .find(|(name, _)| {
+            model
+                .strip_prefix(name)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('-'))
+        })
+        .map_or((0.0, 0.0), |(_, price)| *price)
+}
+
+/// Estimated USD cost of one request.
+fn estimate_cost(model: &str, prompt_tokens: i64, completion_tokens: i64) -> f64 {
+    let (prompt_price, completion_price) = model_pricing(model);
+    (prompt_tokens as f64 * prompt_price + completion_tokens as f64 * completion_price) / 1_000_000.0
+}
+
+/// Record the `usage` block of an OpenAI response (best effort — a failure
+/// to record never fails the analysis itself). Usage is priced as the model
+/// the response names, which may be a dated snapshot of the `requested` one.
+async fn record_usage(pool: &SqlitePool, requested: &str, kind: &str, body: &serde_json::Value) {
+    let model = body["model"].as_str().unwrap_or(requested);
+    let prompt_tokens = body["usage"]["prompt_tokens"].as_i64().unwrap_or(0);
+    let completion_tokens = body["usage"]["completion_tokens"].as_i64().unwrap_or(0);
+    let cost = estimate_cost(model, prompt_tokens, completion_tokens);
+
+    let recorded = db::insert_analysis_cost(pool, model, kind, prompt_tokens, completion_tokens, cost).await;
+    if let Err(e) = recorded {
+        warn!(error = %e, "Failed to record analysis cost");
+    }
+}
+
+const FLEET_SYSTEM_PROMPT: &str = r#"You are the on-call lead for a company that operates cross-chain relayer infrastructure (Ethereum escrow → relayer → Solana execution → Ethereum settlement). You are writing the periodic fleet operations report.
+
+CONTEXT / ASSUMPTIONS
+- The data is an aggregate over a time window, taken directly from our DB (source of truth).
+- This is a simulator right now; IGNORE any "Simulated …" wording entirely.
+- Your output will be rendered as MARKDOWN on a React page.
+- Do NOT browse the web. Use only the data provided.
+
+OUTPUT: Return ONLY Markdown (no code fences). No JSON.
+
+MARKDOWN STRUCTURE (strict):
+
+# Fleet Operations Report
+
+## Summary
+- **Window:** last <hours> hours
+- **Volume:** <total> messages (<settled> settled, <rolled_back> rolled back, <pending> pending)
+- **Rollback rate:** <percent>%
+- **Overall health:** Healthy | Degraded | Unhealthy (one sentence why)
+
+## Top Failure Reasons
+A table of the most frequent failure/retry reasons with counts and a short interpretation.
+
+| Reason | Count | Interpretation |
+|--------|-------|----------------|
+
+## Latency Trend
+Describe how end-to-end latency moved across the hourly buckets. Call out spikes with the hour they occurred.
+
+## Sender Issues
+List senders with disproportionate rollbacks or retries and whether this looks like a customer problem or a relayer problem.
+
+## Recommended Actions
+A checklist of 3-6 concrete, checkable actions for relayer ops.
+
+RULES:
+- Ignore the word "Simulated" wherever it appears; treat events as real.
+- If there is no traffic in the window, say so and keep the report short.
+- Percentages with one decimal place."#;
+
+/// System prompt for the structured (JSON) per-transaction analysis mode.
+pub const STRUCTURED_ANALYSIS_SYSTEM_PROMPT: &str = r#"You are a senior blockchain ops analyst for a company that operates cross-chain relayer infrastructure. You classify a single cross-chain transaction for a React dashboard that renders badges and alerts programmatically.
+
+CONTEXT / ASSUMPTIONS
+- The data comes directly from our DB (source of truth).
+- This is a simulator right now; IGNORE any "Simulated …" wording entirely.
+- Do NOT browse the web. Use only the data provided.
+
+OUTPUT: Return ONLY a single JSON object with EXACTLY this shape (no markdown, no prose):
+
+{
+  "final_status": "settled_success" | "settled_failure_refunded" | "pending" | "inconsistent_state" | "unknown",
+  "confidence": <integer 0-100>,
+  "badges": [<short badge label>, ...],
+  "alerts": [
+    { "level": "info" | "warning" | "error", "message": <string>, "trigger": <condition that raised it> }
+  ],
+  "metrics": {
+    "time_to_observe_secs": <number or null>,
+    "time_to_verify_secs": <number or null>,
+    "time_to_execute_secs": <number or null>,
+    "time_to_settle_secs": <number or null>,
+    "end_to_end_secs": <number or null>,
+    "retries": <integer>
+  }
+}
+
+RULES:
+- Prefer the event timeline order + final "settled/refunded" evidence when lifecycleStage list conflicts.
+- If mint+burn happened but Ethereum settle failed AND a refund is shown: final_status = settled_failure_refunded.
+- If mint happened but burn is missing: add an error alert for possible double-mint / unbacked token risk.
+- If Ethereum lock is present but neither settle nor refund is present: add a warning alert for possible stuck-funds risk.
+- At most 6 badges, each at most 32 characters.
+- Use null for any metric that cannot be computed from the timestamps."#;
+
+/// Base delay before retrying a rate-limited or failed OpenAI request;
+/// doubled on each further attempt.
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+
+/// Longest wait between retries, including a server's `Retry-After`.
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(60);
+
+/// Non-success response from the OpenAI API, after any retries.
+#[derive(Debug)]
+pub struct OpenAiError {
+    pub status: reqwest::StatusCode,
+    pub body: String,
+}
+
+impl std::fmt::Display for OpenAiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "OpenAI returned {}: {}", self.status, self.body)
+    }
+}
+
+impl std::error::Error for OpenAiError {}
+
+/// True if `e` is OpenAI still rate limiting us after every retry.
+pub fn is_rate_limited(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<OpenAiError>()
+        .is_some_and(|e| e.status == reqwest::StatusCode::TOO_MANY_REQUESTS)
+}
+
+/// POST `body` to an OpenAI endpoint, paced by the per-key request budget.
+/// 429 and 5xx responses are retried up to `OPENAI_MAX_RETRIES` times with
+/// exponential backoff, or after the server's `Retry-After`.
+async fn post_openai(state: &AppState, api_key: &str, url: &str, body: &serde_json::Value) -> Result<serde_json::Value> {
+    let client = reqwest::Client::new();
+    let mut attempt = 0;
+    loop {
+        state.analysis_queue.pace(api_key).await;
+        let res = client
+            .post(url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .json(body)
+            .send()
+            .await?;
+
+        let status = res.status();
+        if status.is_success() {
+            return Ok(res.json().await?);
+        }
+        let retryable = status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error();
+        if retryable && attempt < state.config.openai_max_retries {
+            let delay = res
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|v| v.to_str().ok()?.parse().ok())
+                .map(Duration::from_secs)
+                .unwrap_or_else(|| {
+                    2u32.checked_pow(attempt)
+                        .and_then(|factor| RETRY_BASE_DELAY.checked_mul(factor))
+                        .unwrap_or(RETRY_MAX_DELAY)
+                })
+                .min(RETRY_MAX_DELAY);
+            attempt += 1;
+            warn!(url, %status, attempt, delay_ms = delay.as_millis() as u64, "OpenAI request failed, retrying");
+            sleep(delay).await;
+            continue;
+        }
+        let body = res.text().await.unwrap_or_default();
+        return Err(OpenAiError { status, body }.into());
+    }
+}
+
+/// Send a single system + user prompt to the OpenAI chat completions API
+/// and return the assistant message content. Token usage is recorded under `kind`.
+pub async fn chat_completion(
+    state: &AppState,
+    api_key: &str,
+    kind: &str,
+    system_prompt: &str,
+    user_msg: &str,
+) -> Result<String> {
+    request_completion(state, api_key, kind, system_prompt, user_msg, false).await
+}
+
+/// Like `chat_completion`, but forces JSON mode and validates the reply
+/// against the `StructuredAnalysis` schema.
+pub async fn structured_completion(
+    state: &AppState,
+    api_key: &str,
+    system_prompt: &str,
+    user_msg: &str,
+) -> Result<StructuredAnalysis> {
+    let raw = request_completion(state, api_key, "structured", system_prompt, user_msg, true).await?;
+    parse_structured_analysis(&raw)
+}
+
+/// Parse and validate an LLM reply as a `StructuredAnalysis`.
+pub fn parse_structured_analysis(raw: &str) -> Result<StructuredAnalysis> {
+    let analysis: StructuredAnalysis = serde_json::from_str(raw)
+        .map_err(|e| anyhow::anyhow!("LLM returned JSON that does not match the schema: {}", e))?;
+
+    if analysis.confidence > 100 {
+        anyhow::bail!("confidence out of range: {}", analysis.confidence);
+    }
+    if analysis.badges.len() > 6 {
+        anyhow::bail!("too many badges: {}", analysis.badges.len());
+    }
+    if let Some(b) = analysis.badges.iter().find(|b| b.trim().is_empty() || b.len() > 32) {
+        anyhow::bail!("invalid badge label: {:?}", b);
+    }
+    if analysis.alerts.iter().any(|a| a.message.trim().is_empty()) {
+        anyhow::bail!("alert with empty message");
+    }
+    if analysis.metrics.retries < 0 {
+        anyhow::bail!("negative retry count");
+    }
+
+    Ok(analysis)
+}
+
+async fn request_completion(
+    state: &AppState,
+    api_key: &str,
+    kind: &str,
+    system_prompt: &str,
+    user_msg: &str,
+    json_mode: bool,
+) -> Result<String> {
+    let mut body = serde_json::json!({
+        "model": OPENAI_MODEL,
+        "messages": [
+            { "role": "system", "content": system_prompt },
+            { "role": "user", "content": user_msg },
+        ],
+        "temperature": 0.3,
+        "max_tokens": 4000,
+    });
+    if json_mode {
+        body["response_format"] = serde_json::json!({ "type": "json_object" });
+    }
+
+    let body = post_openai(state, api_key, "https://api.openai.com/v1/chat/completions", &body).await?;
+    record_usage(&state.pool, OPENAI_MODEL, kind, &body).await;
+
+    Ok(body["choices"][0]["message"]["content"]
+        .as_str()
+        .unwrap_or("Analysis unavailable")
+        .to_string())
+}
+
+/// Embed a piece of text with the OpenAI embeddings API.
+pub async fn embed(state: &AppState, api_key: &str, text: &str) -> Result<Vec<f32>> {
+    let body = serde_json::json!({
+        "model": EMBEDDING_MODEL,
+        "input": text,
+    });
+    let body = post_openai(state, api_key, "https://api.openai.com/v1/embeddings", &body).await?;
+    record_usage(&state.pool, EMBEDDING_MODEL, "embedding", &body).await;
+
+    let embedding: Vec<f32> = body["data"][0]["embedding"]
+        .as_array()
+        .ok_or_else(|| anyhow::anyhow!("Embedding missing from response"))?
+        .iter()
+        .filter_map(|v| v.as_f64().map(|f| f as f32))
+        .collect();
+
+    if embedding.is_empty() {
+        anyhow::bail!("Empty embedding returned");
+    }
+    Ok(embedding)
+}
+
+/// Build the text that gets embedded for a transaction.
+///
+/// Only the *shape* of the lifecycle is included (steps, statuses, failure
+/// details, final state) — hashes and signatures would make every incident
+/// look unique.
+pub fn incident_summary(msg: &CrossChainMessage, events: &[LifecycleEvent]) -> String {
+    let mut lines = vec![format!(
+        "final_state={} retries={}",
+        msg.state, msg.retry_count
+    )];
+    if let Some(err) = &msg.error_message {
+        lines.push(format!("error={}", err));
+    }
+    for e in events {
+        let step = e.step.to_string();
+        let status = format!("{:?}", e.status).to_lowercase();
+        match (&e.status, &e.detail) {
+            (Status::Success, _) | (_, None) => lines.push(format!("{} {}", step, status)),
+            (_, Some(detail)) => lines.push(format!("{} {}: {}", step, status, detail)),
+        }
+    }
+    lines.join("\n")
+}
+
+/// Embed a transaction's summary and store it in the incident index.
+pub async fn index_incident(
+    state: &Arc<AppState>,
+    api_key: &str,
+    msg: &CrossChainMessage,
+    events: &[LifecycleEvent],
+) -> Result<db::IncidentEmbedding> {
+    let summary = incident_summary(msg, events);
+    let embedding = embed(state, api_key, &summary).await?;
+    db::upsert_incident_embedding(&state.pool, msg.nonce as u64, &summary, EMBEDDING_MODEL, &embedding)
+        .await?;
+
+    db::get_incident_embedding(&state.pool, msg.nonce as u64)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Incident {} vanished after insert", msg.nonce))
+}
+
+/// Rank every other incident indexed with `target`'s model by cosine
+/// similarity to `target`.
+pub async fn find_similar(
+    state: &Arc<AppState>,
+    target: &db::IncidentEmbedding,
+    limit: usize,
+) -> Result<Vec<SimilarIncident>> {
+    let index = db::get_incident_embeddings_for_model(&state.pool, &target.model).await?;
+
+    let mut scored: Vec<(f32, db::IncidentEmbedding)> = index
+        .into_iter()
+        .filter(|c| c.nonce != target.nonce)
+        .map(|c| (cosine_similarity(&target.embedding, &c.embedding), c))
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored.truncate(limit);
+
+    let mut similar = Vec::with_capacity(scored.len());
+    for (similarity, candidate) in scored {
+        let msg = db::get_message_by_nonce(&state.pool, candidate.nonce as u64).await?;
+        similar.push(SimilarIncident {
+            nonce: candidate.nonce,
+            similarity,
+            state: msg.as_ref().map(|m| m.state.clone()),
+            description: msg.and_then(|m| m.description),
+            summary: candidate.summary,
+            indexed_at: candidate.created_at,
+        });
+    }
+    Ok(similar)
+}
+
+/// Cosine similarity; 0.0 for mismatched or zero-length vectors.
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+/// Aggregate the last `hours` hours, ask the LLM for an operations report,
+/// and persist it.
+pub async fn generate_fleet_report(
+    state: &Arc<AppState>,
+    api_key: &str,
+    hours: i64,
+) -> Result<FleetReport> {
+    let _slot = state.analysis_queue.slot().await;
+    let summary = db::get_fleet_summary(&state.pool, hours).await?;
+
+    let user_msg = format!(
+        "NOW WRITE THE REPORT FOR THIS DATA:\n{}",
+        serde_json::to_string_pretty(&summary)?
+    );
+    let report = chat_completion(state, api_key, "fleet", FLEET_SYSTEM_PROMPT, &user_msg).await?;
+
+    let id = db::insert_fleet_report(&state.pool, &summary, &report, OPENAI_MODEL).await?;
+    info!(id, hours, total = summary.total, "Fleet report generated");
+
+    db::get_fleet_report(&state.pool, id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Fleet report {} vanished after insert", id))
+}
+
+/// Background task: generate a fleet report every `FLEET_REPORT_INTERVAL_HOURS`.
+/// Skips silently when no OpenAI key is configured.
+pub async fn run_fleet_scheduler(state: Arc<AppState>) {
+    let interval_hours = state.config.fleet_report_interval_hours;
+    if interval_hours == 0 {
+        info!("Fleet report scheduler disabled");
+        return;
+    }
+
+    info!(interval_hours, "Fleet report scheduler started");
+    loop {
+        sleep(Duration::from_secs(interval_hours * 3600)).await;
+
+        let Ok(api_key) = std::env::var("OPENAI_API_KEY") else {
+            warn!("OPENAI_API_KEY not set, skipping scheduled fleet report");
+            continue;
+        };
+
+        let hours = state.config.fleet_report_window_hours;
+        if let Err(e) = generate_fleet_report(&state, &api_key, hours).await {
+            error!(error = %e, "Scheduled fleet report failed");
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tokio::sync::{Semaphore, SemaphorePermit};
+use tokio::time::{sleep, Duration, Instant};
+
+use crate::types::AnalysisFormat;
+
+/// Finished jobs kept for polling; the oldest are dropped first.
+const JOB_HISTORY: usize = 500;
+
+/// Bounds concurrent analyses, paces OpenAI requests per API key, and keeps
+/// the status of analyses queued with `?queued=true`.
+pub struct AnalysisQueue {
+    permits: Semaphore,
+    /// 0 = unlimited
+    requests_per_minute: usize,
+    /// Most unfinished jobs at once
+    max_queued: usize,
+    /// Send times of each key's requests in the last minute
+    recent: Mutex<HashMap<String, VecDeque<Instant>>>,
+    next_id: AtomicU64,
+    jobs: Mutex<BTreeMap<u64, AnalysisJob>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Done,
+    Failed,
+}
+
+/// A transaction analysis queued by POST /analyze/:nonce?queued=true, for
+/// GET /analyze/jobs/:id.
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalysisJob {
+    pub id: u64,
+    pub nonce: u64,
+    pub format: AnalysisFormat,
+    pub status: JobStatus,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// The body POST /analyze/:nonce would have returned
+    pub result: Option<serde_json::Value>,
+    pub error: Option<String>,
+}
+
+impl AnalysisQueue {
+    pub fn new(concurrency: usize, requests_per_minute: usize, max_queued: usize) -> Self {
+        Self {
+            permits: Semaphore::new(concurrency.max(1)),
+            requests_per_minute,
+            max_queued,
+            recent: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(0),
+            jobs: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Wait for one of the `ANALYSIS_CONCURRENCY` slots; held for a whole analysis.
+    pub async fn slot(&self) -> SemaphorePermit<'_> {
+        self.permits.acquire().await.expect("analysis semaphore is never closed")
+    }
+
+    /// Wait until `api_key` may send another request within its per-minute budget.
+    pub async fn pace(&self, api_key: &str) {
+        if self.requests_per_minute == 0 {
+            return;
+        }
+        loop {
+            let wait = {
+                let mut recent = self.recent.lock().unwrap();
+                let sent = recent.entry(api_key.to_string()).or_default();
+                let now = Instant::now();
+                while sent.front().is_some_and(|t| now.duration_since(*t) >= Duration::from_secs(60)) {
+                    sent.pop_front();
+                }
+                if sent.len() < self.requests_per_minute {
+                    sent.push_back(now);
+                    return;
+                }
+                // Until the oldest request leaves the window
+                Duration::from_secs(60) - now.duration_since(sent[0])
+            };
+            sleep(wait).await;
+        }
+    }
+
+    /// Add a queued job, or None if `max_queued` jobs are already unfinished.
+    /// Finished jobs past `JOB_HISTORY` make room, unfinished ones never do.
+    pub fn submit(&self, nonce: u64, format: AnalysisFormat) -> Option<AnalysisJob> {
+        let mut jobs = self.jobs.lock().unwrap();
+        if jobs.values().filter(|j| j.finished_at.is_none()).count() >= self.max_queued {
+            return None;
+        }
+        let job = AnalysisJob {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed) + 1,
+            nonce,
+            format,
+            status: JobStatus::Queued,
+            created_at: Utc::now(),
+            started_at: None,
+            finished_at: None,
+            result: None,
+            error: None,
+        };
+        jobs.insert(job.id, job.clone());
+        while jobs.len() > JOB_HISTORY {
+            let Some(id) = jobs.values().find(|j| j.finished_at.is_some()).map(|j| j.id) else {
+                break;
+            };
+            jobs.remove(&id);
+        }
+        Some(job)
+    }
+
+    pub fn start(&self, id: u64) {
+        if let Some(job) = self.jobs.lock().unwrap().get_mut(&id) {
+            job.status = JobStatus::Running;
+            job.started_at = Some(Utc::now());
+        }
+    }
+
+    pub fn finish(&self, id: u64, outcome: Result<serde_json::Value, String>) {
+        if let Some(job) = self.jobs.lock().unwrap().get_mut(&id) {
+            job.finished_at = Some(Utc::now());
+            match outcome {
+                Ok(result) => {
+                    job.status = JobStatus::Done;
+                    job.result = Some(result);
+                }
+                Err(error) => {
+                    job.status = JobStatus::Failed;
+                    job.error = Some(error);
+                }
+            }
+        }
+    }
+
+    pub fn job(&self, id: u64) -> Option<AnalysisJob> {
+        self.jobs.lock().unwrap().get(&id).cloned()
+    }
+}
@@ -0,0 +1,229 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::time::{sleep, Duration};
+use tracing::{info, warn};
+
+use crate::chaos;
+use crate::db::{self, DatabaseStats};
+use crate::event::{LifecycleEvent, Step};
+use crate::state_machine;
+use crate::types::{AppState, InjectBehavior, InjectRequest, RunningGuard};
+
+/// Command-line flag: `relayer --bench n=1000` runs a benchmark and exits.
+pub const BENCH_FLAG: &str = "--bench";
+
+/// Upper bound on messages per benchmark.
+pub const MAX_BENCH_MESSAGES: u64 = 100_000;
+
+/// Time allowed for the pipeline to drain: a base plus a share per message.
+const DRAIN_BASE_SECS: u64 = 60;
+const DRAIN_PER_MESSAGE_MS: u64 = 500;
+
+/// Request body for POST /control/benchmark.
+#[derive(Debug, Deserialize)]
+pub struct BenchRequest {
+    pub n: u64,
+}
+
+/// Latency percentiles of one pipeline stage, in milliseconds.
+#[derive(Debug, Clone, Serialize)]
+pub struct StageLatency {
+    pub stage: String,
+    /// Messages that completed the stage
+    pub samples: usize,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchReport {
+    pub messages: u64,
+    pub first_nonce: u64,
+    pub settled: u64,
+    /// Failed or rolled back
+    pub failed: u64,
+    /// Still in flight when the drain timeout hit
+    pub unfinished: u64,
+    pub elapsed_secs: f64,
+    /// Settled messages per second, injection to drain
+    pub throughput_per_sec: f64,
+    pub stages: Vec<StageLatency>,
+    pub database: DatabaseStats,
+    pub solana_mode: String,
+}
+
+/// Why a benchmark did not start.
+#[derive(Debug)]
+pub enum BenchRefused {
+    /// `n` is 0 or above `MAX_BENCH_MESSAGES`
+    InvalidSize,
+    /// The processor is paused or another benchmark is running
+    Busy(&'static str),
+}
+
+impl std::fmt::Display for BenchRefused {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidSize => write!(f, "benchmark size must be between 1 and {}", MAX_BENCH_MESSAGES),
+            Self::Busy(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl std::error::Error for BenchRefused {}
+
+/// Message count from `--bench N` or `--bench n=N`, if the flag was given.
+pub fn size_from_args() -> Result<Option<u64>> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == BENCH_FLAG {
+            let value = args.next().context("--bench needs a message count, e.g. --bench n=1000")?;
+            let n = value
+                .trim_start_matches("n=")
+                .parse()
+                .with_context(|| format!("invalid --bench message count: {}", value))?;
+            return Ok(Some(n));
+        }
+    }
+    Ok(None)
+}
+
+/// Drive `n` injected messages through the full pipeline and measure it.
+///
+/// Injected messages skip the scenario's random failures and settle without
+/// Ethereum, so the run measures the relayer itself against the configured
+/// Solana executor. Chaos is suspended for the run, so failures in the
+/// report are the relayer's own; simulated latency and congestion are kept.
+pub async fn run_benchmark(state: &Arc<AppState>, n: u64) -> Result<BenchReport> {
+    if n == 0 || n > MAX_BENCH_MESSAGES {
+        return Err(BenchRefused::InvalidSize.into());
+    }
+    if state.paused.load(Ordering::Relaxed) {
+        return Err(BenchRefused::Busy("the processor is paused").into());
+    }
+    let Some(_running) = RunningGuard::acquire(&state.benchmark_running) else {
+        return Err(BenchRefused::Busy("a benchmark is already running").into());
+    };
+    let _quiet = chaos::faults().suspend();
+    measure(state, n).await
+}
+
+async fn measure(state: &Arc<AppState>, n: u64) -> Result<BenchReport> {
+    info!(n, "Benchmark started");
+    let started = Instant::now();
+
+    let mut nonces = Vec::with_capacity(n as usize);
+    for i in 0..n {
+        let req = InjectRequest {
+            sender: None,
+            amount: 1_000 + i,
+            description: Some(format!("Benchmark {}/{}", i + 1, n)),
+            behavior: InjectBehavior::default(),
+        };
+        nonces.push(state_machine::inject_message(state, &req, "0x0000000000000000000000000000000000000000").await?);
+    }
+    let (first, last) = (nonces[0], nonces[nonces.len() - 1]);
+
+    let drain_timeout = Duration::from_secs(DRAIN_BASE_SECS) + Duration::from_millis(DRAIN_PER_MESSAGE_MS * n);
+    let unfinished = loop {
+        let unfinished = db::count_unfinished_in_range(&state.pool, first, last).await? as u64;
+        if unfinished == 0 {
+            break 0;
+        }
+        if started.elapsed() > drain_timeout {
+            warn!(unfinished, "Benchmark timed out waiting for messages to finish");
+            break unfinished;
+        }
+        sleep(Duration::from_millis(200)).await;
+    };
+    let elapsed = started.elapsed().as_secs_f64();
+    state.event_writer.flush().await;
+
+    let events = db::get_events_in_range(&state.pool, first, last).await?;
+    let reached = steps_reached(&events);
+    let settled = reached.values().filter(|steps| steps.contains_key(&Step::Settled)).count() as u64;
+    let stages = stage_latencies(&reached);
+
+    let report = BenchReport {
+        messages: n,
+        first_nonce: first,
+        settled,
+        failed: n.saturating_sub(settled + unfinished),
+        unfinished,
+        elapsed_secs: elapsed,
+        throughput_per_sec: settled as f64 / elapsed,
+        stages,
+        database: db::database_stats(&state.pool).await?,
+        solana_mode: state.solana.mode().to_string(),
+    };
+    info!(
+        n,
+        settled,
+        elapsed_secs = report.elapsed_secs,
+        throughput_per_sec = report.throughput_per_sec,
+        "Benchmark finished"
+    );
+    Ok(report)
+}
+
+/// When each message first reached each step, by nonce.
+pub fn steps_reached(events: &[LifecycleEvent]) -> HashMap<u64, HashMap<Step, DateTime<Utc>>> {
+    let mut reached: HashMap<u64, HashMap<Step, DateTime<Utc>>> = HashMap::new();
+    for event in events {
+        let Ok(at) = DateTime::parse_from_rfc3339(&event.timestamp) else {
+            continue;
+        };
+        reached
+            .entry(event.nonce)
+            .or_default()
+            .entry(event.step.clone())
+            .or_insert(at.with_timezone(&Utc));
+    }
+    reached
+}
+
+/// Latency percentiles of the verify, execute and settle stages, and end to end.
+pub fn stage_latencies(reached: &HashMap<u64, HashMap<Step, DateTime<Utc>>>) -> Vec<StageLatency> {
+    [
+        ("verify", Step::Observed, Step::Verified),
+        ("execute", Step::Verified, Step::Executed),
+        ("settle", Step::Executed, Step::Settled),
+        ("end_to_end", Step::Observed, Step::Settled),
+    ]
+    .into_iter()
+    .map(|(stage, from, to)| {
+        let samples: Vec<f64> = reached
+            .values()
+            .filter_map(|steps| Some((*steps.get(&to)? - *steps.get(&from)?).num_microseconds()? as f64 / 1000.0))
+            .collect();
+        latency(stage, samples)
+    })
+    .collect()
+}
+
+fn latency(stage: &str, mut samples: Vec<f64>) -> StageLatency {
+    samples.sort_by(|a, b| a.total_cmp(b));
+    // Nearest-rank percentile
+    let percentile = |p: f64| {
+        if samples.is_empty() {
+            return 0.0;
+        }
+        let rank = ((p / 100.0) * samples.len() as f64).ceil() as usize;
+        samples[rank.clamp(1, samples.len()) - 1]
+    };
+    StageLatency {
+        stage: stage.into(),
+        samples: samples.len(),
+        p50_ms: percentile(50.0),
+        p90_ms: percentile(90.0),
+        p99_ms: percentile(99.0),
+        max_ms: samples.last().copied().unwrap_or(0.0),
+    }
+}
@@ -0,0 +1,225 @@
+use anyhow::Result;
+use sqlx::SqlitePool;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use crate::db;
+use crate::event::LifecycleEvent;
+use crate::types::CrossChainMessage;
+
+/// Changed messages past which a full reload is cheaper than re-reading each.
+const MAX_STALE: usize = 64;
+
+/// Bounded in-memory copy of the newest messages and their events, so the
+/// dashboard's polling skips SQLite.
+///
+/// The cache mirrors the database for every nonce at or above its floor.
+/// Writers mark a message stale after changing it, and the row is re-read
+/// on the next access. Events are appended as they are persisted. Reads
+/// outside the cached window go to the database.
+pub struct RecentCache {
+    capacity: usize,
+    inner: Mutex<Inner>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+#[derive(Default)]
+struct Inner {
+    loaded: bool,
+    /// Bumped by every message write; a read only applies rows whose
+    /// staleness it saw, so rows changed mid-read stay stale
+    generation: u64,
+    /// Generation of the last clear; reads started before it are dropped
+    cleared_at: u64,
+    /// Bumped by every event write, so an events read that raced one is dropped
+    events_generation: u64,
+    /// Newest messages by nonce
+    messages: BTreeMap<u64, CrossChainMessage>,
+    /// Lowest nonce the window covers; None while it holds every message
+    floor: Option<u64>,
+    /// Messages changed since they were read, with the generation of the change
+    stale: HashMap<u64, u64>,
+    /// Events of cached messages, loaded on first read
+    events: HashMap<u64, Vec<LifecycleEvent>>,
+}
+
+impl Inner {
+    fn covers(&self, nonce: u64) -> bool {
+        self.floor.is_none_or(|floor| nonce >= floor)
+    }
+
+    /// Drop the oldest messages beyond `capacity`, raising the floor.
+    fn trim(&mut self, capacity: usize) {
+        while self.messages.len() > capacity {
+            if let Some((nonce, _)) = self.messages.pop_first() {
+                self.events.remove(&nonce);
+            }
+            self.floor = self.messages.keys().next().copied();
+        }
+    }
+}
+
+impl RecentCache {
+    /// A capacity of 0 disables the cache: every read goes to the database.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Mutex::new(Inner::default()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Reads served from memory and from the database.
+    pub fn stats(&self) -> (u64, u64) {
+        (self.hits.load(Ordering::Relaxed), self.misses.load(Ordering::Relaxed))
+    }
+
+    /// All messages, nonce descending.
+    pub async fn all_messages(&self, pool: &SqlitePool) -> Result<Vec<CrossChainMessage>> {
+        if self.refresh(pool).await? {
+            let inner = self.inner.lock().unwrap();
+            // Only a window holding every message can answer
+            if inner.floor.is_none() {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return Ok(inner.messages.values().rev().cloned().collect());
+            }
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        db::get_all_messages(pool).await
+    }
+
+    pub async fn message(&self, pool: &SqlitePool, nonce: u64) -> Result<Option<CrossChainMessage>> {
+        if self.refresh(pool).await? {
+            let inner = self.inner.lock().unwrap();
+            if inner.covers(nonce) {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return Ok(inner.messages.get(&nonce).cloned());
+            }
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        db::get_message_by_nonce(pool, nonce).await
+    }
+
+    pub async fn events(&self, pool: &SqlitePool, nonce: u64) -> Result<Vec<LifecycleEvent>> {
+        let generation = {
+            let inner = self.inner.lock().unwrap();
+            if let Some(events) = inner.events.get(&nonce) {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return Ok(events.clone());
+            }
+            inner.events_generation
+        };
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let events = db::get_events_by_nonce(pool, nonce).await?;
+
+        let mut inner = self.inner.lock().unwrap();
+        if inner.events_generation == generation && inner.messages.contains_key(&nonce) {
+            inner.events.insert(nonce, events.clone());
+        }
+        Ok(events)
+    }
+
+    /// A message row was inserted, updated or deleted.
+    pub fn invalidate(&self, nonce: u64) {
+        self.mark_stale(&mut self.inner.lock().unwrap(), nonce);
+    }
+
+    /// A message and its events were rewritten (reparse) or deleted (purge).
+    pub fn evict(&self, nonce: u64) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.events_generation += 1;
+        inner.events.remove(&nonce);
+        self.mark_stale(&mut inner, nonce);
+    }
+
+    fn mark_stale(&self, inner: &mut Inner, nonce: u64) {
+        inner.generation += 1;
+        let generation = inner.generation;
+        inner.stale.insert(nonce, generation);
+        if inner.stale.len() > MAX_STALE {
+            inner.loaded = false;
+        }
+    }
+
+    /// All data was cleared.
+    pub fn clear(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        *inner = Inner {
+            generation: inner.generation + 1,
+            cleared_at: inner.generation + 1,
+            events_generation: inner.events_generation + 1,
+            ..Inner::default()
+        };
+    }
+
+    /// An event was persisted (it carries its `seq`).
+    pub fn push_event(&self, event: &LifecycleEvent) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.events_generation += 1;
+        if let Some(events) = inner.events.get_mut(&event.nonce) {
+            if events.last().is_none_or(|last| last.seq < event.seq) {
+                events.push(event.clone());
+            }
+        }
+    }
+
+    /// Bring the window up to date: load it on first use, then re-read
+    /// stale messages. False if disabled or the data was cleared meanwhile.
+    async fn refresh(&self, pool: &SqlitePool) -> Result<bool> {
+        if self.capacity == 0 {
+            return Ok(false);
+        }
+        let (loaded, generation, stale) = {
+            let inner = self.inner.lock().unwrap();
+            if inner.loaded && inner.stale.is_empty() {
+                return Ok(true);
+            }
+            (inner.loaded, inner.generation, inner.stale.clone())
+        };
+
+        if !loaded {
+            let rows = db::get_recent_messages(pool, self.capacity as i64 + 1).await?;
+            let mut inner = self.inner.lock().unwrap();
+            if inner.cleared_at > generation {
+                return Ok(false);
+            }
+            inner.messages = rows.into_iter().map(|m| (m.nonce as u64, m)).collect();
+            inner.floor = None;
+            inner.trim(self.capacity);
+            inner.stale.retain(|_, changed| *changed > generation);
+            let Inner { messages, events, .. } = &mut *inner;
+            events.retain(|nonce, _| messages.contains_key(nonce));
+            inner.loaded = true;
+            return Ok(true);
+        }
+
+        let mut rows = Vec::with_capacity(stale.len());
+        for (&nonce, &changed) in &stale {
+            rows.push((nonce, changed, db::get_message_by_nonce(pool, nonce).await?));
+        }
+        let mut inner = self.inner.lock().unwrap();
+        if inner.cleared_at > generation {
+            return Ok(false);
+        }
+        for (nonce, changed, row) in rows {
+            if inner.stale.get(&nonce) != Some(&changed) {
+                continue;
+            }
+            inner.stale.remove(&nonce);
+            match row {
+                Some(msg) if inner.covers(nonce) => {
+                    inner.messages.insert(nonce, msg);
+                }
+                _ => {
+                    inner.messages.remove(&nonce);
+                    inner.events.remove(&nonce);
+                }
+            }
+        }
+        inner.trim(self.capacity);
+        Ok(true)
+    }
+}
@@ -0,0 +1,353 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::executor_error::ExecutorError;
+use crate::types::MessageState;
+
+/// SIMULATION: A failure injected on purpose by the chaos logic, as opposed
+/// to a genuine error from RPC, signing, or the database. Carries the
+/// `ExecutorError` it imitates, so it drives the same retry/rollback policy.
+///
+/// Returned through `anyhow` so the state machine can tell the two apart
+/// with `downcast_ref`.
+#[derive(Debug, Clone)]
+pub struct InjectedFailure(pub ExecutorError);
+
+impl std::fmt::Display for InjectedFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Simulated {}: {}", self.0.kind(), self.0.message())
+    }
+}
+
+impl std::error::Error for InjectedFailure {}
+
+/// Pipeline stage a failure is attributed to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChaosStage {
+    Verify,
+    Execute,
+    Settle,
+}
+
+impl ChaosStage {
+    pub const ALL: [ChaosStage; 3] = [Self::Verify, Self::Execute, Self::Settle];
+
+    /// Stage that a message in `state` is attempting to leave.
+    pub fn for_state(state: MessageState) -> Option<Self> {
+        match state {
+            MessageState::Persisted => Some(Self::Verify),
+            MessageState::Verified | MessageState::SentToSolana => Some(Self::Execute),
+            MessageState::Executed => Some(Self::Settle),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ChaosStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Verify => write!(f, "verify"),
+            Self::Execute => write!(f, "execute"),
+            Self::Settle => write!(f, "settle"),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct StageCounters {
+    injected: AtomicU64,
+    real: AtomicU64,
+}
+
+/// Per-stage failure counters, split into injected (chaos) and real errors.
+#[derive(Debug, Default)]
+pub struct ChaosStats {
+    verify: StageCounters,
+    execute: StageCounters,
+    settle: StageCounters,
+}
+
+/// Snapshot of one stage's counters for the API.
+#[derive(Debug, Clone, Serialize)]
+pub struct StageFailureStats {
+    pub stage: ChaosStage,
+    pub injected: u64,
+    pub real: u64,
+}
+
+impl ChaosStats {
+    fn counters(&self, stage: ChaosStage) -> &StageCounters {
+        match stage {
+            ChaosStage::Verify => &self.verify,
+            ChaosStage::Execute => &self.execute,
+            ChaosStage::Settle => &self.settle,
+        }
+    }
+
+    /// Record a failure, classifying it by whether it is an `InjectedFailure`.
+    pub fn record(&self, stage: ChaosStage, error: &anyhow::Error) {
+        if error.downcast_ref::<InjectedFailure>().is_some() {
+            self.record_injected(stage);
+        } else {
+            self.record_real(stage);
+        }
+    }
+
+    pub fn record_injected(&self, stage: ChaosStage) {
+        self.counters(stage)
+            .injected
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_real(&self, stage: ChaosStage) {
+        self.counters(stage).real.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> Vec<StageFailureStats> {
+        ChaosStage::ALL
+            .iter()
+            .map(|&stage| {
+                let c = self.counters(stage);
+                StageFailureStats {
+                    stage,
+                    injected: c.injected.load(Ordering::Relaxed),
+                    real: c.real.load(Ordering::Relaxed),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Demo mode preset selectable from POST /control/start-simulation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scenario {
+    /// Baseline pacing and the default ~10% failure injection
+    #[default]
+    Steady,
+    /// High transaction rate, normal failure injection
+    Burst,
+    /// Normal pacing, heavy failure injection to exercise retries and rollbacks
+    Failures,
+    /// High transaction rate and heavy failure injection
+    Stress,
+}
+
+/// Traffic + chaos settings resolved from a `Scenario`.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ScenarioSettings {
+    pub scenario: Scenario,
+    /// Base delay between generated transactions (before backlog throttling)
+    pub tx_interval_ms: u64,
+    /// Chance that each verify/execute/settle attempt fails, in percent
+    pub failure_rate_pct: u32,
+    /// Chance that a retried attempt fails again (and rolls back), in percent
+    pub retry_failure_rate_pct: u32,
+}
+
+impl Scenario {
+    pub fn settings(self) -> ScenarioSettings {
+        let (tx_interval_ms, failure_rate_pct, retry_failure_rate_pct) = match self {
+            Self::Steady => (5_000, 10, 50),
+            Self::Burst => (1_000, 10, 50),
+            Self::Failures => (5_000, 40, 60),
+            Self::Stress => (500, 30, 60),
+        };
+        ScenarioSettings {
+            scenario: self,
+            tx_interval_ms,
+            failure_rate_pct,
+            retry_failure_rate_pct,
+        }
+    }
+}
+
+impl std::fmt::Display for Scenario {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Steady => write!(f, "steady"),
+            Self::Burst => write!(f, "burst"),
+            Self::Failures => write!(f, "failures"),
+            Self::Stress => write!(f, "stress"),
+        }
+    }
+}
+
+impl Default for ScenarioSettings {
+    fn default() -> Self {
+        Scenario::default().settings()
+    }
+}
+
+/// Component fault injected from POST /chaos/fault.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FaultKind {
+    /// Every Ethereum RPC call fails as if the node were unreachable
+    EthereumDown,
+    /// Every Ethereum RPC call is delayed
+    EthereumLatency,
+    /// Every Solana executor call fails with a timeout
+    SolanaDown,
+    /// Every Solana executor call is delayed
+    SolanaLatency,
+    /// The processor task panics on its next pass and is restarted
+    ProcessorCrash,
+}
+
+impl std::fmt::Display for FaultKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EthereumDown => write!(f, "ethereum-down"),
+            Self::EthereumLatency => write!(f, "ethereum-latency"),
+            Self::SolanaDown => write!(f, "solana-down"),
+            Self::SolanaLatency => write!(f, "solana-latency"),
+            Self::ProcessorCrash => write!(f, "processor-crash"),
+        }
+    }
+}
+
+/// Component a fault hook guards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultTarget {
+    Ethereum,
+    Solana,
+}
+
+/// Longest fault POST /chaos/fault accepts (one day).
+pub const MAX_FAULT_SECS: u64 = 86_400;
+
+/// Longest per-call delay a latency fault accepts (one minute). Longer
+/// delays would hold calls past the fault's own end.
+pub const MAX_FAULT_LATENCY_MS: u64 = 60_000;
+
+/// Request body for POST /chaos/fault.
+#[derive(Debug, Deserialize)]
+pub struct FaultRequest {
+    pub fault: FaultKind,
+    /// How long the fault lasts (default 60s, at most `MAX_FAULT_SECS`);
+    /// ignored for a crash
+    pub duration_secs: Option<u64>,
+    /// Delay added to each call by a latency fault (default 5000ms, at most
+    /// `MAX_FAULT_LATENCY_MS`)
+    pub latency_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ActiveFault {
+    pub fault: FaultKind,
+    pub latency_ms: Option<u64>,
+    pub until: DateTime<Utc>,
+}
+
+/// Faults currently injected. Process-wide rather than in `AppState`, so
+/// the stateless RPC helpers in `eth` can consult it.
+pub struct Faults {
+    active: Mutex<Vec<ActiveFault>>,
+    crash_pending: AtomicBool,
+    suspended: AtomicBool,
+}
+
+static FAULTS: Faults = Faults {
+    active: Mutex::new(Vec::new()),
+    crash_pending: AtomicBool::new(false),
+    suspended: AtomicBool::new(false),
+};
+
+/// Chaos held off while alive (see `Faults::suspend`); dropping it lets
+/// chaos resume.
+pub struct Suspension(());
+
+impl Drop for Suspension {
+    fn drop(&mut self) {
+        FAULTS.suspended.store(false, Ordering::Relaxed);
+    }
+}
+
+pub fn faults() -> &'static Faults {
+    &FAULTS
+}
+
+impl Faults {
+    /// Start a fault; a fault of the same kind is replaced.
+    pub fn inject(&self, req: &FaultRequest) -> ActiveFault {
+        let now = Utc::now();
+        if req.fault == FaultKind::ProcessorCrash {
+            self.crash_pending.store(true, Ordering::Relaxed);
+            return ActiveFault {
+                fault: req.fault,
+                latency_ms: None,
+                until: now,
+            };
+        }
+
+        let latency_ms = matches!(req.fault, FaultKind::EthereumLatency | FaultKind::SolanaLatency)
+            .then(|| req.latency_ms.unwrap_or(5_000));
+        let fault = ActiveFault {
+            fault: req.fault,
+            latency_ms,
+            until: now + chrono::Duration::seconds(req.duration_secs.unwrap_or(60) as i64),
+        };
+        let mut active = self.active.lock().unwrap();
+        active.retain(|f| f.fault != req.fault);
+        active.push(fault.clone());
+        fault
+    }
+
+    /// Faults that have not expired yet.
+    pub fn active(&self) -> Vec<ActiveFault> {
+        let mut active = self.active.lock().unwrap();
+        let now = Utc::now();
+        active.retain(|f| f.until > now);
+        active.clone()
+    }
+
+    /// End every fault early.
+    pub fn clear(&self) {
+        self.active.lock().unwrap().clear();
+        self.crash_pending.store(false, Ordering::Relaxed);
+    }
+
+    /// True once per requested crash. A crash requested while chaos is
+    /// suspended waits until it resumes.
+    pub fn take_crash(&self) -> bool {
+        !self.suspended() && self.crash_pending.swap(false, Ordering::Relaxed)
+    }
+
+    /// Hold off every source of chaos until the returned guard drops:
+    /// injected faults, simulated blockhash expiry, validator misbehavior,
+    /// the adversary, and the fee simulation and gas ceiling. Faults keep
+    /// their deadlines and apply again afterwards if still active.
+    pub fn suspend(&self) -> Suspension {
+        self.suspended.store(true, Ordering::Relaxed);
+        Suspension(())
+    }
+
+    pub fn suspended(&self) -> bool {
+        self.suspended.load(Ordering::Relaxed)
+    }
+
+    /// Fault hook: delay a call to `target` by any latency fault, then fail
+    /// it if the component is down.
+    pub async fn check(&self, target: FaultTarget) -> Result<(), ExecutorError> {
+        let (down, latency) = match target {
+            FaultTarget::Ethereum => (FaultKind::EthereumDown, FaultKind::EthereumLatency),
+            FaultTarget::Solana => (FaultKind::SolanaDown, FaultKind::SolanaLatency),
+        };
+        if self.suspended() {
+            return Ok(());
+        }
+        let active = self.active();
+        if let Some(ms) = active.iter().find(|f| f.fault == latency).and_then(|f| f.latency_ms) {
+            tokio::time::sleep(Duration::from_millis(ms)).await;
+        }
+        if active.iter().any(|f| f.fault == down) {
+            return Err(ExecutorError::Timeout(format!("{} (injected fault)", down)));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,158 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::bench::{self, StageLatency};
+use crate::db;
+use crate::types::{AppState, SimulationRun};
+
+/// Query for GET /runs/compare
+#[derive(Debug, Deserialize)]
+pub struct RunCompareQuery {
+    /// Baseline run id
+    pub a: String,
+    /// Run compared against the baseline
+    pub b: String,
+}
+
+/// Metrics of one simulation run, for GET /runs/compare.
+#[derive(Debug, Clone, Serialize)]
+pub struct RunStats {
+    pub run: SimulationRun,
+    pub messages: i64,
+    pub settled: i64,
+    /// Failed, rolled back or refunded
+    pub failed: i64,
+    /// Rolled back at some point, including those refunded since
+    pub rolled_back: i64,
+    /// Not yet in a terminal state
+    pub pending: i64,
+    pub retries: i64,
+    /// First to last event of the run
+    pub active_secs: f64,
+    /// Settled messages per second over `active_secs`
+    pub throughput_per_sec: f64,
+    /// Rolled back messages per message
+    pub rollback_rate: f64,
+    /// Retries per message
+    pub retry_rate: f64,
+    pub latency: Vec<StageLatency>,
+    /// Ethereum fees of the run's transactions, in wei (decimal string)
+    pub fee_wei: String,
+    /// Solana fees of the run's transactions
+    pub fee_lamports: u64,
+    /// `fee_wei` per settled message (decimal string)
+    pub fee_wei_per_settled: String,
+}
+
+/// Change from run `a` to run `b` (b minus a).
+#[derive(Debug, Clone, Serialize)]
+pub struct RunDelta {
+    pub throughput_per_sec: f64,
+    pub rollback_rate: f64,
+    pub retry_rate: f64,
+    pub latency: Vec<LatencyDelta>,
+    /// Signed decimal string
+    pub fee_wei: String,
+    pub fee_lamports: i64,
+    /// Signed decimal string
+    pub fee_wei_per_settled: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencyDelta {
+    pub stage: String,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+}
+
+/// Response for GET /runs/compare
+#[derive(Debug, Serialize)]
+pub struct RunComparison {
+    pub a: RunStats,
+    pub b: RunStats,
+    pub delta: RunDelta,
+}
+
+/// Side-by-side metrics of two runs. None if either run does not exist.
+pub async fn compare_runs(state: &AppState, a: &str, b: &str) -> Result<Option<RunComparison>> {
+    let (Some(a), Some(b)) = (run_stats(state, a).await?, run_stats(state, b).await?) else {
+        return Ok(None);
+    };
+    let delta = RunDelta {
+        throughput_per_sec: b.throughput_per_sec - a.throughput_per_sec,
+        rollback_rate: b.rollback_rate - a.rollback_rate,
+        retry_rate: b.retry_rate - a.retry_rate,
+        latency: a
+            .latency
+            .iter()
+            .zip(&b.latency)
+            .map(|(a, b)| LatencyDelta {
+                stage: a.stage.clone(),
+                p50_ms: b.p50_ms - a.p50_ms,
+                p90_ms: b.p90_ms - a.p90_ms,
+                p99_ms: b.p99_ms - a.p99_ms,
+            })
+            .collect(),
+        fee_wei: wei_delta(&a.fee_wei, &b.fee_wei),
+        fee_lamports: b.fee_lamports as i64 - a.fee_lamports as i64,
+        fee_wei_per_settled: wei_delta(&a.fee_wei_per_settled, &b.fee_wei_per_settled),
+    };
+    Ok(Some(RunComparison { a, b, delta }))
+}
+
+async fn run_stats(state: &AppState, run_id: &str) -> Result<Option<RunStats>> {
+    let Some(run) = db::get_run(&state.pool, run_id).await? else {
+        return Ok(None);
+    };
+    let (messages, settled, failed, pending, retries) = db::get_metrics(&state.pool, Some(run_id)).await?;
+    let rolled_back = db::count_rolled_back(&state.pool, run_id).await?;
+    let events = db::get_events_by_run(&state.pool, run_id).await?;
+
+    let times: Vec<DateTime<Utc>> = events
+        .iter()
+        .filter_map(|e| DateTime::parse_from_rfc3339(&e.timestamp).ok())
+        .map(|t| t.with_timezone(&Utc))
+        .collect();
+    let active_secs = match (times.iter().min(), times.iter().max()) {
+        (Some(first), Some(last)) => (*last - *first).num_milliseconds() as f64 / 1000.0,
+        _ => 0.0,
+    };
+
+    let fee_wei: u128 = events
+        .iter()
+        .filter_map(|e| e.fee_wei.as_deref()?.parse::<u128>().ok())
+        .sum();
+    let fee_lamports: u64 = events.iter().filter_map(|e| e.fee_lamports).sum();
+    let per_message = |count: i64| if messages > 0 { count as f64 / messages as f64 } else { 0.0 };
+
+    Ok(Some(RunStats {
+        run,
+        messages,
+        settled,
+        failed,
+        rolled_back,
+        pending,
+        retries,
+        active_secs,
+        throughput_per_sec: if active_secs > 0.0 { settled as f64 / active_secs } else { 0.0 },
+        rollback_rate: per_message(rolled_back),
+        retry_rate: per_message(retries),
+        latency: bench::stage_latencies(&bench::steps_reached(&events)),
+        fee_wei: fee_wei.to_string(),
+        fee_lamports,
+        fee_wei_per_settled: fee_wei.checked_div(settled as u128).unwrap_or(0).to_string(),
+    }))
+}
+
+/// `b - a` of two wei amounts, as a signed decimal string.
+fn wei_delta(a: &str, b: &str) -> String {
+    let a: u128 = a.parse().unwrap_or(0);
+    let b: u128 = b.parse().unwrap_or(0);
+    if b >= a {
+        (b - a).to_string()
+    } else {
+        format!("-{}", a - b)
+    }
+}
@@ -1,4 +1,30 @@
+use anyhow::{bail, Context, Result};
+use ethers::signers::LocalWallet;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 use std::env;
+use tracing::warn;
+
+use crate::keyring::{KeyPurpose, Keyring};
+use crate::s3::S3Bucket;
+use crate::ws_auth::{UnauthenticatedWs, WsRole};
+
+/// Anvil default account #0 private key
+const ANVIL_RELAYER_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+/// Command-line flag to prompt for the keystore passphrase on the terminal.
+pub const UNLOCK_FLAG: &str = "--unlock";
+
+/// A private key or shared secret. Its Debug output is redacted, so the
+/// values never reach the logs when `Config` is logged at startup.
+#[derive(Clone)]
+pub struct Secret(pub String);
+
+impl std::fmt::Debug for Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<redacted>")
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -6,13 +32,129 @@ pub struct Config {
     pub database_url: String,
     pub http_port: u16,
     pub escrow_address: String,
-    pub relayer_private_key: String,
+    /// Signing keys by purpose, falling back to the relayer key
+    pub keyring: Keyring,
     pub poll_interval_ms: u64,
+    /// Pending-message count at which the embedded traffic generator pauses
+    /// (it starts slowing down at half this value). 0 disables throttling.
+    pub traffic_backlog_threshold: i64,
+    /// Codec of the embedded traffic generator's payloads
+    pub traffic_payload_codec: payload::Codec,
+    /// Hours between scheduled fleet analysis reports (0 = disabled)
+    pub fleet_report_interval_hours: u64,
+    /// Default look-back window for fleet reports, in hours
+    pub fleet_report_window_hours: i64,
+    /// AI analyses (transaction or fleet) allowed to run at once; others wait
+    pub analysis_concurrency: usize,
+    /// Unfinished `?queued=true` analyses allowed at once; more get 503
+    pub analysis_max_queued: usize,
+    /// OpenAI requests per minute allowed per API key (0 = unlimited)
+    pub openai_requests_per_minute: usize,
+    /// Retries of an OpenAI request answered with 429 or a 5xx
+    pub openai_max_retries: u32,
+    /// Keys allowed to sign proof bundles (defaults to the proof-validator key alone)
+    pub validator_keys: Vec<Secret>,
+    /// SIMULATION: index into `validator_keys` of a validator that occasionally
+    /// signs an incorrect event root
+    pub misbehaving_validator: Option<usize>,
+    /// Probability that the misbehaving validator signs a bad root when selected
+    pub validator_misbehavior_rate: f64,
+    /// Suspend a validator as soon as it is caught misbehaving
+    pub validator_auto_suspend: bool,
+    /// Number of valid validator signatures a proof needs; always 1 while
+    /// proof bundles carry a single signature
+    pub validator_quorum: usize,
+    /// Escrow event ABI fragment, human-readable or JSON (defaults to `eth::DEFAULT_EVENT_ABI`)
+    pub escrow_event_abi: String,
+    /// Optional `field=param,...` mapping from event parameters to message fields
+    pub escrow_event_fields: Option<String>,
+    /// Optional gas oracle returning `{"base_fee_gwei", "priority_fee_gwei"}`;
+    /// without it gas is read from `eth_feeHistory`
+    pub gas_oracle_url: Option<String>,
+    /// Settlement fee cap = base fee × this + priority fee
+    pub gas_base_fee_multiplier: f64,
+    /// Seconds between gas samples for GET /gas (0 = disabled)
+    pub gas_sample_interval_secs: u64,
+    /// Seconds between escrow balance snapshots for GET /escrow/balance (0 = disabled)
+    pub escrow_sample_interval_secs: u64,
+    /// Simulated fee market replacing the oracle and RPC readings (unset = real fees)
+    pub gas_simulation: Option<crate::gas::FeeProfile>,
+    /// Base fee the simulated market reverts to, in gwei
+    pub gas_sim_base_gwei: f64,
+    /// Settlements wait while the fee cap is above this, in gwei (unset = no ceiling)
+    pub gas_max_fee_gwei: Option<f64>,
+    /// Solana executor: in-process simulation or the reference program over RPC
+    pub solana_mode: crate::solana::SolanaMode,
+    pub solana_rpc_url: String,
+    /// Deployed `cross-chain-executor` program id (required for `rpc` mode)
+    pub solana_program_id: Option<String>,
+    /// Fee payer keypair in Solana CLI JSON format
+    pub solana_keypair_path: String,
+    /// Compute-unit price attached to Solana executions (0 = none)
+    pub solana_priority_fee_micro_lamports: u64,
+    /// How long to wait for a Solana execution to confirm before retrying
+    pub solana_confirm_timeout_ms: u64,
+    /// Cluster behaviour modelled by the simulated executor
+    pub solana_sim: crate::solana_sim::SimModel,
+    /// SHA-256 of `ADMIN_TOKEN`, the bearer token for admin endpoints.
+    /// Only the hash is kept so the token never shows up in logs.
+    /// Unset disables admin endpoints.
+    pub admin_token_sha256: Option<[u8; 32]>,
+    /// Most recent escrow messages compared with on-chain state at startup (0 = no check)
+    pub integrity_check_limit: i64,
+    /// Let the startup check rewrite states that on-chain evidence contradicts
+    pub integrity_auto_correct: bool,
+    /// Shared secret for HMAC-signing streamed events (unset = unsigned)
+    pub event_signing_key: Option<crate::signing::EventSigningKey>,
+    /// Newest messages (with their events) kept in memory for hot reads (0 = no cache)
+    pub cache_recent_messages: usize,
+    /// Seconds between simulated attacks on the relayer and programs (0 = disabled)
+    pub adversary_interval_secs: u64,
+    /// Seconds between scans for expired escrows to refund (0 = disabled)
+    pub refund_interval_secs: u64,
+    /// Milliseconds lifecycle events are buffered before being written in one
+    /// transaction (0 = write each event as it is emitted)
+    pub event_write_interval_ms: u64,
+    /// Most events written per transaction
+    pub event_write_batch_size: usize,
+    /// Directory Parquet exports are written to (unless `export_s3` is set)
+    pub export_dir: String,
+    /// Hours between scheduled Parquet exports (0 = on demand only)
+    pub export_interval_hours: u64,
+    /// S3-compatible bucket for Parquet exports, from `EXPORT_S3_BUCKET`
+    pub export_s3: Option<S3Bucket>,
+    /// SHA-256 of each `WS_API_KEYS` key with its role; keys that may mint
+    /// `/ws` tokens besides `ADMIN_TOKEN`
+    pub ws_api_keys: Vec<(WsRole, [u8; 32])>,
+    /// Lifetime of minted `/ws` tokens, in seconds
+    pub ws_token_ttl_secs: i64,
+    /// What `/ws` connections without a token are sent
+    pub ws_unauthenticated: UnauthenticatedWs,
+    /// Proxies whose `X-Forwarded-For` is believed for `/ws` client addresses
+    pub trusted_proxies: Vec<std::net::IpAddr>,
+    /// Contract daily roots are sent to as `notarize(uint32,bytes32)`; without
+    /// one, the relayer sends the calldata to its own address
+    pub notary_address: Option<String>,
+    /// Seconds between checks for completed days to notarize (0 = on demand only)
+    pub notary_interval_secs: u64,
 }
 
 impl Config {
-    pub fn from_env() -> Self {
-        Self {
+    pub fn from_env() -> Result<Self> {
+        let keyring = Keyring::new(relayer_key_from_env()?, signer_keys_from_env()?)?;
+        let validator_keys = env::var("VALIDATOR_PRIVATE_KEYS")
+            .ok()
+            .map(|v| {
+                v.split(',')
+                    .map(|k| k.trim())
+                    .filter(|k| !k.is_empty())
+                    .map(|k| Secret(k.to_string()))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|keys| !keys.is_empty())
+            .unwrap_or_else(|| vec![keyring.key(KeyPurpose::ProofValidator).clone()]);
+
+        Ok(Self {
             eth_rpc_url: env::var("ETH_RPC_URL")
                 .unwrap_or_else(|_| "http://127.0.0.1:8545".into()),
             database_url: env::var("DATABASE_URL")
@@ -23,14 +165,309 @@ impl Config {
                 .unwrap_or(3001),
             escrow_address: env::var("ESCROW_ADDRESS")
                 .unwrap_or_else(|_| "0x5FbDB2315678afecb367f032d93F642f64180aa3".into()),
-            // Anvil default account #0 private key
-            relayer_private_key: env::var("RELAYER_PRIVATE_KEY").unwrap_or_else(|_| {
-                "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80".into()
-            }),
+            keyring,
             poll_interval_ms: env::var("POLL_INTERVAL_MS")
                 .ok()
                 .and_then(|p| p.parse().ok())
                 .unwrap_or(500),
+            traffic_backlog_threshold: env::var("TRAFFIC_BACKLOG_THRESHOLD")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(100),
+            traffic_payload_codec: env::var("TRAFFIC_PAYLOAD_CODEC")
+                .ok()
+                .and_then(|c| c.parse().ok())
+                .unwrap_or_default(),
+            fleet_report_interval_hours: env::var("FLEET_REPORT_INTERVAL_HOURS")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(6),
+            fleet_report_window_hours: env::var("FLEET_REPORT_WINDOW_HOURS")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(24),
+            analysis_concurrency: env::var("ANALYSIS_CONCURRENCY")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(2),
+            analysis_max_queued: env::var("ANALYSIS_MAX_QUEUED")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(100),
+            openai_requests_per_minute: env::var("OPENAI_REQUESTS_PER_MINUTE")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(60),
+            openai_max_retries: env::var("OPENAI_MAX_RETRIES")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(4),
+            validator_keys,
+            misbehaving_validator: env::var("MISBEHAVING_VALIDATOR")
+                .ok()
+                .and_then(|p| p.parse().ok()),
+            validator_misbehavior_rate: env::var("VALIDATOR_MISBEHAVIOR_RATE")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(0.2),
+            validator_auto_suspend: env::var("VALIDATOR_AUTO_SUSPEND")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(true),
+            validator_quorum: validator_quorum_from_env()?,
+            escrow_event_abi: env::var("ESCROW_EVENT_ABI")
+                .unwrap_or_else(|_| crate::eth::DEFAULT_EVENT_ABI.into()),
+            escrow_event_fields: env::var("ESCROW_EVENT_FIELDS").ok(),
+            gas_oracle_url: env::var("GAS_ORACLE_URL").ok().filter(|u| !u.is_empty()),
+            gas_base_fee_multiplier: env::var("GAS_BASE_FEE_MULTIPLIER")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(2.0),
+            gas_sample_interval_secs: env::var("GAS_SAMPLE_INTERVAL_SECS")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(15),
+            escrow_sample_interval_secs: env::var("ESCROW_SAMPLE_INTERVAL_SECS")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(60),
+            gas_simulation: env::var("GAS_SIMULATION")
+                .ok()
+                .filter(|p| !p.is_empty())
+                .map(|p| p.parse())
+                .transpose()?,
+            gas_sim_base_gwei: env::var("GAS_SIM_BASE_GWEI")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(20.0),
+            gas_max_fee_gwei: env::var("GAS_MAX_FEE_GWEI").ok().and_then(|p| p.parse().ok()),
+            solana_mode: env::var("SOLANA_MODE")
+                .ok()
+                .map(|m| m.parse().unwrap_or_else(|e| panic!("{}", e)))
+                .unwrap_or_default(),
+            solana_rpc_url: env::var("SOLANA_RPC_URL")
+                .unwrap_or_else(|_| "http://127.0.0.1:8899".into()),
+            solana_program_id: env::var("SOLANA_PROGRAM_ID").ok(),
+            solana_keypair_path: env::var("SOLANA_KEYPAIR_PATH").unwrap_or_else(|_| {
+                format!("{}/.config/solana/id.json", env::var("HOME").unwrap_or_default())
+            }),
+            solana_priority_fee_micro_lamports: env::var("SOLANA_PRIORITY_FEE_MICRO_LAMPORTS")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(0),
+            solana_confirm_timeout_ms: env::var("SOLANA_CONFIRM_TIMEOUT_MS")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(10_000),
+            solana_sim: crate::solana_sim::SimModel {
+                latency: env::var("SOLANA_SIM_LATENCY")
+                    .ok()
+                    .map(|l| l.parse().unwrap_or_else(|e| panic!("SOLANA_SIM_LATENCY: {}", e)))
+                    .unwrap_or(crate::solana_sim::LatencyDistribution::Fixed(0.0)),
+                blockhash_expiry_rate: env::var("SOLANA_SIM_BLOCKHASH_EXPIRY_RATE")
+                    .ok()
+                    .and_then(|p| p.parse().ok())
+                    .unwrap_or(0.0),
+                congestion: env::var("SOLANA_SIM_CONGESTION")
+                    .ok()
+                    .and_then(|p| p.parse().ok())
+                    .unwrap_or(0.0),
+                slot_ms: env::var("SOLANA_SIM_SLOT_MS")
+                    .ok()
+                    .and_then(|p| p.parse().ok())
+                    .unwrap_or(400),
+            },
+            admin_token_sha256: env::var("ADMIN_TOKEN")
+                .ok()
+                .filter(|t| !t.is_empty())
+                .map(|t| Sha256::digest(t.as_bytes()).into()),
+            integrity_check_limit: env::var("INTEGRITY_CHECK_LIMIT")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(1000),
+            integrity_auto_correct: env::var("INTEGRITY_AUTO_CORRECT")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(false),
+            event_signing_key: env::var("EVENT_SIGNING_KEY")
+                .ok()
+                .filter(|k| !k.is_empty())
+                .map(|k| crate::signing::EventSigningKey::new(&k)),
+            cache_recent_messages: env::var("CACHE_RECENT_MESSAGES")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(1000),
+            adversary_interval_secs: env::var("ADVERSARY_INTERVAL_SECS")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(0),
+            refund_interval_secs: env::var("REFUND_INTERVAL_SECS")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(30),
+            event_write_interval_ms: env::var("EVENT_WRITE_INTERVAL_MS")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(50),
+            event_write_batch_size: env::var("EVENT_WRITE_BATCH_SIZE")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(500),
+            export_dir: env::var("EXPORT_DIR").unwrap_or_else(|_| "exports".into()),
+            export_interval_hours: env::var("EXPORT_INTERVAL_HOURS")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(0),
+            export_s3: export_s3_from_env()?,
+            ws_api_keys: ws_api_keys_from_env()?,
+            ws_token_ttl_secs: env::var("WS_TOKEN_TTL_SECS")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(300),
+            ws_unauthenticated: env::var("WS_UNAUTHENTICATED")
+                .ok()
+                .filter(|p| !p.is_empty())
+                .map(|p| p.parse())
+                .transpose()?
+                .unwrap_or(UnauthenticatedWs::Full),
+            trusted_proxies: trusted_proxies_from_env()?,
+            notary_address: env::var("NOTARY_ADDRESS").ok().filter(|a| !a.is_empty()),
+            notary_interval_secs: env::var("NOTARY_INTERVAL_SECS")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(3600),
+        })
+    }
+}
+
+/// `VALIDATOR_QUORUM`, default 1. Proof bundles carry one signature, so a
+/// larger quorum could never be met and 0 would accept unsigned proofs.
+fn validator_quorum_from_env() -> Result<usize> {
+    let Some(quorum) = env::var("VALIDATOR_QUORUM").ok().filter(|q| !q.is_empty()) else {
+        return Ok(1);
+    };
+    match quorum.parse::<usize>() {
+        Ok(1) => Ok(1),
+        Ok(_) => bail!("VALIDATOR_QUORUM must be 1 while proofs carry a single signature, got {}", quorum),
+        Err(e) => bail!("invalid VALIDATOR_QUORUM {:?}: {}", quorum, e),
+    }
+}
+
+/// Export bucket from `EXPORT_S3_BUCKET`, with `EXPORT_S3_ENDPOINT`,
+/// `EXPORT_S3_REGION`, `EXPORT_S3_PREFIX` and the usual AWS credential
+/// variables.
+fn export_s3_from_env() -> Result<Option<S3Bucket>> {
+    let Some(bucket) = env::var("EXPORT_S3_BUCKET").ok().filter(|b| !b.is_empty()) else {
+        return Ok(None);
+    };
+    let (Ok(access_key_id), Ok(secret_access_key)) = (env::var("AWS_ACCESS_KEY_ID"), env::var("AWS_SECRET_ACCESS_KEY"))
+    else {
+        bail!("EXPORT_S3_BUCKET needs AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY");
+    };
+    let mut prefix = env::var("EXPORT_S3_PREFIX").unwrap_or_default();
+    if !prefix.is_empty() && !prefix.ends_with('/') {
+        prefix.push('/');
+    }
+    Ok(Some(S3Bucket {
+        endpoint: env::var("EXPORT_S3_ENDPOINT").unwrap_or_else(|_| "https://s3.amazonaws.com".into()),
+        bucket,
+        region: env::var("EXPORT_S3_REGION").unwrap_or_else(|_| "us-east-1".into()),
+        prefix,
+        access_key_id,
+        secret_access_key: Secret(secret_access_key),
+    }))
+}
+
+/// The relayer key: decrypted from the `RELAYER_KEYSTORE` file, or taken in
+/// plaintext from `RELAYER_PRIVATE_KEY`, or the Anvil default.
+fn relayer_key_from_env() -> Result<Secret> {
+    let plaintext = env::var("RELAYER_PRIVATE_KEY").ok().filter(|k| !k.is_empty());
+    let Some(path) = env::var("RELAYER_KEYSTORE").ok().filter(|p| !p.is_empty()) else {
+        let key = plaintext.unwrap_or_else(|| ANVIL_RELAYER_KEY.into());
+        if key.trim_start_matches("0x") != ANVIL_RELAYER_KEY {
+            warn!("RELAYER_PRIVATE_KEY holds a plaintext key; use RELAYER_KEYSTORE outside local Anvil");
+        }
+        return Ok(Secret(key));
+    };
+    if plaintext.is_some() {
+        bail!("Set either RELAYER_KEYSTORE or RELAYER_PRIVATE_KEY, not both");
+    }
+
+    let passphrase = keystore_passphrase(&path)?;
+    let wallet = LocalWallet::decrypt_keystore(&path, passphrase)
+        .with_context(|| format!("Failed to unlock keystore {}", path))?;
+    Ok(Secret(hex::encode(wallet.signer().to_bytes())))
+}
+
+/// Dedicated keys from `SIGNER_KEYS`, e.g.
+/// `proof-validator=0x..,evm-chain-30110=0x..`.
+fn signer_keys_from_env() -> Result<BTreeMap<KeyPurpose, Secret>> {
+    let mut keys = BTreeMap::new();
+    let Some(entries) = env::var("SIGNER_KEYS").ok().filter(|v| !v.is_empty()) else {
+        return Ok(keys);
+    };
+    for entry in entries.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let (purpose, key) = entry
+            .split_once('=')
+            .context("SIGNER_KEYS entries must be <purpose>=<private key>")?;
+        let purpose: KeyPurpose = purpose.trim().parse().context("invalid SIGNER_KEYS entry")?;
+        if keys.insert(purpose, Secret(key.trim().to_string())).is_some() {
+            bail!("SIGNER_KEYS sets {} twice", purpose);
         }
     }
+    Ok(keys)
+}
+
+/// `TRUSTED_PROXIES`, comma-separated IP addresses. Empty by default, so
+/// `X-Forwarded-For` is ignored unless a proxy is named.
+fn trusted_proxies_from_env() -> Result<Vec<std::net::IpAddr>> {
+    let Some(entries) = env::var("TRUSTED_PROXIES").ok().filter(|v| !v.is_empty()) else {
+        return Ok(Vec::new());
+    };
+    entries
+        .split(',')
+        .map(str::trim)
+        .filter(|e| !e.is_empty())
+        .map(|ip| ip.parse().with_context(|| format!("invalid TRUSTED_PROXIES entry {}", ip)))
+        .collect()
+}
+
+/// API keys from `WS_API_KEYS`, e.g. `operator=<key>,viewer=<key>`. Only
+/// their hashes are kept, as for `ADMIN_TOKEN`.
+fn ws_api_keys_from_env() -> Result<Vec<(WsRole, [u8; 32])>> {
+    let Some(entries) = env::var("WS_API_KEYS").ok().filter(|v| !v.is_empty()) else {
+        return Ok(Vec::new());
+    };
+    entries
+        .split(',')
+        .map(str::trim)
+        .filter(|e| !e.is_empty())
+        .map(|entry| {
+            let (role, key) = entry.split_once('=').context("WS_API_KEYS entries must be <role>=<key>")?;
+            let role: WsRole = role.trim().parse().context("invalid WS_API_KEYS entry")?;
+            Ok((role, Sha256::digest(key.trim().as_bytes()).into()))
+        })
+        .collect()
+}
+
+/// Passphrase for the relayer keystore: prompted for with `--unlock`, else
+/// read from `RELAYER_KEYSTORE_PASSWORD_FILE` (e.g. a mounted secret) or
+/// `RELAYER_KEYSTORE_PASSWORD`.
+fn keystore_passphrase(path: &str) -> Result<String> {
+    if env::args().skip(1).any(|arg| arg == UNLOCK_FLAG) {
+        return rpassword::prompt_password(format!("Passphrase for {}: ", path))
+            .context("Failed to read the keystore passphrase");
+    }
+    if let Some(file) = env::var("RELAYER_KEYSTORE_PASSWORD_FILE").ok().filter(|f| !f.is_empty()) {
+        let contents = std::fs::read_to_string(&file)
+            .with_context(|| format!("Failed to read RELAYER_KEYSTORE_PASSWORD_FILE {}", file))?;
+        return Ok(contents.trim_end_matches(['\r', '\n']).to_string());
+    }
+    env::var("RELAYER_KEYSTORE_PASSWORD").map_err(|_| {
+        anyhow::anyhow!(
+            "RELAYER_KEYSTORE needs a passphrase: set RELAYER_KEYSTORE_PASSWORD_FILE or \
+             RELAYER_KEYSTORE_PASSWORD, or start with {}",
+            UNLOCK_FLAG
+        )
+    })
 }
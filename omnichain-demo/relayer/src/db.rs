@@ -1,8 +1,19 @@
 use anyhow::Result;
+use futures::TryStreamExt;
 use sqlx::sqlite::SqlitePoolOptions;
 use sqlx::SqlitePool;
+use std::collections::BTreeMap;
+use tracing::warn;
 
-use crate::types::{CrossChainMessage, MessageState};
+use crate::envelope::{MessageEnvelope, ETHEREUM_CHAIN_ID, SOLANA_CHAIN_ID};
+use crate::escrow::EscrowSnapshot;
+use crate::gas::GasReading;
+use crate::payload_schema::{PayloadSchema, PayloadSchemaRequest};
+use crate::routing::{Computation, RoutingRule, RoutingRuleRequest};
+use crate::types::{
+    ChainConfig, ChainKind, CrossChainMessage, DailyAnalysisCost, DailyStats, FailureReason, FleetReport, FleetSummary, LatencyBucket, MessageState,
+    SenderIssues, SimulationRun, StateTransition, TraceConflict,
+};
 
 /// Initialize the SQLite database and run migrations.
 pub async fn init_db(database_url: &str) -> Result<SqlitePool> {
@@ -26,9 +37,16 @@ pub async fn init_db(database_url: &str) -> Result<SqlitePool> {
             result          TEXT,
             solana_signature TEXT,
             eth_settle_tx   TEXT,
+            refund_tx       TEXT,
             proof_json      TEXT,
             retry_count     INTEGER NOT NULL DEFAULT 0,
             error_message   TEXT,
+            run_id          TEXT,
+            src_chain_id    INTEGER NOT NULL DEFAULT 30101,
+            dst_chain_id    INTEGER NOT NULL DEFAULT 30168,
+            payload_type    INTEGER NOT NULL DEFAULT 0,
+            computation     TEXT NOT NULL DEFAULT 'double',
+            behavior_json   TEXT,
             created_at      TEXT NOT NULL DEFAULT (datetime('now')),
             updated_at      TEXT NOT NULL DEFAULT (datetime('now'))
         )
@@ -48,6 +66,14 @@ pub async fn init_db(database_url: &str) -> Result<SqlitePool> {
             status      TEXT NOT NULL,
             detail      TEXT,
             timestamp   TEXT NOT NULL,
+            run_id      TEXT,
+            src_chain_id INTEGER,
+            dst_chain_id INTEGER,
+            schema_version INTEGER,
+            fee_wei     TEXT,
+            fee_lamports INTEGER,
+            gas_used    INTEGER,
+            actor_chain_id INTEGER,
             created_at  TEXT NOT NULL DEFAULT (datetime('now'))
         )
         "#,
@@ -55,294 +81,2793 @@ pub async fn init_db(database_url: &str) -> Result<SqlitePool> {
     .execute(&pool)
     .await?;
 
+    // Databases created before runs existed lack the run_id columns
+    add_column_if_missing(&pool, "messages", "run_id", "TEXT").await?;
+    add_column_if_missing(&pool, "events", "run_id", "TEXT").await?;
+
+    // Messages predating the envelope were all Ethereum → Solana
+    add_column_if_missing(&pool, "messages", "src_chain_id", "INTEGER NOT NULL DEFAULT 30101").await?;
+    add_column_if_missing(&pool, "messages", "dst_chain_id", "INTEGER NOT NULL DEFAULT 30168").await?;
+    add_column_if_missing(&pool, "events", "src_chain_id", "INTEGER").await?;
+    add_column_if_missing(&pool, "events", "dst_chain_id", "INTEGER").await?;
+    // NULL for events written before versioning (read back as version 1)
+    add_column_if_missing(&pool, "events", "schema_version", "INTEGER").await?;
+    add_column_if_missing(&pool, "events", "fee_wei", "TEXT").await?;
+    add_column_if_missing(&pool, "events", "fee_lamports", "INTEGER").await?;
+    add_column_if_missing(&pool, "events", "gas_used", "INTEGER").await?;
+    // NULL for the relayer, and for chain events written before chain actors
+    add_column_if_missing(&pool, "events", "actor_chain_id", "INTEGER").await?;
+    add_column_if_missing(&pool, "messages", "payload_type", "INTEGER NOT NULL DEFAULT 0").await?;
+    add_column_if_missing(&pool, "messages", "computation", "TEXT NOT NULL DEFAULT 'double'").await?;
+    add_column_if_missing(&pool, "messages", "behavior_json", "TEXT").await?;
+    add_column_if_missing(&pool, "messages", "refund_tx", "TEXT").await?;
+    // Why the refunder left an expired escrow alone; such messages are not checked again
+    add_column_if_missing(&pool, "messages", "refund_skipped", "TEXT").await?;
+    // Settlement receipt; NULL until settled, and for settlements without one
+    add_column_if_missing(&pool, "messages", "settle_gas_used", "INTEGER").await?;
+    add_column_if_missing(&pool, "messages", "settle_gas_price_wei", "TEXT").await?;
+    add_column_if_missing(&pool, "messages", "settle_block_number", "INTEGER").await?;
+    add_column_if_missing(&pool, "messages", "settle_cost_wei", "TEXT").await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS routing_rules (
+            id                  INTEGER PRIMARY KEY AUTOINCREMENT,
+            priority            INTEGER NOT NULL DEFAULT 100,
+            payload_type        INTEGER,
+            header_dst_chain_id INTEGER,
+            dst_chain_id        INTEGER NOT NULL,
+            computation         TEXT NOT NULL DEFAULT 'double',
+            enabled             INTEGER NOT NULL DEFAULT 1,
+            created_at          TEXT NOT NULL DEFAULT (datetime('now'))
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS runs (
+            run_id      TEXT PRIMARY KEY,
+            scenario    TEXT NOT NULL,
+            parameters  TEXT NOT NULL,
+            started_at  TEXT NOT NULL DEFAULT (datetime('now')),
+            ended_at    TEXT
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS fleet_reports (
+            id              INTEGER PRIMARY KEY AUTOINCREMENT,
+            window_hours    INTEGER NOT NULL,
+            summary_json    TEXT NOT NULL,
+            report          TEXT NOT NULL,
+            model           TEXT NOT NULL,
+            created_at      TEXT NOT NULL DEFAULT (datetime('now'))
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS incident_embeddings (
+            nonce           INTEGER PRIMARY KEY,
+            summary         TEXT NOT NULL,
+            model           TEXT NOT NULL,
+            embedding       BLOB NOT NULL,
+            created_at      TEXT NOT NULL DEFAULT (datetime('now'))
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS analysis_costs (
+            id                  INTEGER PRIMARY KEY AUTOINCREMENT,
+            model               TEXT NOT NULL,
+            kind                TEXT NOT NULL,
+            prompt_tokens       INTEGER NOT NULL,
+            completion_tokens   INTEGER NOT NULL,
+            cost_usd            REAL NOT NULL,
+            created_at          TEXT NOT NULL DEFAULT (datetime('now'))
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS chains (
+            chain_id        INTEGER PRIMARY KEY,
+            name            TEXT NOT NULL,
+            kind            TEXT NOT NULL,
+            rpc_url         TEXT,
+            executor_json   TEXT NOT NULL DEFAULT 'null',
+            enabled         INTEGER NOT NULL DEFAULT 1,
+            created_at      TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at      TEXT NOT NULL DEFAULT (datetime('now'))
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS gas_readings (
+            id                  INTEGER PRIMARY KEY AUTOINCREMENT,
+            source              TEXT NOT NULL,
+            base_fee_gwei       REAL NOT NULL,
+            priority_fee_gwei   REAL NOT NULL,
+            max_fee_gwei        REAL NOT NULL,
+            block_number        INTEGER,
+            nonce               INTEGER,
+            observed_at         TEXT NOT NULL
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS escrow_snapshots (
+            id                  INTEGER PRIMARY KEY AUTOINCREMENT,
+            balance_wei         TEXT NOT NULL,
+            locked_wei          TEXT NOT NULL,
+            locked_escrows      INTEGER NOT NULL,
+            unread_escrows      INTEGER NOT NULL DEFAULT 0,
+            db_pending_wei      TEXT NOT NULL,
+            db_pending_messages INTEGER NOT NULL,
+            block_number        INTEGER,
+            observed_at         TEXT NOT NULL
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+    add_column_if_missing(&pool, "escrow_snapshots", "unread_escrows", "INTEGER NOT NULL DEFAULT 0").await?;
+
+    // Named payload layouts for GET /transactions/:nonce/decoded-payload
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS payload_schemas (
+            id           INTEGER PRIMARY KEY AUTOINCREMENT,
+            name         TEXT NOT NULL UNIQUE,
+            payload_type INTEGER,
+            fields_json  TEXT NOT NULL,
+            created_at   TEXT NOT NULL DEFAULT (datetime('now'))
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+
+    // Completed analytics exports; the latest per destination is the cursor
+    // of the next one
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS exports (
+            id                 INTEGER PRIMARY KEY AUTOINCREMENT,
+            destination        TEXT NOT NULL,
+            last_seq           INTEGER NOT NULL,
+            messages_watermark TEXT,
+            summary_json       TEXT NOT NULL,
+            created_at         TEXT NOT NULL DEFAULT (datetime('now'))
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+
+    // Escrow logs as received, for reparsing
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS raw_logs (
+            nonce        INTEGER PRIMARY KEY,
+            block_number INTEGER,
+            block_hash   TEXT,
+            tx_hash      TEXT,
+            log_index    INTEGER,
+            log_json     TEXT NOT NULL,
+            created_at   TEXT NOT NULL DEFAULT (datetime('now'))
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+
+    // Messages rejected for reusing a stored message's trace id
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS trace_conflicts (
+            id              INTEGER PRIMARY KEY AUTOINCREMENT,
+            trace_id        TEXT NOT NULL,
+            nonce           INTEGER NOT NULL UNIQUE,
+            existing_nonce  INTEGER NOT NULL,
+            sender          TEXT NOT NULL,
+            amount          TEXT NOT NULL,
+            tx_hash         TEXT,
+            block_number    INTEGER,
+            rejected        INTEGER NOT NULL DEFAULT 1,
+            detected_at     TEXT NOT NULL DEFAULT (datetime('now'))
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+
+    // Append-only history of `messages.state`, written in the same
+    // transaction as each change
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS state_transitions (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            nonce       INTEGER NOT NULL,
+            from_state  TEXT,
+            to_state    TEXT NOT NULL,
+            reason      TEXT NOT NULL,
+            applied     INTEGER NOT NULL DEFAULT 1,
+            at          TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+    // 0 for an update that lost a race: the message had left the expected state
+    add_column_if_missing(&pool, "state_transitions", "applied", "INTEGER NOT NULL DEFAULT 1").await?;
+
+    // Admin actions; kept across clear-data
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS audit_log (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            action      TEXT NOT NULL,
+            target      TEXT NOT NULL,
+            detail      TEXT NOT NULL,
+            created_at  TEXT NOT NULL DEFAULT (datetime('now'))
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+
+    // Daily Merkle roots and their Ethereum anchors; kept across clear-data
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS notarizations (
+            id             INTEGER PRIMARY KEY AUTOINCREMENT,
+            day            TEXT NOT NULL UNIQUE,
+            messages       INTEGER NOT NULL,
+            events         INTEGER NOT NULL,
+            messages_root  TEXT NOT NULL,
+            events_root    TEXT NOT NULL,
+            root           TEXT NOT NULL,
+            anchor_tx      TEXT,
+            anchor_block   INTEGER,
+            anchor_error   TEXT,
+            created_at     TEXT NOT NULL DEFAULT (datetime('now')),
+            anchored_at    TEXT
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+
     sqlx::query(
         "CREATE INDEX IF NOT EXISTS idx_messages_state ON messages(state)",
     )
     .execute(&pool)
     .await?;
 
-    sqlx::query(
-        "CREATE INDEX IF NOT EXISTS idx_events_nonce ON events(nonce)",
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_events_nonce ON events(nonce)",
+    )
+    .execute(&pool)
+    .await?;
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_state_transitions_nonce ON state_transitions(nonce)",
+    )
+    .execute(&pool)
+    .await?;
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_messages_run ON messages(run_id)",
+    )
+    .execute(&pool)
+    .await?;
+
+    // `messages.nonce` is unique on its own, so an envelope index added nothing
+    sqlx::query("DROP INDEX IF EXISTS idx_messages_envelope")
+        .execute(&pool)
+        .await?;
+
+    // Databases from before trace ids were unique may hold duplicates; the
+    // index is created once they have been purged
+    let shared = record_stored_duplicates(&pool).await?;
+    if shared == 0 {
+        sqlx::query("CREATE UNIQUE INDEX IF NOT EXISTS idx_messages_trace ON messages(trace_id)")
+            .execute(&pool)
+            .await?;
+    } else {
+        warn!(
+            trace_ids = shared,
+            "Stored messages share trace ids; uniqueness is not enforced until they are purged (see GET /duplicates)"
+        );
+    }
+
+    Ok(pool)
+}
+
+/// Record stored messages that reuse an earlier message's trace id as
+/// conflicts; returns how many trace ids are shared.
+async fn record_stored_duplicates(pool: &SqlitePool) -> Result<i64> {
+    let shared: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM (SELECT 1 FROM messages GROUP BY trace_id HAVING COUNT(*) > 1)",
+    )
+    .fetch_one(pool)
+    .await?;
+    if shared == 0 {
+        return Ok(0);
+    }
+
+    sqlx::query(
+        r#"
+        INSERT OR IGNORE INTO trace_conflicts (trace_id, nonce, existing_nonce, sender, amount, rejected)
+        SELECT m.trace_id, m.nonce, d.first_nonce, m.sender, m.amount, 0
+        FROM messages m
+        JOIN (
+            SELECT trace_id, MIN(nonce) AS first_nonce FROM messages GROUP BY trace_id HAVING COUNT(*) > 1
+        ) d ON d.trace_id = m.trace_id
+        WHERE m.nonce > d.first_nonce
+        "#,
+    )
+    .execute(pool)
+    .await?;
+    Ok(shared)
+}
+
+/// `ALTER TABLE ... ADD COLUMN` unless the column already exists.
+async fn add_column_if_missing(
+    pool: &SqlitePool,
+    table: &str,
+    column: &str,
+    decl: &str,
+) -> Result<()> {
+    let exists: i64 = sqlx::query_scalar(&format!(
+        "SELECT COUNT(*) FROM pragma_table_info('{table}') WHERE name = ?"
+    ))
+    .bind(column)
+    .fetch_one(pool)
+    .await?;
+
+    if exists == 0 {
+        sqlx::query(&format!("ALTER TABLE {table} ADD COLUMN {column} {decl}"))
+            .execute(pool)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Outcome of `insert_message`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Inserted {
+    New,
+    /// The nonce is already stored
+    NonceExists,
+    /// Another message already has the trace id
+    DuplicateTrace { existing_nonce: u64 },
+}
+
+/// Insert a new cross-chain message unless its nonce or trace id is taken.
+#[allow(clippy::too_many_arguments)]
+pub async fn insert_message(
+    pool: &SqlitePool,
+    envelope: &MessageEnvelope,
+    trace_id: &str,
+    sender: &str,
+    amount: &str,
+    deadline: i64,
+    description: Option<&str>,
+    run_id: Option<&str>,
+    payload_type: u8,
+    computation: Computation,
+    behavior_json: Option<&str>,
+) -> Result<Inserted> {
+    // Checked up front as well: databases still holding duplicates lack the unique index
+    if get_nonce_by_trace_id(pool, trace_id).await?.is_none() {
+        let mut tx = pool.begin().await?;
+        let inserted = sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO messages
+                (src_chain_id, dst_chain_id, nonce, trace_id, sender, amount, payload, deadline, description, state, run_id,
+                 payload_type, computation, behavior_json)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, 'observed', ?, ?, ?, ?)
+            "#,
+        )
+        .bind(envelope.src_chain_id)
+        .bind(envelope.dst_chain_id)
+        .bind(envelope.nonce as i64)
+        .bind(trace_id)
+        .bind(sender)
+        .bind(amount)
+        .bind(hex::encode(&envelope.payload))
+        .bind(deadline)
+        .bind(description)
+        .bind(run_id)
+        .bind(payload_type)
+        .bind(computation.to_string())
+        .bind(behavior_json)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        if inserted > 0 {
+            sqlx::query(
+                "INSERT INTO state_transitions (nonce, from_state, to_state, reason) VALUES (?, NULL, 'observed', 'observed')",
+            )
+            .bind(envelope.nonce as i64)
+            .execute(&mut *tx)
+            .await?;
+            tx.commit().await?;
+            return Ok(Inserted::New);
+        }
+    }
+    // The nonce or trace id is taken, possibly by a concurrent insert
+    match get_nonce_by_trace_id(pool, trace_id).await? {
+        Some(existing_nonce) if existing_nonce != envelope.nonce => Ok(Inserted::DuplicateTrace { existing_nonce }),
+        _ => Ok(Inserted::NonceExists),
+    }
+}
+
+/// Nonce of the message holding a trace id.
+pub async fn get_nonce_by_trace_id(pool: &SqlitePool, trace_id: &str) -> Result<Option<u64>> {
+    let nonce: Option<i64> = sqlx::query_scalar("SELECT MIN(nonce) FROM messages WHERE trace_id = ?")
+        .bind(trace_id)
+        .fetch_one(pool)
+        .await?;
+    Ok(nonce.map(|n| n as u64))
+}
+
+/// Record a message rejected for reusing a trace id; false if its nonce
+/// was already recorded (e.g. the log was polled again after a restart).
+#[allow(clippy::too_many_arguments)]
+pub async fn record_trace_conflict(
+    pool: &SqlitePool,
+    trace_id: &str,
+    nonce: u64,
+    existing_nonce: u64,
+    sender: &str,
+    amount: &str,
+    tx_hash: Option<&str>,
+    block_number: Option<u64>,
+) -> Result<bool> {
+    let recorded = sqlx::query(
+        r#"
+        INSERT OR IGNORE INTO trace_conflicts (trace_id, nonce, existing_nonce, sender, amount, tx_hash, block_number)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(trace_id)
+    .bind(nonce as i64)
+    .bind(existing_nonce as i64)
+    .bind(sender)
+    .bind(amount)
+    .bind(tx_hash)
+    .bind(block_number.map(|b| b as i64))
+    .execute(pool)
+    .await?
+    .rows_affected();
+    Ok(recorded > 0)
+}
+
+/// Recorded trace id conflicts, newest first.
+pub async fn get_trace_conflicts(pool: &SqlitePool, limit: i64) -> Result<Vec<TraceConflict>> {
+    let rows = sqlx::query_as::<_, TraceConflict>(
+        r#"
+        SELECT id, trace_id, nonce, existing_nonce, sender, amount, tx_hash, block_number, rejected, detected_at
+        FROM trace_conflicts
+        ORDER BY id DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+/// Next unused nonce at or above `base`.
+pub async fn next_nonce_from(pool: &SqlitePool, base: u64) -> Result<u64> {
+    let max: Option<i64> = sqlx::query_scalar("SELECT MAX(nonce) FROM messages WHERE nonce >= ?")
+        .bind(base as i64)
+        .fetch_one(pool)
+        .await?;
+    Ok(max.map_or(base, |n| n as u64 + 1))
+}
+
+/// Keep the escrow log a message was parsed from, for audit and reparsing.
+pub async fn insert_raw_log(pool: &SqlitePool, nonce: u64, log: &ethers::types::Log) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT OR IGNORE INTO raw_logs (nonce, block_number, block_hash, tx_hash, log_index, log_json)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(nonce as i64)
+    .bind(log.block_number.map(|b| b.as_u64() as i64))
+    .bind(log.block_hash.map(|h| format!("{:?}", h)))
+    .bind(log.transaction_hash.map(|h| format!("{:?}", h)))
+    .bind(log.log_index.map(|i| i.as_u64() as i64))
+    .bind(serde_json::to_string(log)?)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// The stored escrow log for a nonce, if the message came from one.
+pub async fn get_raw_log(pool: &SqlitePool, nonce: u64) -> Result<Option<ethers::types::Log>> {
+    let json: Option<String> = sqlx::query_scalar("SELECT log_json FROM raw_logs WHERE nonce = ?")
+        .bind(nonce as i64)
+        .fetch_optional(pool)
+        .await?;
+    Ok(json.map(|j| serde_json::from_str(&j)).transpose()?)
+}
+
+/// Rewrite a message's parsed fields after a reparse and record the
+/// changes in the audit log. Events follow the message's trace id.
+pub async fn apply_reparse(
+    pool: &SqlitePool,
+    nonce: u64,
+    fields: &crate::types::ParsedFields,
+    changes: &[crate::types::FieldChange],
+) -> Result<i64> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        r#"
+        UPDATE messages SET
+            trace_id = ?, sender = ?, amount = ?, payload = ?, deadline = ?, description = ?, payload_type = ?,
+            updated_at = datetime('now')
+        WHERE nonce = ?
+        "#,
+    )
+    .bind(&fields.trace_id)
+    .bind(&fields.sender)
+    .bind(&fields.amount)
+    .bind(&fields.payload)
+    .bind(fields.deadline)
+    .bind(&fields.description)
+    .bind(fields.payload_type)
+    .bind(nonce as i64)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query("UPDATE events SET trace_id = ? WHERE nonce = ?")
+        .bind(&fields.trace_id)
+        .bind(nonce as i64)
+        .execute(&mut *tx)
+        .await?;
+
+    let audit_id: i64 = sqlx::query_scalar(
+        "INSERT INTO audit_log (action, target, detail) VALUES ('reparse_transaction', ?, ?) RETURNING id",
+    )
+    .bind(nonce.to_string())
+    .bind(serde_json::to_string(changes)?)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(audit_id)
+}
+
+/// Reparse and purge entries of the audit log that changed rows of `day`:
+/// reparses of a message created or with events that day, and purges
+/// that recorded the day.
+pub async fn get_admin_changes_on(pool: &SqlitePool, day: &str) -> Result<Vec<crate::types::AuditEntry>> {
+    let rows: Vec<(i64, String, String, String, String)> = sqlx::query_as(
+        r#"
+        SELECT id, action, target, detail, created_at
+        FROM audit_log a
+        WHERE (a.action = 'reparse_transaction' AND CAST(a.target AS INTEGER) IN (
+                SELECT nonce FROM messages WHERE date(created_at) = ?1
+                UNION SELECT nonce FROM events WHERE substr(timestamp, 1, 10) = ?1
+            ))
+            OR (a.action = 'purge_transaction' AND EXISTS (
+                SELECT 1 FROM json_each(a.detail, '$.days') WHERE value = ?1
+            ))
+        ORDER BY id ASC
+        "#,
+    )
+    .bind(day)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(id, action, target, detail, created_at)| crate::types::AuditEntry {
+            id,
+            action,
+            target,
+            detail: serde_json::from_str(&detail).unwrap_or(serde_json::Value::String(detail)),
+            created_at,
+        })
+        .collect())
+}
+
+/// A state update that lost a race: the message was no longer in the state
+/// the update expected (or was gone). The attempt is recorded in
+/// `state_transitions` with `applied` false, and nothing else changes.
+#[derive(Debug)]
+pub struct StateConflict {
+    pub nonce: u64,
+    pub expected: String,
+    pub attempted: String,
+}
+
+impl std::fmt::Display for StateConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "message {} left {} before it could move to {}",
+            self.nonce, self.expected, self.attempted
+        )
+    }
+}
+
+impl std::error::Error for StateConflict {}
+
+/// Move a message from `from_state` to `new_state` with optional fields, and
+/// record the transition and its `reason` in `state_transitions`. Fails with
+/// `StateConflict` if the message is no longer in `from_state`.
+#[allow(clippy::too_many_arguments)]
+pub async fn update_message_state(
+    pool: &SqlitePool,
+    nonce: u64,
+    from_state: MessageState,
+    new_state: MessageState,
+    result: Option<&str>,
+    solana_sig: Option<&str>,
+    eth_settle_tx: Option<&str>,
+    error_msg: Option<&str>,
+    reason: &str,
+) -> Result<()> {
+    let (from, to) = (from_state.to_string(), new_state.to_string());
+    let mut tx = pool.begin().await?;
+    record_transition(&mut tx, nonce, &from, &to, reason).await?;
+    let updated = sqlx::query(
+        r#"
+        UPDATE messages SET
+            state = ?,
+            result = COALESCE(?, result),
+            solana_signature = COALESCE(?, solana_signature),
+            eth_settle_tx = COALESCE(?, eth_settle_tx),
+            error_message = COALESCE(?, error_message),
+            updated_at = datetime('now')
+        WHERE nonce = ? AND state = ?
+        "#,
+    )
+    .bind(&to)
+    .bind(result)
+    .bind(solana_sig)
+    .bind(eth_settle_tx)
+    .bind(error_msg)
+    .bind(nonce as i64)
+    .bind(&from)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+    finish_transition(tx, nonce, from, to, updated).await
+}
+
+/// Append a transition from the message's current state, applied if that is
+/// `expected`. Written before the state update it records, so the
+/// transaction takes the write lock first and `from_state` cannot go stale.
+/// Unknown nonces record nothing.
+async fn record_transition(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    nonce: u64,
+    expected: &str,
+    to_state: &str,
+    reason: &str,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO state_transitions (nonce, from_state, to_state, reason, applied)
+        SELECT nonce, state, ?1, CASE WHEN state = ?2 THEN ?3 ELSE ?3 || ' (expected ' || ?2 || ')' END, state = ?2
+        FROM messages
+        WHERE nonce = ?4
+        "#,
+    )
+    .bind(to_state)
+    .bind(expected)
+    .bind(reason)
+    .bind(nonce as i64)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+/// Commit a transition whose state update matched `updated` rows; a lost
+/// race still commits, to keep the record of the attempt.
+async fn finish_transition(
+    tx: sqlx::Transaction<'_, sqlx::Sqlite>,
+    nonce: u64,
+    expected: String,
+    attempted: String,
+    updated: u64,
+) -> Result<()> {
+    tx.commit().await?;
+    if updated == 0 {
+        return Err(StateConflict { nonce, expected, attempted }.into());
+    }
+    Ok(())
+}
+
+/// State history of a message, oldest first.
+pub async fn get_state_transitions(pool: &SqlitePool, nonce: u64) -> Result<Vec<StateTransition>> {
+    let rows = sqlx::query_as::<_, StateTransition>(
+        "SELECT id, nonce, from_state, to_state, reason, applied, at FROM state_transitions WHERE nonce = ? ORDER BY id",
+    )
+    .bind(nonce as i64)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+/// Store the proof bundle JSON for a message.
+pub async fn store_proof(pool: &SqlitePool, nonce: u64, proof_json: &str) -> Result<()> {
+    sqlx::query(
+        "UPDATE messages SET proof_json = ?, updated_at = datetime('now') WHERE nonce = ?",
+    )
+    .bind(proof_json)
+    .bind(nonce as i64)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Spend one of a message's retries in `state`, recorded as a transition
+/// that stays in `state`, with the failure as its reason.
+pub async fn increment_retry(pool: &SqlitePool, nonce: u64, state: MessageState, reason: &str) -> Result<()> {
+    let state = state.to_string();
+    let mut tx = pool.begin().await?;
+    record_transition(&mut tx, nonce, &state, &state, reason).await?;
+    let updated = sqlx::query(
+        "UPDATE messages SET retry_count = retry_count + 1, updated_at = datetime('now') WHERE nonce = ? AND state = ?",
+    )
+    .bind(nonce as i64)
+    .bind(&state)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+    finish_transition(tx, nonce, state.clone(), state, updated).await
+}
+
+/// Get all messages in a given state (for crash-safe resume).
+pub async fn get_messages_by_state(
+    pool: &SqlitePool,
+    state: MessageState,
+) -> Result<Vec<CrossChainMessage>> {
+    let state_str = state.to_string();
+    let rows = sqlx::query_as::<_, CrossChainMessage>(
+        r#"
+        SELECT
+            id, nonce, trace_id, sender, amount, payload, deadline,
+            description, state, result, solana_signature, eth_settle_tx, refund_tx, proof_json,
+            retry_count, error_message, run_id, src_chain_id, dst_chain_id, payload_type, computation, behavior_json,
+            settle_gas_used, settle_gas_price_wei, settle_block_number, settle_cost_wei,
+            created_at, updated_at
+        FROM messages
+        WHERE state = ?
+        ORDER BY nonce ASC
+        "#,
+    )
+    .bind(&state_str)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// The most recent messages that came from the Ethereum escrow (injected
+/// ones have none), newest first.
+pub async fn get_escrow_messages(pool: &SqlitePool, limit: i64) -> Result<Vec<CrossChainMessage>> {
+    let rows = sqlx::query_as::<_, CrossChainMessage>(
+        r#"
+        SELECT
+            id, nonce, trace_id, sender, amount, payload, deadline,
+            description, state, result, solana_signature, eth_settle_tx, refund_tx, proof_json,
+            retry_count, error_message, run_id, src_chain_id, dst_chain_id, payload_type, computation, behavior_json,
+            settle_gas_used, settle_gas_price_wei, settle_block_number, settle_cost_wei,
+            created_at, updated_at
+        FROM messages
+        WHERE src_chain_id = ? AND behavior_json IS NULL
+        ORDER BY nonce DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(ETHEREUM_CHAIN_ID)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Escrow-backed messages that ended failed or rolled back and whose
+/// deadline passed before `now`, oldest first: candidates for `refund()`.
+/// Messages already refunded, or found not refundable, are left out.
+pub async fn get_expired_escrow_messages(pool: &SqlitePool, now: i64) -> Result<Vec<CrossChainMessage>> {
+    let rows = sqlx::query_as::<_, CrossChainMessage>(
+        r#"
+        SELECT
+            id, nonce, trace_id, sender, amount, payload, deadline,
+            description, state, result, solana_signature, eth_settle_tx, refund_tx, proof_json,
+            retry_count, error_message, run_id, src_chain_id, dst_chain_id, payload_type, computation, behavior_json,
+            settle_gas_used, settle_gas_price_wei, settle_block_number, settle_cost_wei,
+            created_at, updated_at
+        FROM messages
+        WHERE src_chain_id = ? AND behavior_json IS NULL
+          AND state IN ('failed', 'rolled_back') AND deadline > 0 AND deadline < ?
+          AND refund_tx IS NULL AND refund_skipped IS NULL
+        ORDER BY nonce ASC
+        "#,
+    )
+    .bind(ETHEREUM_CHAIN_ID)
+    .bind(now)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Record why an expired escrow will not be refunded (settled, reclaimed,
+/// missing, or `refund()` would revert), so later passes skip it.
+pub async fn record_refund_skipped(pool: &SqlitePool, nonce: u64, reason: &str) -> Result<()> {
+    sqlx::query("UPDATE messages SET refund_skipped = ? WHERE nonce = ?")
+        .bind(reason)
+        .bind(nonce as i64)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Receipt figures of a settlement transaction, for `record_settlement`.
+#[derive(Debug, Clone, Default)]
+pub struct SettlementGas {
+    pub gas_used: Option<u64>,
+    /// Effective gas price, in wei (decimal string)
+    pub gas_price_wei: Option<String>,
+    pub block_number: Option<u64>,
+    /// `gas_used` × `gas_price_wei` (decimal string)
+    pub cost_wei: Option<String>,
+}
+
+/// Mark a message settled by `settle_tx`, with the transaction's gas figures,
+/// in one transaction.
+pub async fn record_settlement(
+    pool: &SqlitePool,
+    nonce: u64,
+    settle_tx: &str,
+    gas: &SettlementGas,
+    reason: &str,
+) -> Result<()> {
+    let (from, to) = (MessageState::Executed.to_string(), MessageState::Settled.to_string());
+    let mut tx = pool.begin().await?;
+    record_transition(&mut tx, nonce, &from, &to, reason).await?;
+    let updated = sqlx::query(
+        r#"
+        UPDATE messages SET
+            state = ?,
+            eth_settle_tx = ?,
+            settle_gas_used = ?,
+            settle_gas_price_wei = ?,
+            settle_block_number = ?,
+            settle_cost_wei = ?,
+            updated_at = datetime('now')
+        WHERE nonce = ? AND state = ?
+        "#,
+    )
+    .bind(&to)
+    .bind(settle_tx)
+    .bind(gas.gas_used.map(|g| g as i64))
+    .bind(gas.gas_price_wei.as_deref())
+    .bind(gas.block_number.map(|b| b as i64))
+    .bind(gas.cost_wei.as_deref())
+    .bind(nonce as i64)
+    .bind(&from)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+    finish_transition(tx, nonce, from, to, updated).await
+}
+
+/// Mark a message refunded by the relayer's refund() transaction, unless it
+/// left `from_state` meanwhile.
+pub async fn record_refund(pool: &SqlitePool, nonce: u64, from_state: MessageState, refund_tx: &str) -> Result<()> {
+    let (from, to) = (from_state.to_string(), MessageState::Refunded.to_string());
+    let mut tx = pool.begin().await?;
+    record_transition(&mut tx, nonce, &from, &to, &format!("refund tx {}", refund_tx)).await?;
+    let updated = sqlx::query(
+        r#"
+        UPDATE messages SET
+            state = ?,
+            refund_tx = ?,
+            updated_at = datetime('now')
+        WHERE nonce = ? AND state = ?
+        "#,
+    )
+    .bind(&to)
+    .bind(refund_tx)
+    .bind(nonce as i64)
+    .bind(&from)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+    finish_transition(tx, nonce, from, to, updated).await
+}
+
+/// Get a single message by nonce.
+pub async fn get_message_by_nonce(
+    pool: &SqlitePool,
+    nonce: u64,
+) -> Result<Option<CrossChainMessage>> {
+    let row = sqlx::query_as::<_, CrossChainMessage>(
+        r#"
+        SELECT
+            id, nonce, trace_id, sender, amount, payload, deadline,
+            description, state, result, solana_signature, eth_settle_tx, refund_tx, proof_json,
+            retry_count, error_message, run_id, src_chain_id, dst_chain_id, payload_type, computation, behavior_json,
+            settle_gas_used, settle_gas_price_wei, settle_block_number, settle_cost_wei,
+            created_at, updated_at
+        FROM messages
+        WHERE nonce = ?
+        "#,
+    )
+    .bind(nonce as i64)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row)
+}
+
+/// Get all messages ordered by nonce descending.
+pub async fn get_all_messages(pool: &SqlitePool) -> Result<Vec<CrossChainMessage>> {
+    let rows = sqlx::query_as::<_, CrossChainMessage>(
+        r#"
+        SELECT
+            id, nonce, trace_id, sender, amount, payload, deadline,
+            description, state, result, solana_signature, eth_settle_tx, refund_tx, proof_json,
+            retry_count, error_message, run_id, src_chain_id, dst_chain_id, payload_type, computation, behavior_json,
+            settle_gas_used, settle_gas_price_wei, settle_block_number, settle_cost_wei,
+            created_at, updated_at
+        FROM messages
+        ORDER BY nonce DESC
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Get the newest `limit` messages, ordered by nonce descending.
+pub async fn get_recent_messages(pool: &SqlitePool, limit: i64) -> Result<Vec<CrossChainMessage>> {
+    let rows = sqlx::query_as::<_, CrossChainMessage>(
+        r#"
+        SELECT
+            id, nonce, trace_id, sender, amount, payload, deadline,
+            description, state, result, solana_signature, eth_settle_tx, refund_tx, proof_json,
+            retry_count, error_message, run_id, src_chain_id, dst_chain_id, payload_type, computation, behavior_json,
+            settle_gas_used, settle_gas_price_wei, settle_block_number, settle_cost_wei,
+            created_at, updated_at
+        FROM messages
+        ORDER BY nonce DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Get metrics aggregate (single query), optionally restricted to one run.
+pub async fn get_metrics(
+    pool: &SqlitePool,
+    run_id: Option<&str>,
+) -> Result<(i64, i64, i64, i64, i64)> {
+    let row: (i64, i64, i64, i64, i64) = sqlx::query_as(
+        r#"
+        SELECT
+            COUNT(*) AS total,
+            SUM(CASE WHEN state = 'settled' THEN 1 ELSE 0 END) AS settled,
+            SUM(CASE WHEN state IN ('failed', 'rolled_back', 'refunded') THEN 1 ELSE 0 END) AS failed,
+            SUM(CASE WHEN state NOT IN ('settled', 'failed', 'rolled_back', 'refunded') THEN 1 ELSE 0 END) AS pending,
+            COALESCE(SUM(retry_count), 0) AS retries
+        FROM messages
+        WHERE ? IS NULL OR run_id = ?
+        "#,
+    )
+    .bind(run_id)
+    .bind(run_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row)
+}
+
+/// Messages of a run that were rolled back, by their `rollback` events:
+/// a rolled-back message may since have been refunded.
+pub async fn count_rolled_back(pool: &SqlitePool, run_id: &str) -> Result<i64> {
+    let count: i64 = sqlx::query_scalar(
+        "SELECT COUNT(DISTINCT nonce) FROM events WHERE run_id = ? AND step = 'rollback'",
+    )
+        .bind(run_id)
+        .fetch_one(pool)
+        .await?;
+    Ok(count)
+}
+
+/// Count messages that have not yet reached a terminal state.
+pub async fn count_pending(pool: &SqlitePool) -> Result<i64> {
+    let count: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM messages WHERE state NOT IN ('settled', 'failed', 'rolled_back', 'refunded')",
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count)
+}
+
+/// Count messages to or from `chain_id` that have not yet reached a terminal state.
+pub async fn count_pending_for_chain(pool: &SqlitePool, chain_id: u32) -> Result<i64> {
+    let count: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*) FROM messages
+        WHERE (src_chain_id = ? OR dst_chain_id = ?)
+          AND state NOT IN ('settled', 'failed', 'rolled_back', 'refunded')
+        "#,
+    )
+    .bind(chain_id)
+    .bind(chain_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count)
+}
+
+/// Message count per state.
+pub async fn count_by_state(pool: &SqlitePool) -> Result<std::collections::BTreeMap<String, i64>> {
+    let rows: Vec<(String, i64)> = sqlx::query_as("SELECT state, COUNT(*) FROM messages GROUP BY state")
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows.into_iter().collect())
+}
+
+/// Messages the relayer executed on Solana, as counted by
+/// `solana_execution_totals`.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SolanaExecutionTotals {
+    pub executions: u64,
+    pub total_amount: u128,
+    /// Executions whose stored amount is not a u64, left out of `total_amount`
+    pub unparsable_amounts: u64,
+}
+
+/// Messages the relayer executed on Solana (they carry a signature) and
+/// the sum of their amounts. Amounts are decimal strings that can exceed
+/// an i64, so the rows are streamed and summed here rather than in SQL.
+pub async fn solana_execution_totals(pool: &SqlitePool) -> Result<SolanaExecutionTotals> {
+    let mut rows = sqlx::query_as::<_, (String,)>("SELECT amount FROM messages WHERE solana_signature IS NOT NULL")
+        .fetch(pool);
+    let mut totals = SolanaExecutionTotals::default();
+    while let Some((amount,)) = rows.try_next().await? {
+        totals.executions += 1;
+        // Parsed the way the executor request is built
+        match amount.parse::<u64>() {
+            Ok(amount) => totals.total_amount += amount as u128,
+            Err(_) => totals.unparsable_amounts += 1,
+        }
+    }
+    Ok(totals)
+}
+
+/// Per-day totals of the messages created in the last `days` days (today
+/// included). Amounts are summed here rather than in SQL, since they are
+/// stored as decimal strings and can exceed an i64.
+pub async fn get_daily_stats(pool: &SqlitePool, days: i64) -> Result<Vec<DailyStats>> {
+    let rows: Vec<(String, String, String, bool, Option<f64>)> = sqlx::query_as(
+        r#"
+        SELECT
+            date(m.created_at) AS day,
+            m.amount,
+            m.state,
+            EXISTS (SELECT 1 FROM events e WHERE e.nonce = m.nonce AND e.step = 'rollback'),
+            CASE WHEN m.state = 'settled' THEN (
+                SELECT (MAX(julianday(e.timestamp)) - MIN(julianday(e.timestamp))) * 86400.0
+                FROM events e WHERE e.nonce = m.nonce
+            ) END
+        FROM messages m
+        WHERE m.created_at >= date('now', ?)
+        ORDER BY day ASC
+        "#,
+    )
+    .bind(format!("-{} days", days - 1))
+    .fetch_all(pool)
+    .await?;
+
+    #[derive(Default)]
+    struct Totals {
+        messages: u64,
+        locked: u128,
+        settled: u64,
+        settled_amount: u128,
+        refunded: u128,
+        rollbacks: u64,
+        e2e_sum: f64,
+        e2e_count: u64,
+    }
+
+    let mut by_day: BTreeMap<String, Totals> = BTreeMap::new();
+    for (day, amount, state, rolled_back, e2e) in rows {
+        let amount = amount.parse::<u128>().unwrap_or(0);
+        let totals = by_day.entry(day).or_default();
+        totals.messages += 1;
+        totals.locked += amount;
+        match state.as_str() {
+            "settled" => {
+                totals.settled += 1;
+                totals.settled_amount += amount;
+            }
+            "rolled_back" | "refunded" => totals.refunded += amount,
+            _ => {}
+        }
+        if rolled_back {
+            totals.rollbacks += 1;
+        }
+        if let Some(e2e) = e2e {
+            totals.e2e_sum += e2e;
+            totals.e2e_count += 1;
+        }
+    }
+
+    Ok(by_day
+        .into_iter()
+        .map(|(day, t)| DailyStats {
+            day,
+            messages: t.messages,
+            locked_amount: t.locked.to_string(),
+            settled: t.settled,
+            settled_amount: t.settled_amount.to_string(),
+            refunded_amount: t.refunded.to_string(),
+            rollbacks: t.rollbacks,
+            avg_e2e_secs: (t.e2e_count > 0).then(|| t.e2e_sum / t.e2e_count as f64),
+        })
+        .collect())
+}
+
+/// Messages with a nonce in `from..=to` that have not reached settled,
+/// failed, rolled_back or refunded.
+pub async fn count_unfinished_in_range(pool: &SqlitePool, from: u64, to: u64) -> Result<i64> {
+    let (count,): (i64,) = sqlx::query_as(
+        r#"
+        SELECT COUNT(*) FROM messages
+        WHERE nonce BETWEEN ? AND ? AND state NOT IN ('settled', 'failed', 'rolled_back', 'refunded')
+        "#,
+    )
+    .bind(from as i64)
+    .bind(to as i64)
+    .fetch_one(pool)
+    .await?;
+    Ok(count)
+}
+
+/// Database file size (pages in use) and row counts.
+pub async fn database_stats(pool: &SqlitePool) -> Result<DatabaseStats> {
+    let (page_count,): (i64,) = sqlx::query_as("PRAGMA page_count").fetch_one(pool).await?;
+    let (page_size,): (i64,) = sqlx::query_as("PRAGMA page_size").fetch_one(pool).await?;
+    let (freelist_count,): (i64,) = sqlx::query_as("PRAGMA freelist_count").fetch_one(pool).await?;
+    let (messages,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM messages").fetch_one(pool).await?;
+    let (events,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM events").fetch_one(pool).await?;
+    Ok(DatabaseStats {
+        size_bytes: page_count * page_size,
+        free_bytes: freelist_count * page_size,
+        messages,
+        events,
+    })
+}
+
+/// Aggregate fleet-wide health over the last `hours` hours.
+pub async fn get_fleet_summary(pool: &SqlitePool, hours: i64) -> Result<FleetSummary> {
+    let window = format!("-{} hours", hours);
+
+    let (total, settled, rolled_back, failed, pending, total_retries): (i64, i64, i64, i64, i64, i64) =
+        sqlx::query_as(
+            r#"
+            SELECT
+                COUNT(*),
+                COALESCE(SUM(CASE WHEN state = 'settled' THEN 1 ELSE 0 END), 0),
+                COALESCE(SUM(CASE WHEN state = 'rolled_back' THEN 1 ELSE 0 END), 0),
+                COALESCE(SUM(CASE WHEN state IN ('failed', 'refunded') THEN 1 ELSE 0 END), 0),
+                COALESCE(SUM(CASE WHEN state NOT IN ('settled', 'failed', 'rolled_back', 'refunded') THEN 1 ELSE 0 END), 0),
+                COALESCE(SUM(retry_count), 0)
+            FROM messages
+            WHERE created_at >= datetime('now', ?)
+            "#,
+        )
+        .bind(&window)
+        .fetch_one(pool)
+        .await?;
+
+    // Retry/failure event details are the most specific failure reasons we have
+    let top_failure_reasons = sqlx::query_as::<_, FailureReason>(
+        r#"
+        SELECT COALESCE(e.detail, 'unknown') AS reason, COUNT(*) AS count
+        FROM events e
+        JOIN messages m ON m.nonce = e.nonce
+        WHERE e.status IN ('retry', 'failure')
+          AND m.created_at >= datetime('now', ?)
+        GROUP BY reason
+        ORDER BY count DESC
+        LIMIT 10
+        "#,
+    )
+    .bind(&window)
+    .fetch_all(pool)
+    .await?;
+
+    let latency_trend = sqlx::query_as::<_, LatencyBucket>(
+        r#"
+        SELECT
+            strftime('%Y-%m-%dT%H:00', m.created_at) AS hour,
+            COUNT(*) AS messages,
+            AVG(t.e2e) AS avg_e2e_secs,
+            MAX(t.e2e) AS max_e2e_secs
+        FROM messages m
+        JOIN (
+            SELECT nonce, (MAX(julianday(timestamp)) - MIN(julianday(timestamp))) * 86400.0 AS e2e
+            FROM events
+            GROUP BY nonce
+        ) t ON t.nonce = m.nonce
+        WHERE m.state IN ('settled', 'rolled_back')
+          AND m.created_at >= datetime('now', ?)
+        GROUP BY hour
+        ORDER BY hour ASC
+        "#,
+    )
+    .bind(&window)
+    .fetch_all(pool)
+    .await?;
+
+    let sender_issues = sqlx::query_as::<_, SenderIssues>(
+        r#"
+        SELECT
+            sender,
+            COUNT(*) AS total,
+            SUM(CASE WHEN state = 'rolled_back' THEN 1 ELSE 0 END) AS rolled_back,
+            SUM(retry_count) AS retries
+        FROM messages
+        WHERE created_at >= datetime('now', ?)
+        GROUP BY sender
+        HAVING rolled_back > 0 OR retries > 0
+        ORDER BY rolled_back DESC, retries DESC
+        LIMIT 10
+        "#,
+    )
+    .bind(&window)
+    .fetch_all(pool)
+    .await?;
+
+    let rollback_rate = if total > 0 {
+        rolled_back as f64 / total as f64
+    } else {
+        0.0
+    };
+
+    Ok(FleetSummary {
+        window_hours: hours,
+        total,
+        settled,
+        rolled_back,
+        failed,
+        pending,
+        total_retries,
+        rollback_rate,
+        top_failure_reasons,
+        latency_trend,
+        sender_issues,
+    })
+}
+
+/// Store a generated fleet report. Returns the new row id.
+pub async fn insert_fleet_report(
+    pool: &SqlitePool,
+    summary: &FleetSummary,
+    report: &str,
+    model: &str,
+) -> Result<i64> {
+    let id = sqlx::query(
+        "INSERT INTO fleet_reports (window_hours, summary_json, report, model) VALUES (?, ?, ?, ?)",
+    )
+    .bind(summary.window_hours)
+    .bind(serde_json::to_string(summary)?)
+    .bind(report)
+    .bind(model)
+    .execute(pool)
+    .await?
+    .last_insert_rowid();
+
+    Ok(id)
+}
+
+/// Get the most recent fleet reports, newest first.
+pub async fn get_fleet_reports(pool: &SqlitePool, limit: i64) -> Result<Vec<FleetReport>> {
+    let rows = sqlx::query_as::<_, FleetReportRow>(
+        r#"
+        SELECT id, window_hours, summary_json, report, model, created_at
+        FROM fleet_reports
+        ORDER BY id DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter().map(FleetReportRow::into_report).collect()
+}
+
+/// Get a single fleet report by id.
+pub async fn get_fleet_report(pool: &SqlitePool, id: i64) -> Result<Option<FleetReport>> {
+    let row = sqlx::query_as::<_, FleetReportRow>(
+        r#"
+        SELECT id, window_hours, summary_json, report, model, created_at
+        FROM fleet_reports
+        WHERE id = ?
+        "#,
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await?;
+
+    row.map(FleetReportRow::into_report).transpose()
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct FleetReportRow {
+    id: i64,
+    window_hours: i64,
+    summary_json: String,
+    report: String,
+    model: String,
+    created_at: String,
+}
+
+impl FleetReportRow {
+    fn into_report(self) -> Result<FleetReport> {
+        Ok(FleetReport {
+            id: self.id,
+            window_hours: self.window_hours,
+            summary: serde_json::from_str(&self.summary_json)?,
+            report: self.report,
+            model: self.model,
+            created_at: self.created_at,
+        })
+    }
+}
+
+/// Stored embedding of a transaction's event summary.
+#[derive(Debug, Clone)]
+pub struct IncidentEmbedding {
+    pub nonce: i64,
+    pub summary: String,
+    pub model: String,
+    pub embedding: Vec<f32>,
+    pub created_at: String,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct IncidentEmbeddingRow {
+    nonce: i64,
+    summary: String,
+    model: String,
+    embedding: Vec<u8>,
+    created_at: String,
+}
+
+impl From<IncidentEmbeddingRow> for IncidentEmbedding {
+    fn from(r: IncidentEmbeddingRow) -> Self {
+        Self {
+            nonce: r.nonce,
+            summary: r.summary,
+            model: r.model,
+            embedding: r
+                .embedding
+                .chunks_exact(4)
+                .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+                .collect(),
+            created_at: r.created_at,
+        }
+    }
+}
+
+/// Insert or replace the embedding for a nonce.
+pub async fn upsert_incident_embedding(
+    pool: &SqlitePool,
+    nonce: u64,
+    summary: &str,
+    model: &str,
+    embedding: &[f32],
+) -> Result<()> {
+    let blob: Vec<u8> = embedding.iter().flat_map(|f| f.to_le_bytes()).collect();
+    sqlx::query(
+        r#"
+        INSERT INTO incident_embeddings (nonce, summary, model, embedding)
+        VALUES (?, ?, ?, ?)
+        ON CONFLICT(nonce) DO UPDATE SET
+            summary = excluded.summary,
+            model = excluded.model,
+            embedding = excluded.embedding,
+            created_at = datetime('now')
+        "#,
+    )
+    .bind(nonce as i64)
+    .bind(summary)
+    .bind(model)
+    .bind(blob)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Get the stored embedding for a nonce, if it has been indexed.
+pub async fn get_incident_embedding(
+    pool: &SqlitePool,
+    nonce: u64,
+) -> Result<Option<IncidentEmbedding>> {
+    let row = sqlx::query_as::<_, IncidentEmbeddingRow>(
+        "SELECT nonce, summary, model, embedding, created_at FROM incident_embeddings WHERE nonce = ?",
+    )
+    .bind(nonce as i64)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(Into::into))
+}
+
+/// Load the incident index for one embedding model (small enough to scan in
+/// memory). Vectors from different models are not comparable.
+pub async fn get_incident_embeddings_for_model(pool: &SqlitePool, model: &str) -> Result<Vec<IncidentEmbedding>> {
+    let rows = sqlx::query_as::<_, IncidentEmbeddingRow>(
+        "SELECT nonce, summary, model, embedding, created_at FROM incident_embeddings WHERE model = ?",
+    )
+    .bind(model)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(Into::into).collect())
+}
+
+/// Record token usage and estimated cost for one LLM request.
+pub async fn insert_analysis_cost(
+    pool: &SqlitePool,
+    model: &str,
+    kind: &str,
+    prompt_tokens: i64,
+    completion_tokens: i64,
+    cost_usd: f64,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO analysis_costs (model, kind, prompt_tokens, completion_tokens, cost_usd)
+        VALUES (?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(model)
+    .bind(kind)
+    .bind(prompt_tokens)
+    .bind(completion_tokens)
+    .bind(cost_usd)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Daily LLM usage totals per model over the last `days` days, newest first.
+pub async fn get_analysis_costs(pool: &SqlitePool, days: i64) -> Result<Vec<DailyAnalysisCost>> {
+    let rows = sqlx::query_as::<_, DailyAnalysisCost>(
+        r#"
+        SELECT
+            date(created_at) AS day,
+            model,
+            COUNT(*) AS requests,
+            SUM(prompt_tokens) AS prompt_tokens,
+            SUM(completion_tokens) AS completion_tokens,
+            SUM(cost_usd) AS cost_usd
+        FROM analysis_costs
+        WHERE created_at >= datetime('now', ?)
+        GROUP BY day, model
+        ORDER BY day DESC, model ASC
+        "#,
+    )
+    .bind(format!("-{} days", days))
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// SQLite size and row counts, for benchmark reports.
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+pub struct DatabaseStats {
+    pub size_bytes: i64,
+    /// Free pages inside the file, reclaimable with VACUUM
+    pub free_bytes: i64,
+    pub messages: i64,
+    pub events: i64,
+}
+
+/// Sequence number and envelope chain ids assigned to a persisted event.
+#[derive(Debug, Clone, Copy)]
+pub struct PersistedEvent {
+    pub seq: u64,
+    pub src_chain_id: Option<u32>,
+    pub dst_chain_id: Option<u32>,
+}
+
+/// Persist a lifecycle event. Run and chain ids are inherited from its message.
+pub async fn insert_event<'e, E: sqlx::SqliteExecutor<'e>>(
+    executor: E,
+    event: &crate::event::LifecycleEvent,
+) -> Result<PersistedEvent> {
+    let (seq, src_chain_id, dst_chain_id): (i64, Option<u32>, Option<u32>) = sqlx::query_as(
+        r#"
+        INSERT INTO events (nonce, trace_id, actor, actor_chain_id, step, status, detail, timestamp, schema_version,
+                            fee_wei, fee_lamports, gas_used, run_id, src_chain_id, dst_chain_id)
+        SELECT ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, m.run_id, m.src_chain_id, m.dst_chain_id
+        FROM (SELECT 1) LEFT JOIN messages m ON m.nonce = ?
+        RETURNING id, src_chain_id, dst_chain_id
+        "#,
+    )
+    .bind(event.nonce as i64)
+    .bind(&event.trace_id)
+    .bind(&event.actor.name)
+    .bind(event.actor.chain_id)
+    .bind(event.step.to_string())
+    .bind(format!("{:?}", event.status).to_lowercase())
+    .bind(event.detail.as_deref())
+    .bind(&event.timestamp)
+    .bind(event.schema_version)
+    .bind(event.fee_wei.as_deref())
+    .bind(event.fee_lamports.map(|f| f as i64))
+    .bind(event.gas_used.map(|g| g as i64))
+    .bind(event.nonce as i64)
+    .fetch_one(executor)
+    .await?;
+
+    Ok(PersistedEvent {
+        seq: seq as u64,
+        src_chain_id,
+        dst_chain_id,
+    })
+}
+
+/// Persist lifecycle events in one transaction, in order. Either all are
+/// written or none.
+pub async fn insert_events(
+    pool: &SqlitePool,
+    events: &[crate::event::LifecycleEvent],
+) -> Result<Vec<PersistedEvent>> {
+    let mut tx = pool.begin().await?;
+    let mut persisted = Vec::with_capacity(events.len());
+    for event in events {
+        persisted.push(insert_event(&mut *tx, event).await?);
+    }
+    tx.commit().await?;
+    Ok(persisted)
+}
+
+/// Get events for a given nonce.
+pub async fn get_events_by_nonce(
+    pool: &SqlitePool,
+    nonce: u64,
+) -> Result<Vec<crate::event::LifecycleEvent>> {
+    let rows = sqlx::query_as::<_, EventRow>(
+        r#"
+        SELECT id, trace_id, nonce, actor, actor_chain_id, step, status, detail, timestamp, src_chain_id, dst_chain_id, schema_version,
+               fee_wei, fee_lamports, gas_used
+        FROM events
+        WHERE nonce = ?
+        ORDER BY id ASC
+        "#,
+    )
+    .bind(nonce as i64)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(events_from_rows(rows))
+}
+
+/// Events of messages with a nonce in `from..=to`, in sequence order.
+pub async fn get_events_in_range(
+    pool: &SqlitePool,
+    from: u64,
+    to: u64,
+) -> Result<Vec<crate::event::LifecycleEvent>> {
+    let rows = sqlx::query_as::<_, EventRow>(
+        r#"
+        SELECT id, trace_id, nonce, actor, actor_chain_id, step, status, detail, timestamp, src_chain_id, dst_chain_id, schema_version,
+               fee_wei, fee_lamports, gas_used
+        FROM events
+        WHERE nonce BETWEEN ? AND ?
+        ORDER BY id ASC
+        "#,
+    )
+    .bind(from as i64)
+    .bind(to as i64)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(events_from_rows(rows))
+}
+
+/// Get events across all nonces with a sequence number greater than `since_seq`,
+/// in sequence order.
+pub async fn get_events_since(
+    pool: &SqlitePool,
+    since_seq: u64,
+    limit: i64,
+) -> Result<Vec<crate::event::LifecycleEvent>> {
+    let rows = sqlx::query_as::<_, EventRow>(
+        r#"
+        SELECT id, trace_id, nonce, actor, actor_chain_id, step, status, detail, timestamp, src_chain_id, dst_chain_id, schema_version,
+               fee_wei, fee_lamports, gas_used
+        FROM events
+        WHERE id > ?
+        ORDER BY id ASC
+        LIMIT ?
+        "#,
+    )
+    .bind(since_seq as i64)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(events_from_rows(rows))
+}
+
+/// Get events of the newest `messages` messages with a sequence number greater
+/// than `since_seq`, in sequence order, at most `limit` at a time.
+pub async fn get_recent_message_events(
+    pool: &SqlitePool,
+    messages: i64,
+    since_seq: u64,
+    limit: i64,
+) -> Result<Vec<crate::event::LifecycleEvent>> {
+    let rows = sqlx::query_as::<_, EventRow>(
+        r#"
+        SELECT e.id, e.trace_id, e.nonce, e.actor, e.actor_chain_id, e.step, e.status, e.detail, e.timestamp,
+               e.src_chain_id, e.dst_chain_id, e.schema_version, e.fee_wei, e.fee_lamports, e.gas_used
+        FROM events e
+        JOIN (SELECT nonce FROM messages ORDER BY nonce DESC LIMIT ?) m ON m.nonce = e.nonce
+        WHERE e.id > ?
+        ORDER BY e.id ASC
+        LIMIT ?
+        "#,
+    )
+    .bind(messages)
+    .bind(since_seq as i64)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(events_from_rows(rows))
+}
+
+/// Get all events recorded during a simulation run, in sequence order.
+pub async fn get_events_by_run(
+    pool: &SqlitePool,
+    run_id: &str,
+) -> Result<Vec<crate::event::LifecycleEvent>> {
+    let rows = sqlx::query_as::<_, EventRow>(
+        r#"
+        SELECT id, trace_id, nonce, actor, actor_chain_id, step, status, detail, timestamp, src_chain_id, dst_chain_id, schema_version,
+               fee_wei, fee_lamports, gas_used
+        FROM events
+        WHERE run_id = ?
+        ORDER BY id ASC
+        "#,
+    )
+    .bind(run_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(events_from_rows(rows))
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct EventRow {
+    id: i64,
+    trace_id: String,
+    nonce: i64,
+    actor: String,
+    actor_chain_id: Option<u32>,
+    step: String,
+    status: String,
+    detail: Option<String>,
+    timestamp: String,
+    src_chain_id: Option<u32>,
+    dst_chain_id: Option<u32>,
+    schema_version: Option<u32>,
+    fee_wei: Option<String>,
+    fee_lamports: Option<i64>,
+    gas_used: Option<i64>,
+}
+
+impl TryFrom<EventRow> for crate::event::LifecycleEvent {
+    type Error = anyhow::Error;
+
+    fn try_from(r: EventRow) -> Result<Self> {
+        Ok(Self {
+            schema_version: r.schema_version.unwrap_or(crate::event::LEGACY_SCHEMA_VERSION),
+            trace_id: r.trace_id,
+            nonce: r.nonce as u64,
+            actor: crate::event::Actor::from_parts(&r.actor, r.actor_chain_id),
+            step: r.step.parse()?,
+            status: match r.status.as_str() {
+                "failure" => crate::event::Status::Failure,
+                "retry" => crate::event::Status::Retry,
+                _ => crate::event::Status::Success,
+            },
+            timestamp: r.timestamp,
+            detail: r.detail,
+            seq: Some(r.id as u64),
+            src_chain_id: r.src_chain_id,
+            dst_chain_id: r.dst_chain_id,
+            fee_wei: r.fee_wei,
+            fee_lamports: r.fee_lamports.map(|f| f as u64),
+            gas_used: r.gas_used.map(|g| g as u64),
+            replay_run_id: None,
+        })
+    }
+}
+
+/// Events of stored rows. A row whose step this build does not know (written
+/// by a newer relayer) is logged and skipped rather than misreported.
+fn events_from_rows(rows: Vec<EventRow>) -> Vec<crate::event::LifecycleEvent> {
+    rows.into_iter()
+        .filter_map(|r| {
+            let seq = r.id;
+            crate::event::LifecycleEvent::try_from(r)
+                .map_err(|e| warn!(seq, error = %e, "Skipping stored event"))
+                .ok()
+        })
+        .collect()
+}
+
+/// Delete one message and everything recorded against it, and record the
+/// purge in the audit log, all in one transaction. `None` if the nonce is unknown.
+pub async fn purge_message(
+    pool: &SqlitePool,
+    nonce: u64,
+    reason: Option<&str>,
+) -> Result<Option<crate::types::PurgeSummary>> {
+    let mut tx = pool.begin().await?;
+
+    let deleted_message: Option<(String, String)> =
+        sqlx::query_as("DELETE FROM messages WHERE nonce = ? RETURNING trace_id, date(created_at)")
+            .bind(nonce as i64)
+            .fetch_optional(&mut *tx)
+            .await?;
+    let Some((trace_id, created_on)) = deleted_message else {
+        return Ok(None);
+    };
+    // Notarized days the purge changes, for GET /notarizations/:day
+    let mut days: Vec<String> =
+        sqlx::query_scalar("SELECT DISTINCT substr(timestamp, 1, 10) FROM events WHERE nonce = ?")
+            .bind(nonce as i64)
+            .fetch_all(&mut *tx)
+            .await?;
+    if !days.contains(&created_on) {
+        days.push(created_on);
+    }
+    days.sort();
+
+    let mut deleted = [0u64; 5];
+    for (i, table) in ["events", "incident_embeddings", "gas_readings", "raw_logs", "state_transitions"]
+        .iter()
+        .enumerate()
+    {
+        deleted[i] = sqlx::query(&format!("DELETE FROM {} WHERE nonce = ?", table))
+            .bind(nonce as i64)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+    }
+    let [events, analyses, gas_readings, raw_logs, state_transitions] = deleted;
+    // Collisions the message took part in, on either side
+    let trace_conflicts = sqlx::query("DELETE FROM trace_conflicts WHERE nonce = ?1 OR existing_nonce = ?1")
+        .bind(nonce as i64)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    let detail = serde_json::json!({
+        "trace_id": trace_id,
+        "reason": reason,
+        "events": events,
+        "analyses": analyses,
+        "gas_readings": gas_readings,
+        "raw_logs": raw_logs,
+        "state_transitions": state_transitions,
+        "trace_conflicts": trace_conflicts,
+        "days": days,
+    });
+    let audit_id: i64 = sqlx::query_scalar(
+        "INSERT INTO audit_log (action, target, detail) VALUES ('purge_transaction', ?, ?) RETURNING id",
+    )
+    .bind(nonce.to_string())
+    .bind(detail.to_string())
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(Some(crate::types::PurgeSummary {
+        nonce,
+        trace_id,
+        events,
+        analyses,
+        gas_readings,
+        raw_logs,
+        state_transitions,
+        trace_conflicts,
+        audit_id,
+    }))
+}
+
+/// Delete all messages and events (clear demo data).
+pub async fn clear_all_data(pool: &SqlitePool) -> Result<()> {
+    sqlx::query("DELETE FROM fleet_reports").execute(pool).await?;
+    sqlx::query("DELETE FROM incident_embeddings").execute(pool).await?;
+    sqlx::query("DELETE FROM events").execute(pool).await?;
+    sqlx::query("DELETE FROM messages").execute(pool).await?;
+    sqlx::query("DELETE FROM runs").execute(pool).await?;
+    sqlx::query("DELETE FROM gas_readings").execute(pool).await?;
+    sqlx::query("DELETE FROM escrow_snapshots").execute(pool).await?;
+    sqlx::query("DELETE FROM raw_logs").execute(pool).await?;
+    sqlx::query("DELETE FROM trace_conflicts").execute(pool).await?;
+    sqlx::query("DELETE FROM state_transitions").execute(pool).await?;
+    Ok(())
+}
+
+/// Register the built-in Ethereum and Solana chains if they are missing.
+/// Existing rows (possibly edited via the API) are left untouched.
+pub async fn ensure_default_chains(pool: &SqlitePool, eth_rpc_url: &str) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT OR IGNORE INTO chains (chain_id, name, kind, rpc_url, executor_json)
+        VALUES (?, 'ethereum', 'evm', ?, 'null'),
+               (?, 'solana', 'solana', NULL, '{"simulated":true}')
+        "#,
+    )
+    .bind(ETHEREUM_CHAIN_ID)
+    .bind(eth_rpc_url)
+    .bind(SOLANA_CHAIN_ID)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Get all registered chains.
+pub async fn get_chains(pool: &SqlitePool) -> Result<Vec<ChainConfig>> {
+    let rows = sqlx::query_as::<_, ChainRow>(
+        r#"
+        SELECT chain_id, name, kind, rpc_url, executor_json, enabled, created_at, updated_at
+        FROM chains
+        ORDER BY chain_id ASC
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter().map(ChainRow::into_chain).collect()
+}
+
+/// Get a single registered chain.
+pub async fn get_chain(pool: &SqlitePool, chain_id: u32) -> Result<Option<ChainConfig>> {
+    let row = sqlx::query_as::<_, ChainRow>(
+        r#"
+        SELECT chain_id, name, kind, rpc_url, executor_json, enabled, created_at, updated_at
+        FROM chains
+        WHERE chain_id = ?
+        "#,
+    )
+    .bind(chain_id)
+    .fetch_optional(pool)
+    .await?;
+
+    row.map(ChainRow::into_chain).transpose()
+}
+
+/// Register a chain. Returns false if the chain id is already taken.
+pub async fn insert_chain(
+    pool: &SqlitePool,
+    chain_id: u32,
+    name: &str,
+    kind: ChainKind,
+    rpc_url: Option<&str>,
+    executor: &serde_json::Value,
+    enabled: bool,
+) -> Result<bool> {
+    let result = sqlx::query(
+        r#"
+        INSERT OR IGNORE INTO chains (chain_id, name, kind, rpc_url, executor_json, enabled)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(chain_id)
+    .bind(name)
+    .bind(kind.to_string())
+    .bind(rpc_url)
+    .bind(executor.to_string())
+    .bind(enabled)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Update a registered chain. Returns false if it does not exist.
+pub async fn update_chain(
+    pool: &SqlitePool,
+    chain_id: u32,
+    name: &str,
+    kind: ChainKind,
+    rpc_url: Option<&str>,
+    executor: &serde_json::Value,
+    enabled: bool,
+) -> Result<bool> {
+    let result = sqlx::query(
+        r#"
+        UPDATE chains SET
+            name = ?, kind = ?, rpc_url = ?, executor_json = ?, enabled = ?,
+            updated_at = datetime('now')
+        WHERE chain_id = ?
+        "#,
+    )
+    .bind(name)
+    .bind(kind.to_string())
+    .bind(rpc_url)
+    .bind(executor.to_string())
+    .bind(enabled)
+    .bind(chain_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Remove a chain from the registry. Returns false if it does not exist.
+pub async fn delete_chain(pool: &SqlitePool, chain_id: u32) -> Result<bool> {
+    let result = sqlx::query("DELETE FROM chains WHERE chain_id = ?")
+        .bind(chain_id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct ChainRow {
+    chain_id: u32,
+    name: String,
+    kind: String,
+    rpc_url: Option<String>,
+    executor_json: String,
+    enabled: bool,
+    created_at: String,
+    updated_at: String,
+}
+
+impl ChainRow {
+    fn into_chain(self) -> Result<ChainConfig> {
+        Ok(ChainConfig {
+            chain_id: self.chain_id,
+            name: self.name,
+            kind: serde_json::from_value(serde_json::Value::String(self.kind))?,
+            rpc_url: self.rpc_url,
+            executor: serde_json::from_str(&self.executor_json)?,
+            enabled: self.enabled,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        })
+    }
+}
+
+/// Get routing rules in evaluation order (priority, then creation order).
+pub async fn get_routing_rules(pool: &SqlitePool) -> Result<Vec<RoutingRule>> {
+    let rows = sqlx::query_as::<_, RoutingRuleRow>(
+        r#"
+        SELECT id, priority, payload_type, header_dst_chain_id, dst_chain_id, computation, enabled, created_at
+        FROM routing_rules
+        ORDER BY priority ASC, id ASC
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter().map(RoutingRuleRow::into_rule).collect()
+}
+
+/// Add a routing rule. Returns the new rule id.
+pub async fn insert_routing_rule(pool: &SqlitePool, rule: &RoutingRuleRequest) -> Result<i64> {
+    let id = sqlx::query(
+        r#"
+        INSERT INTO routing_rules (priority, payload_type, header_dst_chain_id, dst_chain_id, computation, enabled)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(rule.priority)
+    .bind(rule.payload_type)
+    .bind(rule.header_dst_chain_id)
+    .bind(rule.dst_chain_id)
+    .bind(rule.computation.to_string())
+    .bind(rule.enabled)
+    .execute(pool)
+    .await?
+    .last_insert_rowid();
+
+    Ok(id)
+}
+
+/// Delete a routing rule. Returns false if it does not exist.
+pub async fn delete_routing_rule(pool: &SqlitePool, id: i64) -> Result<bool> {
+    let result = sqlx::query("DELETE FROM routing_rules WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct RoutingRuleRow {
+    id: i64,
+    priority: i64,
+    payload_type: Option<u8>,
+    header_dst_chain_id: Option<u32>,
+    dst_chain_id: u32,
+    computation: String,
+    enabled: bool,
+    created_at: String,
+}
+
+impl RoutingRuleRow {
+    fn into_rule(self) -> Result<RoutingRule> {
+        Ok(RoutingRule {
+            id: self.id,
+            priority: self.priority,
+            payload_type: self.payload_type,
+            header_dst_chain_id: self.header_dst_chain_id,
+            dst_chain_id: self.dst_chain_id,
+            computation: self.computation.parse()?,
+            enabled: self.enabled,
+            created_at: self.created_at,
+        })
+    }
+}
+
+/// Registered payload schemas, oldest first.
+pub async fn get_payload_schemas(pool: &SqlitePool) -> Result<Vec<PayloadSchema>> {
+    let rows = sqlx::query_as::<_, PayloadSchemaRow>(
+        "SELECT id, name, payload_type, fields_json, created_at FROM payload_schemas ORDER BY id ASC",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter().map(PayloadSchemaRow::into_schema).collect()
+}
+
+/// Register a payload schema. Returns its id, or None if the name is taken.
+pub async fn insert_payload_schema(pool: &SqlitePool, schema: &PayloadSchemaRequest) -> Result<Option<i64>> {
+    let result = sqlx::query(
+        "INSERT OR IGNORE INTO payload_schemas (name, payload_type, fields_json) VALUES (?, ?, ?)",
+    )
+    .bind(&schema.name)
+    .bind(schema.payload_type)
+    .bind(serde_json::to_string(&schema.fields)?)
+    .execute(pool)
+    .await?;
+
+    Ok((result.rows_affected() > 0).then(|| result.last_insert_rowid()))
+}
+
+/// Delete a payload schema. Returns false if it does not exist.
+pub async fn delete_payload_schema(pool: &SqlitePool, id: i64) -> Result<bool> {
+    let result = sqlx::query("DELETE FROM payload_schemas WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct PayloadSchemaRow {
+    id: i64,
+    name: String,
+    payload_type: Option<u8>,
+    fields_json: String,
+    created_at: String,
+}
+
+impl PayloadSchemaRow {
+    fn into_schema(self) -> Result<PayloadSchema> {
+        Ok(PayloadSchema {
+            id: self.id,
+            name: self.name,
+            payload_type: self.payload_type,
+            fields: serde_json::from_str(&self.fields_json)?,
+            created_at: self.created_at,
+        })
+    }
+}
+
+/// Record the start of a simulation run.
+pub async fn insert_run(
+    pool: &SqlitePool,
+    run_id: &str,
+    scenario: &str,
+    parameters: &serde_json::Value,
+) -> Result<()> {
+    sqlx::query("INSERT INTO runs (run_id, scenario, parameters) VALUES (?, ?, ?)")
+        .bind(run_id)
+        .bind(scenario)
+        .bind(parameters.to_string())
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Mark a simulation run as ended (no-op if already ended).
+pub async fn end_run(pool: &SqlitePool, run_id: &str) -> Result<()> {
+    sqlx::query("UPDATE runs SET ended_at = datetime('now') WHERE run_id = ? AND ended_at IS NULL")
+        .bind(run_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Get all simulation runs, newest first.
+pub async fn get_runs(pool: &SqlitePool) -> Result<Vec<SimulationRun>> {
+    let rows = sqlx::query_as::<_, RunRow>(
+        r#"
+        SELECT r.run_id, r.scenario, r.parameters, r.started_at, r.ended_at,
+            (SELECT COUNT(*) FROM messages m WHERE m.run_id = r.run_id) AS message_count
+        FROM runs r
+        ORDER BY r.started_at DESC, r.rowid DESC
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter().map(RunRow::into_run).collect()
+}
+
+/// Get a single simulation run by id.
+pub async fn get_run(pool: &SqlitePool, run_id: &str) -> Result<Option<SimulationRun>> {
+    let row = sqlx::query_as::<_, RunRow>(
+        r#"
+        SELECT r.run_id, r.scenario, r.parameters, r.started_at, r.ended_at,
+            (SELECT COUNT(*) FROM messages m WHERE m.run_id = r.run_id) AS message_count
+        FROM runs r
+        WHERE r.run_id = ?
+        "#,
     )
-    .execute(&pool)
+    .bind(run_id)
+    .fetch_optional(pool)
     .await?;
 
-    Ok(pool)
+    row.map(RunRow::into_run).transpose()
 }
 
-/// Insert a new cross-chain message.
-pub async fn insert_message(
-    pool: &SqlitePool,
-    nonce: u64,
-    trace_id: &str,
-    sender: &str,
-    amount: &str,
-    payload: &str,
-    deadline: i64,
-    description: Option<&str>,
-) -> Result<()> {
+#[derive(Debug, sqlx::FromRow)]
+struct RunRow {
+    run_id: String,
+    scenario: String,
+    parameters: String,
+    started_at: String,
+    ended_at: Option<String>,
+    message_count: i64,
+}
+
+impl RunRow {
+    fn into_run(self) -> Result<SimulationRun> {
+        Ok(SimulationRun {
+            run_id: self.run_id,
+            scenario: self.scenario,
+            parameters: serde_json::from_str(&self.parameters)?,
+            started_at: self.started_at,
+            ended_at: self.ended_at,
+            message_count: self.message_count,
+        })
+    }
+}
+
+/// Record a gas reading (periodic sample, or taken for a settlement when `nonce` is set).
+pub async fn insert_gas_reading(pool: &SqlitePool, reading: &GasReading) -> Result<()> {
     sqlx::query(
         r#"
-        INSERT OR IGNORE INTO messages (nonce, trace_id, sender, amount, payload, deadline, description, state)
-        VALUES (?, ?, ?, ?, ?, ?, ?, 'observed')
+        INSERT INTO gas_readings (source, base_fee_gwei, priority_fee_gwei, max_fee_gwei, block_number, nonce, observed_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
         "#,
     )
-    .bind(nonce as i64)
-    .bind(trace_id)
-    .bind(sender)
-    .bind(amount)
-    .bind(payload)
-    .bind(deadline)
-    .bind(description)
+    .bind(reading.source.to_string())
+    .bind(reading.base_fee_gwei)
+    .bind(reading.priority_fee_gwei)
+    .bind(reading.max_fee_gwei)
+    .bind(reading.block_number.map(|b| b as i64))
+    .bind(reading.nonce.map(|n| n as i64))
+    .bind(&reading.observed_at)
     .execute(pool)
     .await?;
-
     Ok(())
 }
 
-/// Update message state with optional fields.
-pub async fn update_message_state(
-    pool: &SqlitePool,
-    nonce: u64,
-    new_state: MessageState,
-    result: Option<&str>,
-    solana_sig: Option<&str>,
-    eth_settle_tx: Option<&str>,
-    error_msg: Option<&str>,
-) -> Result<()> {
-    sqlx::query(
+/// Most recent gas readings, newest first. `settlements` selects readings taken
+/// for settlements instead of periodic samples.
+pub async fn get_gas_readings(pool: &SqlitePool, settlements: bool, limit: i64) -> Result<Vec<GasReading>> {
+    let rows = sqlx::query_as::<_, GasReadingRow>(
         r#"
-        UPDATE messages SET
-            state = ?,
-            result = COALESCE(?, result),
-            solana_signature = COALESCE(?, solana_signature),
-            eth_settle_tx = COALESCE(?, eth_settle_tx),
-            error_message = COALESCE(?, error_message),
-            updated_at = datetime('now')
-        WHERE nonce = ?
+        SELECT source, base_fee_gwei, priority_fee_gwei, max_fee_gwei, block_number, nonce, observed_at
+        FROM gas_readings
+        WHERE (nonce IS NOT NULL) = ?
+        ORDER BY id DESC
+        LIMIT ?
         "#,
     )
-    .bind(new_state.to_string())
-    .bind(result)
-    .bind(solana_sig)
-    .bind(eth_settle_tx)
-    .bind(error_msg)
-    .bind(nonce as i64)
-    .execute(pool)
+    .bind(settlements)
+    .bind(limit)
+    .fetch_all(pool)
     .await?;
 
+    rows.into_iter().map(GasReadingRow::into_reading).collect()
+}
+
+/// Drop periodic samples older than `days`. Settlement readings are kept.
+pub async fn prune_gas_readings(pool: &SqlitePool, days: i64) -> Result<()> {
+    sqlx::query("DELETE FROM gas_readings WHERE nonce IS NULL AND observed_at < ?")
+        .bind((chrono::Utc::now() - chrono::Duration::days(days)).to_rfc3339())
+        .execute(pool)
+        .await?;
     Ok(())
 }
 
-/// Store the proof bundle JSON for a message.
-pub async fn store_proof(pool: &SqlitePool, nonce: u64, proof_json: &str) -> Result<()> {
+/// A message backed by an Ethereum escrow that is neither settled nor refunded.
+#[derive(Debug, Clone)]
+pub struct OpenEscrowMessage {
+    pub nonce: u64,
+    pub amount: String,
+    pub state: String,
+    /// The refunder found its escrow already released on chain
+    pub refund_skipped: bool,
+}
+
+/// Escrow-backed messages the database has not settled or refunded, by nonce.
+pub async fn get_open_escrow_messages(pool: &SqlitePool) -> Result<Vec<OpenEscrowMessage>> {
+    let rows: Vec<(i64, String, String, bool)> = sqlx::query_as(
+        r#"
+        SELECT nonce, amount, state, refund_skipped IS NOT NULL
+        FROM messages
+        WHERE src_chain_id = ? AND behavior_json IS NULL AND state NOT IN ('settled', 'refunded')
+        ORDER BY nonce ASC
+        "#,
+    )
+    .bind(ETHEREUM_CHAIN_ID)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|(nonce, amount, state, refund_skipped)| OpenEscrowMessage {
+            nonce: nonce as u64,
+            amount,
+            state,
+            refund_skipped,
+        })
+        .collect())
+}
+
+pub async fn insert_escrow_snapshot(pool: &SqlitePool, snapshot: &EscrowSnapshot) -> Result<()> {
     sqlx::query(
-        "UPDATE messages SET proof_json = ?, updated_at = datetime('now') WHERE nonce = ?",
+        r#"
+        INSERT INTO escrow_snapshots (balance_wei, locked_wei, locked_escrows, unread_escrows, db_pending_wei, db_pending_messages, block_number, observed_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
     )
-    .bind(proof_json)
-    .bind(nonce as i64)
+    .bind(&snapshot.balance_wei)
+    .bind(&snapshot.locked_wei)
+    .bind(snapshot.locked_escrows as i64)
+    .bind(snapshot.unread_escrows as i64)
+    .bind(&snapshot.db_pending_wei)
+    .bind(snapshot.db_pending_messages as i64)
+    .bind(snapshot.block_number.map(|b| b as i64))
+    .bind(&snapshot.observed_at)
     .execute(pool)
     .await?;
     Ok(())
 }
 
-/// Increment retry count for a message.
-pub async fn increment_retry(pool: &SqlitePool, nonce: u64) -> Result<()> {
-    sqlx::query(
-        "UPDATE messages SET retry_count = retry_count + 1, updated_at = datetime('now') WHERE nonce = ?",
+/// Most recent escrow snapshots, newest first.
+pub async fn get_escrow_snapshots(pool: &SqlitePool, limit: i64) -> Result<Vec<EscrowSnapshot>> {
+    let rows = sqlx::query_as::<_, EscrowSnapshotRow>(
+        r#"
+        SELECT balance_wei, locked_wei, locked_escrows, unread_escrows, db_pending_wei, db_pending_messages, block_number, observed_at
+        FROM escrow_snapshots
+        ORDER BY id DESC
+        LIMIT ?
+        "#,
     )
-    .bind(nonce as i64)
-    .execute(pool)
+    .bind(limit)
+    .fetch_all(pool)
     .await?;
 
+    Ok(rows.into_iter().map(EscrowSnapshotRow::into_snapshot).collect())
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct EscrowSnapshotRow {
+    balance_wei: String,
+    locked_wei: String,
+    locked_escrows: i64,
+    unread_escrows: i64,
+    db_pending_wei: String,
+    db_pending_messages: i64,
+    block_number: Option<i64>,
+    observed_at: String,
+}
+
+impl EscrowSnapshotRow {
+    fn into_snapshot(self) -> EscrowSnapshot {
+        EscrowSnapshot {
+            balance_wei: self.balance_wei,
+            locked_wei: self.locked_wei,
+            locked_escrows: self.locked_escrows as u64,
+            unread_escrows: self.unread_escrows as u64,
+            db_pending_wei: self.db_pending_wei,
+            db_pending_messages: self.db_pending_messages as u64,
+            block_number: self.block_number.map(|b| b as u64),
+            observed_at: self.observed_at,
+        }
+    }
+}
+
+/// Drop escrow snapshots older than `days`.
+pub async fn prune_escrow_snapshots(pool: &SqlitePool, days: i64) -> Result<()> {
+    sqlx::query("DELETE FROM escrow_snapshots WHERE observed_at < ?")
+        .bind((chrono::Utc::now() - chrono::Duration::days(days)).to_rfc3339())
+        .execute(pool)
+        .await?;
     Ok(())
 }
 
-/// Get all messages in a given state (for crash-safe resume).
-pub async fn get_messages_by_state(
-    pool: &SqlitePool,
-    state: MessageState,
-) -> Result<Vec<CrossChainMessage>> {
-    let state_str = state.to_string();
+#[derive(Debug, sqlx::FromRow)]
+struct GasReadingRow {
+    source: String,
+    base_fee_gwei: f64,
+    priority_fee_gwei: f64,
+    max_fee_gwei: f64,
+    block_number: Option<i64>,
+    nonce: Option<i64>,
+    observed_at: String,
+}
+
+impl GasReadingRow {
+    fn into_reading(self) -> Result<GasReading> {
+        Ok(GasReading {
+            source: self.source.parse()?,
+            base_fee_gwei: self.base_fee_gwei,
+            priority_fee_gwei: self.priority_fee_gwei,
+            max_fee_gwei: self.max_fee_gwei,
+            block_number: self.block_number.map(|b| b as u64),
+            nonce: self.nonce.map(|n| n as u64),
+            observed_at: self.observed_at,
+        })
+    }
+}
+
+/// Check if a nonce already exists (for idempotency).
+pub async fn nonce_exists(pool: &SqlitePool, nonce: u64) -> Result<bool> {
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM messages WHERE nonce = ?")
+        .bind(nonce as i64)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(count > 0)
+}
+
+/// Where the last export to `destination` stopped: the highest event
+/// sequence number and message `updated_at` it covered.
+pub async fn get_export_cursor(pool: &SqlitePool, destination: &str) -> Result<Option<(u64, Option<String>)>> {
+    let row: Option<(i64, Option<String>)> = sqlx::query_as(
+        "SELECT last_seq, messages_watermark FROM exports WHERE destination = ? ORDER BY id DESC LIMIT 1",
+    )
+    .bind(destination)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row.map(|(seq, watermark)| (seq as u64, watermark)))
+}
+
+/// Latest `updated_at` of any message.
+pub async fn get_messages_watermark(pool: &SqlitePool) -> Result<Option<String>> {
+    let (watermark,): (Option<String>,) = sqlx::query_as("SELECT MAX(updated_at) FROM messages")
+        .fetch_one(pool)
+        .await?;
+    Ok(watermark)
+}
+
+/// Days (`YYYY-MM-DD`, by `created_at`) with a message updated at or after
+/// `since`, or all days when `since` is None.
+pub async fn get_message_days_updated_since(pool: &SqlitePool, since: Option<&str>) -> Result<Vec<String>> {
+    let days: Vec<(String,)> = sqlx::query_as(
+        r#"
+        SELECT DISTINCT date(created_at) AS day
+        FROM messages
+        WHERE ? IS NULL OR updated_at >= ?
+        ORDER BY day
+        "#,
+    )
+    .bind(since)
+    .bind(since)
+    .fetch_all(pool)
+    .await?;
+    Ok(days.into_iter().map(|(day,)| day).collect())
+}
+
+/// Messages created on `day` (`YYYY-MM-DD`), by nonce.
+pub async fn get_messages_created_on(pool: &SqlitePool, day: &str) -> Result<Vec<CrossChainMessage>> {
     let rows = sqlx::query_as::<_, CrossChainMessage>(
         r#"
         SELECT
             id, nonce, trace_id, sender, amount, payload, deadline,
-            description, state, result, solana_signature, eth_settle_tx, proof_json,
-            retry_count, error_message, created_at, updated_at
+            description, state, result, solana_signature, eth_settle_tx, refund_tx, proof_json,
+            retry_count, error_message, run_id, src_chain_id, dst_chain_id, payload_type, computation, behavior_json,
+            settle_gas_used, settle_gas_price_wei, settle_block_number, settle_cost_wei,
+            created_at, updated_at
         FROM messages
-        WHERE state = ?
+        WHERE date(created_at) = ?
         ORDER BY nonce ASC
         "#,
     )
-    .bind(&state_str)
+    .bind(day)
     .fetch_all(pool)
     .await?;
-
     Ok(rows)
 }
 
-/// Get a single message by nonce.
-pub async fn get_message_by_nonce(
-    pool: &SqlitePool,
-    nonce: u64,
-) -> Result<Option<CrossChainMessage>> {
-    let row = sqlx::query_as::<_, CrossChainMessage>(
+/// Events timestamped on `day` (`YYYY-MM-DD`), in sequence order.
+pub async fn get_events_on(pool: &SqlitePool, day: &str) -> Result<Vec<crate::event::LifecycleEvent>> {
+    let rows = sqlx::query_as::<_, EventRow>(
         r#"
-        SELECT
-            id, nonce, trace_id, sender, amount, payload, deadline,
-            description, state, result, solana_signature, eth_settle_tx, proof_json,
-            retry_count, error_message, created_at, updated_at
-        FROM messages
-        WHERE nonce = ?
+        SELECT id, trace_id, nonce, actor, actor_chain_id, step, status, detail, timestamp, src_chain_id, dst_chain_id, schema_version,
+               fee_wei, fee_lamports, gas_used
+        FROM events
+        WHERE substr(timestamp, 1, 10) = ?
+        ORDER BY id ASC
         "#,
     )
-    .bind(nonce as i64)
-    .fetch_optional(pool)
+    .bind(day)
+    .fetch_all(pool)
     .await?;
 
-    Ok(row)
+    Ok(events_from_rows(rows))
 }
 
-/// Get all messages ordered by nonce descending.
-pub async fn get_all_messages(pool: &SqlitePool) -> Result<Vec<CrossChainMessage>> {
-    let rows = sqlx::query_as::<_, CrossChainMessage>(
+/// Days before `today` with messages or events but no anchored notarization.
+pub async fn get_unnotarized_days(pool: &SqlitePool, today: &str) -> Result<Vec<String>> {
+    let days: Vec<(String,)> = sqlx::query_as(
         r#"
-        SELECT
-            id, nonce, trace_id, sender, amount, payload, deadline,
-            description, state, result, solana_signature, eth_settle_tx, proof_json,
-            retry_count, error_message, created_at, updated_at
-        FROM messages
-        ORDER BY nonce DESC
+        SELECT day FROM (
+            SELECT date(created_at) AS day FROM messages
+            UNION
+            SELECT substr(timestamp, 1, 10) AS day FROM events
+        )
+        WHERE day < ?
+          AND day NOT IN (SELECT day FROM notarizations WHERE anchor_tx IS NOT NULL)
+        ORDER BY day
         "#,
     )
+    .bind(today)
     .fetch_all(pool)
     .await?;
-
-    Ok(rows)
+    Ok(days.into_iter().map(|(day,)| day).collect())
 }
 
-/// Get metrics aggregate (single query).
-pub async fn get_metrics(pool: &SqlitePool) -> Result<(i64, i64, i64, i64, i64)> {
-    let row: (i64, i64, i64, i64, i64) = sqlx::query_as(
+/// Store a day's roots, replacing those of an earlier unanchored attempt.
+pub async fn upsert_notarization(pool: &SqlitePool, day: &str, roots: &crate::notary::DayRoots) -> Result<()> {
+    sqlx::query(
         r#"
-        SELECT
-            COUNT(*) AS total,
-            SUM(CASE WHEN state = 'settled' THEN 1 ELSE 0 END) AS settled,
-            SUM(CASE WHEN state IN ('failed', 'rolled_back') THEN 1 ELSE 0 END) AS failed,
-            SUM(CASE WHEN state NOT IN ('settled', 'failed', 'rolled_back') THEN 1 ELSE 0 END) AS pending,
-            COALESCE(SUM(retry_count), 0) AS retries
-        FROM messages
+        INSERT INTO notarizations (day, messages, events, messages_root, events_root, root)
+        VALUES (?, ?, ?, ?, ?, ?)
+        ON CONFLICT(day) DO UPDATE SET
+            messages = excluded.messages,
+            events = excluded.events,
+            messages_root = excluded.messages_root,
+            events_root = excluded.events_root,
+            root = excluded.root,
+            created_at = datetime('now')
+        WHERE notarizations.anchor_tx IS NULL
         "#,
     )
-    .fetch_one(pool)
+    .bind(day)
+    .bind(roots.messages)
+    .bind(roots.events)
+    .bind(&roots.messages_root)
+    .bind(&roots.events_root)
+    .bind(&roots.root)
+    .execute(pool)
     .await?;
-
-    Ok(row)
+    Ok(())
 }
 
-/// Persist a lifecycle event.
-pub async fn insert_event(
+/// Record the outcome of anchoring a day's root: its transaction, or why it failed.
+pub async fn record_notarization_anchor(
     pool: &SqlitePool,
-    nonce: u64,
-    trace_id: &str,
-    actor: &str,
-    step: &str,
-    status: &str,
-    detail: Option<&str>,
-    timestamp: &str,
+    day: &str,
+    anchor_tx: Option<&str>,
+    anchor_block: Option<u64>,
+    anchor_error: Option<&str>,
 ) -> Result<()> {
     sqlx::query(
         r#"
-        INSERT INTO events (nonce, trace_id, actor, step, status, detail, timestamp)
-        VALUES (?, ?, ?, ?, ?, ?, ?)
+        UPDATE notarizations
+        SET anchor_tx = ?, anchor_block = ?, anchor_error = ?,
+            anchored_at = CASE WHEN ? IS NOT NULL THEN datetime('now') END
+        WHERE day = ?
         "#,
     )
-    .bind(nonce as i64)
-    .bind(trace_id)
-    .bind(actor)
-    .bind(step)
-    .bind(status)
-    .bind(detail)
-    .bind(timestamp)
+    .bind(anchor_tx)
+    .bind(anchor_block.map(|b| b as i64))
+    .bind(anchor_error)
+    .bind(anchor_tx)
+    .bind(day)
     .execute(pool)
     .await?;
-
     Ok(())
 }
 
-/// Get events for a given nonce.
-pub async fn get_events_by_nonce(
-    pool: &SqlitePool,
-    nonce: u64,
-) -> Result<Vec<crate::event::LifecycleEvent>> {
-    let rows = sqlx::query_as::<_, EventRow>(
+pub async fn get_notarization(pool: &SqlitePool, day: &str) -> Result<Option<crate::notary::Notarization>> {
+    let row = sqlx::query_as::<_, crate::notary::Notarization>(
         r#"
-        SELECT trace_id, nonce, actor, step, status, detail, timestamp
-        FROM events
-        WHERE nonce = ?
-        ORDER BY id ASC
+        SELECT id, day, messages, events, messages_root, events_root, root, anchor_tx, anchor_block, anchor_error, created_at, anchored_at
+        FROM notarizations
+        WHERE day = ?
         "#,
     )
-    .bind(nonce as i64)
-    .fetch_all(pool)
+    .bind(day)
+    .fetch_optional(pool)
     .await?;
-
-    Ok(rows
-        .into_iter()
-        .map(|r| crate::event::LifecycleEvent {
-            trace_id: r.trace_id,
-            nonce: r.nonce as u64,
-            actor: match r.actor.as_str() {
-                "ethereum" => crate::event::Actor::Ethereum,
-                "solana" => crate::event::Actor::Solana,
-                "dashboard" => crate::event::Actor::Dashboard,
-                _ => crate::event::Actor::Relayer,
-            },
-            step: match r.step.as_str() {
-                "locked" => crate::event::Step::Locked,
-                "observed" => crate::event::Step::Observed,
-                "verified" => crate::event::Step::Verified,
-                "executed" => crate::event::Step::Executed,
-                "minted" => crate::event::Step::Minted,
-                "burned" => crate::event::Step::Burned,
-                _ => crate::event::Step::Settled,
-            },
-            status: match r.status.as_str() {
-                "failure" => crate::event::Status::Failure,
-                "retry" => crate::event::Status::Retry,
-                _ => crate::event::Status::Success,
-            },
-            timestamp: r.timestamp,
-            detail: r.detail,
-        })
-        .collect())
+    Ok(row)
 }
 
-#[derive(Debug, sqlx::FromRow)]
-struct EventRow {
-    trace_id: String,
-    nonce: i64,
-    actor: String,
-    step: String,
-    status: String,
-    detail: Option<String>,
-    timestamp: String,
+/// Most recent notarizations first.
+pub async fn list_notarizations(pool: &SqlitePool, limit: i64) -> Result<Vec<crate::notary::Notarization>> {
+    let rows = sqlx::query_as::<_, crate::notary::Notarization>(
+        r#"
+        SELECT id, day, messages, events, messages_root, events_root, root, anchor_tx, anchor_block, anchor_error, created_at, anchored_at
+        FROM notarizations
+        ORDER BY day DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
 }
 
-/// Delete all messages and events (clear demo data).
-pub async fn clear_all_data(pool: &SqlitePool) -> Result<()> {
-    sqlx::query("DELETE FROM events").execute(pool).await?;
-    sqlx::query("DELETE FROM messages").execute(pool).await?;
-    Ok(())
+/// Record a completed export. Returns its id.
+pub async fn insert_export(
+    pool: &SqlitePool,
+    summary: &crate::export::ExportSummary,
+    messages_watermark: Option<&str>,
+) -> Result<i64> {
+    let id = sqlx::query(
+        "INSERT INTO exports (destination, last_seq, messages_watermark, summary_json) VALUES (?, ?, ?, ?)",
+    )
+    .bind(&summary.destination)
+    .bind(summary.last_seq as i64)
+    .bind(messages_watermark)
+    .bind(serde_json::to_string(summary)?)
+    .execute(pool)
+    .await?
+    .last_insert_rowid();
+    Ok(id)
 }
 
-/// Check if a nonce already exists (for idempotency).
-pub async fn nonce_exists(pool: &SqlitePool, nonce: u64) -> Result<bool> {
-    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM messages WHERE nonce = ?")
-        .bind(nonce as i64)
-        .fetch_one(pool)
+/// Most recent exports, newest first.
+pub async fn get_exports(pool: &SqlitePool, limit: i64) -> Result<Vec<crate::export::ExportSummary>> {
+    let rows: Vec<(i64, String)> = sqlx::query_as("SELECT id, summary_json FROM exports ORDER BY id DESC LIMIT ?")
+        .bind(limit)
+        .fetch_all(pool)
         .await?;
+    rows.into_iter()
+        .map(|(id, json)| {
+            let mut summary: crate::export::ExportSummary = serde_json::from_str(&json)?;
+            summary.id = id;
+            Ok(summary)
+        })
+        .collect()
+}
 
-    Ok(count > 0)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn test_pool(name: &str) -> SqlitePool {
+        let path = std::env::temp_dir().join(format!("relayer-db-{}-{}.db", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        init_db(&format!("sqlite:{}?mode=rwc", path.display())).await.unwrap()
+    }
+
+    async fn insert(pool: &SqlitePool, nonce: u64) {
+        let envelope = MessageEnvelope::new(ETHEREUM_CHAIN_ID, SOLANA_CHAIN_ID, nonce, Vec::new());
+        let trace_id = format!("trace-{}", nonce);
+        let inserted = insert_message(
+            pool,
+            &envelope,
+            &trace_id,
+            "0x0000000000000000000000000000000000000000",
+            "1000",
+            0,
+            None,
+            None,
+            0,
+            Computation::Double,
+            None,
+        )
+        .await
+        .unwrap();
+        assert!(matches!(inserted, Inserted::New));
+    }
+
+    async fn step(pool: &SqlitePool, nonce: u64, from: MessageState, to: MessageState, reason: &str) -> Result<()> {
+        update_message_state(pool, nonce, from, to, None, None, None, None, reason).await
+    }
+
+    #[tokio::test]
+    async fn transitions_record_retries_and_lost_races() {
+        use MessageState::*;
+        let pool = test_pool("transitions").await;
+        insert(&pool, 1).await;
+
+        step(&pool, 1, Observed, Persisted, "persisted").await.unwrap();
+        step(&pool, 1, Persisted, Verified, "proof verified").await.unwrap();
+        increment_retry(&pool, 1, Verified, "retry: timeout: no answer").await.unwrap();
+        step(&pool, 1, Verified, RolledBack, "rolled back").await.unwrap();
+        // A writer that still thinks the message is verified loses
+        let lost = step(&pool, 1, Verified, SentToSolana, "sent to Solana").await.unwrap_err();
+        assert!(lost.downcast_ref::<StateConflict>().is_some());
+
+        let msg = get_message_by_nonce(&pool, 1).await.unwrap().unwrap();
+        assert_eq!(msg.state, "rolled_back");
+        assert_eq!(msg.retry_count, 1);
+
+        let path: Vec<_> = get_state_transitions(&pool, 1)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| (t.from_state, t.to_state, t.reason, t.applied))
+            .collect();
+        let row = |from: Option<&str>, to: &str, reason: &str, applied| {
+            (from.map(String::from), to.to_string(), reason.to_string(), applied)
+        };
+        assert_eq!(
+            path,
+            vec![
+                row(None, "observed", "observed", true),
+                row(Some("observed"), "persisted", "persisted", true),
+                row(Some("persisted"), "verified", "proof verified", true),
+                row(Some("verified"), "verified", "retry: timeout: no answer", true),
+                row(Some("verified"), "rolled_back", "rolled back", true),
+                row(Some("rolled_back"), "sent_to_solana", "sent to Solana (expected verified)", false),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn execution_totals_count_unparsable_amounts_apart() {
+        let pool = test_pool("execution-totals").await;
+        for (nonce, amount) in [(1, "1000"), (2, "18446744073709551616"), (3, "not a number")] {
+            insert(&pool, nonce).await;
+            sqlx::query("UPDATE messages SET solana_signature = 'sig', amount = ? WHERE nonce = ?")
+                .bind(amount)
+                .bind(nonce as i64)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        // Never executed on Solana
+        insert(&pool, 4).await;
+
+        assert_eq!(
+            solana_execution_totals(&pool).await.unwrap(),
+            SolanaExecutionTotals { executions: 3, total_amount: 1000, unparsable_amounts: 2 }
+        );
+    }
+
+    #[tokio::test]
+    async fn rollbacks_count_refunded_messages_once() {
+        use crate::event::{Actor, LifecycleEvent, Status, Step};
+        let pool = test_pool("rollbacks").await;
+        for nonce in 1..=3 {
+            insert(&pool, nonce).await;
+        }
+        sqlx::query("UPDATE messages SET run_id = 'run'").execute(&pool).await.unwrap();
+        // Nonce 1 emits the failure and success pair, nonce 2 was refunded since
+        for (nonce, status) in [(1, Status::Failure), (1, Status::Success), (2, Status::Success)] {
+            let event = LifecycleEvent::new(&format!("trace-{}", nonce), nonce, Actor::relayer(), Step::Rollback, status);
+            insert_event(&pool, &event).await.unwrap();
+        }
+        sqlx::query("UPDATE messages SET state = 'refunded' WHERE nonce = 2").execute(&pool).await.unwrap();
+
+        assert_eq!(count_rolled_back(&pool, "run").await.unwrap(), 2);
+        assert_eq!(count_rolled_back(&pool, "other").await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn admin_changes_are_found_by_day() {
+        use crate::event::{Actor, LifecycleEvent, Status, Step};
+        let pool = test_pool("admin-changes").await;
+        let today = chrono::Utc::now().date_naive().to_string();
+        for nonce in 1..=2 {
+            insert(&pool, nonce).await;
+            let event = LifecycleEvent::new(&format!("trace-{}", nonce), nonce, Actor::ethereum(), Step::Locked, Status::Success);
+            insert_event(&pool, &event).await.unwrap();
+        }
+        sqlx::query("INSERT INTO audit_log (action, target, detail) VALUES ('reparse_transaction', '1', '[]')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        purge_message(&pool, 2, Some("test")).await.unwrap().unwrap();
+
+        let actions: Vec<_> = get_admin_changes_on(&pool, &today)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.action, e.target))
+            .collect();
+        assert_eq!(
+            actions,
+            vec![("reparse_transaction".into(), "1".into()), ("purge_transaction".into(), "2".into())]
+        );
+        assert!(get_admin_changes_on(&pool, "2000-01-01").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn purge_removes_the_message_trace_conflicts() {
+        let pool = test_pool("purge-conflicts").await;
+        insert(&pool, 1).await;
+        insert(&pool, 2).await;
+        for (nonce, existing) in [(10, 1), (11, 2)] {
+            record_trace_conflict(&pool, "trace", nonce, existing, "0x00", "1", None, None).await.unwrap();
+        }
+
+        let summary = purge_message(&pool, 1, None).await.unwrap().unwrap();
+        assert_eq!(summary.trace_conflicts, 1);
+        let left: Vec<_> = get_trace_conflicts(&pool, 10).await.unwrap().into_iter().map(|c| c.nonce).collect();
+        assert_eq!(left, vec![11]);
+    }
 }
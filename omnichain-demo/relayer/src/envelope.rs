@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::types::{ChainConfig, ChainKind};
+
+/// Chain identifiers of the built-in chains, following LayerZero v2 endpoint ids.
+/// Further chains are added through the registry (`/chains`).
+pub const ETHEREUM_CHAIN_ID: u32 = 30101;
+pub const SOLANA_CHAIN_ID: u32 = 30168;
+
+/// Chain-agnostic message envelope. The chain ids say where a message goes;
+/// it is identified by its nonce alone, which is unique across routes
+/// (`messages.nonce` is UNIQUE, and every API and table keys on it).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageEnvelope {
+    pub src_chain_id: u32,
+    pub dst_chain_id: u32,
+    pub nonce: u64,
+    pub payload: Vec<u8>,
+}
+
+impl MessageEnvelope {
+    pub fn new(src_chain_id: u32, dst_chain_id: u32, nonce: u64, payload: Vec<u8>) -> Self {
+        Self {
+            src_chain_id,
+            dst_chain_id,
+            nonce,
+            payload,
+        }
+    }
+}
+
+/// Source → destination adapter pairs the relayer knows how to deliver.
+/// The state machine dispatches verify/execute/settle on this.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Route {
+    /// Escrow lock on an EVM chain, (simulated) execution on Solana, settlement back on the EVM chain
+    EthereumToSolana,
+}
+
+/// Outcome of looking up a message's route in the chain registry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RouteDecision {
+    Deliver(Route),
+    /// A chain on the route is disabled; leave the message pending
+    Hold(String),
+    /// Unregistered chain or no adapter for the kinds involved
+    Reject(String),
+}
+
+impl Route {
+    /// Resolve by chain kind, so any registered chain of a supported kind
+    /// is routable without code changes.
+    pub fn resolve(src: &ChainConfig, dst: &ChainConfig) -> Option<Self> {
+        match (src.kind, dst.kind) {
+            (ChainKind::Evm, ChainKind::Solana) => Some(Self::EthereumToSolana),
+            _ => None,
+        }
+    }
+
+    /// Consult the registry for the `(src_chain_id, dst_chain_id)` pair.
+    pub fn decide(chains: &HashMap<u32, ChainConfig>, src_chain_id: u32, dst_chain_id: u32) -> RouteDecision {
+        let (Some(src), Some(dst)) = (chains.get(&src_chain_id), chains.get(&dst_chain_id)) else {
+            let missing = if chains.contains_key(&src_chain_id) { dst_chain_id } else { src_chain_id };
+            return RouteDecision::Reject(format!("Chain {} is not registered", missing));
+        };
+
+        if let Some(disabled) = [src, dst].into_iter().find(|c| !c.enabled) {
+            return RouteDecision::Hold(format!("Chain {} ({}) is disabled", disabled.name, disabled.chain_id));
+        }
+
+        match Self::resolve(src, dst) {
+            Some(route) => RouteDecision::Deliver(route),
+            None => RouteDecision::Reject(format!(
+                "No adapter from {} ({}, {}) to {} ({}, {})",
+                src.name, src.chain_id, src.kind, dst.name, dst.chain_id, dst.kind
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for Route {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EthereumToSolana => write!(f, "ethereum→solana"),
+        }
+    }
+}
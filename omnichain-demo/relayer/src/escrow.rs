@@ -0,0 +1,196 @@
+use anyhow::Result;
+use ethers::types::U256;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::time::{sleep, Duration};
+use tracing::{debug, info, warn};
+
+use crate::db;
+use crate::eth::{self, EscrowState};
+use crate::types::AppState;
+
+/// Snapshots older than this are pruned.
+const HISTORY_RETENTION_DAYS: i64 = 7;
+
+/// Escrows read from the contract per snapshot (two `eth_call`s each); the
+/// rest keep the amount from their last read until their turn comes.
+const MAX_READS_PER_SAMPLE: usize = 100;
+
+/// Value held by the escrow contract at one point (`escrow_snapshots` table).
+/// Amounts are wei, as decimal strings.
+#[derive(Debug, Clone, Serialize)]
+pub struct EscrowSnapshot {
+    /// ETH balance of the contract
+    pub balance_wei: String,
+    /// Sum of the escrows the database holds open that are still locked on
+    /// chain, as of each escrow's last read
+    pub locked_wei: String,
+    pub locked_escrows: u64,
+    /// Open escrows not read from the contract yet, so missing from `locked_wei`
+    pub unread_escrows: u64,
+    /// Escrow-backed messages the database has not settled or refunded
+    pub db_pending_wei: String,
+    pub db_pending_messages: u64,
+    pub block_number: Option<u64>,
+    pub observed_at: String,
+}
+
+/// An escrow still locked on chain.
+#[derive(Debug, Clone, Serialize)]
+pub struct LockedEscrow {
+    pub nonce: u64,
+    pub amount_wei: String,
+    /// The message's state in the database
+    pub state: String,
+}
+
+/// Latest snapshot and the escrows behind it.
+#[derive(Debug, Clone)]
+pub struct EscrowReading {
+    pub snapshot: EscrowSnapshot,
+    pub locked: Vec<LockedEscrow>,
+}
+
+/// Response for GET /escrow/balance
+#[derive(Debug, Serialize)]
+pub struct EscrowBalanceResponse {
+    /// Latest snapshot, `None` until Ethereum has answered
+    pub current: Option<EscrowSnapshot>,
+    /// Escrows locked at the latest snapshot, by nonce
+    pub locked: Vec<LockedEscrow>,
+    /// Stored snapshots, newest first
+    pub history: Vec<EscrowSnapshot>,
+}
+
+/// Query for GET /escrow/balance
+#[derive(Debug, Deserialize)]
+pub struct EscrowBalanceQuery {
+    /// History entries (default 100, capped at 1000)
+    #[serde(default = "default_history_limit")]
+    pub limit: i64,
+}
+
+fn default_history_limit() -> i64 {
+    100
+}
+
+/// Snapshot the escrow contract every `ESCROW_SAMPLE_INTERVAL_SECS` for
+/// GET /escrow/balance.
+pub async fn run_escrow_sampler(state: Arc<AppState>) {
+    let interval_secs = state.config.escrow_sample_interval_secs;
+    if interval_secs == 0 {
+        info!("Escrow sampler disabled");
+        return;
+    }
+
+    info!(interval_secs, "Escrow sampler started");
+    let mut known = Known::default();
+    loop {
+        match read(&state, &mut known).await {
+            Ok(reading) => {
+                if let Err(e) = db::insert_escrow_snapshot(&state.pool, &reading.snapshot).await {
+                    warn!(error = %e, "Failed to record escrow snapshot");
+                }
+                *state.escrow.lock().unwrap() = Some(reading);
+            }
+            Err(e) => debug!(error = %e, "Escrow snapshot failed"),
+        }
+        if let Err(e) = db::prune_escrow_snapshots(&state.pool, HISTORY_RETENTION_DAYS).await {
+            warn!(error = %e, "Failed to prune escrow snapshots");
+        }
+
+        sleep(Duration::from_secs(interval_secs)).await;
+    }
+}
+
+/// What the sampler has read from the contract, carried between snapshots.
+#[derive(Default)]
+struct Known {
+    /// Amount of each escrow found locked at its last read
+    locked: HashMap<u64, U256>,
+    /// Settled, reclaimed or missing on chain: never locked again
+    released: HashSet<u64>,
+    /// Reads resume from this nonce, so every escrow gets its turn
+    cursor: u64,
+}
+
+async fn read(state: &AppState, known: &mut Known) -> Result<EscrowReading> {
+    let cfg = &state.config;
+    let balance = eth::get_balance(&cfg.eth_rpc_url, &cfg.escrow_address).await?;
+    let block_number = eth::get_block_number(&cfg.eth_rpc_url).await.ok();
+
+    // Only messages the database holds open: the rest are done with their escrow
+    let open = db::get_open_escrow_messages(&state.pool).await?;
+    let mut db_pending_wei = 0u128;
+    for msg in &open {
+        db_pending_wei += msg.amount.parse::<u128>().unwrap_or(0);
+        // The refunder found the escrow settled, reclaimed or missing
+        if msg.refund_skipped {
+            known.released.insert(msg.nonce);
+        }
+    }
+    let open_nonces: HashSet<u64> = open.iter().map(|m| m.nonce).collect();
+    known.locked.retain(|nonce, _| open_nonces.contains(nonce));
+    known.released.retain(|nonce| open_nonces.contains(nonce));
+
+    // Read a batch, starting at the cursor and wrapping around
+    let mut candidates: Vec<u64> = open
+        .iter()
+        .map(|m| m.nonce)
+        .filter(|nonce| !known.released.contains(nonce))
+        .collect();
+    let split = candidates.partition_point(|&nonce| nonce < known.cursor);
+    candidates.rotate_left(split);
+    candidates.truncate(MAX_READS_PER_SAMPLE);
+    for &nonce in &candidates {
+        // An RPC error ends this round; what was read so far is kept
+        let escrow = match eth::get_escrows(&cfg.eth_rpc_url, &cfg.escrow_address, &[nonce]).await {
+            Ok(escrows) => escrows[0],
+            Err(e) => {
+                debug!(nonce, error = %e, "Escrow read failed");
+                break;
+            }
+        };
+        if escrow.state == EscrowState::Locked {
+            known.locked.insert(nonce, escrow.amount);
+        } else {
+            known.locked.remove(&nonce);
+            known.released.insert(nonce);
+        }
+        known.cursor = nonce + 1;
+    }
+
+    let mut locked = Vec::new();
+    let mut locked_wei = U256::zero();
+    let mut unread_escrows = 0;
+    for msg in &open {
+        if known.released.contains(&msg.nonce) {
+            continue;
+        }
+        let Some(amount) = known.locked.get(&msg.nonce) else {
+            unread_escrows += 1;
+            continue;
+        };
+        locked_wei += *amount;
+        locked.push(LockedEscrow {
+            nonce: msg.nonce,
+            amount_wei: amount.to_string(),
+            state: msg.state.clone(),
+        });
+    }
+
+    Ok(EscrowReading {
+        snapshot: EscrowSnapshot {
+            balance_wei: balance.to_string(),
+            locked_wei: locked_wei.to_string(),
+            locked_escrows: locked.len() as u64,
+            unread_escrows,
+            db_pending_wei: db_pending_wei.to_string(),
+            db_pending_messages: open.len() as u64,
+            block_number,
+            observed_at: chrono::Utc::now().to_rfc3339(),
+        },
+        locked,
+    })
+}
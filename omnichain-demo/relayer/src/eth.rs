@@ -1,9 +1,15 @@
 use anyhow::Result;
+use ethers::abi::Token;
 use ethers::prelude::*;
 use ethers::types::{Address, Filter, Log, H256, U256};
+use serde::Serialize;
+use std::collections::HashMap;
 use std::str::FromStr;
 use tracing::{debug, info, warn};
 
+use crate::chaos::{FaultTarget, InjectedFailure};
+use crate::executor_error::ExecutorError;
+
 /// Parsed CrossChainRequest event from the Ethereum escrow contract.
 #[derive(Debug, Clone)]
 pub struct CrossChainRequestEvent {
@@ -17,55 +23,144 @@ pub struct CrossChainRequestEvent {
     pub tx_hash: H256,
 }
 
-/// Compute the event topic hash for CrossChainRequest.
-pub fn event_signature() -> H256 {
-    // keccak256("CrossChainRequest(bytes32,uint64,address,uint256,bytes,uint256)")
-    let hash = ethers::utils::keccak256(
-        b"CrossChainRequest(bytes32,uint64,address,uint256,bytes,uint256)",
-    );
-    H256::from(hash)
+/// Escrow event as declared in `eth-contract/src/CrossChainEscrow.sol`.
+pub const DEFAULT_EVENT_ABI: &str = "event CrossChainRequest(bytes32 indexed traceId, uint64 indexed nonce, \
+     address sender, uint256 amount, bytes payload, uint256 deadline)";
+
+/// Event parameter names that fill each `CrossChainRequestEvent` field,
+/// tried in order when no explicit mapping is configured.
+const FIELD_ALIASES: [(&str, &[&str]); 6] = [
+    ("trace_id", &["traceId", "trace_id", "id", "guid"]),
+    ("nonce", &["nonce", "requestId", "seq"]),
+    ("sender", &["sender", "from", "user"]),
+    ("amount", &["amount", "value"]),
+    ("payload", &["payload", "data", "message"]),
+    ("deadline", &["deadline", "expiry", "expiresAt"]),
+];
+
+/// Escrow event layout: the ABI event plus which parameter feeds each field.
+///
+/// Lets the relayer ingest escrow versions whose event is renamed, reordered,
+/// or indexes different parameters without recompiling.
+#[derive(Debug, Clone)]
+pub struct EventLayout {
+    pub event: ethers::abi::Event,
+    /// Field name → event parameter name
+    fields: HashMap<&'static str, String>,
 }
 
-/// Build a log filter for CrossChainRequest events from a given block.
-pub fn build_filter(escrow_address: &str, from_block: u64) -> Result<Filter> {
-    let address = Address::from_str(escrow_address)?;
-    let topic = event_signature();
-
-    Ok(Filter::new()
-        .address(address)
-        .topic0(topic)
-        .from_block(from_block))
-}
+impl EventLayout {
+    /// Build from an ABI fragment — human-readable (`event X(...)`) or a JSON
+    /// event object — and an optional `field=param,...` mapping that overrides
+    /// the alias lookup.
+    pub fn parse(abi: &str, mapping: Option<&str>) -> Result<Self> {
+        let abi = abi.trim();
+        let event: ethers::abi::Event = if abi.starts_with('{') {
+            serde_json::from_str(abi)?
+        } else {
+            ethers::abi::HumanReadableParser::parse_event(abi)
+                .map_err(|e| anyhow::anyhow!("invalid event ABI: {:?}", e))?
+        };
+
+        let mut overrides = HashMap::new();
+        for pair in mapping.unwrap_or_default().split(',').filter(|p| !p.trim().is_empty()) {
+            let (field, param) = pair
+                .split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("invalid field mapping {:?}, expected field=param", pair))?;
+            overrides.insert(field.trim().to_string(), param.trim().to_string());
+        }
 
-/// Parse a raw log into a CrossChainRequestEvent.
-pub fn parse_log(log: &Log) -> Result<CrossChainRequestEvent> {
-    // topic[0] = event signature
-    // topic[1] = traceId (indexed bytes32)
-    // topic[2] = nonce (indexed uint64, padded to 32 bytes)
-    let trace_id = log.topics.get(1).copied().unwrap_or_default();
+        let mut fields = HashMap::new();
+        for (field, aliases) in FIELD_ALIASES {
+            let param = match overrides.remove(field) {
+                Some(param) => Some(param),
+                None => aliases
+                    .iter()
+                    .find(|a| event.inputs.iter().any(|i| i.name == **a))
+                    .map(|a| a.to_string()),
+            };
+            match param {
+                Some(param) if event.inputs.iter().any(|i| i.name == param) => {
+                    fields.insert(field, param);
+                }
+                Some(param) => anyhow::bail!("event {} has no parameter {:?}", event.name, param),
+                // trace_id and deadline are optional; the rest are required
+                None if matches!(field, "trace_id" | "deadline") => {}
+                None => anyhow::bail!("event {} has no parameter for {}", event.name, field),
+            }
+        }
+        if let Some(field) = overrides.keys().next() {
+            anyhow::bail!("unknown field in mapping: {}", field);
+        }
 
-    let nonce_bytes = log.topics.get(2).copied().unwrap_or_default();
-    let nonce = U256::from_big_endian(nonce_bytes.as_bytes()).as_u64();
+        Ok(Self { event, fields })
+    }
 
-    // data = abi.encode(address sender, uint256 amount, bytes payload, uint256 deadline)
-    let data = &log.data.0;
-    if data.len() < 128 {
-        anyhow::bail!("Log data too short: {} bytes", data.len());
+    /// Topic hash of the event (topic0).
+    pub fn signature(&self) -> H256 {
+        self.event.signature()
     }
 
-    // sender is at offset 0, right-padded in 32 bytes
-    let sender = Address::from_slice(&data[12..32]);
+    fn token(&self, log: &ethers::abi::Log, field: &str) -> Option<ethers::abi::Token> {
+        let param = self.fields.get(field)?;
+        log.params.iter().find(|p| &p.name == param).map(|p| p.value.clone())
+    }
+}
 
-    // amount is at offset 32
-    let amount = U256::from_big_endian(&data[32..64]);
+/// Build a log filter for the escrow event from a given block.
+pub fn build_filter(escrow_address: &str, from_block: u64, layout: &EventLayout) -> Result<Filter> {
+    let address = Address::from_str(escrow_address)?;
+    let topic = layout.signature();
 
-    // payload offset is at offset 64 (pointer to dynamic data)
-    let payload_offset = U256::from_big_endian(&data[64..96]).as_usize();
-    let payload_len = U256::from_big_endian(&data[payload_offset..payload_offset + 32]).as_usize();
-    let payload = data[payload_offset + 32..payload_offset + 32 + payload_len].to_vec();
+    Ok(Filter::new()
+        .address(address)
+        .topic0(topic)
+        .from_block(from_block))
+}
 
-    // deadline is at offset 96
-    let deadline = U256::from_big_endian(&data[96..128]);
+/// Parse a raw log into a CrossChainRequestEvent using the configured layout.
+pub fn parse_log(log: &Log, layout: &EventLayout) -> Result<CrossChainRequestEvent> {
+    let decoded = layout.event.parse_log(ethers::abi::RawLog {
+        topics: log.topics.clone(),
+        data: log.data.to_vec(),
+    })?;
+
+    let uint = |field: &str| -> Result<U256> {
+        match layout.token(&decoded, field) {
+            Some(Token::Uint(v)) | Some(Token::Int(v)) => Ok(v),
+            Some(other) => anyhow::bail!("{} is not an integer: {:?}", field, other),
+            None => anyhow::bail!("{} missing from log", field),
+        }
+    };
+
+    let nonce = uint("nonce")?;
+    let nonce = u64::try_from(nonce).map_err(|_| anyhow::anyhow!("nonce {} does not fit in 64 bits", nonce))?;
+    let trace_id = match layout.token(&decoded, "trace_id") {
+        Some(Token::FixedBytes(b)) => {
+            let mut out = [0u8; 32];
+            let len = b.len().min(32);
+            out[..len].copy_from_slice(&b[..len]);
+            H256::from(out)
+        }
+        Some(Token::Uint(v)) => H256::from_uint(&v),
+        // Trace ids are unique per message; without one, the nonce stands in
+        _ => H256::from_low_u64_be(nonce),
+    };
+    let sender = match layout.token(&decoded, "sender") {
+        Some(Token::Address(a)) => a,
+        other => anyhow::bail!("sender is not an address: {:?}", other),
+    };
+    let amount = uint("amount")?;
+    let payload = match layout.token(&decoded, "payload") {
+        Some(Token::Bytes(b)) | Some(Token::FixedBytes(b)) => b,
+        Some(Token::String(s)) => s.into_bytes(),
+        other => anyhow::bail!("payload is not bytes: {:?}", other),
+    };
+    let deadline = if layout.fields.contains_key("deadline") {
+        uint("deadline")?
+    } else {
+        U256::zero()
+    };
 
     let block_number = log.block_number.map(|b| b.as_u64()).unwrap_or(0);
     let tx_hash = log.transaction_hash.unwrap_or_default();
@@ -75,7 +170,8 @@ pub fn parse_log(log: &Log) -> Result<CrossChainRequestEvent> {
         ?sender,
         %amount,
         %deadline,
-        "Parsed CrossChainRequest event"
+        event = %layout.event.name,
+        "Parsed escrow event"
     );
 
     Ok(CrossChainRequestEvent {
@@ -90,10 +186,24 @@ pub fn parse_log(log: &Log) -> Result<CrossChainRequestEvent> {
     })
 }
 
+/// Apply faults injected with POST /chaos/fault before an RPC call.
+pub async fn fault_hook() -> Result<()> {
+    crate::chaos::faults()
+        .check(FaultTarget::Ethereum)
+        .await
+        .map_err(|e| InjectedFailure(e).into())
+}
+
 /// Fetch logs from Ethereum RPC.
-pub async fn fetch_logs(rpc_url: &str, escrow_address: &str, from_block: u64) -> Result<Vec<Log>> {
+pub async fn fetch_logs(
+    rpc_url: &str,
+    escrow_address: &str,
+    from_block: u64,
+    layout: &EventLayout,
+) -> Result<Vec<Log>> {
+    fault_hook().await?;
     let provider = Provider::<Http>::try_from(rpc_url)?;
-    let filter = build_filter(escrow_address, from_block)?;
+    let filter = build_filter(escrow_address, from_block, layout)?;
     let logs = provider.get_logs(&filter).await?;
     info!(count = logs.len(), from_block, "Fetched Ethereum logs");
     Ok(logs)
@@ -101,6 +211,7 @@ pub async fn fetch_logs(rpc_url: &str, escrow_address: &str, from_block: u64) ->
 
 /// Get the current block number.
 pub async fn get_block_number(rpc_url: &str) -> Result<u64> {
+    fault_hook().await?;
     let provider = Provider::<Http>::try_from(rpc_url)?;
     let block = provider.get_block_number().await?;
     Ok(block.as_u64())
@@ -108,6 +219,7 @@ pub async fn get_block_number(rpc_url: &str) -> Result<u64> {
 
 /// Get the ETH balance of an address (in wei).
 pub async fn get_balance(rpc_url: &str, address: &str) -> Result<U256> {
+    fault_hook().await?;
     let provider = Provider::<Http>::try_from(rpc_url)?;
     let addr = Address::from_str(address)?;
     let balance = provider.get_balance(addr, None).await?;
@@ -116,6 +228,7 @@ pub async fn get_balance(rpc_url: &str, address: &str) -> Result<U256> {
 
 /// Get the current gas price (in wei).
 pub async fn get_gas_price(rpc_url: &str) -> Result<U256> {
+    fault_hook().await?;
     let provider = Provider::<Http>::try_from(rpc_url)?;
     let price = provider.get_gas_price().await?;
     Ok(price)
@@ -123,11 +236,169 @@ pub async fn get_gas_price(rpc_url: &str) -> Result<U256> {
 
 /// Check if the Ethereum RPC is reachable by fetching the chain ID.
 pub async fn check_rpc(rpc_url: &str) -> Result<u64> {
+    fault_hook().await?;
     let provider = Provider::<Http>::try_from(rpc_url)?;
     let chain_id = provider.get_chainid().await?;
     Ok(chain_id.as_u64())
 }
 
+/// Where an escrow stands on chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EscrowState {
+    /// No escrow under the nonce
+    Missing,
+    Locked,
+    Settled,
+    /// Refunded to the sender after the deadline
+    Reclaimed,
+}
+
+impl std::fmt::Display for EscrowState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing => write!(f, "missing"),
+            Self::Locked => write!(f, "locked"),
+            Self::Settled => write!(f, "settled"),
+            Self::Reclaimed => write!(f, "reclaimed"),
+        }
+    }
+}
+
+/// An escrow as read from the contract.
+#[derive(Debug, Clone, Copy)]
+pub struct OnChainEscrow {
+    pub state: EscrowState,
+    pub trace_id: H256,
+    /// Locked value in wei
+    pub amount: U256,
+}
+
+/// Read escrows via the contract's `getEscrow(uint64)` and `settled(uint64)`.
+pub async fn get_escrows(rpc_url: &str, escrow_address: &str, nonces: &[u64]) -> Result<Vec<OnChainEscrow>> {
+    use ethers::abi::ParamType;
+
+    fault_hook().await?;
+    let provider = Provider::<Http>::try_from(rpc_url)?;
+    let contract_address = Address::from_str(escrow_address)?;
+    let call = |signature: &'static str, nonce: u64| {
+        let mut calldata = ethers::utils::keccak256(signature.as_bytes())[..4].to_vec();
+        calldata.extend_from_slice(&ethers::abi::encode(&[Token::Uint(U256::from(nonce))]));
+        let tx: ethers::types::transaction::eip2718::TypedTransaction =
+            TransactionRequest::new().to(contract_address).data(calldata).into();
+        let provider = &provider;
+        async move { provider.call(&tx, None).await }
+    };
+
+    let mut escrows = Vec::with_capacity(nonces.len());
+    for &nonce in nonces {
+        // (sender, amount, deadline, executed, traceId, payload)
+        let returned = call("getEscrow(uint64)", nonce).await?;
+        let tokens = ethers::abi::decode(
+            &[
+                ParamType::Address,
+                ParamType::Uint(256),
+                ParamType::Uint(256),
+                ParamType::Bool,
+                ParamType::FixedBytes(32),
+                ParamType::Bytes,
+            ],
+            &returned,
+        )?;
+        let (Token::Address(sender), Token::Uint(amount), Token::Bool(executed), Token::FixedBytes(trace_id)) =
+            (&tokens[0], &tokens[1], &tokens[3], &tokens[4])
+        else {
+            anyhow::bail!("unexpected getEscrow return for nonce {}", nonce);
+        };
+
+        let state = if sender.is_zero() {
+            EscrowState::Missing
+        } else if !executed {
+            EscrowState::Locked
+        } else {
+            let settled = ethers::abi::decode(&[ParamType::Bool], &call("settled(uint64)", nonce).await?)?;
+            match settled.first() {
+                Some(Token::Bool(true)) => EscrowState::Settled,
+                _ => EscrowState::Reclaimed,
+            }
+        };
+        escrows.push(OnChainEscrow {
+            state,
+            trace_id: H256::from_slice(trace_id),
+            amount: *amount,
+        });
+    }
+    Ok(escrows)
+}
+
+/// Encode a settlement result as uint256 (32 bytes, big-endian).
+pub fn encode_settlement_result(value: u64) -> Vec<u8> {
+    let mut result = vec![0u8; 32];
+    result[24..32].copy_from_slice(&value.to_be_bytes());
+    result
+}
+
+/// ABI encode: settle(uint64 _nonce, bytes result, bytes signature)
+fn settle_calldata(nonce: u64, result: &[u8], signature: &[u8]) -> Vec<u8> {
+    let mut calldata = ethers::utils::keccak256(b"settle(uint64,bytes,bytes)")[..4].to_vec();
+    calldata.extend_from_slice(&ethers::abi::encode(&[
+        Token::Uint(U256::from(nonce)),
+        Token::Bytes(result.to_vec()),
+        Token::Bytes(signature.to_vec()),
+    ]));
+    calldata
+}
+
+/// Custom errors `settle()` can revert with (CrossChainEscrow.sol).
+const SETTLE_ERRORS: &[&str] = &[
+    "OnlyRelayer()",
+    "EscrowNotFound()",
+    "AlreadyExecuted()",
+    "AlreadySettled()",
+    "DeadlineExceeded()",
+    "InvalidSignature()",
+    "InvalidNonce()",
+];
+
+/// Dry-run settle() from the relayer's address with `eth_call`, without
+/// sending a transaction. Returns the contract error it reverts with, or
+/// `None` if the call would succeed.
+pub async fn simulate_settle(
+    rpc_url: &str,
+    private_key: &str,
+    escrow_address: &str,
+    nonce: u64,
+    result: &[u8],
+    signature: &[u8],
+) -> Result<Option<String>> {
+    use ethers::providers::RpcError;
+    use ethers::signers::{LocalWallet, Signer};
+
+    fault_hook().await?;
+    let provider = Provider::<Http>::try_from(rpc_url)?;
+    let wallet: LocalWallet = private_key.parse()?;
+    let tx: ethers::types::transaction::eip2718::TypedTransaction = TransactionRequest::new()
+        .from(wallet.address())
+        .to(Address::from_str(escrow_address)?)
+        .data(settle_calldata(nonce, result, signature))
+        .into();
+
+    match provider.call(&tx, None).await {
+        Ok(_) => Ok(None),
+        Err(e) => {
+            let Some(data) = RpcError::as_error_response(&e).and_then(|r| r.as_revert_data()) else {
+                return Err(e.into());
+            };
+            let name = SETTLE_ERRORS
+                .iter()
+                .find(|sig| data.starts_with(&ethers::utils::keccak256(sig.as_bytes())[..4]))
+                .map(|sig| sig.trim_end_matches("()").to_string())
+                .unwrap_or_else(|| format!("revert 0x{}", hex::encode(&data)));
+            Ok(Some(name))
+        }
+    }
+}
+
 /// Sign a settlement message: keccak256(abi.encodePacked(nonce, result))
 /// Returns the 65-byte signature.
 pub fn sign_settlement(private_key: &str, nonce: u64, result: &[u8]) -> Result<Vec<u8>> {
@@ -148,8 +419,20 @@ pub fn sign_settlement(private_key: &str, nonce: u64, result: &[u8]) -> Result<V
     Ok(sig_bytes)
 }
 
-/// Call settle() on the escrow contract.
-/// Returns the transaction hash.
+/// A confirmed settlement (or refund) and what it cost.
+#[derive(Debug, Clone)]
+pub struct Settlement {
+    pub tx_hash: H256,
+    pub gas_used: Option<u64>,
+    pub effective_gas_price: Option<U256>,
+    pub block_number: Option<u64>,
+    /// `gas_used × effective gas price`, when the node reports both
+    pub fee_wei: Option<U256>,
+}
+
+/// Call settle() on the escrow contract, priced from `gas` when a reading is
+/// available (EIP-1559 fee cap, or legacy gas price) and by the node otherwise.
+/// Failures are typed as `ExecutorError`, a reverted receipt included.
 pub async fn call_settle(
     rpc_url: &str,
     private_key: &str,
@@ -157,51 +440,351 @@ pub async fn call_settle(
     nonce: u64,
     result: &[u8],
     signature: &[u8],
-) -> Result<H256> {
-    use ethers::abi::Token;
+    gas: Option<&crate::gas::GasReading>,
+) -> Result<Settlement> {
     use ethers::signers::{LocalWallet, Signer};
 
+    fault_hook().await?;
     let provider = Provider::<Http>::try_from(rpc_url)?;
     let wallet: LocalWallet = private_key.parse()?;
     let client = SignerMiddleware::new(provider, wallet.with_chain_id(31337u64));
 
     let contract_address = Address::from_str(escrow_address)?;
 
-    // ABI encode: settle(uint64 _nonce, bytes result, bytes signature)
-    let selector = &ethers::utils::keccak256(b"settle(uint64,bytes,bytes)")[..4];
-    let encoded = ethers::abi::encode(&[
-        Token::Uint(U256::from(nonce)),
-        Token::Bytes(result.to_vec()),
-        Token::Bytes(signature.to_vec()),
-    ]);
-
-    let mut calldata = selector.to_vec();
-    calldata.extend_from_slice(&encoded);
-
-    let tx = TransactionRequest::new()
-        .to(contract_address)
-        .data(calldata)
-        .gas(500_000u64);
+    let calldata = settle_calldata(nonce, result, signature);
+    let tx = priced_tx(contract_address, calldata, gas);
 
-    let pending = client.send_transaction(tx, None).await?;
+    let pending = client
+        .send_transaction(tx, None)
+        .await
+        .map_err(|e| classify_eth_error("settle()", &e))?;
     let tx_hash = pending.tx_hash();
 
     info!(%tx_hash, nonce, "Settlement transaction sent");
 
     // Wait for confirmation
-    let receipt = pending.await?;
+    let receipt = pending.await.map_err(|e| classify_eth_error("settle() receipt", &e))?;
     match receipt {
+        Some(r) if r.status == Some(U64::zero()) => {
+            warn!(nonce, tx_hash = %r.transaction_hash, "Settlement reverted");
+            Err(ExecutorError::Reverted(format!("settle() reverted in {:?}", r.transaction_hash)).into())
+        }
         Some(r) => {
+            let fee_wei = r.gas_used.zip(r.effective_gas_price).map(|(used, price)| used * price);
             info!(
                 tx_hash = %r.transaction_hash,
                 status = ?r.status,
+                gas_used = ?r.gas_used,
+                fee_wei = ?fee_wei,
                 "Settlement confirmed"
             );
-            Ok(r.transaction_hash)
+            Ok(Settlement {
+                tx_hash: r.transaction_hash,
+                gas_used: r.gas_used.map(|g| g.as_u64()),
+                effective_gas_price: r.effective_gas_price,
+                block_number: r.block_number.map(|b| b.as_u64()),
+                fee_wei,
+            })
         }
         None => {
             warn!(nonce, "Settlement tx dropped");
-            anyhow::bail!("Settlement transaction was dropped")
+            Err(ExecutorError::Timeout("Settlement transaction was dropped".into()).into())
+        }
+    }
+}
+
+/// Map a failed Ethereum RPC call to an `ExecutorError`.
+fn classify_eth_error<E: MiddlewareError<Inner = ProviderError>>(call: &str, e: &E) -> ExecutorError {
+    let message = format!("{} failed: {}", call, e);
+    if let Some(ProviderError::HTTPError(http)) = e.as_inner() {
+        return if http.status() == Some(reqwest::StatusCode::TOO_MANY_REQUESTS) {
+            ExecutorError::RateLimited(message)
+        } else if http.is_timeout() || http.is_connect() {
+            ExecutorError::Timeout(message)
+        } else {
+            ExecutorError::Unknown(message)
+        };
+    }
+    let Some(response) = e.as_error_response() else {
+        return ExecutorError::Unknown(message);
+    };
+    // 429 from some providers, -32005 "limit exceeded" from others
+    if response.code == 429 || response.code == -32005 {
+        ExecutorError::RateLimited(message)
+    } else if response.is_revert() {
+        ExecutorError::Reverted(message)
+    } else if response.message.contains("insufficient funds") {
+        ExecutorError::InsufficientFunds(message)
+    } else {
+        ExecutorError::Unknown(message)
+    }
+}
+
+/// A relayer transaction to the escrow, priced from `gas` when a reading is
+/// available (EIP-1559 fee cap, or legacy gas price) and by the node otherwise.
+fn priced_tx(
+    to: Address,
+    calldata: Vec<u8>,
+    gas: Option<&crate::gas::GasReading>,
+) -> ethers::types::transaction::eip2718::TypedTransaction {
+    match gas.map(|g| g.fees_wei()) {
+        Some((max_fee, Some(priority_fee))) => Eip1559TransactionRequest::new()
+            .to(to)
+            .data(calldata)
+            .gas(500_000u64)
+            .max_fee_per_gas(max_fee)
+            .max_priority_fee_per_gas(priority_fee)
+            .into(),
+        Some((gas_price, None)) => TransactionRequest::new()
+            .to(to)
+            .data(calldata)
+            .gas(500_000u64)
+            .gas_price(gas_price)
+            .into(),
+        None => TransactionRequest::new()
+            .to(to)
+            .data(calldata)
+            .gas(500_000u64)
+            .into(),
+    }
+}
+
+/// ABI encode: refund(uint64 _nonce)
+fn refund_calldata(nonce: u64) -> Vec<u8> {
+    let mut calldata = ethers::utils::keccak256(b"refund(uint64)")[..4].to_vec();
+    calldata.extend_from_slice(&ethers::abi::encode(&[Token::Uint(U256::from(nonce))]));
+    calldata
+}
+
+/// Custom errors `refund()` can revert with (CrossChainEscrow.sol).
+const REFUND_ERRORS: &[&str] = &[
+    "OnlyRelayer()",
+    "EscrowNotFound()",
+    "AlreadyExecuted()",
+    "DeadlineNotReached()",
+    "TransferFailed()",
+];
+
+/// What a refund() dry run found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RefundCheck {
+    Refundable,
+    /// The contract error it would revert with
+    Reverted(String),
+    /// The deployed escrow has no refund(): it reverts without data
+    Unsupported,
+}
+
+/// Dry-run refund() from the relayer's address with `eth_call`.
+pub async fn simulate_refund(rpc_url: &str, private_key: &str, escrow_address: &str, nonce: u64) -> Result<RefundCheck> {
+    use ethers::providers::RpcError;
+    use ethers::signers::{LocalWallet, Signer};
+
+    fault_hook().await?;
+    let provider = Provider::<Http>::try_from(rpc_url)?;
+    let wallet: LocalWallet = private_key.parse()?;
+    let tx: ethers::types::transaction::eip2718::TypedTransaction = TransactionRequest::new()
+        .from(wallet.address())
+        .to(Address::from_str(escrow_address)?)
+        .data(refund_calldata(nonce))
+        .into();
+
+    match provider.call(&tx, None).await {
+        Ok(_) => Ok(RefundCheck::Refundable),
+        Err(e) => {
+            let Some(data) = RpcError::as_error_response(&e).and_then(|r| r.as_revert_data()) else {
+                return Err(e.into());
+            };
+            if data.is_empty() {
+                return Ok(RefundCheck::Unsupported);
+            }
+            let name = REFUND_ERRORS
+                .iter()
+                .find(|sig| data.starts_with(&ethers::utils::keccak256(sig.as_bytes())[..4]))
+                .map(|sig| sig.trim_end_matches("()").to_string())
+                .unwrap_or_else(|| format!("revert 0x{}", hex::encode(&data)));
+            Ok(RefundCheck::Reverted(name))
+        }
+    }
+}
+
+/// Call refund() on the escrow contract, returning the sender's funds after
+/// the deadline. Fails if the transaction reverts or is dropped.
+pub async fn call_refund(
+    rpc_url: &str,
+    private_key: &str,
+    escrow_address: &str,
+    nonce: u64,
+    gas: Option<&crate::gas::GasReading>,
+) -> Result<Settlement> {
+    use ethers::signers::{LocalWallet, Signer};
+
+    fault_hook().await?;
+    let provider = Provider::<Http>::try_from(rpc_url)?;
+    let wallet: LocalWallet = private_key.parse()?;
+    let client = SignerMiddleware::new(provider, wallet.with_chain_id(31337u64));
+
+    let tx = priced_tx(Address::from_str(escrow_address)?, refund_calldata(nonce), gas);
+    let pending = client.send_transaction(tx, None).await?;
+    let tx_hash = pending.tx_hash();
+    info!(%tx_hash, nonce, "Refund transaction sent");
+
+    let Some(r) = pending.await? else {
+        anyhow::bail!("Refund transaction {:?} was dropped", tx_hash);
+    };
+    if r.status == Some(0u64.into()) {
+        anyhow::bail!("Refund transaction {:?} reverted", r.transaction_hash);
+    }
+    let fee_wei = r.gas_used.zip(r.effective_gas_price).map(|(used, price)| used * price);
+    info!(tx_hash = %r.transaction_hash, gas_used = ?r.gas_used, fee_wei = ?fee_wei, "Refund confirmed");
+    Ok(Settlement {
+        tx_hash: r.transaction_hash,
+        gas_used: r.gas_used.map(|g| g.as_u64()),
+        effective_gas_price: r.effective_gas_price,
+        block_number: r.block_number.map(|b| b.as_u64()),
+        fee_wei,
+    })
+}
+
+/// ABI encode: notarize(uint32 day, bytes32 root), `day` as YYYYMMDD
+fn notarize_calldata(day: u32, root: [u8; 32]) -> Vec<u8> {
+    let mut calldata = ethers::utils::keccak256(b"notarize(uint32,bytes32)")[..4].to_vec();
+    calldata.extend_from_slice(&ethers::abi::encode(&[
+        Token::Uint(U256::from(day)),
+        Token::FixedBytes(root.to_vec()),
+    ]));
+    calldata
+}
+
+/// Anchor a day's notarization root on Ethereum as `notarize(day, root)`
+/// calldata, sent to `notary_address` or, without one, to the signer itself.
+/// Fails if the transaction reverts or is dropped.
+pub async fn call_notarize(
+    rpc_url: &str,
+    private_key: &str,
+    notary_address: Option<&str>,
+    day: u32,
+    root: [u8; 32],
+    gas: Option<&crate::gas::GasReading>,
+) -> Result<Settlement> {
+    use ethers::signers::{LocalWallet, Signer};
+
+    let provider = Provider::<Http>::try_from(rpc_url)?;
+    let wallet: LocalWallet = private_key.parse()?;
+    let to = match notary_address {
+        Some(address) => Address::from_str(address)?,
+        None => wallet.address(),
+    };
+    let client = SignerMiddleware::new(provider, wallet.with_chain_id(31337u64));
+
+    let tx = priced_tx(to, notarize_calldata(day, root), gas);
+    let pending = client.send_transaction(tx, None).await?;
+    let tx_hash = pending.tx_hash();
+    info!(%tx_hash, day, "Notarization transaction sent");
+
+    let Some(r) = pending.await? else {
+        anyhow::bail!("Notarization transaction {:?} was dropped", tx_hash);
+    };
+    if r.status == Some(0u64.into()) {
+        anyhow::bail!("Notarization transaction {:?} reverted", r.transaction_hash);
+    }
+    let fee_wei = r.gas_used.zip(r.effective_gas_price).map(|(used, price)| used * price);
+    Ok(Settlement {
+        tx_hash: r.transaction_hash,
+        gas_used: r.gas_used.map(|g| g.as_u64()),
+        effective_gas_price: r.effective_gas_price,
+        block_number: r.block_number.map(|b| b.as_u64()),
+        fee_wei,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JSON_EVENT: &str = r#"{
+        "type": "event",
+        "name": "Deposit",
+        "anonymous": false,
+        "inputs": [
+            {"name": "requestId", "type": "uint256", "indexed": true},
+            {"name": "from", "type": "address", "indexed": false},
+            {"name": "value", "type": "uint256", "indexed": false},
+            {"name": "data", "type": "bytes", "indexed": false}
+        ]
+    }"#;
+
+    fn log(layout: &EventLayout, nonce: U256, sender: Address, amount: u64, payload: &[u8]) -> Log {
+        Log {
+            topics: vec![layout.signature(), H256::from_uint(&nonce)],
+            data: ethers::abi::encode(&[
+                Token::Address(sender),
+                Token::Uint(amount.into()),
+                Token::Bytes(payload.to_vec()),
+            ])
+            .into(),
+            ..Default::default()
         }
     }
+
+    #[test]
+    fn default_layout_matches_the_escrow_event() {
+        let layout = EventLayout::parse(DEFAULT_EVENT_ABI, None).unwrap();
+        assert_eq!(layout.event.name, "CrossChainRequest");
+        assert_eq!(
+            layout.signature(),
+            H256::from(ethers::utils::keccak256(
+                "CrossChainRequest(bytes32,uint64,address,uint256,bytes,uint256)"
+            ))
+        );
+        assert_eq!(layout.fields["trace_id"], "traceId");
+        assert_eq!(layout.fields["nonce"], "nonce");
+        assert_eq!(layout.fields["deadline"], "deadline");
+    }
+
+    #[test]
+    fn json_fragment_resolves_aliases_and_optional_fields() {
+        let layout = EventLayout::parse(JSON_EVENT, None).unwrap();
+        assert_eq!(layout.event.name, "Deposit");
+        assert_eq!(layout.fields["nonce"], "requestId");
+        assert_eq!(layout.fields["sender"], "from");
+        assert_eq!(layout.fields["amount"], "value");
+        assert_eq!(layout.fields["payload"], "data");
+        assert!(!layout.fields.contains_key("trace_id"));
+        assert!(!layout.fields.contains_key("deadline"));
+
+        let sender = Address::repeat_byte(0x11);
+        let event = parse_log(&log(&layout, U256::from(7), sender, 500, b"hi"), &layout).unwrap();
+        assert_eq!(event.nonce, 7);
+        assert_eq!(event.sender, sender);
+        assert_eq!(event.amount, U256::from(500));
+        assert_eq!(event.payload, b"hi");
+        // No trace id parameter: the nonce stands in
+        assert_eq!(event.trace_id, H256::from_low_u64_be(7));
+    }
+
+    #[test]
+    fn mapping_overrides_aliases_and_is_checked() {
+        let abi = "event Sent(uint256 indexed seq, uint256 indexed id, address user, uint256 amount, bytes message)";
+        let layout = EventLayout::parse(abi, Some("nonce=id, trace_id=seq")).unwrap();
+        assert_eq!(layout.fields["nonce"], "id");
+        assert_eq!(layout.fields["trace_id"], "seq");
+
+        assert!(EventLayout::parse(abi, Some("nonce=missing")).is_err());
+        assert!(EventLayout::parse(abi, Some("colour=id")).is_err());
+        assert!(EventLayout::parse(abi, Some("nonce")).is_err());
+        // No parameter for the required amount
+        assert!(EventLayout::parse("event Sent(uint256 nonce, address sender, bytes payload)", None).is_err());
+        assert!(EventLayout::parse("not an event", None).is_err());
+    }
+
+    #[test]
+    fn nonce_above_u64_is_rejected() {
+        let layout = EventLayout::parse(JSON_EVENT, None).unwrap();
+        let sender = Address::zero();
+        let too_big = U256::from(u64::MAX) + 1;
+        assert!(parse_log(&log(&layout, too_big, sender, 1, b""), &layout).is_err());
+        let event = parse_log(&log(&layout, U256::from(u64::MAX), sender, 1, b""), &layout).unwrap();
+        assert_eq!(event.nonce, u64::MAX);
+    }
 }
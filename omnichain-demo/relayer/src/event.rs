@@ -1,30 +1,168 @@
 use chrono::Utc;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::envelope::{ETHEREUM_CHAIN_ID, SOLANA_CHAIN_ID};
+use crate::types::ChainConfig;
+
+/// Version of the `LifecycleEvent` shape emitted by this build.
+/// See "Event schema evolution" in the README before changing the struct.
+pub const SCHEMA_VERSION: u32 = 9;
+
+/// Version of events that predate `schema_version` (stored rows and JSON).
+pub const LEGACY_SCHEMA_VERSION: u32 = 1;
+
+fn legacy_schema_version() -> u32 {
+    LEGACY_SCHEMA_VERSION
+}
 
 /// Lifecycle event conforming to the shared event model.
 /// All components emit events in this structure.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LifecycleEvent {
+    /// Shape version the event was written with
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
     pub trace_id: String,
     pub nonce: u64,
+    /// Serialized as `actor` (the name) plus `actor_chain_id` for chains
+    #[serde(flatten)]
     pub actor: Actor,
     pub step: Step,
     pub status: Status,
     pub timestamp: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub detail: Option<String>,
+    /// Global, monotonically increasing sequence number assigned at persist time.
+    /// Consumers resume from it with `?since_seq=`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seq: Option<u64>,
+    /// Envelope chain ids of the message, filled in at persist time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub src_chain_id: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dst_chain_id: Option<u32>,
+    /// Fee of the step's Ethereum transaction, in wei. A decimal string,
+    /// as fees can exceed JavaScript's safe integer range.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_wei: Option<String>,
+    /// Fee of the step's Solana transaction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_lamports: Option<u64>,
+    /// Gas (Ethereum) or compute units (Solana) the step's transaction consumed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_used: Option<u64>,
+    /// Run an event was replayed from by POST /runs/:id/replay; live events
+    /// have none. Never stored.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay_run_id: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
-pub enum Actor {
-    Ethereum,
+pub enum ActorKind {
+    /// A chain from the registry
+    Chain,
     Relayer,
-    Solana,
     Dashboard,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+/// Component that emitted an event. Chains are identified by their registry
+/// name and chain id, so any registered chain can be an actor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Actor {
+    pub kind: ActorKind,
+    /// `relayer`, `dashboard`, or the chain's registry name
+    pub name: String,
+    pub chain_id: Option<u32>,
+}
+
+impl Actor {
+    pub fn relayer() -> Self {
+        Self {
+            kind: ActorKind::Relayer,
+            name: "relayer".into(),
+            chain_id: None,
+        }
+    }
+
+    pub fn dashboard() -> Self {
+        Self {
+            kind: ActorKind::Dashboard,
+            name: "dashboard".into(),
+            chain_id: None,
+        }
+    }
+
+    pub fn chain(name: impl Into<String>, chain_id: u32) -> Self {
+        Self {
+            kind: ActorKind::Chain,
+            name: name.into(),
+            chain_id: Some(chain_id),
+        }
+    }
+
+    pub fn ethereum() -> Self {
+        Self::chain("ethereum", ETHEREUM_CHAIN_ID)
+    }
+
+    pub fn solana() -> Self {
+        Self::chain("solana", SOLANA_CHAIN_ID)
+    }
+
+    pub fn for_chain(chain: &ChainConfig) -> Self {
+        Self::chain(chain.name.clone(), chain.chain_id)
+    }
+
+    /// Rebuild from a stored or serialized name and chain id. Names written
+    /// before chain ids were recorded resolve to the built-in chains.
+    pub fn from_parts(name: &str, chain_id: Option<u32>) -> Self {
+        match (name, chain_id) {
+            ("relayer", None) => Self::relayer(),
+            ("dashboard", None) => Self::dashboard(),
+            ("ethereum", None) => Self::ethereum(),
+            ("solana", None) => Self::solana(),
+            (name, chain_id) => Self {
+                kind: ActorKind::Chain,
+                name: name.to_string(),
+                chain_id,
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for Actor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Wire form of `Actor`, flattened into the event: `actor` keeps its
+/// original string values and chains add `actor_chain_id`.
+#[derive(Serialize, Deserialize)]
+struct ActorFields {
+    actor: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    actor_chain_id: Option<u32>,
+}
+
+impl Serialize for Actor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ActorFields {
+            actor: self.name.clone(),
+            actor_chain_id: self.chain_id,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Actor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fields = ActorFields::deserialize(deserializer)?;
+        Ok(Self::from_parts(&fields.actor, fields.actor_chain_id))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "lowercase")]
 pub enum Step {
     Locked,
@@ -35,6 +173,68 @@ pub enum Step {
     Burned,
     Rollback,
     Settled,
+    /// The escrow was refunded after a rollback
+    Refunded,
+    /// The escrow deadline passed before settlement; the sender reclaims it
+    Expired,
+    /// A validator signed a proof whose event root does not match the observed event
+    #[serde(rename = "validator-misbehavior")]
+    ValidatorMisbehavior,
+    /// An escrow event reused the trace id of a stored message and was not ingested
+    #[serde(rename = "duplicate-trace")]
+    DuplicateTrace,
+    /// The startup integrity check found the message at odds with on-chain state
+    #[serde(rename = "integrity-mismatch")]
+    IntegrityMismatch,
+    /// A simulated attack was caught and rejected by a relayer or chain check
+    #[serde(rename = "attack-detected")]
+    AttackDetected,
+}
+
+impl std::fmt::Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Locked => write!(f, "locked"),
+            Self::Observed => write!(f, "observed"),
+            Self::Verified => write!(f, "verified"),
+            Self::Executed => write!(f, "executed"),
+            Self::Minted => write!(f, "minted"),
+            Self::Burned => write!(f, "burned"),
+            Self::Rollback => write!(f, "rollback"),
+            Self::Settled => write!(f, "settled"),
+            Self::Refunded => write!(f, "refunded"),
+            Self::Expired => write!(f, "expired"),
+            Self::ValidatorMisbehavior => write!(f, "validator-misbehavior"),
+            Self::DuplicateTrace => write!(f, "duplicate-trace"),
+            Self::IntegrityMismatch => write!(f, "integrity-mismatch"),
+            Self::AttackDetected => write!(f, "attack-detected"),
+        }
+    }
+}
+
+/// Inverse of `Display`, for steps stored in the events table.
+impl std::str::FromStr for Step {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "locked" => Self::Locked,
+            "observed" => Self::Observed,
+            "verified" => Self::Verified,
+            "executed" => Self::Executed,
+            "minted" => Self::Minted,
+            "burned" => Self::Burned,
+            "rollback" => Self::Rollback,
+            "settled" => Self::Settled,
+            "refunded" => Self::Refunded,
+            "expired" => Self::Expired,
+            "validator-misbehavior" => Self::ValidatorMisbehavior,
+            "duplicate-trace" => Self::DuplicateTrace,
+            "integrity-mismatch" => Self::IntegrityMismatch,
+            "attack-detected" => Self::AttackDetected,
+            other => anyhow::bail!("unknown step: {}", other),
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -48,6 +248,7 @@ pub enum Status {
 impl LifecycleEvent {
     pub fn new(trace_id: &str, nonce: u64, actor: Actor, step: Step, status: Status) -> Self {
         Self {
+            schema_version: SCHEMA_VERSION,
             trace_id: trace_id.to_string(),
             nonce,
             actor,
@@ -55,6 +256,13 @@ impl LifecycleEvent {
             status,
             timestamp: Utc::now().to_rfc3339(),
             detail: None,
+            seq: None,
+            src_chain_id: None,
+            dst_chain_id: None,
+            fee_wei: None,
+            fee_lamports: None,
+            gas_used: None,
+            replay_run_id: None,
         }
     }
 
@@ -63,3 +271,120 @@ impl LifecycleEvent {
         self
     }
 }
+
+/// Every schema version has a fixture of events as consumers received them.
+/// Each must still parse, and re-serializing must keep every field it had.
+#[cfg(test)]
+mod compatibility {
+    use super::*;
+    use serde_json::Value;
+
+    const FIXTURES: &[(u32, &str)] = &[
+        (1, include_str!("../fixtures/events/v1.json")),
+        (2, include_str!("../fixtures/events/v2.json")),
+        (3, include_str!("../fixtures/events/v3.json")),
+        (4, include_str!("../fixtures/events/v4.json")),
+        (5, include_str!("../fixtures/events/v5.json")),
+        (6, include_str!("../fixtures/events/v6.json")),
+        (7, include_str!("../fixtures/events/v7.json")),
+        (8, include_str!("../fixtures/events/v8.json")),
+        (9, include_str!("../fixtures/events/v9.json")),
+    ];
+
+    #[test]
+    fn latest_fixture_is_current_version() {
+        assert_eq!(FIXTURES.last().unwrap().0, SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn fixtures_parse_with_their_version() {
+        for (version, fixture) in FIXTURES {
+            let events: Vec<LifecycleEvent> = serde_json::from_str(fixture)
+                .unwrap_or_else(|e| panic!("v{} fixture no longer parses: {}", version, e));
+            assert!(!events.is_empty());
+            for event in events {
+                assert_eq!(event.schema_version, *version);
+            }
+        }
+    }
+
+    #[test]
+    fn fixtures_round_trip_without_losing_fields() {
+        for (version, fixture) in FIXTURES {
+            let raw: Vec<Value> = serde_json::from_str(fixture).unwrap();
+            for original in raw {
+                let event: LifecycleEvent = serde_json::from_value(original.clone()).unwrap();
+                let reserialized = serde_json::to_value(&event).unwrap();
+                for (key, value) in original.as_object().unwrap() {
+                    assert_eq!(
+                        reserialized.get(key),
+                        Some(value),
+                        "v{} field '{}' changed on round trip",
+                        version,
+                        key
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn original_actors_keep_their_wire_names() {
+        for (name, kind) in [
+            ("ethereum", ActorKind::Chain),
+            ("solana", ActorKind::Chain),
+            ("relayer", ActorKind::Relayer),
+            ("dashboard", ActorKind::Dashboard),
+        ] {
+            let actor: Actor = serde_json::from_value(serde_json::json!({ "actor": name })).unwrap();
+            assert_eq!(actor.kind, kind);
+            assert_eq!(serde_json::to_value(&actor).unwrap()["actor"], name);
+        }
+    }
+
+    #[test]
+    fn stored_steps_parse_back() {
+        let steps: Vec<Step> = FIXTURES
+            .iter()
+            .flat_map(|(_, fixture)| serde_json::from_str::<Vec<LifecycleEvent>>(fixture).unwrap())
+            .map(|e| e.step)
+            .collect();
+        for step in steps {
+            assert_eq!(step.to_string().parse::<Step>().unwrap(), step);
+        }
+        assert!("teleported".parse::<Step>().is_err());
+    }
+
+    #[test]
+    fn registered_chain_actor_round_trips() {
+        let actor = Actor::chain("solana-devnet", 40168);
+        let value = serde_json::to_value(&actor).unwrap();
+        assert_eq!(value, serde_json::json!({ "actor": "solana-devnet", "actor_chain_id": 40168 }));
+        assert_eq!(serde_json::from_value::<Actor>(value).unwrap(), actor);
+    }
+
+    /// A field added without a new fixture (and version) fails here.
+    #[test]
+    fn current_fixture_covers_every_field() {
+        let mut event = LifecycleEvent::new("trace", 1, Actor::ethereum(), Step::Settled, Status::Success)
+            .with_detail("detail");
+        event.seq = Some(1);
+        event.src_chain_id = Some(30101);
+        event.dst_chain_id = Some(30168);
+        event.fee_wei = Some("1".into());
+        event.fee_lamports = Some(5000);
+        event.gas_used = Some(21000);
+        event.replay_run_id = Some("run".into());
+        let emitted = serde_json::to_value(&event).unwrap();
+
+        let current: Vec<Value> = serde_json::from_str(FIXTURES.last().unwrap().1).unwrap();
+        for key in emitted.as_object().unwrap().keys() {
+            assert!(
+                current.iter().any(|e| e.get(key).is_some()),
+                "field '{}' is missing from the v{} fixture",
+                key,
+                SCHEMA_VERSION
+            );
+        }
+    }
+}
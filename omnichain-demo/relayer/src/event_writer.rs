@@ -0,0 +1,182 @@
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::{timeout_at, Duration, Instant};
+use tracing::{debug, error, info};
+
+use crate::db::{self, PersistedEvent};
+use crate::event::LifecycleEvent;
+use crate::types::AppState;
+
+enum Command {
+    Write(Box<LifecycleEvent>),
+    /// Answered once everything queued before it is persisted
+    Flush(oneshot::Sender<()>),
+}
+
+/// Queues lifecycle events for the writer task.
+#[derive(Debug, Clone)]
+pub struct EventWriter {
+    tx: mpsc::UnboundedSender<Command>,
+}
+
+/// Receiving end of an `EventWriter`, consumed by `run_event_writer`.
+pub struct EventQueue(mpsc::UnboundedReceiver<Command>);
+
+pub fn channel() -> (EventWriter, EventQueue) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    (EventWriter { tx }, EventQueue(rx))
+}
+
+impl EventWriter {
+    /// Queue an event. False if the writer task is not running.
+    pub fn write(&self, event: LifecycleEvent) -> bool {
+        self.tx.send(Command::Write(Box::new(event))).is_ok()
+    }
+
+    /// Wait until every event queued before this call is persisted and
+    /// broadcast. Returns at once if the writer task is not running.
+    pub async fn flush(&self) {
+        let (done_tx, done_rx) = oneshot::channel();
+        if self.tx.send(Command::Flush(done_tx)).is_ok() {
+            let _ = done_rx.await;
+        }
+    }
+}
+
+/// Persist queued events a transaction at a time: everything that arrives
+/// within `EVENT_WRITE_INTERVAL_MS` of the first event, up to
+/// `EVENT_WRITE_BATCH_SIZE` events. Events are then stamped with their
+/// sequence numbers and broadcast in the order they were queued.
+pub async fn run_event_writer(state: Arc<AppState>, queue: EventQueue) {
+    let interval_ms = state.config.event_write_interval_ms;
+    if interval_ms == 0 {
+        info!("Event writer disabled, events are written one at a time");
+        return;
+    }
+    let batch_size = state.config.event_write_batch_size.max(1);
+    let window = Duration::from_millis(interval_ms);
+
+    info!(interval_ms, batch_size, "Event writer started");
+    let EventQueue(mut rx) = queue;
+    let mut batch = Vec::with_capacity(batch_size);
+    let mut flushes = Vec::new();
+    while let Some(command) = rx.recv().await {
+        let mut next = Some(command);
+        let deadline = Instant::now() + window;
+        while let Some(command) = next.take() {
+            match command {
+                Command::Write(event) => batch.push(*event),
+                Command::Flush(done) => {
+                    flushes.push(done);
+                    break;
+                }
+            }
+            if batch.len() >= batch_size {
+                break;
+            }
+            next = timeout_at(deadline, rx.recv()).await.ok().flatten();
+        }
+
+        persist(&state, std::mem::take(&mut batch)).await;
+        for done in flushes.drain(..) {
+            let _ = done.send(());
+        }
+    }
+}
+
+async fn persist(state: &AppState, events: Vec<LifecycleEvent>) {
+    for (event, p) in write_batch(&state.pool, events).await {
+        publish(state, event, p);
+    }
+}
+
+/// Write `events` in one transaction, in order. If the transaction fails,
+/// nothing was written, so fall back to one insert per event; a bad event
+/// then doesn't take the rest of the batch with it. Returns the events that
+/// were written, in order, with what they were assigned.
+async fn write_batch(pool: &SqlitePool, events: Vec<LifecycleEvent>) -> Vec<(LifecycleEvent, PersistedEvent)> {
+    if events.is_empty() {
+        return Vec::new();
+    }
+    match db::insert_events(pool, &events).await {
+        Ok(persisted) => {
+            debug!(events = events.len(), "Event batch written");
+            events.into_iter().zip(persisted).collect()
+        }
+        Err(e) => {
+            error!(error = %e, events = events.len(), "Event batch failed, writing events one at a time");
+            let mut written = Vec::with_capacity(events.len());
+            for event in events {
+                match db::insert_event(pool, &event).await {
+                    Ok(p) => written.push((event, p)),
+                    Err(e) => error!(nonce = event.nonce, step = %event.step, error = %e, "Failed to persist event"),
+                }
+            }
+            written
+        }
+    }
+}
+
+/// Stamp a persisted event with its sequence number and chain ids, then hand
+/// it to the cache and WebSocket subscribers.
+pub fn publish(state: &AppState, mut event: LifecycleEvent, persisted: PersistedEvent) {
+    event.seq = Some(persisted.seq);
+    event.src_chain_id = persisted.src_chain_id;
+    event.dst_chain_id = persisted.dst_chain_id;
+    state.cache.push_event(&event);
+    // Ignore if no receivers
+    let _ = state.event_tx.send(event);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{Actor, Status, Step};
+
+    async fn test_pool(name: &str) -> SqlitePool {
+        let path = std::env::temp_dir().join(format!("relayer-event-writer-{}-{}.db", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        db::init_db(&format!("sqlite:{}?mode=rwc", path.display())).await.unwrap()
+    }
+
+    fn event(nonce: u64, detail: &str) -> LifecycleEvent {
+        LifecycleEvent::new("trace", nonce, Actor::relayer(), Step::Observed, Status::Success).with_detail(detail)
+    }
+
+    #[tokio::test]
+    async fn batches_keep_their_order_and_get_increasing_seqs() {
+        let pool = test_pool("order").await;
+        let events: Vec<_> = (1..=5).map(|n| event(n, &format!("event {}", n))).collect();
+        let written = write_batch(&pool, events).await;
+
+        let nonces: Vec<u64> = written.iter().map(|(e, _)| e.nonce).collect();
+        assert_eq!(nonces, vec![1, 2, 3, 4, 5]);
+        let seqs: Vec<u64> = written.iter().map(|(_, p)| p.seq).collect();
+        assert!(seqs.windows(2).all(|w| w[0] < w[1]));
+
+        // Stored under the seq each event was handed
+        let stored = db::get_events_since(&pool, 0, 100).await.unwrap();
+        assert_eq!(stored.iter().map(|e| e.seq.unwrap()).collect::<Vec<_>>(), seqs);
+        assert_eq!(stored.iter().map(|e| e.nonce).collect::<Vec<_>>(), nonces);
+    }
+
+    #[tokio::test]
+    async fn a_failing_event_does_not_drop_the_rest_of_its_batch() {
+        let pool = test_pool("fallback").await;
+        sqlx::query(
+            "CREATE TRIGGER reject_bad BEFORE INSERT ON events WHEN NEW.detail = 'bad' \
+             BEGIN SELECT RAISE(ABORT, 'bad event'); END",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let written = write_batch(&pool, vec![event(1, "good"), event(2, "bad"), event(3, "good")]).await;
+        assert_eq!(written.iter().map(|(e, _)| e.nonce).collect::<Vec<_>>(), vec![1, 3]);
+
+        let stored = db::get_events_since(&pool, 0, 100).await.unwrap();
+        assert_eq!(stored.iter().map(|e| e.nonce).collect::<Vec<_>>(), vec![1, 3]);
+        assert!(stored[0].seq < stored[1].seq);
+    }
+}
@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::chaos::InjectedFailure;
+use crate::types::MessageState;
+
+/// Deferrals in a row after which a failure spends the retry budget like
+/// any other, so a message cannot be deferred forever.
+pub const MAX_DEFERS: u32 = 8;
+
+/// Wait before retrying a message after its first deferral; doubled for each
+/// further one, up to `DEFER_MAX_DELAY`.
+const DEFER_BASE_DELAY: Duration = Duration::from_secs(2);
+const DEFER_MAX_DELAY: Duration = Duration::from_secs(60);
+
+/// Typed failure from a downstream executor (Solana execution, Ethereum
+/// settlement), real or simulated. The state machine picks retry, defer or
+/// rollback from the variant instead of parsing error strings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExecutorError {
+    /// No answer or no confirmation in time; the same request may still succeed
+    Timeout(String),
+    /// Rejected by the program or contract; resubmitting fails the same way
+    Reverted(String),
+    /// The relayer's fee payer cannot cover the transaction
+    InsufficientFunds(String),
+    /// Throttled by the RPC provider
+    RateLimited(String),
+    /// The stored message cannot be turned into a request at all
+    Invalid(String),
+    Unknown(String),
+}
+
+/// What the state machine does with a failed transition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureAction {
+    /// Retry, spending the message's retry budget
+    Retry,
+    /// Retry after a backoff without spending the budget; the fault is on
+    /// the relayer's side, not the message's. Only `MAX_DEFERS` times in a row.
+    Defer,
+    /// Give up now and refund
+    Rollback,
+}
+
+/// Consecutive deferrals of each message in its current state, and when it
+/// may be retried. Entries go when the message leaves the state.
+#[derive(Default)]
+pub struct Deferrals(Mutex<HashMap<u64, Deferral>>);
+
+struct Deferral {
+    state: MessageState,
+    count: u32,
+    retry_at: Instant,
+}
+
+impl Deferrals {
+    /// Whether `nonce` is still backing off from a deferral in `state`.
+    pub fn waiting(&self, nonce: u64, state: MessageState) -> bool {
+        let now = Instant::now();
+        self.0
+            .lock()
+            .unwrap()
+            .get(&nonce)
+            .is_some_and(|d| d.state == state && d.retry_at > now)
+    }
+
+    /// Record a deferral of `nonce` in `state` and start its backoff.
+    /// Returns how many deferrals in a row the message has had in that
+    /// state, this one included.
+    pub fn defer(&self, nonce: u64, state: MessageState) -> u32 {
+        let mut deferrals = self.0.lock().unwrap();
+        let count = match deferrals.get(&nonce) {
+            Some(d) if d.state == state => d.count + 1,
+            _ => 1,
+        };
+        deferrals.insert(
+            nonce,
+            Deferral {
+                state,
+                count,
+                retry_at: Instant::now() + defer_delay(count),
+            },
+        );
+        count
+    }
+
+    pub fn clear(&self, nonce: u64) {
+        self.0.lock().unwrap().remove(&nonce);
+    }
+}
+
+/// Backoff after the `count`th deferral in a row.
+fn defer_delay(count: u32) -> Duration {
+    2u32.checked_pow(count.saturating_sub(1))
+        .and_then(|factor| DEFER_BASE_DELAY.checked_mul(factor))
+        .map_or(DEFER_MAX_DELAY, |delay| delay.min(DEFER_MAX_DELAY))
+}
+
+impl ExecutorError {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Timeout(_) => "timeout",
+            Self::Reverted(_) => "reverted",
+            Self::InsufficientFunds(_) => "insufficient_funds",
+            Self::RateLimited(_) => "rate_limited",
+            Self::Invalid(_) => "invalid",
+            Self::Unknown(_) => "unknown",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            Self::Timeout(m)
+            | Self::Reverted(m)
+            | Self::InsufficientFunds(m)
+            | Self::RateLimited(m)
+            | Self::Invalid(m)
+            | Self::Unknown(m) => m,
+        }
+    }
+
+    pub fn action(&self) -> FailureAction {
+        match self {
+            Self::Timeout(_) | Self::Unknown(_) => FailureAction::Retry,
+            Self::RateLimited(_) | Self::InsufficientFunds(_) => FailureAction::Defer,
+            Self::Reverted(_) | Self::Invalid(_) => FailureAction::Rollback,
+        }
+    }
+
+    /// Recover the typed error from a transition failure, looking through
+    /// chaos injection. Errors that never went through an executor are `Unknown`.
+    pub fn classify(error: &anyhow::Error) -> Self {
+        if let Some(injected) = error.downcast_ref::<InjectedFailure>() {
+            return injected.0.clone();
+        }
+        match error.downcast_ref::<ExecutorError>() {
+            Some(e) => e.clone(),
+            None => Self::Unknown(error.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for ExecutorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.kind(), self.message())
+    }
+}
+
+impl std::error::Error for ExecutorError {}
+
+impl From<anyhow::Error> for ExecutorError {
+    fn from(e: anyhow::Error) -> Self {
+        Self::Unknown(format!("{:#}", e))
+    }
+}
+
+impl From<reqwest::Error> for ExecutorError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.status() == Some(reqwest::StatusCode::TOO_MANY_REQUESTS) {
+            Self::RateLimited(e.to_string())
+        } else if e.is_timeout() || e.is_connect() {
+            Self::Timeout(e.to_string())
+        } else {
+            Self::Unknown(e.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deferrals_count_per_state_and_back_off() {
+        let deferrals = Deferrals::default();
+        assert_eq!(deferrals.defer(1, MessageState::Executed), 1);
+        assert!(deferrals.waiting(1, MessageState::Executed));
+        assert!(!deferrals.waiting(1, MessageState::Verified));
+        assert!(!deferrals.waiting(2, MessageState::Executed));
+        assert_eq!(deferrals.defer(1, MessageState::Executed), 2);
+        // A new state starts over
+        assert_eq!(deferrals.defer(1, MessageState::Verified), 1);
+        deferrals.clear(1);
+        assert!(!deferrals.waiting(1, MessageState::Verified));
+    }
+
+    #[test]
+    fn message_faults_roll_back_and_relayer_faults_defer() {
+        assert_eq!(ExecutorError::Invalid("stored computation".into()).action(), FailureAction::Rollback);
+        assert_eq!(ExecutorError::Reverted("revert".into()).action(), FailureAction::Rollback);
+        assert_eq!(ExecutorError::RateLimited("429".into()).action(), FailureAction::Defer);
+        assert_eq!(ExecutorError::Timeout("slow".into()).action(), FailureAction::Retry);
+    }
+
+    #[test]
+    fn defer_delay_doubles_up_to_the_cap() {
+        assert_eq!(defer_delay(1), DEFER_BASE_DELAY);
+        assert_eq!(defer_delay(2), DEFER_BASE_DELAY * 2);
+        assert_eq!(defer_delay(6), DEFER_MAX_DELAY);
+        assert_eq!(defer_delay(u32::MAX), DEFER_MAX_DELAY);
+    }
+}
@@ -0,0 +1,336 @@
+use anyhow::{Context, Result};
+use arrow_array::{
+    ArrayRef, Int32Array, Int64Array, RecordBatch, StringArray, TimestampMicrosecondArray, UInt32Array, UInt64Array,
+    UInt8Array,
+};
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use chrono::{DateTime, NaiveDateTime};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::time::{sleep, Duration};
+use tracing::{error, info};
+
+use crate::db;
+use crate::event::LifecycleEvent;
+use crate::s3::S3Bucket;
+use crate::types::{AppState, CrossChainMessage, RunningGuard};
+
+/// Events read and written per query during an export.
+const EXPORT_CHUNK: i64 = 50_000;
+
+/// One completed export (`exports` table).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportSummary {
+    #[serde(default)]
+    pub id: i64,
+    /// Directory or `s3://bucket/prefix` the files were written to
+    pub destination: String,
+    pub started_at: String,
+    pub finished_at: String,
+    /// Messages in the rewritten `messages/` partitions
+    pub messages: u64,
+    /// Events appended under `events/`
+    pub events: u64,
+    /// Events with a sequence number above this were exported
+    pub since_seq: u64,
+    /// Highest sequence number exported so far
+    pub last_seq: u64,
+    /// Files written, relative to the destination
+    pub files: Vec<String>,
+}
+
+/// Query for GET /exports
+#[derive(Debug, Deserialize)]
+pub struct ExportsQuery {
+    #[serde(default = "default_exports_limit")]
+    pub limit: i64,
+}
+
+fn default_exports_limit() -> i64 {
+    20
+}
+
+/// Where export files go: `EXPORT_DIR`, or a bucket when `EXPORT_S3_BUCKET`
+/// is set.
+enum Destination {
+    Dir(PathBuf),
+    S3(S3Bucket, reqwest::Client),
+}
+
+impl Destination {
+    fn from_state(state: &AppState) -> Self {
+        match &state.config.export_s3 {
+            Some(bucket) => Self::S3(bucket.clone(), reqwest::Client::new()),
+            None => Self::Dir(PathBuf::from(&state.config.export_dir)),
+        }
+    }
+
+    fn location(&self) -> String {
+        match self {
+            Self::Dir(dir) => dir.display().to_string(),
+            Self::S3(bucket, _) => bucket.location(),
+        }
+    }
+
+    /// Write `bytes` to `key`, replacing an earlier file of the same name.
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<()> {
+        match self {
+            Self::Dir(dir) => {
+                let path = dir.join(key);
+                if let Some(parent) = path.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                // Readers never see a half-written file
+                let tmp = path.with_extension("parquet.tmp");
+                tokio::fs::write(&tmp, bytes).await?;
+                tokio::fs::rename(&tmp, &path).await?;
+                Ok(())
+            }
+            Self::S3(bucket, client) => bucket.put(client, key, bytes).await,
+        }
+    }
+}
+
+/// Export every `EXPORT_INTERVAL_HOURS` (0 = on demand only).
+pub async fn run_export_scheduler(state: Arc<AppState>) {
+    let interval_hours = state.config.export_interval_hours;
+    if interval_hours == 0 {
+        info!("Export scheduler disabled");
+        return;
+    }
+
+    info!(interval_hours, "Export scheduler started");
+    loop {
+        sleep(Duration::from_secs(interval_hours * 3600)).await;
+        if let Err(e) = run_export(&state).await {
+            error!(error = %e, "Scheduled export failed");
+        }
+    }
+}
+
+/// Run one export unless another is in progress.
+pub async fn run_export(state: &Arc<AppState>) -> Result<ExportSummary> {
+    let Some(_running) = RunningGuard::acquire(&state.export_running) else {
+        anyhow::bail!("an export is already running");
+    };
+    export(state).await
+}
+
+/// Write what changed since the previous export to the same destination.
+///
+/// Messages are partitioned by the day they were created. A day with any
+/// message updated since the last export is rewritten whole, so its file
+/// always holds the latest state. Events never change: new ones are
+/// appended as files named after their sequence range, partitioned by the
+/// day of their timestamp.
+async fn export(state: &AppState) -> Result<ExportSummary> {
+    let destination = Destination::from_state(state);
+    let location = destination.location();
+    let started_at = chrono::Utc::now().to_rfc3339();
+    let (since_seq, since_watermark) = db::get_export_cursor(&state.pool, &location).await?.unwrap_or_default();
+    info!(destination = %location, since_seq, "Export started");
+
+    // Buffered events are part of this export
+    state.event_writer.flush().await;
+
+    let mut files = Vec::new();
+    let watermark = db::get_messages_watermark(&state.pool).await?;
+    let mut messages = 0;
+    for day in db::get_message_days_updated_since(&state.pool, since_watermark.as_deref()).await? {
+        let rows = db::get_messages_created_on(&state.pool, &day).await?;
+        messages += rows.len() as u64;
+        let bytes = tokio::task::spawn_blocking(move || write_parquet(messages_batch(&rows)?)).await??;
+        let key = format!("messages/date={}/messages.parquet", day);
+        destination.put(&key, bytes).await.with_context(|| format!("failed to write {}", key))?;
+        files.push(key);
+    }
+
+    let mut last_seq = since_seq;
+    let mut events = 0;
+    loop {
+        let chunk = db::get_events_since(&state.pool, last_seq, EXPORT_CHUNK).await?;
+        let Some(last) = chunk.last().and_then(|e| e.seq) else {
+            break;
+        };
+        last_seq = last;
+        events += chunk.len() as u64;
+        let complete = (chunk.len() as i64) < EXPORT_CHUNK;
+
+        let mut by_day: BTreeMap<String, Vec<LifecycleEvent>> = BTreeMap::new();
+        for event in chunk {
+            by_day.entry(event_day(&event.timestamp)).or_default().push(event);
+        }
+        for (day, day_events) in by_day {
+            let first = day_events[0].seq.unwrap_or(0);
+            let last = day_events[day_events.len() - 1].seq.unwrap_or(0);
+            let bytes = tokio::task::spawn_blocking(move || write_parquet(events_batch(&day_events)?)).await??;
+            let key = format!("events/date={}/seq-{:012}-{:012}.parquet", day, first, last);
+            destination.put(&key, bytes).await.with_context(|| format!("failed to write {}", key))?;
+            files.push(key);
+        }
+        if complete {
+            break;
+        }
+    }
+
+    let mut summary = ExportSummary {
+        id: 0,
+        destination: location,
+        started_at,
+        finished_at: chrono::Utc::now().to_rfc3339(),
+        messages,
+        events,
+        since_seq,
+        last_seq,
+        files,
+    };
+    summary.id = db::insert_export(&state.pool, &summary, watermark.as_deref()).await?;
+    info!(
+        destination = %summary.destination,
+        messages,
+        events,
+        files = summary.files.len(),
+        "Export finished"
+    );
+    Ok(summary)
+}
+
+/// UTC day of an RFC 3339 timestamp.
+fn event_day(timestamp: &str) -> String {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| t.to_utc().format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|_| timestamp.chars().take(10).collect())
+}
+
+/// Microseconds since the epoch of an RFC 3339 or SQLite `datetime()` value.
+fn timestamp_micros(value: &str) -> Option<i64> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.timestamp_micros())
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").map(|t| t.and_utc().timestamp_micros()))
+        .ok()
+}
+
+fn utc_timestamp() -> DataType {
+    DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()))
+}
+
+fn timestamps<'a>(values: impl Iterator<Item = &'a str>) -> ArrayRef {
+    Arc::new(TimestampMicrosecondArray::from(values.map(timestamp_micros).collect::<Vec<_>>()).with_timezone("UTC"))
+}
+
+fn messages_batch(rows: &[CrossChainMessage]) -> Result<RecordBatch> {
+    let schema = Schema::new(vec![
+        Field::new("nonce", DataType::UInt64, false),
+        Field::new("trace_id", DataType::Utf8, false),
+        Field::new("sender", DataType::Utf8, false),
+        Field::new("amount_wei", DataType::Utf8, false),
+        Field::new("payload", DataType::Utf8, false),
+        Field::new("deadline", DataType::Int64, false),
+        Field::new("description", DataType::Utf8, true),
+        Field::new("state", DataType::Utf8, false),
+        Field::new("result", DataType::Utf8, true),
+        Field::new("solana_signature", DataType::Utf8, true),
+        Field::new("eth_settle_tx", DataType::Utf8, true),
+        Field::new("refund_tx", DataType::Utf8, true),
+        Field::new("retry_count", DataType::Int32, false),
+        Field::new("error_message", DataType::Utf8, true),
+        Field::new("run_id", DataType::Utf8, true),
+        Field::new("src_chain_id", DataType::UInt32, false),
+        Field::new("dst_chain_id", DataType::UInt32, false),
+        Field::new("payload_type", DataType::UInt8, false),
+        Field::new("computation", DataType::Utf8, false),
+        Field::new("settle_gas_used", DataType::Int64, true),
+        Field::new("settle_gas_price_wei", DataType::Utf8, true),
+        Field::new("settle_block_number", DataType::Int64, true),
+        Field::new("settle_cost_wei", DataType::Utf8, true),
+        Field::new("created_at", utc_timestamp(), true),
+        Field::new("updated_at", utc_timestamp(), true),
+    ]);
+    let strings = |f: fn(&CrossChainMessage) -> &str| -> ArrayRef {
+        Arc::new(StringArray::from(rows.iter().map(f).collect::<Vec<_>>()))
+    };
+    let optional = |f: fn(&CrossChainMessage) -> Option<&str>| -> ArrayRef {
+        Arc::new(StringArray::from(rows.iter().map(f).collect::<Vec<_>>()))
+    };
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(UInt64Array::from(rows.iter().map(|m| m.nonce as u64).collect::<Vec<_>>())),
+        strings(|m| &m.trace_id),
+        strings(|m| &m.sender),
+        strings(|m| &m.amount),
+        strings(|m| &m.payload),
+        Arc::new(Int64Array::from(rows.iter().map(|m| m.deadline).collect::<Vec<_>>())),
+        optional(|m| m.description.as_deref()),
+        strings(|m| &m.state),
+        optional(|m| m.result.as_deref()),
+        optional(|m| m.solana_signature.as_deref()),
+        optional(|m| m.eth_settle_tx.as_deref()),
+        optional(|m| m.refund_tx.as_deref()),
+        Arc::new(Int32Array::from(rows.iter().map(|m| m.retry_count).collect::<Vec<_>>())),
+        optional(|m| m.error_message.as_deref()),
+        optional(|m| m.run_id.as_deref()),
+        Arc::new(UInt32Array::from(rows.iter().map(|m| m.src_chain_id).collect::<Vec<_>>())),
+        Arc::new(UInt32Array::from(rows.iter().map(|m| m.dst_chain_id).collect::<Vec<_>>())),
+        Arc::new(UInt8Array::from(rows.iter().map(|m| m.payload_type).collect::<Vec<_>>())),
+        strings(|m| &m.computation),
+        Arc::new(Int64Array::from(rows.iter().map(|m| m.settle_gas_used).collect::<Vec<_>>())),
+        optional(|m| m.settle_gas_price_wei.as_deref()),
+        Arc::new(Int64Array::from(rows.iter().map(|m| m.settle_block_number).collect::<Vec<_>>())),
+        optional(|m| m.settle_cost_wei.as_deref()),
+        timestamps(rows.iter().map(|m| m.created_at.as_str())),
+        timestamps(rows.iter().map(|m| m.updated_at.as_str())),
+    ];
+    Ok(RecordBatch::try_new(Arc::new(schema), columns)?)
+}
+
+fn events_batch(events: &[LifecycleEvent]) -> Result<RecordBatch> {
+    let schema = Schema::new(vec![
+        Field::new("seq", DataType::UInt64, false),
+        Field::new("nonce", DataType::UInt64, false),
+        Field::new("trace_id", DataType::Utf8, false),
+        Field::new("actor", DataType::Utf8, false),
+        Field::new("actor_chain_id", DataType::UInt32, true),
+        Field::new("step", DataType::Utf8, false),
+        Field::new("status", DataType::Utf8, false),
+        Field::new("detail", DataType::Utf8, true),
+        Field::new("timestamp", utc_timestamp(), true),
+        Field::new("src_chain_id", DataType::UInt32, true),
+        Field::new("dst_chain_id", DataType::UInt32, true),
+        Field::new("schema_version", DataType::UInt32, false),
+        Field::new("fee_wei", DataType::Utf8, true),
+        Field::new("fee_lamports", DataType::UInt64, true),
+        Field::new("gas_used", DataType::UInt64, true),
+    ]);
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(UInt64Array::from(events.iter().map(|e| e.seq.unwrap_or(0)).collect::<Vec<_>>())),
+        Arc::new(UInt64Array::from(events.iter().map(|e| e.nonce).collect::<Vec<_>>())),
+        Arc::new(StringArray::from(events.iter().map(|e| e.trace_id.as_str()).collect::<Vec<_>>())),
+        Arc::new(StringArray::from(events.iter().map(|e| e.actor.name.as_str()).collect::<Vec<_>>())),
+        Arc::new(UInt32Array::from(events.iter().map(|e| e.actor.chain_id).collect::<Vec<_>>())),
+        Arc::new(StringArray::from(events.iter().map(|e| e.step.to_string()).collect::<Vec<_>>())),
+        Arc::new(StringArray::from(
+            events.iter().map(|e| format!("{:?}", e.status).to_lowercase()).collect::<Vec<_>>(),
+        )),
+        Arc::new(StringArray::from(events.iter().map(|e| e.detail.as_deref()).collect::<Vec<_>>())),
+        timestamps(events.iter().map(|e| e.timestamp.as_str())),
+        Arc::new(UInt32Array::from(events.iter().map(|e| e.src_chain_id).collect::<Vec<_>>())),
+        Arc::new(UInt32Array::from(events.iter().map(|e| e.dst_chain_id).collect::<Vec<_>>())),
+        Arc::new(UInt32Array::from(events.iter().map(|e| e.schema_version).collect::<Vec<_>>())),
+        Arc::new(StringArray::from(events.iter().map(|e| e.fee_wei.as_deref()).collect::<Vec<_>>())),
+        Arc::new(UInt64Array::from(events.iter().map(|e| e.fee_lamports).collect::<Vec<_>>())),
+        Arc::new(UInt64Array::from(events.iter().map(|e| e.gas_used).collect::<Vec<_>>())),
+    ];
+    Ok(RecordBatch::try_new(Arc::new(schema), columns)?)
+}
+
+/// Snappy-compressed Parquet file holding `batch`.
+fn write_parquet(batch: RecordBatch) -> Result<Vec<u8>> {
+    let props = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+    let mut writer = ArrowWriter::try_new(Vec::new(), batch.schema(), Some(props))?;
+    writer.write(&batch)?;
+    Ok(writer.into_inner()?)
+}
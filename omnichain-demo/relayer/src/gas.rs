@@ -0,0 +1,403 @@
+use anyhow::Result;
+use ethers::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::time::{sleep, Duration};
+use tracing::{debug, info, warn};
+
+use crate::config::Config;
+use crate::types::AppState;
+
+/// Blocks of fee history sampled per reading.
+const FEE_HISTORY_BLOCKS: u64 = 10;
+
+/// Priority-fee percentile taken from each sampled block.
+const PRIORITY_PERCENTILE: f64 = 50.0;
+
+/// Age past which the gas ceiling check takes a fresh reading.
+const CEILING_READING_MAX_AGE_SECS: i64 = 5;
+
+/// Sampled readings older than this are pruned; settlement readings are kept.
+const HISTORY_RETENTION_DAYS: i64 = 7;
+
+/// Where a gas reading came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GasSource {
+    /// `eth_feeHistory` on the Ethereum RPC
+    FeeHistory,
+    /// External oracle at `GAS_ORACLE_URL`
+    Oracle,
+    /// Legacy `eth_gasPrice` (RPC without EIP-1559 support)
+    GasPrice,
+    /// Fee market simulation (`GAS_SIMULATION`)
+    Simulated,
+}
+
+impl std::fmt::Display for GasSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FeeHistory => write!(f, "fee_history"),
+            Self::Oracle => write!(f, "oracle"),
+            Self::GasPrice => write!(f, "gas_price"),
+            Self::Simulated => write!(f, "simulated"),
+        }
+    }
+}
+
+impl std::str::FromStr for GasSource {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "fee_history" => Ok(Self::FeeHistory),
+            "oracle" => Ok(Self::Oracle),
+            "gas_price" => Ok(Self::GasPrice),
+            "simulated" => Ok(Self::Simulated),
+            other => anyhow::bail!("unknown gas source: {}", other),
+        }
+    }
+}
+
+/// A gas price observation and the fee cap derived from it (`gas_readings` table).
+#[derive(Debug, Clone, Serialize)]
+pub struct GasReading {
+    pub source: GasSource,
+    /// Base fee of the next block (the plain gas price for `gas_price` readings)
+    pub base_fee_gwei: f64,
+    pub priority_fee_gwei: f64,
+    /// Fee cap the settlement strategy would use: base × multiplier + priority
+    pub max_fee_gwei: f64,
+    pub block_number: Option<u64>,
+    /// Set when the reading was taken for a settlement
+    pub nonce: Option<u64>,
+    pub observed_at: String,
+}
+
+impl GasReading {
+    fn new(cfg: &Config, source: GasSource, base_fee_gwei: f64, priority_fee_gwei: f64, block_number: Option<u64>) -> Self {
+        // Legacy gas prices already include the tip and are used as-is
+        let max_fee_gwei = match source {
+            GasSource::GasPrice => base_fee_gwei,
+            _ => base_fee_gwei * cfg.gas_base_fee_multiplier + priority_fee_gwei,
+        };
+        Self {
+            source,
+            base_fee_gwei,
+            priority_fee_gwei,
+            max_fee_gwei,
+            block_number,
+            nonce: None,
+            observed_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// Fee fields for the settlement transaction, in wei.
+    /// `None` priority fee means a legacy (`gasPrice`) transaction.
+    pub fn fees_wei(&self) -> (U256, Option<U256>) {
+        match self.source {
+            GasSource::GasPrice => (gwei_to_wei(self.max_fee_gwei), None),
+            _ => (
+                gwei_to_wei(self.max_fee_gwei),
+                Some(gwei_to_wei(self.priority_fee_gwei)),
+            ),
+        }
+    }
+}
+
+/// Response for GET /gas
+#[derive(Debug, Serialize)]
+pub struct GasResponse {
+    /// Latest reading, `None` if no source has answered yet
+    pub current: Option<GasReading>,
+    /// Periodic samples, newest first
+    pub history: Vec<GasReading>,
+    /// Readings the last settlements were sent with, newest first
+    pub settlements: Vec<GasReading>,
+}
+
+/// Query for GET /gas
+#[derive(Debug, Deserialize)]
+pub struct GasQuery {
+    /// Entries per list (default 100, capped at 1000)
+    #[serde(default = "default_gas_limit")]
+    pub limit: i64,
+}
+
+fn default_gas_limit() -> i64 {
+    100
+}
+
+/// Expected body of the external oracle.
+#[derive(Debug, Deserialize)]
+struct OracleResponse {
+    base_fee_gwei: f64,
+    #[serde(default)]
+    priority_fee_gwei: f64,
+}
+
+fn gwei_to_wei(gwei: f64) -> U256 {
+    U256::from((gwei * 1_000_000_000.0).round() as u128)
+}
+
+fn wei_to_gwei(wei: U256) -> f64 {
+    wei.as_u128() as f64 / 1_000_000_000.0
+}
+
+/// How simulated fees move over time (`GAS_SIMULATION`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeeProfile {
+    /// Short spikes to ~8× the baseline, lasting under a minute
+    Spikes,
+    /// Sustained highs at ~3× the baseline for several minutes
+    Sustained,
+    /// Both, with noisier fees in between
+    Volatile,
+}
+
+impl std::fmt::Display for FeeProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Spikes => write!(f, "spikes"),
+            Self::Sustained => write!(f, "sustained"),
+            Self::Volatile => write!(f, "volatile"),
+        }
+    }
+}
+
+impl std::str::FromStr for FeeProfile {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "spikes" => Ok(Self::Spikes),
+            "sustained" => Ok(Self::Sustained),
+            "volatile" => Ok(Self::Volatile),
+            other => anyhow::bail!("unknown GAS_SIMULATION: {} (expected spikes, sustained or volatile)", other),
+        }
+    }
+}
+
+/// Per-second chances of leaving normal conditions, and the noise around them.
+struct FeeProcess {
+    spike_chance: f64,
+    high_chance: f64,
+    noise: f64,
+}
+
+impl FeeProfile {
+    fn process(self) -> FeeProcess {
+        let (spike_chance, high_chance, noise) = match self {
+            Self::Spikes => (1.0 / 120.0, 0.0, 0.05),
+            Self::Sustained => (0.0, 1.0 / 300.0, 0.05),
+            Self::Volatile => (1.0 / 90.0, 1.0 / 240.0, 0.15),
+        };
+        FeeProcess {
+            spike_chance,
+            high_chance,
+            noise,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Regime {
+    Normal,
+    Spike,
+    High,
+}
+
+impl Regime {
+    /// Multiple of the baseline the base fee is pulled towards
+    fn level(self) -> f64 {
+        match self {
+            Self::Normal => 1.0,
+            Self::Spike => 8.0,
+            Self::High => 3.0,
+        }
+    }
+}
+
+/// Simulated steps replayed at most per reading, after a long idle.
+const MAX_SIM_STEPS: u64 = 3_600;
+
+struct SimState {
+    base_gwei: f64,
+    regime: Regime,
+    /// Seconds left in a spike or high
+    regime_left: u64,
+    stepped_at: std::time::Instant,
+}
+
+/// Simulated fee market: the base fee mean-reverts to `GAS_SIM_BASE_GWEI`
+/// with noise, and randomly enters spikes or sustained highs. The process
+/// advances in one-second steps whenever it is read.
+pub struct FeeSimulator {
+    state: std::sync::Mutex<SimState>,
+}
+
+impl FeeSimulator {
+    pub fn new(baseline_gwei: f64) -> Self {
+        Self {
+            state: std::sync::Mutex::new(SimState {
+                base_gwei: baseline_gwei,
+                regime: Regime::Normal,
+                regime_left: 0,
+                stepped_at: std::time::Instant::now(),
+            }),
+        }
+    }
+
+    fn reading(&self, cfg: &Config, profile: FeeProfile) -> GasReading {
+        use rand::Rng;
+
+        let process = profile.process();
+        let baseline = cfg.gas_sim_base_gwei;
+        let mut rng = rand::thread_rng();
+        let mut sim = self.state.lock().unwrap();
+
+        let elapsed = sim.stepped_at.elapsed().as_secs();
+        sim.stepped_at += Duration::from_secs(elapsed);
+        for _ in 0..elapsed.min(MAX_SIM_STEPS) {
+            if sim.regime_left > 0 {
+                sim.regime_left -= 1;
+                if sim.regime_left == 0 {
+                    sim.regime = Regime::Normal;
+                }
+            } else if rng.gen_bool(process.spike_chance) {
+                sim.regime = Regime::Spike;
+                sim.regime_left = rng.gen_range(15..=45);
+            } else if rng.gen_bool(process.high_chance) {
+                sim.regime = Regime::High;
+                sim.regime_left = rng.gen_range(120..=300);
+            }
+
+            let target = baseline * sim.regime.level();
+            let noise = 1.0 + rng.gen_range(-process.noise..=process.noise);
+            sim.base_gwei = ((sim.base_gwei + (target - sim.base_gwei) * 0.3) * noise).max(baseline * 0.25);
+        }
+
+        // Tips rise with congestion
+        let priority = 0.1 * sim.base_gwei;
+        GasReading::new(cfg, GasSource::Simulated, sim.base_gwei, priority, None)
+    }
+}
+
+/// Take a reading: the fee simulation when enabled, then the external oracle
+/// if configured, otherwise `eth_feeHistory`, falling back to `eth_gasPrice`
+/// on nodes without EIP-1559.
+pub async fn read(state: &AppState) -> Result<GasReading> {
+    let cfg = &state.config;
+    if let Some(profile) = cfg.gas_simulation {
+        return Ok(state.fee_simulator.reading(cfg, profile));
+    }
+    if let Some(url) = &cfg.gas_oracle_url {
+        match read_oracle(cfg, url).await {
+            Ok(reading) => return Ok(reading),
+            Err(e) => debug!(error = %e, "Gas oracle unavailable, using RPC"),
+        }
+    }
+
+    crate::eth::fault_hook().await?;
+    let provider = Provider::<Http>::try_from(cfg.eth_rpc_url.as_str())?;
+    match provider
+        .fee_history(FEE_HISTORY_BLOCKS, BlockNumber::Latest, &[PRIORITY_PERCENTILE])
+        .await
+    {
+        Ok(history) if !history.base_fee_per_gas.is_empty() => {
+            // The last entry is the base fee of the upcoming block
+            let base_fee = history.base_fee_per_gas.last().copied().unwrap_or_default();
+            let mut tips: Vec<U256> = history.reward.iter().filter_map(|r| r.first().copied()).collect();
+            tips.sort();
+            let tip = tips.get(tips.len() / 2).copied().unwrap_or_default();
+            let newest = history.oldest_block.as_u64() + history.gas_used_ratio.len().saturating_sub(1) as u64;
+            Ok(GasReading::new(cfg, GasSource::FeeHistory, wei_to_gwei(base_fee), wei_to_gwei(tip), Some(newest)))
+        }
+        _ => {
+            let price = crate::eth::get_gas_price(&cfg.eth_rpc_url).await?;
+            Ok(GasReading::new(cfg, GasSource::GasPrice, wei_to_gwei(price), 0.0, None))
+        }
+    }
+}
+
+async fn read_oracle(cfg: &Config, url: &str) -> Result<GasReading> {
+    let body: OracleResponse = reqwest::Client::new()
+        .get(url)
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    Ok(GasReading::new(cfg, GasSource::Oracle, body.base_fee_gwei, body.priority_fee_gwei, None))
+}
+
+/// Take a fresh reading for a settlement and record it against the message.
+/// Returns `None` when no source is reachable; the node then picks the fees.
+pub async fn reading_for_settlement(state: &AppState, nonce: u64) -> Option<GasReading> {
+    let mut reading = match read(state).await {
+        Ok(r) => r,
+        Err(e) => {
+            debug!(nonce, error = %e, "No gas reading for settlement");
+            return None;
+        }
+    };
+    reading.nonce = Some(nonce);
+
+    if let Err(e) = crate::db::insert_gas_reading(&state.pool, &reading).await {
+        warn!(nonce, error = %e, "Failed to record settlement gas reading");
+    }
+    *state.gas.lock().unwrap() = Some(reading.clone());
+    Some(reading)
+}
+
+/// Fee cap above `GAS_MAX_FEE_GWEI`, if any: settlements wait until it
+/// drops back. Uses the latest reading while it is fresh. Never set while
+/// chaos is suspended.
+pub async fn above_ceiling(state: &AppState) -> Option<f64> {
+    let ceiling = state.config.gas_max_fee_gwei?;
+    if crate::chaos::faults().suspended() {
+        return None;
+    }
+    let latest = state.gas.lock().unwrap().clone().filter(|r| {
+        chrono::DateTime::parse_from_rfc3339(&r.observed_at)
+            .is_ok_and(|at| chrono::Utc::now().signed_duration_since(at).num_seconds() < CEILING_READING_MAX_AGE_SECS)
+    });
+    let reading = match latest {
+        Some(r) => r,
+        None => {
+            let r = read(state).await.ok()?;
+            *state.gas.lock().unwrap() = Some(r.clone());
+            r
+        }
+    };
+    (reading.max_fee_gwei > ceiling).then_some(reading.max_fee_gwei)
+}
+
+/// Sample gas conditions every `GAS_SAMPLE_INTERVAL_SECS` for GET /gas.
+pub async fn run_gas_sampler(state: Arc<AppState>) {
+    let interval_secs = state.config.gas_sample_interval_secs;
+    if interval_secs == 0 {
+        info!("Gas sampler disabled");
+        return;
+    }
+
+    info!(interval_secs, oracle = ?state.config.gas_oracle_url, "Gas sampler started");
+    loop {
+        match read(&state).await {
+            Ok(reading) => {
+                if let Err(e) = crate::db::insert_gas_reading(&state.pool, &reading).await {
+                    warn!(error = %e, "Failed to record gas reading");
+                }
+                *state.gas.lock().unwrap() = Some(reading);
+            }
+            Err(e) => debug!(error = %e, "Gas reading failed"),
+        }
+        if let Err(e) = crate::db::prune_gas_readings(&state.pool, HISTORY_RETENTION_DAYS).await {
+            warn!(error = %e, "Failed to prune gas readings");
+        }
+
+        sleep(Duration::from_secs(interval_secs)).await;
+    }
+}
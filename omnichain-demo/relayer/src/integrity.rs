@@ -0,0 +1,414 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::db;
+use crate::envelope::SOLANA_CHAIN_ID;
+use crate::eth::{self, EscrowState, OnChainEscrow};
+use crate::event::{Actor, LifecycleEvent, Status, Step};
+use crate::solana::SolanaMode;
+use crate::state_machine::emit_and_persist;
+use crate::types::{AppState, CrossChainMessage, MessageState};
+
+/// How a stored message disagrees with the chains.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscrepancyKind {
+    /// No escrow under the nonce, e.g. the chain was reset under an old database
+    MissingEscrow,
+    /// The escrow under the nonce carries another trace id
+    TraceMismatch,
+    /// The message state contradicts the escrow or the destination receipt
+    StateMismatch,
+    /// Executed or settled, but the destination has no receipt
+    MissingReceipt,
+    /// The destination receipt holds another result
+    ResultMismatch,
+}
+
+impl std::fmt::Display for DiscrepancyKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingEscrow => write!(f, "missing_escrow"),
+            Self::TraceMismatch => write!(f, "trace_mismatch"),
+            Self::StateMismatch => write!(f, "state_mismatch"),
+            Self::MissingReceipt => write!(f, "missing_receipt"),
+            Self::ResultMismatch => write!(f, "result_mismatch"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Discrepancy {
+    pub nonce: u64,
+    pub trace_id: String,
+    pub kind: DiscrepancyKind,
+    pub db_state: String,
+    pub detail: String,
+    /// State the message was moved to; None unless auto-correction applied
+    pub corrected_to: Option<String>,
+}
+
+/// Outcome of the startup integrity check, for GET /integrity.
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrityReport {
+    pub started_at: String,
+    pub finished_at: String,
+    /// Messages compared
+    pub checked: usize,
+    /// Destination receipts are only compared in rpc mode: the simulator
+    /// keeps none across restarts
+    pub receipts_checked: bool,
+    pub auto_correct: bool,
+    pub discrepancies: Vec<Discrepancy>,
+    /// Why the check stopped early (e.g. Ethereum unreachable)
+    pub error: Option<String>,
+}
+
+/// A state on-chain evidence proves, with the result and error to store.
+struct Correction {
+    to: MessageState,
+    result: Option<String>,
+    error: Option<String>,
+}
+
+struct Finding {
+    kind: DiscrepancyKind,
+    detail: String,
+    correction: Option<Correction>,
+}
+
+impl Finding {
+    fn report(kind: DiscrepancyKind, detail: impl Into<String>) -> Self {
+        Self {
+            kind,
+            detail: detail.into(),
+            correction: None,
+        }
+    }
+
+    fn correct(kind: DiscrepancyKind, detail: impl Into<String>, to: MessageState, error: Option<String>) -> Self {
+        Self {
+            kind,
+            detail: detail.into(),
+            correction: Some(Correction { to, result: None, error }),
+        }
+    }
+}
+
+/// Compare stored messages with the escrow contract and, in rpc mode, the
+/// Solana receipts, once before the processor resumes. Discrepancies are
+/// logged, emitted as `integrity-mismatch` events and kept for
+/// GET /integrity. Never fails startup, and is skipped when the processor
+/// is restarted after a crash, as the report from the first run stands.
+pub async fn run_startup_check(state: &Arc<AppState>) {
+    let cfg = &state.config;
+    if cfg.integrity_check_limit <= 0 {
+        info!("Integrity check disabled");
+        return;
+    }
+    if state.integrity.lock().unwrap().is_some() {
+        return;
+    }
+
+    let mut report = IntegrityReport {
+        started_at: chrono::Utc::now().to_rfc3339(),
+        finished_at: String::new(),
+        checked: 0,
+        receipts_checked: state.solana.mode() == SolanaMode::Rpc,
+        auto_correct: cfg.integrity_auto_correct,
+        discrepancies: Vec::new(),
+        error: None,
+    };
+    if let Err(e) = check(state, &mut report).await {
+        warn!(error = %e, "Integrity check did not complete");
+        report.error = Some(e.to_string());
+    }
+    report.finished_at = chrono::Utc::now().to_rfc3339();
+
+    let corrected = report.discrepancies.iter().filter(|d| d.corrected_to.is_some()).count();
+    info!(
+        checked = report.checked,
+        discrepancies = report.discrepancies.len(),
+        corrected,
+        "Integrity check finished"
+    );
+    *state.integrity.lock().unwrap() = Some(report);
+}
+
+async fn check(state: &Arc<AppState>, report: &mut IntegrityReport) -> Result<()> {
+    let cfg = &state.config;
+    let messages = db::get_escrow_messages(&state.pool, cfg.integrity_check_limit).await?;
+    let nonces: Vec<u64> = messages.iter().map(|m| m.nonce as u64).collect();
+    let escrows = eth::get_escrows(&cfg.eth_rpc_url, &cfg.escrow_address, &nonces).await?;
+    let now = chrono::Utc::now().timestamp();
+
+    for (msg, escrow) in messages.iter().zip(&escrows) {
+        let mut finding = compare_escrow(msg, escrow, now);
+        // The receipt only matters for a message the escrow agrees with
+        if finding.is_none() && report.receipts_checked && msg.dst_chain_id == SOLANA_CHAIN_ID {
+            let receipt = state.solana.receipt(msg.nonce as u64).await?;
+            finding = compare_receipt(msg, receipt.map(|r| r.result));
+        }
+        report.checked += 1;
+
+        if let Some(finding) = finding {
+            let discrepancy = record(state, msg, finding, report.auto_correct).await?;
+            report.discrepancies.push(discrepancy);
+        }
+    }
+    Ok(())
+}
+
+/// What the escrow says about a message, if it disagrees.
+fn compare_escrow(msg: &CrossChainMessage, escrow: &OnChainEscrow, now: i64) -> Option<Finding> {
+    use DiscrepancyKind::*;
+
+    let db_state = MessageState::from_str(&msg.state);
+    match escrow.state {
+        EscrowState::Missing => Some(Finding::report(MissingEscrow, "no escrow under this nonce")),
+        _ if format!("{:?}", escrow.trace_id) != msg.trace_id => Some(Finding::report(
+            TraceMismatch,
+            format!("escrow carries trace id {:?}", escrow.trace_id),
+        )),
+        EscrowState::Settled if db_state != MessageState::Settled => Some(Finding::correct(
+            StateMismatch,
+            "escrow is settled",
+            MessageState::Settled,
+            None,
+        )),
+        // Rollbacks and expiry leave the escrow for the sender to reclaim,
+        // or for the relayer to refund
+        EscrowState::Reclaimed
+            if !matches!(db_state, MessageState::Failed | MessageState::RolledBack | MessageState::Refunded) =>
+        {
+            Some(Finding::correct(
+                StateMismatch,
+                "escrow was reclaimed by the sender",
+                MessageState::Failed,
+                Some("Integrity: escrow reclaimed by the sender".into()),
+            ))
+        }
+        EscrowState::Locked if db_state == MessageState::Settled => {
+            let detail = match msg.eth_settle_tx.as_deref() {
+                Some(tx) if tx.starts_with("0xsim_") => {
+                    format!("settlement was simulated ({}) but the escrow is still locked", tx)
+                }
+                _ => "settled, but the escrow is still locked".to_string(),
+            };
+            // Settlement is retried from Executed while the deadline allows
+            Some(if msg.deadline > 0 && now > msg.deadline {
+                Finding::correct(
+                    StateMismatch,
+                    detail,
+                    MessageState::Failed,
+                    Some(format!("Integrity: escrow never settled, deadline {} passed", msg.deadline)),
+                )
+            } else {
+                Finding::correct(StateMismatch, detail, MessageState::Executed, None)
+            })
+        }
+        _ => None,
+    }
+}
+
+/// What the destination receipt says about a message, if it disagrees.
+fn compare_receipt(msg: &CrossChainMessage, receipt_result: Option<u64>) -> Option<Finding> {
+    use DiscrepancyKind::*;
+
+    let db_state = MessageState::from_str(&msg.state);
+    let executed = matches!(db_state, MessageState::Executed | MessageState::Settled);
+    match receipt_result {
+        None if executed => Some(Finding::report(MissingReceipt, "no execution receipt on Solana")),
+        Some(result) if executed && msg.result.as_deref() != Some(result.to_string().as_str()) => Some(Finding::report(
+            ResultMismatch,
+            format!("receipt holds result {}, stored {}", result, msg.result.as_deref().unwrap_or("none")),
+        )),
+        Some(result) if matches!(
+            db_state,
+            MessageState::Observed | MessageState::Persisted | MessageState::Verified | MessageState::SentToSolana
+        ) =>
+        {
+            Some(Finding {
+                kind: StateMismatch,
+                detail: format!("already executed on Solana with result {}", result),
+                correction: Some(Correction {
+                    to: MessageState::Executed,
+                    result: Some(result.to_string()),
+                    error: None,
+                }),
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Log and emit a finding, applying its correction if allowed.
+async fn record(
+    state: &Arc<AppState>,
+    msg: &CrossChainMessage,
+    finding: Finding,
+    auto_correct: bool,
+) -> Result<Discrepancy> {
+    let nonce = msg.nonce as u64;
+    let mut corrected_to = None;
+    if let Some(correction) = finding.correction.filter(|_| auto_correct) {
+        db::update_message_state(
+            &state.pool,
+            nonce,
+            MessageState::from_str(&msg.state),
+            correction.to,
+            correction.result.as_deref(),
+            None,
+            None,
+            correction.error.as_deref(),
+            &format!("integrity correction: {}", finding.detail),
+        )
+        .await?;
+        state.cache.invalidate(nonce);
+        corrected_to = Some(correction.to.to_string());
+    }
+
+    warn!(
+        nonce,
+        kind = %finding.kind,
+        db_state = %msg.state,
+        detail = %finding.detail,
+        corrected_to = ?corrected_to,
+        "Integrity check: message disagrees with on-chain state"
+    );
+    let detail = match &corrected_to {
+        Some(to) => format!("{}: {}; corrected to {}", finding.kind, finding.detail, to),
+        None => format!("{}: {}", finding.kind, finding.detail),
+    };
+    let event = LifecycleEvent::new(&msg.trace_id, nonce, Actor::relayer(), Step::IntegrityMismatch, Status::Failure)
+        .with_detail(detail);
+    emit_and_persist(state, &event).await?;
+
+    Ok(Discrepancy {
+        nonce,
+        trace_id: msg.trace_id.clone(),
+        kind: finding.kind,
+        db_state: msg.state.clone(),
+        detail: finding.detail,
+        corrected_to,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::{H256, U256};
+
+    const TRACE: H256 = H256::repeat_byte(0xab);
+
+    fn message(state: MessageState) -> CrossChainMessage {
+        CrossChainMessage {
+            id: 1,
+            nonce: 7,
+            trace_id: format!("{:?}", TRACE),
+            sender: "0x0000000000000000000000000000000000000000".into(),
+            amount: "1000".into(),
+            payload: "0x".into(),
+            deadline: 0,
+            description: None,
+            state: state.to_string(),
+            result: None,
+            solana_signature: None,
+            eth_settle_tx: None,
+            refund_tx: None,
+            proof_json: None,
+            retry_count: 0,
+            error_message: None,
+            run_id: None,
+            src_chain_id: crate::envelope::ETHEREUM_CHAIN_ID,
+            dst_chain_id: SOLANA_CHAIN_ID,
+            payload_type: 0,
+            computation: "double".into(),
+            behavior_json: None,
+            settle_gas_used: None,
+            settle_gas_price_wei: None,
+            settle_block_number: None,
+            settle_cost_wei: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    fn escrow(state: EscrowState) -> OnChainEscrow {
+        OnChainEscrow {
+            state,
+            trace_id: TRACE,
+            amount: U256::from(1000),
+        }
+    }
+
+    fn correction(finding: Option<Finding>) -> Option<(DiscrepancyKind, Option<MessageState>)> {
+        finding.map(|f| (f.kind, f.correction.map(|c| c.to)))
+    }
+
+    #[test]
+    fn settled_escrow_settles_the_message() {
+        let outcome = compare_escrow(&message(MessageState::Executed), &escrow(EscrowState::Settled), 0);
+        assert_eq!(correction(outcome), Some((DiscrepancyKind::StateMismatch, Some(MessageState::Settled))));
+        assert!(compare_escrow(&message(MessageState::Settled), &escrow(EscrowState::Settled), 0).is_none());
+    }
+
+    #[test]
+    fn missing_escrow_is_only_reported() {
+        let outcome = compare_escrow(&message(MessageState::Settled), &escrow(EscrowState::Missing), 0);
+        assert_eq!(correction(outcome), Some((DiscrepancyKind::MissingEscrow, None)));
+    }
+
+    #[test]
+    fn reclaimed_escrow_fails_a_message_in_flight() {
+        let outcome = compare_escrow(&message(MessageState::SentToSolana), &escrow(EscrowState::Reclaimed), 0);
+        assert_eq!(correction(outcome), Some((DiscrepancyKind::StateMismatch, Some(MessageState::Failed))));
+        // Rollbacks leave the escrow to be reclaimed
+        assert!(compare_escrow(&message(MessageState::RolledBack), &escrow(EscrowState::Reclaimed), 0).is_none());
+    }
+
+    #[test]
+    fn other_trace_id_is_only_reported() {
+        let other = OnChainEscrow {
+            trace_id: H256::repeat_byte(0xcd),
+            ..escrow(EscrowState::Settled)
+        };
+        let outcome = compare_escrow(&message(MessageState::Executed), &other, 0);
+        assert_eq!(correction(outcome), Some((DiscrepancyKind::TraceMismatch, None)));
+    }
+
+    #[test]
+    fn locked_escrow_stored_as_settled_retries_until_the_deadline() {
+        let msg = CrossChainMessage {
+            deadline: 100,
+            ..message(MessageState::Settled)
+        };
+        let outcome = compare_escrow(&msg, &escrow(EscrowState::Locked), 50);
+        assert_eq!(correction(outcome), Some((DiscrepancyKind::StateMismatch, Some(MessageState::Executed))));
+        let outcome = compare_escrow(&msg, &escrow(EscrowState::Locked), 150);
+        assert_eq!(correction(outcome), Some((DiscrepancyKind::StateMismatch, Some(MessageState::Failed))));
+    }
+
+    #[test]
+    fn receipts_are_compared_with_the_stored_execution() {
+        let executed = CrossChainMessage {
+            result: Some("2000".into()),
+            ..message(MessageState::Executed)
+        };
+        assert!(compare_receipt(&executed, Some(2000)).is_none());
+        assert_eq!(
+            correction(compare_receipt(&executed, None)),
+            Some((DiscrepancyKind::MissingReceipt, None))
+        );
+        assert_eq!(
+            correction(compare_receipt(&executed, Some(3000))),
+            Some((DiscrepancyKind::ResultMismatch, None))
+        );
+        assert_eq!(
+            correction(compare_receipt(&message(MessageState::SentToSolana), Some(2000))),
+            Some((DiscrepancyKind::StateMismatch, Some(MessageState::Executed)))
+        );
+    }
+}
@@ -0,0 +1,103 @@
+use anyhow::{Context, Result};
+use ethers::signers::{LocalWallet, Signer};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::config::Secret;
+
+/// What an EVM signing key is used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum KeyPurpose {
+    /// Signs and sends `settle()` and `refund()`; must be the escrow's relayer
+    EthSettlement,
+    /// Signs proof bundles as the default validator
+    ProofValidator,
+    /// Sends transactions on another EVM chain, by chain ID
+    EvmChain(u32),
+}
+
+impl std::fmt::Display for KeyPurpose {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EthSettlement => write!(f, "eth-settlement"),
+            Self::ProofValidator => write!(f, "proof-validator"),
+            Self::EvmChain(chain_id) => write!(f, "evm-chain-{}", chain_id),
+        }
+    }
+}
+
+impl std::str::FromStr for KeyPurpose {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "eth-settlement" => Ok(Self::EthSettlement),
+            "proof-validator" => Ok(Self::ProofValidator),
+            other => match other.strip_prefix("evm-chain-").map(str::parse) {
+                Some(Ok(chain_id)) => Ok(Self::EvmChain(chain_id)),
+                _ => anyhow::bail!("unknown key purpose: {}", other),
+            },
+        }
+    }
+}
+
+/// Signing keys by purpose. Purposes without a key of their own use the
+/// relayer key.
+#[derive(Debug, Clone)]
+pub struct Keyring {
+    relayer: Secret,
+    keys: BTreeMap<KeyPurpose, Secret>,
+}
+
+/// One key as reported by GET /relayer/status.
+#[derive(Debug, Clone, Serialize)]
+pub struct SignerKey {
+    pub purpose: String,
+    /// `None` when there is no key (simulated Solana)
+    pub address: Option<String>,
+    /// Configured for this purpose rather than falling back to the relayer key
+    pub dedicated: bool,
+}
+
+impl Keyring {
+    /// Fails if any key is not a valid secp256k1 private key.
+    pub fn new(relayer: Secret, keys: BTreeMap<KeyPurpose, Secret>) -> Result<Self> {
+        relayer.0.parse::<LocalWallet>().context("invalid relayer key")?;
+        for (purpose, key) in &keys {
+            key.0.parse::<LocalWallet>().with_context(|| format!("invalid {} key", purpose))?;
+        }
+        Ok(Self { relayer, keys })
+    }
+
+    /// Hex private key for `purpose`.
+    pub fn key(&self, purpose: KeyPurpose) -> &Secret {
+        self.keys.get(&purpose).unwrap_or(&self.relayer)
+    }
+
+    pub fn eth_settlement(&self) -> &str {
+        &self.key(KeyPurpose::EthSettlement).0
+    }
+
+    pub fn address(&self, purpose: KeyPurpose) -> String {
+        // Keys are checked in `new`
+        self.key(purpose)
+            .0
+            .parse::<LocalWallet>()
+            .map(|w| format!("{:?}", w.address()))
+            .unwrap_or_default()
+    }
+
+    /// Settlement and validator keys, then any per-chain keys.
+    pub fn report(&self) -> Vec<SignerKey> {
+        let chains = self.keys.keys().filter(|p| matches!(p, KeyPurpose::EvmChain(_)));
+        [KeyPurpose::EthSettlement, KeyPurpose::ProofValidator]
+            .iter()
+            .chain(chains)
+            .map(|&purpose| SignerKey {
+                purpose: purpose.to_string(),
+                address: Some(self.address(purpose)),
+                dedicated: self.keys.contains_key(&purpose),
+            })
+            .collect()
+    }
+}
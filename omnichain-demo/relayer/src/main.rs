@@ -1,13 +1,40 @@
+mod adversary;
+mod analysis;
+mod analysis_queue;
+mod bench;
+mod cache;
+mod chaos;
+mod compare;
 mod config;
 mod db;
+mod envelope;
+mod escrow;
 mod eth;
 mod event;
+mod event_writer;
+mod executor_error;
+mod export;
+mod gas;
+mod integrity;
+mod keyring;
+mod notary;
+mod payload_schema;
+mod prometheus;
+mod refund;
+mod replay;
+mod routing;
+mod s3;
+mod signing;
 mod server;
+mod solana;
+mod solana_rpc;
 mod solana_sim;
 mod state_machine;
 mod traffic_gen;
 mod types;
 mod verification;
+mod ws_auth;
+mod ws_stats;
 
 use anyhow::Result;
 use std::sync::Arc;
@@ -25,11 +52,13 @@ async fn main() -> Result<()> {
 
     info!("Starting omnichain relayer...");
 
-    let cfg = config::Config::from_env();
+    let cfg = config::Config::from_env()?;
+    let bench_size = bench::size_from_args()?;
     info!(?cfg, "Loaded configuration");
 
     // Initialize SQLite database
     let pool = db::init_db(&cfg.database_url).await?;
+    db::ensure_default_chains(&pool, &cfg.eth_rpc_url).await?;
     info!("Database initialized");
 
     // Event broadcast channel for WebSocket streaming
@@ -48,6 +77,17 @@ async fn main() -> Result<()> {
         0
     };
 
+    let validators = verification::ValidatorSet::from_keys(&cfg.validator_keys)?;
+    info!(count = cfg.validator_keys.len(), "Validator set loaded");
+
+    let event_layout = eth::EventLayout::parse(&cfg.escrow_event_abi, cfg.escrow_event_fields.as_deref())?;
+    info!(event = %event_layout.event.name, topic = ?event_layout.signature(), "Escrow event layout loaded");
+
+    let solana = solana::from_config(&cfg)?;
+    info!(mode = %solana.mode(), "Solana executor selected");
+
+    let (event_writer, event_queue) = event_writer::channel();
+
     // Shared application state
     let app_state = Arc::new(types::AppState {
         pool: pool.clone(),
@@ -55,11 +95,42 @@ async fn main() -> Result<()> {
         paused: std::sync::atomic::AtomicBool::new(false),
         simulation_running: std::sync::atomic::AtomicBool::new(auto_start),
         simulation_deadline: std::sync::atomic::AtomicI64::new(auto_deadline),
+        traffic_throttled: std::sync::atomic::AtomicBool::new(false),
         config: cfg.clone(),
+        validators,
+        chaos_stats: chaos::ChaosStats::default(),
+        scenario: std::sync::Mutex::new(chaos::ScenarioSettings::default()),
+        current_run: std::sync::Mutex::new(None),
+        replay_generation: std::sync::atomic::AtomicU64::new(0),
+        event_layout,
+        gas: std::sync::Mutex::new(None),
+        fee_simulator: gas::FeeSimulator::new(cfg.gas_sim_base_gwei),
+        solana,
+        started_at: chrono::Utc::now(),
+        last_processed_block: std::sync::atomic::AtomicU64::new(0),
+        integrity: std::sync::Mutex::new(None),
+        cache: cache::RecentCache::new(cfg.cache_recent_messages),
+        benchmark_running: std::sync::atomic::AtomicBool::new(false),
+        escrow: std::sync::Mutex::new(None),
+        ws_connections: ws_stats::WsConnections::default(),
+        export_running: std::sync::atomic::AtomicBool::new(false),
+        event_writer,
+        analysis_queue: analysis_queue::AnalysisQueue::new(
+            cfg.analysis_concurrency,
+            cfg.openai_requests_per_minute,
+            cfg.analysis_max_queued,
+        ),
+        ws_tokens: ws_auth::WsTokenIssuer::new(cfg.ws_token_ttl_secs),
+        notary_running: std::sync::atomic::AtomicBool::new(false),
+        deferrals: executor_error::Deferrals::default(),
     });
 
+    // Spawn the event writer before anything emits events
+    tokio::spawn(event_writer::run_event_writer(app_state.clone(), event_queue));
+
     if auto_start {
         info!("Auto-starting simulation (1 hour)");
+        traffic_gen::begin_run(&app_state, chaos::ScenarioSettings::default(), 60).await?;
     }
 
     // Spawn the HTTP + WebSocket server
@@ -75,8 +146,21 @@ async fn main() -> Result<()> {
     let processor_state = app_state.clone();
     let processor_cfg = cfg.clone();
     let processor_handle = tokio::spawn(async move {
-        if let Err(e) = state_machine::run_processor(processor_state, processor_cfg).await {
-            error!(?e, "Processor error");
+        // Restart the processor if it panics; it resumes in-flight messages
+        // from the database like after a process restart
+        loop {
+            let task = tokio::spawn(state_machine::run_processor(processor_state.clone(), processor_cfg.clone()));
+            match task.await {
+                Ok(Err(e)) => {
+                    error!(?e, "Processor error");
+                    break;
+                }
+                Err(e) if e.is_panic() => {
+                    error!("Processor task crashed, restarting in 1s");
+                    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                }
+                _ => break,
+            }
         }
     });
 
@@ -88,6 +172,32 @@ async fn main() -> Result<()> {
         traffic_gen::run_traffic_generator(traffic_state, traffic_rpc, traffic_escrow).await;
     });
 
+    // Spawn the periodic fleet analysis report job
+    tokio::spawn(analysis::run_fleet_scheduler(app_state.clone()));
+
+    // Spawn the Parquet export job (EXPORT_INTERVAL_HOURS > 0)
+    tokio::spawn(export::run_export_scheduler(app_state.clone()));
+
+    // Spawn the gas sampler
+    tokio::spawn(gas::run_gas_sampler(app_state.clone()));
+    tokio::spawn(escrow::run_escrow_sampler(app_state.clone()));
+
+    // Spawn the adversary (ADVERSARY_INTERVAL_SECS > 0)
+    tokio::spawn(adversary::run_adversary(app_state.clone()));
+
+    // Spawn the expired escrow refunder (REFUND_INTERVAL_SECS > 0)
+    tokio::spawn(refund::run_refunder(app_state.clone()));
+
+    // Spawn the daily notary (NOTARY_INTERVAL_SECS > 0)
+    tokio::spawn(notary::run_notary(app_state.clone()));
+
+    // --bench: measure the pipeline, print the report and exit
+    if let Some(n) = bench_size {
+        let report = bench::run_benchmark(&app_state, n).await?;
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
     // Wait for any to finish (they shouldn't under normal operation)
     tokio::select! {
         r = server_handle => {
@@ -99,7 +209,37 @@ async fn main() -> Result<()> {
         r = traffic_handle => {
             error!(?r, "Traffic generator task ended");
         }
+        _ = shutdown_signal() => {
+            info!("Shutdown requested, writing buffered events");
+            // Stop advancing messages, so state changes stop queueing events
+            app_state.paused.store(true, std::sync::atomic::Ordering::SeqCst);
+        }
     }
+    // Events still buffered by the writer would otherwise be lost
+    app_state.event_writer.flush().await;
 
     Ok(())
 }
+
+/// Resolves on SIGTERM (`docker stop`, supervisord) or Ctrl-C.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                error!(error = %e, "Failed to listen for SIGTERM");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = terminate => {}
+    }
+}
@@ -0,0 +1,285 @@
+use anyhow::Result;
+use chrono::{NaiveDate, Utc};
+use ethers::utils::keccak256;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::time::{sleep, Duration};
+use tracing::{info, warn};
+
+use crate::db;
+use crate::eth;
+use crate::event::LifecycleEvent;
+use crate::gas;
+use crate::types::{AppState, CrossChainMessage, RunningGuard};
+
+/// Merkle roots of one UTC day of messages and events (`notarizations` table).
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Notarization {
+    pub id: i64,
+    /// YYYY-MM-DD
+    pub day: String,
+    /// Messages created that day
+    pub messages: i64,
+    /// Events timestamped that day
+    pub events: i64,
+    pub messages_root: String,
+    pub events_root: String,
+    /// Root over `messages_root` and `events_root`; the anchored value
+    pub root: String,
+    /// Ethereum transaction carrying `root`; None until anchored
+    pub anchor_tx: Option<String>,
+    pub anchor_block: Option<i64>,
+    /// Why the last anchoring attempt failed
+    pub anchor_error: Option<String>,
+    pub created_at: String,
+    pub anchored_at: Option<String>,
+}
+
+/// Roots of a day as the database holds it now.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DayRoots {
+    pub messages: i64,
+    pub events: i64,
+    pub messages_root: String,
+    pub events_root: String,
+    pub root: String,
+}
+
+/// Response for GET /notarizations/:day
+#[derive(Debug, Serialize)]
+pub struct NotarizationCheck {
+    pub notarization: Notarization,
+    /// Roots recomputed from the current rows
+    pub current: DayRoots,
+    /// False if the day's rows changed since they were notarized
+    pub matches: bool,
+    /// Reparses and purges that changed the day's rows, which account for
+    /// a mismatch they made
+    pub admin_changes: Vec<crate::types::AuditEntry>,
+}
+
+/// Query for POST /control/notarize
+#[derive(Debug, Deserialize)]
+pub struct NotarizeQuery {
+    /// YYYY-MM-DD; defaults to yesterday (UTC)
+    pub date: Option<NaiveDate>,
+}
+
+/// Query for GET /notarizations
+#[derive(Debug, Deserialize)]
+pub struct NotarizationsQuery {
+    #[serde(default = "default_notarizations_limit")]
+    pub limit: i64,
+}
+
+fn default_notarizations_limit() -> i64 {
+    30
+}
+
+/// Every `NOTARY_INTERVAL_SECS`, notarize each completed UTC day that has
+/// data but no anchored notarization yet.
+pub async fn run_notary(state: Arc<AppState>) {
+    let interval_secs = state.config.notary_interval_secs;
+    if interval_secs == 0 {
+        info!("Notary disabled");
+        return;
+    }
+
+    info!(interval_secs, "Notary started");
+    loop {
+        sleep(Duration::from_secs(interval_secs)).await;
+        let today = Utc::now().date_naive().to_string();
+        let days = match db::get_unnotarized_days(&state.pool, &today).await {
+            Ok(days) => days,
+            Err(e) => {
+                warn!(error = %e, "Failed to list days to notarize");
+                continue;
+            }
+        };
+        for day in days {
+            let Ok(day) = day.parse::<NaiveDate>() else {
+                continue;
+            };
+            if let Err(e) = notarize(&state, day).await {
+                warn!(%day, error = %e, "Notarization failed, will retry");
+            }
+        }
+    }
+}
+
+/// Compute a day's roots, store them and anchor the root on Ethereum, unless
+/// another notarization is running. A day is only notarized once its root
+/// is anchored; until then each attempt recomputes it.
+pub async fn notarize(state: &AppState, day: NaiveDate) -> Result<Notarization> {
+    let Some(_running) = RunningGuard::acquire(&state.notary_running) else {
+        anyhow::bail!("a notarization is already running");
+    };
+    notarize_day(state, day).await
+}
+
+async fn notarize_day(state: &AppState, day: NaiveDate) -> Result<Notarization> {
+    let day_str = day.to_string();
+    if let Some(existing) = db::get_notarization(&state.pool, &day_str).await? {
+        if existing.anchor_tx.is_some() {
+            return Ok(existing);
+        }
+    }
+
+    let roots = day_roots(state, &day_str).await?;
+    db::upsert_notarization(&state.pool, &day_str, &roots).await?;
+    info!(day = %day_str, messages = roots.messages, events = roots.events, root = %roots.root, "Day notarized");
+
+    let cfg = &state.config;
+    let gas = gas::read(state).await.ok();
+    let anchor = eth::call_notarize(
+        &cfg.eth_rpc_url,
+        cfg.keyring.eth_settlement(),
+        cfg.notary_address.as_deref(),
+        day_number(day),
+        parse_root(&roots.root),
+        gas.as_ref(),
+    )
+    .await;
+    match &anchor {
+        Ok(tx) => {
+            db::record_notarization_anchor(
+                &state.pool,
+                &day_str,
+                Some(&format!("{:?}", tx.tx_hash)),
+                tx.block_number,
+                None,
+            )
+            .await?;
+            info!(day = %day_str, tx_hash = ?tx.tx_hash, "Notarization anchored on Ethereum");
+        }
+        Err(e) => {
+            db::record_notarization_anchor(&state.pool, &day_str, None, None, Some(&e.to_string())).await?;
+            warn!(day = %day_str, error = %e, "Failed to anchor notarization");
+        }
+    }
+
+    db::get_notarization(&state.pool, &day_str)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Notarization of {} vanished after insert", day_str))
+}
+
+/// Recompute a day's roots from the rows as they are now.
+pub async fn day_roots(state: &AppState, day: &str) -> Result<DayRoots> {
+    // Buffered events belong to the day as well
+    state.event_writer.flush().await;
+    let messages = db::get_messages_created_on(&state.pool, day).await?;
+    let events = db::get_events_on(&state.pool, day).await?;
+
+    let messages_root = merkle_root(messages.iter().map(message_leaf));
+    let events_root = merkle_root(events.iter().map(event_leaf));
+    Ok(DayRoots {
+        messages: messages.len() as i64,
+        events: events.len() as i64,
+        root: hex_root(node(&messages_root, &events_root)),
+        messages_root: hex_root(messages_root),
+        events_root: hex_root(events_root),
+    })
+}
+
+/// Leaf of a message: its fields fixed at creation, so later state changes
+/// keep the day's root stable.
+fn message_leaf(msg: &CrossChainMessage) -> Vec<u8> {
+    serde_json::json!({
+        "nonce": msg.nonce,
+        "trace_id": msg.trace_id,
+        "sender": msg.sender,
+        "amount": msg.amount,
+        "payload": msg.payload,
+        "deadline": msg.deadline,
+        "src_chain_id": msg.src_chain_id,
+        "dst_chain_id": msg.dst_chain_id,
+        "created_at": msg.created_at,
+    })
+    .to_string()
+    .into_bytes()
+}
+
+/// Leaf of an event: the whole row. Events are only changed by admin
+/// actions (a reparse rewrites their trace id, a purge deletes them),
+/// which the audit log records.
+fn event_leaf(event: &LifecycleEvent) -> Vec<u8> {
+    serde_json::to_vec(event).unwrap_or_default()
+}
+
+/// Merkle root over `leaves` in order. Leaves and inner nodes are hashed
+/// with distinct prefixes, an odd node is carried up unchanged, and no
+/// leaves give the zero hash.
+pub fn merkle_root(leaves: impl Iterator<Item = Vec<u8>>) -> [u8; 32] {
+    let mut level: Vec<[u8; 32]> = leaves
+        .map(|leaf| keccak256([&[0u8][..], &leaf].concat()))
+        .collect();
+    if level.is_empty() {
+        return [0; 32];
+    }
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => node(left, right),
+                [single] => *single,
+                _ => unreachable!("chunks of two"),
+            })
+            .collect();
+    }
+    level[0]
+}
+
+fn node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    keccak256([&[1u8][..], left, right].concat())
+}
+
+fn hex_root(root: [u8; 32]) -> String {
+    format!("0x{}", hex::encode(root))
+}
+
+fn parse_root(root: &str) -> [u8; 32] {
+    let mut bytes = [0; 32];
+    if let Ok(decoded) = hex::decode(root.trim_start_matches("0x")) {
+        if decoded.len() == 32 {
+            bytes.copy_from_slice(&decoded);
+        }
+    }
+    bytes
+}
+
+/// YYYYMMDD, as anchored in the calldata.
+fn day_number(day: NaiveDate) -> u32 {
+    day.format("%Y%m%d").to_string().parse().unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaves<'a>(values: &'a [&str]) -> impl Iterator<Item = Vec<u8>> + 'a {
+        values.iter().map(|v| v.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn root_depends_on_every_leaf_and_its_order() {
+        let root = merkle_root(leaves(&["a", "b", "c"]));
+        assert_ne!(root, merkle_root(leaves(&["a", "b", "d"])));
+        assert_ne!(root, merkle_root(leaves(&["b", "a", "c"])));
+        assert_ne!(root, merkle_root(leaves(&["a", "b"])));
+        assert_eq!(root, merkle_root(leaves(&["a", "b", "c"])));
+        assert_eq!(merkle_root(leaves(&[])), [0; 32]);
+    }
+
+    #[test]
+    fn a_single_leaf_is_not_its_own_root() {
+        let leaf = b"a".to_vec();
+        assert_ne!(merkle_root(std::iter::once(leaf.clone())), keccak256(&leaf));
+    }
+
+    #[test]
+    fn roots_and_days_encode_for_the_calldata() {
+        let root = merkle_root(leaves(&["a"]));
+        assert_eq!(parse_root(&hex_root(root)), root);
+        assert_eq!(day_number(NaiveDate::from_ymd_opt(2026, 10, 16).unwrap()), 20261016);
+    }
+}
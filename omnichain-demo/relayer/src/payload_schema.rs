@@ -0,0 +1,296 @@
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Type of one payload field. Integers are big-endian, like the routing
+/// header. `string` and `bytes` take the rest of the body, so only the last
+/// field may have one of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum FieldType {
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    Bool,
+    /// 20-byte EVM address
+    Address,
+    /// Fixed-size byte string, `bytes1` to `bytes64`
+    FixedBytes(usize),
+    /// UTF-8 text, rest of the body
+    String,
+    /// Rest of the body
+    Bytes,
+}
+
+impl FieldType {
+    /// Encoded size, None for the variable-length types.
+    pub fn size(self) -> Option<usize> {
+        match self {
+            Self::U8 | Self::I8 | Self::Bool => Some(1),
+            Self::U16 | Self::I16 => Some(2),
+            Self::U32 | Self::I32 => Some(4),
+            Self::U64 | Self::I64 => Some(8),
+            Self::U128 | Self::I128 => Some(16),
+            Self::Address => Some(20),
+            Self::FixedBytes(n) => Some(n),
+            Self::String | Self::Bytes => None,
+        }
+    }
+
+    /// JSON value of `bytes`, which is exactly `size()` long for fixed types.
+    /// 64- and 128-bit integers are decimal strings, as they can exceed
+    /// JavaScript's safe integer range.
+    fn decode(self, bytes: &[u8]) -> Result<Value> {
+        fn be<const N: usize>(bytes: &[u8]) -> [u8; N] {
+            bytes.try_into().expect("field slice has the type's size")
+        }
+        Ok(match self {
+            Self::U8 => Value::from(bytes[0]),
+            Self::U16 => Value::from(u16::from_be_bytes(be(bytes))),
+            Self::U32 => Value::from(u32::from_be_bytes(be(bytes))),
+            Self::U64 => Value::from(u64::from_be_bytes(be(bytes)).to_string()),
+            Self::U128 => Value::from(u128::from_be_bytes(be(bytes)).to_string()),
+            Self::I8 => Value::from(bytes[0] as i8),
+            Self::I16 => Value::from(i16::from_be_bytes(be(bytes))),
+            Self::I32 => Value::from(i32::from_be_bytes(be(bytes))),
+            Self::I64 => Value::from(i64::from_be_bytes(be(bytes)).to_string()),
+            Self::I128 => Value::from(i128::from_be_bytes(be(bytes)).to_string()),
+            Self::Bool => match bytes[0] {
+                0 => Value::Bool(false),
+                1 => Value::Bool(true),
+                other => bail!("invalid bool byte {}", other),
+            },
+            Self::Address | Self::FixedBytes(_) | Self::Bytes => Value::from(format!("0x{}", hex::encode(bytes))),
+            Self::String => Value::from(std::str::from_utf8(bytes)?),
+        })
+    }
+}
+
+impl std::fmt::Display for FieldType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::U8 => write!(f, "u8"),
+            Self::U16 => write!(f, "u16"),
+            Self::U32 => write!(f, "u32"),
+            Self::U64 => write!(f, "u64"),
+            Self::U128 => write!(f, "u128"),
+            Self::I8 => write!(f, "i8"),
+            Self::I16 => write!(f, "i16"),
+            Self::I32 => write!(f, "i32"),
+            Self::I64 => write!(f, "i64"),
+            Self::I128 => write!(f, "i128"),
+            Self::Bool => write!(f, "bool"),
+            Self::Address => write!(f, "address"),
+            Self::FixedBytes(n) => write!(f, "bytes{}", n),
+            Self::String => write!(f, "string"),
+            Self::Bytes => write!(f, "bytes"),
+        }
+    }
+}
+
+impl std::str::FromStr for FieldType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "u8" => Self::U8,
+            "u16" => Self::U16,
+            "u32" => Self::U32,
+            "u64" => Self::U64,
+            "u128" => Self::U128,
+            "i8" => Self::I8,
+            "i16" => Self::I16,
+            "i32" => Self::I32,
+            "i64" => Self::I64,
+            "i128" => Self::I128,
+            "bool" => Self::Bool,
+            "address" => Self::Address,
+            "string" => Self::String,
+            "bytes" => Self::Bytes,
+            other => match other.strip_prefix("bytes").map(str::parse) {
+                Some(Ok(n @ 1..=64)) => Self::FixedBytes(n),
+                _ => bail!("unknown field type: {}", other),
+            },
+        })
+    }
+}
+
+impl TryFrom<String> for FieldType {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<FieldType> for String {
+    fn from(t: FieldType) -> Self {
+        t.to_string()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub field_type: FieldType,
+}
+
+/// A registered payload layout (`payload_schemas` table).
+#[derive(Debug, Clone, Serialize)]
+pub struct PayloadSchema {
+    pub id: i64,
+    /// Unique name, e.g. `transfer-v1`
+    pub name: String,
+    /// Routing header payload type the schema is the default decoder for
+    pub payload_type: Option<u8>,
+    /// Fields in body order, after any routing header
+    pub fields: Vec<SchemaField>,
+    pub created_at: String,
+}
+
+/// Request body for POST /payload-schemas
+#[derive(Debug, Deserialize)]
+pub struct PayloadSchemaRequest {
+    pub name: String,
+    pub payload_type: Option<u8>,
+    pub fields: Vec<SchemaField>,
+}
+
+impl PayloadSchemaRequest {
+    /// A name, at least one field, unique field names, and variable-length
+    /// types only last.
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            bail!("schema name is empty");
+        }
+        if self.fields.is_empty() {
+            bail!("schema has no fields");
+        }
+        for (i, field) in self.fields.iter().enumerate() {
+            if field.name.is_empty() {
+                bail!("field {} has no name", i);
+            }
+            if self.fields[..i].iter().any(|f| f.name == field.name) {
+                bail!("field {} appears twice", field.name);
+            }
+            if field.field_type.size().is_none() && i + 1 < self.fields.len() {
+                bail!("{} field {} must be the last field", field.field_type, field.name);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Query for GET /transactions/:nonce/decoded-payload
+#[derive(Debug, Deserialize)]
+pub struct DecodedPayloadQuery {
+    /// Schema name; defaults to the one registered for the payload type
+    pub schema: Option<String>,
+}
+
+/// Response for GET /transactions/:nonce/decoded-payload
+#[derive(Debug, Serialize)]
+pub struct DecodedPayload {
+    pub nonce: u64,
+    pub schema: String,
+    /// Routing header payload type (0 without a header)
+    pub payload_type: u8,
+    /// Codec the body was decoded with before applying the schema; None if
+    /// no codec could, and the schema was applied to the whole body
+    pub codec: Option<String>,
+    /// Field values by name
+    pub fields: Map<String, Value>,
+    /// Hex of the body bytes after the last field, if any
+    pub trailing: Option<String>,
+}
+
+impl PayloadSchema {
+    /// Decode payload data (routing header and codec frame already removed).
+    /// Fails if the data is shorter than the fixed fields or a value is invalid.
+    pub fn decode(&self, body: &[u8]) -> Result<(Map<String, Value>, Option<String>)> {
+        let mut fields = Map::new();
+        let mut offset = 0;
+        for field in &self.fields {
+            let end = match field.field_type.size() {
+                Some(size) => offset + size,
+                None => body.len(),
+            };
+            let Some(bytes) = body.get(offset..end) else {
+                bail!("body ends before field {} ({} bytes, needs {})", field.name, body.len(), end);
+            };
+            let value = field
+                .field_type
+                .decode(bytes)
+                .map_err(|e| anyhow::anyhow!("field {}: {}", field.name, e))?;
+            fields.insert(field.name.clone(), value);
+            offset = end;
+        }
+        let trailing = (offset < body.len()).then(|| format!("0x{}", hex::encode(&body[offset..])));
+        Ok((fields, trailing))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema(fields: &[(&str, &str)]) -> PayloadSchema {
+        PayloadSchema {
+            id: 1,
+            name: "test".into(),
+            payload_type: None,
+            fields: fields
+                .iter()
+                .map(|(name, t)| SchemaField {
+                    name: name.to_string(),
+                    field_type: t.parse().unwrap(),
+                })
+                .collect(),
+            created_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn decodes_fixed_and_trailing_fields() {
+        let mut body = vec![0x01, 0x00, 0x00, 0x00, 0x2a];
+        body.extend_from_slice(&[0xab; 20]);
+        body.extend_from_slice(b"hello");
+        let (fields, trailing) = schema(&[("flag", "bool"), ("amount", "u32"), ("to", "address"), ("memo", "string")])
+            .decode(&body)
+            .unwrap();
+        assert_eq!(fields["flag"], Value::Bool(true));
+        assert_eq!(fields["amount"], Value::from(42));
+        assert_eq!(fields["to"], Value::from(format!("0x{}", "ab".repeat(20))));
+        assert_eq!(fields["memo"], Value::from("hello"));
+        assert_eq!(trailing, None);
+    }
+
+    #[test]
+    fn large_integers_are_strings_and_leftovers_are_reported() {
+        let mut body = u64::MAX.to_be_bytes().to_vec();
+        body.push(0xff);
+        let (fields, trailing) = schema(&[("amount", "u64")]).decode(&body).unwrap();
+        assert_eq!(fields["amount"], Value::from(u64::MAX.to_string()));
+        assert_eq!(trailing.as_deref(), Some("0xff"));
+    }
+
+    #[test]
+    fn rejects_short_bodies_and_misplaced_variable_fields() {
+        assert!(schema(&[("amount", "u128")]).decode(&[0; 8]).is_err());
+        let request = PayloadSchemaRequest {
+            name: "bad".into(),
+            payload_type: None,
+            fields: schema(&[("memo", "string"), ("amount", "u8")]).fields,
+        };
+        assert!(request.validate().is_err());
+        assert!("bytes65".parse::<FieldType>().is_err());
+    }
+}
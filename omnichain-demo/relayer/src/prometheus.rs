@@ -0,0 +1,53 @@
+use anyhow::Result;
+use std::fmt::Write;
+use std::sync::Arc;
+
+use crate::db;
+use crate::types::AppState;
+
+/// Render relayer metrics in the Prometheus text exposition format.
+pub async fn render(state: &Arc<AppState>) -> Result<String> {
+    let mut out = String::new();
+
+    let (total, settled, failed, pending, retries) = db::get_metrics(&state.pool, None).await?;
+    writeln!(out, "# HELP relayer_messages Messages by outcome.")?;
+    writeln!(out, "# TYPE relayer_messages gauge")?;
+    writeln!(out, "relayer_messages{{outcome=\"total\"}} {}", total)?;
+    writeln!(out, "relayer_messages{{outcome=\"settled\"}} {}", settled)?;
+    writeln!(out, "relayer_messages{{outcome=\"failed\"}} {}", failed)?;
+    writeln!(out, "relayer_messages{{outcome=\"pending\"}} {}", pending)?;
+
+    writeln!(
+        out,
+        "# HELP relayer_retries_total Retries across all messages."
+    )?;
+    writeln!(out, "# TYPE relayer_retries_total counter")?;
+    writeln!(out, "relayer_retries_total {}", retries)?;
+
+    writeln!(out, "# HELP relayer_stage_failures_total Failures per pipeline stage, split into injected (chaos) and real.")?;
+    writeln!(out, "# TYPE relayer_stage_failures_total counter")?;
+    for s in state.chaos_stats.snapshot() {
+        writeln!(
+            out,
+            "relayer_stage_failures_total{{stage=\"{}\",origin=\"injected\"}} {}",
+            s.stage, s.injected
+        )?;
+        writeln!(
+            out,
+            "relayer_stage_failures_total{{stage=\"{}\",origin=\"real\"}} {}",
+            s.stage, s.real
+        )?;
+    }
+
+    let (hits, misses) = state.cache.stats();
+    writeln!(out, "# HELP relayer_cache_reads_total Transaction and event reads, served from memory or SQLite.")?;
+    writeln!(out, "# TYPE relayer_cache_reads_total counter")?;
+    writeln!(out, "relayer_cache_reads_total{{source=\"memory\"}} {}", hits)?;
+    writeln!(out, "relayer_cache_reads_total{{source=\"database\"}} {}", misses)?;
+
+    writeln!(out, "# HELP relayer_ws_connections Open WebSocket event stream connections.")?;
+    writeln!(out, "# TYPE relayer_ws_connections gauge")?;
+    writeln!(out, "relayer_ws_connections {}", state.ws_connections.active())?;
+
+    Ok(out)
+}
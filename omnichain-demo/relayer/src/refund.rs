@@ -0,0 +1,109 @@
+use anyhow::Result;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use tokio::time::{sleep, Duration};
+use tracing::{info, warn};
+
+use crate::db;
+use crate::envelope::ETHEREUM_CHAIN_ID;
+use crate::eth::{self, EscrowState, RefundCheck};
+use crate::event::{Actor, LifecycleEvent, Status, Step};
+use crate::gas;
+use crate::types::{AppState, MessageState};
+
+/// Refund expired escrows every `REFUND_INTERVAL_SECS`, so funds of messages
+/// that failed or rolled back go back to the sender without a `reclaim()`.
+/// Stops for good if the deployed escrow has no `refund()`.
+pub async fn run_refunder(state: Arc<AppState>) {
+    let interval_secs = state.config.refund_interval_secs;
+    if interval_secs == 0 {
+        return;
+    }
+
+    loop {
+        sleep(Duration::from_secs(interval_secs)).await;
+        if state.paused.load(Ordering::Relaxed) {
+            continue;
+        }
+        match refund_expired(&state).await {
+            Ok(true) => {}
+            Ok(false) => {
+                warn!("Escrow contract has no refund(); expired escrows are left for their senders to reclaim");
+                return;
+            }
+            Err(e) => warn!(error = %e, "Refund pass failed, will retry"),
+        }
+    }
+}
+
+/// One pass over the expired escrows. False if the escrow has no `refund()`.
+async fn refund_expired(state: &Arc<AppState>) -> Result<bool> {
+    let cfg = &state.config;
+    let now = chrono::Utc::now().timestamp();
+    let expired = db::get_expired_escrow_messages(&state.pool, now).await?;
+    if expired.is_empty() {
+        return Ok(true);
+    }
+
+    let actor = db::get_chain(&state.pool, ETHEREUM_CHAIN_ID)
+        .await?
+        .map(|chain| Actor::for_chain(&chain))
+        .unwrap_or_else(Actor::ethereum);
+
+    for msg in expired {
+        if state.paused.load(Ordering::Relaxed) {
+            break;
+        }
+        let nonce = msg.nonce as u64;
+
+        // Settled, reclaimed or missing escrows are never checked again
+        let escrow = eth::get_escrows(&cfg.eth_rpc_url, &cfg.escrow_address, &[nonce]).await?;
+        let escrow_state = escrow.first().map(|e| e.state).unwrap_or(EscrowState::Missing);
+        if escrow_state != EscrowState::Locked {
+            db::record_refund_skipped(&state.pool, nonce, &format!("escrow {}", escrow_state)).await?;
+            continue;
+        }
+
+        match eth::simulate_refund(&cfg.eth_rpc_url, cfg.keyring.eth_settlement(), &cfg.escrow_address, nonce).await? {
+            RefundCheck::Refundable => {}
+            RefundCheck::Unsupported => return Ok(false),
+            // The chain's clock is behind ours; try again next pass
+            RefundCheck::Reverted(error) if error == "DeadlineNotReached" => continue,
+            RefundCheck::Reverted(error) => {
+                warn!(nonce, %error, "Escrow refund would revert, leaving it for the sender");
+                db::record_refund_skipped(&state.pool, nonce, &format!("refund() reverts: {}", error)).await?;
+                continue;
+            }
+        }
+
+        let gas = gas::read(state).await.ok();
+        let refund = match eth::call_refund(
+            &cfg.eth_rpc_url,
+            cfg.keyring.eth_settlement(),
+            &cfg.escrow_address,
+            nonce,
+            gas.as_ref(),
+        )
+        .await
+        {
+            Ok(refund) => refund,
+            Err(e) => {
+                warn!(nonce, error = %e, "Escrow refund failed, will retry");
+                continue;
+            }
+        };
+        let tx_hash = format!("{:?}", refund.tx_hash);
+
+        db::record_refund(&state.pool, nonce, MessageState::from_str(&msg.state), &tx_hash).await?;
+        state.cache.invalidate(nonce);
+
+        let mut event = LifecycleEvent::new(&msg.trace_id, nonce, actor.clone(), Step::Refunded, Status::Success)
+            .with_detail(format!("Expired escrow refunded to the sender (was {}), tx:{}", msg.state, tx_hash));
+        event.fee_wei = refund.fee_wei.map(|f| f.to_string());
+        event.gas_used = refund.gas_used;
+        crate::state_machine::emit_and_persist(state, &event).await?;
+
+        info!(nonce, %tx_hash, amount = %msg.amount, "Expired escrow refunded");
+    }
+    Ok(true)
+}
@@ -0,0 +1,102 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use tokio::time::{sleep_until, Duration, Instant};
+use tracing::{info, warn};
+
+use crate::event::{LifecycleEvent, SCHEMA_VERSION};
+use crate::types::{AppState, ReplaySummary};
+
+/// Upper bound on the playback multiplier (1 hour → 3.6 seconds).
+pub const MAX_SPEED: f64 = 1000.0;
+
+/// Load a recorded run and compute its playback schedule.
+///
+/// Each event is rescheduled at `(original - first) / speed` after the replay
+/// starts, and its timestamp is rewritten to that instant so the stream stays
+/// internally consistent. Replayed events are not persisted, carry no `seq`,
+/// and are marked with `replay_run_id` so consumers can tell them from live ones.
+pub async fn prepare(
+    state: &AppState,
+    run_id: &str,
+    speed: f64,
+) -> Result<(ReplaySummary, Vec<(Duration, LifecycleEvent)>)> {
+    let events = crate::db::get_events_by_run(&state.pool, run_id).await?;
+
+    let times: Vec<Option<DateTime<Utc>>> = events
+        .iter()
+        .map(|e| {
+            DateTime::parse_from_rfc3339(&e.timestamp)
+                .ok()
+                .map(|t| t.with_timezone(&Utc))
+        })
+        .collect();
+    let first = times.iter().flatten().min().copied();
+    let last = times.iter().flatten().max().copied();
+
+    // Events with unparseable timestamps keep the offset of the one before them
+    let mut offset = Duration::ZERO;
+    let schedule = events
+        .into_iter()
+        .zip(times)
+        .map(|(event, time)| {
+            if let (Some(t), Some(first)) = (time, first) {
+                let original = (t - first).to_std().unwrap_or_default();
+                offset = original.div_f64(speed);
+            }
+            (offset, event)
+        })
+        .collect::<Vec<_>>();
+
+    let original_secs = match (first, last) {
+        (Some(first), Some(last)) => (last - first).num_milliseconds() as f64 / 1000.0,
+        _ => 0.0,
+    };
+
+    let summary = ReplaySummary {
+        run_id: run_id.to_string(),
+        speed,
+        events: schedule.len(),
+        original_duration_secs: original_secs,
+        playback_duration_secs: original_secs / speed,
+    };
+    Ok((summary, schedule))
+}
+
+/// Start a new replay generation, superseding any replay still playing.
+pub fn next_generation(state: &AppState) -> u64 {
+    state.replay_generation.fetch_add(1, Ordering::Relaxed) + 1
+}
+
+/// Play a prepared schedule through the event broadcast channel, each event
+/// marked as replayed from `run_id`.
+/// Stops early once a newer replay starts or POST /control/stop-replay is called.
+pub async fn play(
+    state: Arc<AppState>,
+    generation: u64,
+    run_id: String,
+    schedule: Vec<(Duration, LifecycleEvent)>,
+) {
+    let started = Instant::now();
+    let started_at = Utc::now();
+    let mut sent = 0usize;
+
+    for (offset, mut event) in schedule {
+        sleep_until(started + offset).await;
+        if state.replay_generation.load(Ordering::Relaxed) != generation {
+            warn!(%run_id, sent, "Replay stopped");
+            return;
+        }
+
+        let at = started_at + chrono::Duration::from_std(offset).unwrap_or_default();
+        event.timestamp = at.to_rfc3339();
+        event.seq = None;
+        event.schema_version = SCHEMA_VERSION;
+        event.replay_run_id = Some(run_id.clone());
+        let _ = state.event_tx.send(event);
+        sent += 1;
+    }
+
+    info!(%run_id, sent, "Replay finished");
+}
@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+
+use crate::envelope::SOLANA_CHAIN_ID;
+
+/// Magic prefix marking a payload that carries a routing header.
+pub const HEADER_MAGIC: &[u8; 4] = b"OMNI";
+
+/// Header length: magic (4) + payload type (1) + destination chain id (4, BE).
+pub const HEADER_LEN: usize = 9;
+
+/// Optional routing header at the start of a payload.
+///
+/// Layout: `"OMNI" | type: u8 | dst_chain_id: u32 BE | body...`. A destination
+/// of 0 means "let the rules decide". Payloads without the magic are treated
+/// as type 0 with no destination, so existing traffic keeps working.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PayloadHeader {
+    pub payload_type: u8,
+    pub dst_chain_id: Option<u32>,
+}
+
+impl PayloadHeader {
+    /// Split a payload into its header (if present) and body.
+    pub fn parse(payload: &[u8]) -> (Self, &[u8]) {
+        if payload.len() < HEADER_LEN || &payload[..4] != HEADER_MAGIC {
+            return (Self::default(), payload);
+        }
+        let dst = u32::from_be_bytes([payload[5], payload[6], payload[7], payload[8]]);
+        let header = Self {
+            payload_type: payload[4],
+            dst_chain_id: (dst != 0).then_some(dst),
+        };
+        (header, &payload[HEADER_LEN..])
+    }
+}
+
+/// What the destination executor computes for a message.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Computation {
+    /// amount × 2 (matches the reference Solana program)
+    #[default]
+    Double,
+    /// amount, unchanged (plain transfer)
+    Identity,
+}
+
+impl std::fmt::Display for Computation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Double => write!(f, "double"),
+            Self::Identity => write!(f, "identity"),
+        }
+    }
+}
+
+impl std::str::FromStr for Computation {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "double" => Ok(Self::Double),
+            "identity" => Ok(Self::Identity),
+            other => anyhow::bail!("unknown computation kind: {}", other),
+        }
+    }
+}
+
+/// A configurable routing rule (`routing_rules` table).
+/// `None` match fields are wildcards; the lowest priority matching rule wins.
+#[derive(Debug, Clone, Serialize)]
+pub struct RoutingRule {
+    pub id: i64,
+    pub priority: i64,
+    /// Match on the header's payload type
+    pub payload_type: Option<u8>,
+    /// Match on the header's destination field
+    pub header_dst_chain_id: Option<u32>,
+    /// Destination chain to deliver to
+    pub dst_chain_id: u32,
+    pub computation: Computation,
+    pub enabled: bool,
+    pub created_at: String,
+}
+
+/// Request body for POST /routing/rules
+#[derive(Debug, Deserialize)]
+pub struct RoutingRuleRequest {
+    #[serde(default = "default_priority")]
+    pub priority: i64,
+    pub payload_type: Option<u8>,
+    pub header_dst_chain_id: Option<u32>,
+    pub dst_chain_id: u32,
+    #[serde(default)]
+    pub computation: Computation,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_priority() -> i64 {
+    100
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Destination and computation selected for a payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoutingDecision {
+    pub dst_chain_id: u32,
+    pub computation: Computation,
+    /// Rule that matched, if any
+    pub rule_id: Option<i64>,
+}
+
+impl RoutingRule {
+    fn matches(&self, header: &PayloadHeader) -> bool {
+        self.enabled
+            && self.payload_type.is_none_or(|t| t == header.payload_type)
+            && self
+                .header_dst_chain_id
+                .is_none_or(|d| header.dst_chain_id == Some(d))
+    }
+}
+
+/// Pick the destination for a payload. `rules` must be in priority order.
+///
+/// Without a matching rule the header's destination is used, falling back to
+/// Solana with the default computation.
+pub fn route(rules: &[RoutingRule], header: &PayloadHeader) -> RoutingDecision {
+    match rules.iter().find(|r| r.matches(header)) {
+        Some(rule) => RoutingDecision {
+            dst_chain_id: rule.dst_chain_id,
+            computation: rule.computation,
+            rule_id: Some(rule.id),
+        },
+        None => RoutingDecision {
+            dst_chain_id: header.dst_chain_id.unwrap_or(SOLANA_CHAIN_ID),
+            computation: Computation::default(),
+            rule_id: None,
+        },
+    }
+}
@@ -1,35 +1,94 @@
 use axum::{
     extract::{
         ws::{Message, WebSocket, WebSocketUpgrade},
-        Path, State,
+        ConnectInfo, Path, Query, State,
     },
-    http::StatusCode,
-    response::IntoResponse,
-    routing::{get, post},
+    http::{header::AUTHORIZATION, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
     Json, Router,
 };
 use futures::{SinkExt, StreamExt};
+use std::net::SocketAddr;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::watch;
+use tokio::time::{Duration, Instant};
 use tower_http::cors::CorsLayer;
 use tower_http::services::{ServeDir, ServeFile};
-use tracing::{error, info};
-
+use tracing::{debug, error, info, warn};
+
+use crate::adversary::{self, AttackKind, AttackOutcome, AttackRequest};
+use crate::analysis;
+use crate::analysis_queue::AnalysisJob;
+use crate::bench::{self, BenchRefused, BenchReport, BenchRequest};
+use crate::chaos::{self, ActiveFault, FaultRequest};
+use crate::compare::{self, RunComparison, RunCompareQuery};
 use crate::db;
+use crate::envelope::{ETHEREUM_CHAIN_ID, SOLANA_CHAIN_ID};
+use crate::export::{self, ExportSummary, ExportsQuery};
+use crate::keyring::{KeyPurpose, SignerKey};
+use crate::notary::{self, Notarization, NotarizationCheck, NotarizationsQuery, NotarizeQuery};
+use crate::payload_schema::{DecodedPayload, DecodedPayloadQuery, PayloadSchema, PayloadSchemaRequest};
+use crate::replay;
+use crate::routing::PayloadHeader;
+use crate::signing;
+use crate::state_machine;
+use crate::traffic_gen;
 use crate::types::{
-    AppState, GasInfo, MetricsResponse, SimulationRequest, SimulationStatus,
-    SubsystemHealth, SubsystemStatus, SystemHealthResponse,
-    TransactionDetailResponse, TransactionListResponse,
+    AnalysisCostQuery, AnalysisCostResponse, AnalysisFormat, AnalyzeQuery, AppState, ChainConfig,
+    ChainUpsertRequest, ChaosStatsResponse, CircuitBreaker, CrossChainMessage, DailyStatsQuery,
+    DailyStatsResponse, DuplicatesQuery, EthereumStatus, EventsQuery, ExecutionCounters,
+    FleetAnalysisRequest, FleetReport, GasInfo, InjectRequest, MetricsResponse, ParsedFields,
+    PurgeQuery, PurgeSummary, RelayerStatus, ReparseQuery, ReparseResponse, ReplayRequest,
+    RunMetricsResponse, SimilarIncidentsResponse, SimilarQuery, SimulationRequest,
+    SimulationStatus, SolanaStatsReport, SolanaStatus, SubsystemHealth, SubsystemStatus,
+    SystemHealthResponse, TraceConflict, TransactionDetailResponse, TransactionListResponse,
+    VerifyRequest, WsClientMessage, WsConnectQuery, WsServerMessage,
 };
+use crate::ws_auth::{self, UnauthenticatedWs, WsToken, WsView};
+use crate::ws_stats::{WsConnection, WsStatsReport};
 
 /// Run the HTTP + WebSocket server.
 pub async fn run_server(state: Arc<AppState>, port: u16) -> anyhow::Result<()> {
     let app = Router::new()
         // Transaction endpoints
         .route("/transactions", get(list_transactions))
-        .route("/transactions/:nonce", get(get_transaction))
+        .route("/transactions/:nonce", get(get_transaction).delete(purge_transaction))
+        .route("/transactions/:nonce/similar", get(similar_transactions))
+        .route("/transactions/:nonce/reparse", post(reparse_transaction))
+        .route("/transactions/:nonce/decoded-payload", get(decoded_payload))
+        .route("/duplicates", get(list_duplicates))
+        .route("/events", get(list_events))
         // Metrics
         .route("/metrics", get(get_metrics))
+        .route("/metrics/prometheus", get(prometheus_metrics))
+        .route("/chaos/stats", get(chaos_stats))
+        .route("/chaos/fault", post(inject_fault))
+        .route("/chaos/faults", get(list_faults).delete(clear_faults))
+        .route("/gas", get(gas_conditions))
+        .route("/escrow/balance", get(escrow_balance))
+        // Chain registry
+        .route("/chains", get(list_chains).post(create_chain))
+        .route("/chains/:id", get(get_chain).put(update_chain).delete(delete_chain))
+        // Payload schemas
+        .route("/payload-schemas", get(list_payload_schemas).post(create_payload_schema))
+        .route("/payload-schemas/:id", delete(delete_payload_schema))
+        // Payload routing rules
+        .route("/routing/rules", get(list_routing_rules).post(create_routing_rule))
+        .route("/routing/rules/:id", delete(delete_routing_rule))
+        // Simulation runs
+        .route("/runs", get(list_runs))
+        .route("/runs/compare", get(compare_runs))
+        .route("/runs/:id/metrics", get(run_metrics))
+        .route("/runs/:id/replay", post(replay_run))
+        .route("/control/stop-replay", post(stop_replay))
+        // Public proof verification
+        .route("/verify", post(verify_proof))
+        // Validator set
+        .route("/validators", get(list_validators))
+        .route("/validators/:address/reinstate", post(reinstate_validator))
         // Control endpoints
         .route("/control/pause", post(pause))
         .route("/control/resume", post(resume))
@@ -39,13 +98,31 @@ pub async fn run_server(state: Arc<AppState>, port: u16) -> anyhow::Result<()> {
         .route("/control/simulation-status", get(simulation_status))
         // Data management
         .route("/control/clear-data", post(clear_data))
+        .route("/control/inject", post(inject_message))
+        .route("/control/attack", post(attack))
+        .route("/control/benchmark", post(benchmark))
+        .route("/control/export", post(export_now))
+        .route("/exports", get(list_exports))
+        .route("/control/notarize", post(notarize_now))
+        .route("/notarizations", get(list_notarizations))
+        .route("/notarizations/:day", get(get_notarization))
         // AI analysis
+        .route("/analyze/fleet", post(analyze_fleet).get(list_fleet_reports))
+        .route("/analyze/fleet/:id", get(get_fleet_report))
+        .route("/analyze/costs", get(analysis_costs))
+        .route("/analyze/jobs/:id", get(analysis_job))
         .route("/analyze/:nonce", post(analyze_transaction))
         // WebSocket endpoint for real-time event streaming
         .route("/ws", get(ws_handler))
+        .route("/ws/stats", get(ws_stats))
+        .route("/ws/token", post(mint_ws_token))
         // Health check
         .route("/health", get(health))
         .route("/health/systems", get(system_health))
+        .route("/relayer/status", get(relayer_status))
+        .route("/integrity", get(integrity_report))
+        .route("/solana/stats", get(solana_stats))
+        .route("/stats/daily", get(daily_stats))
         .layer(CorsLayer::permissive())
         .with_state(state)
         // Serve the dashboard static files as a fallback.
@@ -58,7 +135,7 @@ pub async fn run_server(state: Arc<AppState>, port: u16) -> anyhow::Result<()> {
     let addr = format!("0.0.0.0:{}", port);
     let listener = tokio::net::TcpListener::bind(&addr).await?;
     info!(%addr, "HTTP + WebSocket server listening");
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await?;
     Ok(())
 }
 
@@ -97,13 +174,23 @@ async fn system_health(
     };
     systems.push(eth_status);
 
-    // 2. Solana (simulated) health — always online since it's in-process
-    systems.push(SubsystemHealth {
-        name: "Solana".into(),
-        status: SubsystemStatus::Online,
-        latency_ms: Some(0),
-        detail: Some("Simulated (in-process)".into()),
-    });
+    // 2. Solana health — the simulated executor is always online
+    let sol_start = std::time::Instant::now();
+    let sol_status = match state.solana.health().await {
+        Ok(detail) => SubsystemHealth {
+            name: "Solana".into(),
+            status: SubsystemStatus::Online,
+            latency_ms: Some(sol_start.elapsed().as_millis() as u64),
+            detail: Some(detail),
+        },
+        Err(e) => SubsystemHealth {
+            name: "Solana".into(),
+            status: SubsystemStatus::Offline,
+            latency_ms: None,
+            detail: Some(format!("RPC unreachable: {}", e)),
+        },
+    };
+    systems.push(sol_status);
 
     // 3. Relayer health
     let sim_running = state.simulation_running.load(Ordering::Relaxed);
@@ -135,15 +222,189 @@ async fn system_health(
     Json(SystemHealthResponse { systems, gas })
 }
 
-async fn get_gas_info(cfg: &crate::config::Config) -> GasInfo {
-    // Derive relayer address from private key
-    let relayer_address = {
-        use ethers::signers::{LocalWallet, Signer};
-        match cfg.relayer_private_key.parse::<LocalWallet>() {
-            Ok(w) => format!("{:?}", w.address()),
-            Err(_) => "unknown".into(),
-        }
+/// Upper bound on each chain query in GET /relayer/status.
+const STATUS_RPC_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// Discrepancies found by the startup integrity check. 503 while it is
+/// still running, 404 when it is disabled.
+async fn integrity_report(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<crate::integrity::IntegrityReport>, StatusCode> {
+    if state.config.integrity_check_limit <= 0 {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    state
+        .integrity
+        .lock()
+        .unwrap()
+        .clone()
+        .map(Json)
+        .ok_or(StatusCode::SERVICE_UNAVAILABLE)
+}
+
+/// The program's stats PDA against the relayer's own execution records.
+async fn solana_stats(State(state): State<Arc<AppState>>) -> Result<Json<SolanaStatsReport>, StatusCode> {
+    let on_chain = state.solana.stats().await.map_err(|e| {
+        error!(error = %e, "Failed to read Solana program stats");
+        StatusCode::BAD_GATEWAY
+    })?;
+    let totals = db::solana_execution_totals(&state.pool).await.map_err(|e| {
+        error!(error = %e, "Failed to count Solana executions");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let program = on_chain.unwrap_or_default();
+    Ok(Json(SolanaStatsReport {
+        mode: state.solana.mode(),
+        in_sync: program.total_executions == totals.executions
+            && program.total_amount == totals.total_amount
+            && totals.unparsable_amounts == 0,
+        on_chain: on_chain.map(|stats| ExecutionCounters {
+            executions: stats.total_executions,
+            total_amount: stats.total_amount.to_string(),
+        }),
+        last_execution_slot: on_chain.map(|stats| stats.last_execution_slot),
+        relayer: ExecutionCounters {
+            executions: totals.executions,
+            total_amount: totals.total_amount.to_string(),
+        },
+        unparsable_amounts: totals.unparsable_amounts,
+    }))
+}
+
+/// Longest window GET /stats/daily aggregates.
+const MAX_DAILY_STATS_DAYS: i64 = 366;
+
+/// Per-day volume and outcomes.
+async fn daily_stats(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<DailyStatsQuery>,
+) -> Result<Json<DailyStatsResponse>, StatusCode> {
+    if !(1..=MAX_DAILY_STATS_DAYS).contains(&query.days) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let daily = db::get_daily_stats(&state.pool, query.days).await.map_err(|e| {
+        error!(error = %e, "Failed to aggregate daily stats");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(DailyStatsResponse { days: query.days, daily }))
+}
+
+/// Balances, chain heads, backlog and breakers for the ops header bar.
+async fn relayer_status(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<RelayerStatus>, StatusCode> {
+    let (ethereum, solana) = tokio::join!(ethereum_status(&state), solana_status(&state));
+
+    let backlog = db::count_by_state(&state.pool)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let chains = db::get_chains(&state.pool)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let mut circuit_breakers = vec![
+        CircuitBreaker {
+            name: "processor".into(),
+            open: state.paused.load(Ordering::Relaxed),
+            detail: Some("POST /control/pause and /control/resume".into()),
+        },
+        CircuitBreaker {
+            name: "traffic_backlog".into(),
+            open: state.traffic_throttled.load(Ordering::Relaxed),
+            detail: Some(format!(
+                "Traffic generator pauses at {} pending messages",
+                state.config.traffic_backlog_threshold
+            )),
+        },
+    ];
+    if let Some(ceiling) = state.config.gas_max_fee_gwei {
+        let max_fee = state.gas.lock().unwrap().as_ref().map(|g| g.max_fee_gwei);
+        circuit_breakers.push(CircuitBreaker {
+            name: "gas_ceiling".into(),
+            open: max_fee.is_some_and(|fee| fee > ceiling),
+            detail: Some(format!("Settlements wait while the fee cap is above {} gwei", ceiling)),
+        });
+    }
+    circuit_breakers.extend(state.validators.snapshot().into_iter().map(|v| CircuitBreaker {
+        name: format!("validator:{}", v.address),
+        open: v.suspended,
+        detail: v.suspended.then(|| "Suspended; POST /validators/:address/reinstate".into()),
+    }));
+    circuit_breakers.extend(chains.into_iter().map(|c| CircuitBreaker {
+        name: format!("chain:{}", c.chain_id),
+        open: !c.enabled,
+        detail: Some(c.name),
+    }));
+
+    let mut keys = state.config.keyring.report();
+    keys.push(SignerKey {
+        purpose: "solana-payer".into(),
+        address: state.solana.payer(),
+        dedicated: state.solana.payer().is_some(),
+    });
+
+    Ok(Json(RelayerStatus {
+        started_at: state.started_at.to_rfc3339(),
+        uptime_secs: (chrono::Utc::now() - state.started_at).num_seconds(),
+        ethereum,
+        solana,
+        keys,
+        backlog,
+        circuit_breakers,
+    }))
+}
+
+async fn ethereum_status(state: &AppState) -> EthereumStatus {
+    let cfg = &state.config;
+    let address = cfg.keyring.address(KeyPurpose::EthSettlement);
+    let last_processed_block = state.last_processed_block.load(Ordering::Relaxed);
+
+    let queried = tokio::time::timeout(STATUS_RPC_TIMEOUT, async {
+        let block = crate::eth::get_block_number(&cfg.eth_rpc_url).await?;
+        let balance = crate::eth::get_balance(&cfg.eth_rpc_url, &address).await?;
+        anyhow::Ok((block, balance))
+    })
+    .await
+    .unwrap_or_else(|_| Err(anyhow::anyhow!("timed out")));
+
+    match queried {
+        Ok((block, balance)) => EthereumStatus {
+            address,
+            balance_wei: Some(balance.to_string()),
+            block_height: Some(block),
+            last_processed_block,
+            blocks_behind: Some(block.saturating_sub(last_processed_block)),
+            error: None,
+        },
+        Err(e) => EthereumStatus {
+            address,
+            balance_wei: None,
+            block_height: None,
+            last_processed_block,
+            blocks_behind: None,
+            error: Some(format!("RPC unreachable: {}", e)),
+        },
+    }
+}
+
+async fn solana_status(state: &AppState) -> SolanaStatus {
+    let queried = tokio::time::timeout(STATUS_RPC_TIMEOUT, state.solana.status())
+        .await
+        .unwrap_or_else(|_| Err(anyhow::anyhow!("timed out")));
+    let (executor, error) = match queried {
+        Ok(status) => (status, None),
+        Err(e) => (Default::default(), Some(format!("RPC unreachable: {}", e))),
     };
+    SolanaStatus {
+        mode: state.solana.mode(),
+        executor,
+        error,
+    }
+}
+
+async fn get_gas_info(cfg: &crate::config::Config) -> GasInfo {
+    let relayer_address = cfg.keyring.address(KeyPurpose::EthSettlement);
 
     let balance = crate::eth::get_balance(&cfg.eth_rpc_url, &relayer_address)
         .await
@@ -189,10 +450,51 @@ async fn get_gas_info(cfg: &crate::config::Config) -> GasInfo {
     }
 }
 
+async fn gas_conditions(
+    State(state): State<Arc<AppState>>,
+    Query(q): Query<crate::gas::GasQuery>,
+) -> Result<Json<crate::gas::GasResponse>, StatusCode> {
+    let limit = q.limit.clamp(1, 1000);
+    let history = db::get_gas_readings(&state.pool, false, limit)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let settlements = db::get_gas_readings(&state.pool, true, limit)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let current = state.gas.lock().unwrap().clone();
+
+    Ok(Json(crate::gas::GasResponse {
+        current,
+        history,
+        settlements,
+    }))
+}
+
+async fn escrow_balance(
+    State(state): State<Arc<AppState>>,
+    Query(q): Query<crate::escrow::EscrowBalanceQuery>,
+) -> Result<Json<crate::escrow::EscrowBalanceResponse>, StatusCode> {
+    let history = db::get_escrow_snapshots(&state.pool, q.limit.clamp(1, 1000))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let (current, locked) = match state.escrow.lock().unwrap().clone() {
+        Some(reading) => (Some(reading.snapshot), reading.locked),
+        None => (None, Vec::new()),
+    };
+
+    Ok(Json(crate::escrow::EscrowBalanceResponse {
+        current,
+        locked,
+        history,
+    }))
+}
+
 async fn list_transactions(
     State(state): State<Arc<AppState>>,
 ) -> Result<Json<TransactionListResponse>, StatusCode> {
-    let messages = db::get_all_messages(&state.pool)
+    let messages = state
+        .cache
+        .all_messages(&state.pool)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
@@ -206,32 +508,288 @@ async fn list_transactions(
 async fn get_transaction(
     State(state): State<Arc<AppState>>,
     Path(nonce): Path<u64>,
+    Query(query): Query<EventsQuery>,
 ) -> Result<Json<TransactionDetailResponse>, StatusCode> {
-    let msg = db::get_message_by_nonce(&state.pool, nonce)
+    let msg = state
+        .cache
+        .message(&state.pool, nonce)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
         .ok_or(StatusCode::NOT_FOUND)?;
 
-    let events = db::get_events_by_nonce(&state.pool, nonce)
+    let mut events = state
+        .cache
+        .events(&state.pool, nonce)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if let Some(since) = query.since_seq {
+        events.retain(|e| e.seq.is_some_and(|seq| seq > since));
+    }
 
     // Load stored proof bundle from DB (stable hashes, no regeneration)
     let proof = msg.proof_json.as_ref().and_then(|json| {
         serde_json::from_str::<crate::types::ProofBundle>(json).ok()
     });
 
+    let transitions = db::get_state_transitions(&state.pool, nonce)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
     Ok(Json(TransactionDetailResponse {
         transaction: msg,
         events,
         proof,
+        transitions,
+    }))
+}
+
+/// Remove one message and all data recorded against it (admin only).
+/// In-flight messages can only be purged while the relayer is paused.
+async fn purge_transaction(
+    State(state): State<Arc<AppState>>,
+    Path(nonce): Path<u64>,
+    Query(query): Query<PurgeQuery>,
+    headers: HeaderMap,
+) -> Result<Json<PurgeSummary>, StatusCode> {
+    require_admin(&state, &headers)?;
+
+    let msg = db::get_message_by_nonce(&state.pool, nonce)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let terminal = matches!(msg.state.as_str(), "settled" | "failed" | "rolled_back" | "refunded");
+    if !terminal && !state.paused.load(Ordering::Relaxed) {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    // Events still buffered would otherwise be written after the purge
+    state.event_writer.flush().await;
+    let summary = db::purge_message(&state.pool, nonce, query.reason.as_deref())
+        .await
+        .map_err(|e| {
+            error!(nonce, error = %e, "Failed to purge transaction");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    state.cache.evict(nonce);
+
+    warn!(
+        nonce,
+        events = summary.events,
+        audit_id = summary.audit_id,
+        reason = ?query.reason,
+        "Transaction purged"
+    );
+    Ok(Json(summary))
+}
+
+/// Re-run event parsing over the stored escrow log (admin only), e.g. after
+/// a parser fix. Changed fields are written back and audited; with
+/// `?dry_run=true` they are only reported.
+async fn reparse_transaction(
+    State(state): State<Arc<AppState>>,
+    Path(nonce): Path<u64>,
+    Query(query): Query<ReparseQuery>,
+    headers: HeaderMap,
+) -> Result<Json<ReparseResponse>, StatusCode> {
+    require_admin(&state, &headers)?;
+
+    let msg = db::get_message_by_nonce(&state.pool, nonce)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    // Injected messages and those observed before raw logs were kept have none
+    let log = db::get_raw_log(&state.pool, nonce)
+        .await
+        .map_err(|e| {
+            error!(nonce, error = %e, "Failed to load raw log");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let fields = state_machine::reparse_log(&state, nonce, &log).map_err(|e| {
+        warn!(nonce, error = %e, "Stored log does not reparse");
+        StatusCode::UNPROCESSABLE_ENTITY
+    })?;
+    let changes = ParsedFields::of(&msg).changes_to(&fields);
+    // Trace ids are unique: the reparse cannot take one another message holds
+    let holder = db::get_nonce_by_trace_id(&state.pool, &fields.trace_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if let Some(holder) = holder.filter(|&n| n != nonce) {
+        warn!(nonce, holder, trace_id = %fields.trace_id, "Reparsed trace id belongs to another message");
+        return Err(StatusCode::CONFLICT);
+    }
+
+    let mut audit_id = None;
+    if !query.dry_run && !changes.is_empty() {
+        state.event_writer.flush().await;
+        let id = db::apply_reparse(&state.pool, nonce, &fields, &changes)
+            .await
+            .map_err(|e| {
+                error!(nonce, error = %e, "Failed to apply reparse");
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+        state.cache.evict(nonce);
+        warn!(nonce, changed = changes.len(), audit_id = id, "Transaction reparsed");
+        audit_id = Some(id);
+    }
+
+    let message = db::get_message_by_nonce(&state.pool, nonce)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(ReparseResponse {
+        nonce,
+        dry_run: query.dry_run,
+        changes,
+        audit_id,
+        message,
+    }))
+}
+
+/// Messages that reused a stored message's trace id, newest first.
+async fn list_duplicates(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<DuplicatesQuery>,
+) -> Result<Json<Vec<TraceConflict>>, StatusCode> {
+    let limit = query.limit.unwrap_or(100).clamp(1, 1000);
+    db::get_trace_conflicts(&state.pool, limit)
+        .await
+        .map(Json)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// Admin endpoints need `Authorization: Bearer $ADMIN_TOKEN`. Without a
+/// configured token they are disabled.
+fn require_admin(state: &AppState, headers: &HeaderMap) -> Result<(), StatusCode> {
+    use sha2::{Digest, Sha256};
+
+    let expected = state.config.admin_token_sha256.ok_or(StatusCode::FORBIDDEN)?;
+    let token = headers
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+    // Compare digests so response timing reveals nothing about the token
+    let provided: [u8; 32] = Sha256::digest(token.as_bytes()).into();
+    if provided != expected {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    Ok(())
+}
+
+/// Global event feed in sequence order. Poll with `?since_seq=<last seen>` to resume.
+async fn list_events(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<EventsQuery>,
+) -> Result<Json<Vec<crate::event::LifecycleEvent>>, StatusCode> {
+    let limit = query.limit.unwrap_or(500).clamp(1, 5000);
+    db::get_events_since(&state.pool, query.since_seq.unwrap_or(0), limit)
+        .await
+        .map(Json)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+async fn similar_transactions(
+    State(state): State<Arc<AppState>>,
+    Path(nonce): Path<u64>,
+    Query(query): Query<SimilarQuery>,
+) -> Result<Json<SimilarIncidentsResponse>, StatusCode> {
+    // Only analyzed transactions are indexed; a GET never calls the embeddings API
+    let target = db::get_incident_embedding(&state.pool, nonce)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let similar = analysis::find_similar(&state, &target, query.limit.min(50))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(SimilarIncidentsResponse {
+        nonce,
+        summary: target.summary,
+        similar,
     }))
 }
 
+async fn list_validators(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    Json(state.validators.snapshot())
+}
+
+/// Lift a validator's suspension. Admin only, as the suspension is what
+/// keeps a validator that signed bad roots out of the rotation.
+async fn reinstate_validator(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(address): Path<String>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    require_admin(&state, &headers)?;
+    let reinstated = state.validators.reinstate(&address);
+    Ok(Json(serde_json::json!({"address": address, "reinstated": reinstated})))
+}
+
+async fn chaos_stats(State(state): State<Arc<AppState>>) -> Json<ChaosStatsResponse> {
+    let stages = state.chaos_stats.snapshot();
+    Json(ChaosStatsResponse {
+        total_injected: stages.iter().map(|s| s.injected).sum(),
+        total_real: stages.iter().map(|s| s.real).sum(),
+        stages,
+    })
+}
+
+/// Start a component fault (Ethereum or Solana down or slow, processor crash).
+async fn inject_fault(Json(req): Json<FaultRequest>) -> Result<(StatusCode, Json<ActiveFault>), StatusCode> {
+    if req.duration_secs.is_some_and(|secs| secs > chaos::MAX_FAULT_SECS)
+        || req.latency_ms.is_some_and(|ms| ms > chaos::MAX_FAULT_LATENCY_MS)
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let fault = chaos::faults().inject(&req);
+    warn!(fault = %fault.fault, until = %fault.until, latency_ms = ?fault.latency_ms, "Fault injected");
+    Ok((StatusCode::CREATED, Json(fault)))
+}
+
+async fn list_faults() -> Json<Vec<ActiveFault>> {
+    Json(chaos::faults().active())
+}
+
+async fn clear_faults() -> StatusCode {
+    chaos::faults().clear();
+    info!("Injected faults cleared");
+    StatusCode::NO_CONTENT
+}
+
+async fn prometheus_metrics(
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let body = crate::prometheus::render(&state)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok((
+        [(axum::http::header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        body,
+    ))
+}
+
+async fn verify_proof(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<VerifyRequest>,
+) -> impl IntoResponse {
+    let report = crate::verification::audit_proof_bundle(
+        &req.proof,
+        req.event_data.as_deref().map(str::as_bytes),
+        &state.validators,
+        state.config.validator_quorum,
+    );
+    info!(nonce = report.nonce, valid = report.valid, "Audited external proof bundle");
+    Json(report)
+}
+
 async fn get_metrics(
     State(state): State<Arc<AppState>>,
 ) -> Result<Json<MetricsResponse>, StatusCode> {
-    let (total, settled, failed, pending, retries) = db::get_metrics(&state.pool)
+    let (total, settled, failed, pending, retries) = db::get_metrics(&state.pool, None)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
@@ -244,6 +802,336 @@ async fn get_metrics(
     }))
 }
 
+// ──────────────────────────────────────────────
+// Chain registry
+// ──────────────────────────────────────────────
+
+async fn list_chains(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<ChainConfig>>, StatusCode> {
+    db::get_chains(&state.pool)
+        .await
+        .map(Json)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+async fn get_chain(
+    State(state): State<Arc<AppState>>,
+    Path(chain_id): Path<u32>,
+) -> Result<Json<ChainConfig>, StatusCode> {
+    db::get_chain(&state.pool, chain_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn create_chain(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<ChainUpsertRequest>,
+) -> Result<(StatusCode, Json<ChainConfig>), StatusCode> {
+    let chain_id = req.chain_id.ok_or(StatusCode::BAD_REQUEST)?;
+    let created = db::insert_chain(
+        &state.pool,
+        chain_id,
+        &req.name,
+        req.kind,
+        req.rpc_url.as_deref(),
+        &req.executor,
+        req.enabled,
+    )
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if !created {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    info!(chain_id, name = %req.name, kind = %req.kind, "Chain registered");
+    let chain = db::get_chain(&state.pool, chain_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok((StatusCode::CREATED, Json(chain)))
+}
+
+async fn update_chain(
+    State(state): State<Arc<AppState>>,
+    Path(chain_id): Path<u32>,
+    Json(req): Json<ChainUpsertRequest>,
+) -> Result<Json<ChainConfig>, StatusCode> {
+    let updated = db::update_chain(
+        &state.pool,
+        chain_id,
+        &req.name,
+        req.kind,
+        req.rpc_url.as_deref(),
+        &req.executor,
+        req.enabled,
+    )
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if !updated {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    info!(chain_id, enabled = req.enabled, "Chain updated");
+    db::get_chain(&state.pool, chain_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn delete_chain(
+    State(state): State<Arc<AppState>>,
+    Path(chain_id): Path<u32>,
+) -> Result<StatusCode, StatusCode> {
+    // The relayer's own route needs both built-in chains; disable them instead
+    if matches!(chain_id, ETHEREUM_CHAIN_ID | SOLANA_CHAIN_ID) {
+        return Err(StatusCode::CONFLICT);
+    }
+    // Messages in flight would fail as an unsupported route
+    let pending = db::count_pending_for_chain(&state.pool, chain_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if pending > 0 {
+        warn!(chain_id, pending, "Chain still has messages in flight, not removed");
+        return Err(StatusCode::CONFLICT);
+    }
+    let deleted = db::delete_chain(&state.pool, chain_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if !deleted {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    info!(chain_id, "Chain removed from registry");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ──────────────────────────────────────────────
+// Payload schemas
+// ──────────────────────────────────────────────
+
+async fn list_payload_schemas(State(state): State<Arc<AppState>>) -> Result<Json<Vec<PayloadSchema>>, StatusCode> {
+    db::get_payload_schemas(&state.pool)
+        .await
+        .map(Json)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+async fn create_payload_schema(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<PayloadSchemaRequest>,
+) -> Result<(StatusCode, Json<PayloadSchema>), StatusCode> {
+    if let Err(e) = req.validate() {
+        warn!(name = %req.name, error = %e, "Invalid payload schema");
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let id = db::insert_payload_schema(&state.pool, &req)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::CONFLICT)?;
+    info!(id, name = %req.name, payload_type = ?req.payload_type, "Payload schema registered");
+
+    let schema = db::get_payload_schemas(&state.pool)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .into_iter()
+        .find(|s| s.id == id)
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok((StatusCode::CREATED, Json(schema)))
+}
+
+async fn delete_payload_schema(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, StatusCode> {
+    let deleted = db::delete_payload_schema(&state.pool, id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if !deleted {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    info!(id, "Payload schema removed");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Decode a message's payload data with `?schema=<name>`, or else with the
+/// newest schema registered for its routing header's payload type.
+async fn decoded_payload(
+    State(state): State<Arc<AppState>>,
+    Path(nonce): Path<u64>,
+    Query(query): Query<DecodedPayloadQuery>,
+) -> Result<Json<DecodedPayload>, StatusCode> {
+    let msg = db::get_message_by_nonce(&state.pool, nonce)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let payload = hex::decode(msg.payload.trim_start_matches("0x")).map_err(|e| {
+        error!(nonce, error = %e, "Stored payload is not hex");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let (header, body) = PayloadHeader::parse(&payload);
+
+    let schemas = db::get_payload_schemas(&state.pool)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let schema = match &query.schema {
+        Some(name) => schemas.into_iter().find(|s| &s.name == name),
+        None => schemas.into_iter().rev().find(|s| s.payload_type == Some(header.payload_type)),
+    }
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    // The schema describes the application data inside the codec frame; a
+    // body no codec can decode is matched as a whole
+    let decoded = payload::Payload::decode(body).ok();
+    let data = decoded.as_ref().map_or(body, |(p, _)| p.data.as_slice());
+    let (fields, trailing) = schema.decode(data).map_err(|e| {
+        warn!(nonce, schema = %schema.name, error = %e, "Payload does not match schema");
+        StatusCode::UNPROCESSABLE_ENTITY
+    })?;
+    Ok(Json(DecodedPayload {
+        nonce,
+        schema: schema.name,
+        payload_type: header.payload_type,
+        codec: decoded.map(|(_, codec)| codec.to_string()),
+        fields,
+        trailing,
+    }))
+}
+
+// ──────────────────────────────────────────────
+// Routing rules
+// ──────────────────────────────────────────────
+
+async fn list_routing_rules(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<crate::routing::RoutingRule>>, StatusCode> {
+    db::get_routing_rules(&state.pool)
+        .await
+        .map(Json)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+async fn create_routing_rule(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<crate::routing::RoutingRuleRequest>,
+) -> Result<(StatusCode, Json<crate::routing::RoutingRule>), StatusCode> {
+    // A rule may only route to a chain the relayer knows
+    db::get_chain(&state.pool, req.dst_chain_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::BAD_REQUEST)?;
+    let id = db::insert_routing_rule(&state.pool, &req)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    info!(id, dst_chain_id = req.dst_chain_id, computation = %req.computation, "Routing rule added");
+
+    let rule = db::get_routing_rules(&state.pool)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .into_iter()
+        .find(|r| r.id == id)
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok((StatusCode::CREATED, Json(rule)))
+}
+
+async fn delete_routing_rule(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, StatusCode> {
+    let deleted = db::delete_routing_rule(&state.pool, id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if !deleted {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    info!(id, "Routing rule removed");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_runs(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<crate::types::SimulationRun>>, StatusCode> {
+    db::get_runs(&state.pool)
+        .await
+        .map(Json)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+async fn run_metrics(
+    State(state): State<Arc<AppState>>,
+    Path(run_id): Path<String>,
+) -> Result<Json<RunMetricsResponse>, StatusCode> {
+    let run = db::get_run(&state.pool, &run_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let (total, settled, failed, pending, retries) = db::get_metrics(&state.pool, Some(&run_id))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(RunMetricsResponse {
+        run,
+        metrics: MetricsResponse {
+            total_transactions: total,
+            settled,
+            failed,
+            pending,
+            total_retries: retries,
+        },
+    }))
+}
+
+/// Throughput, rollback rate, latency percentiles and fee spend of two
+/// runs, with the change from `a` to `b`.
+async fn compare_runs(
+    State(state): State<Arc<AppState>>,
+    Query(q): Query<RunCompareQuery>,
+) -> Result<Json<RunComparison>, StatusCode> {
+    compare::compare_runs(&state, &q.a, &q.b)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to compare runs");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+/// Play a recorded run back through the event stream, time-compressed by `speed`.
+async fn replay_run(
+    State(state): State<Arc<AppState>>,
+    Path(run_id): Path<String>,
+    req: Option<Json<ReplayRequest>>,
+) -> Result<Json<crate::types::ReplaySummary>, StatusCode> {
+    let req = req.map(|Json(r)| r).unwrap_or_default();
+    if !(req.speed > 0.0 && req.speed <= replay::MAX_SPEED) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    db::get_run(&state.pool, &run_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let (summary, schedule) = replay::prepare(&state, &run_id, req.speed)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let generation = replay::next_generation(&state);
+    info!(%run_id, speed = req.speed, events = summary.events, "Replay started");
+    tokio::spawn(replay::play(state.clone(), generation, run_id, schedule));
+
+    Ok(Json(summary))
+}
+
+async fn stop_replay(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    replay::next_generation(&state);
+    Json(serde_json::json!({"replaying": false}))
+}
+
 async fn pause(State(state): State<Arc<AppState>>) -> impl IntoResponse {
     state.paused.store(true, Ordering::Relaxed);
     info!("Relayer paused");
@@ -259,17 +1147,29 @@ async fn resume(State(state): State<Arc<AppState>>) -> impl IntoResponse {
 async fn start_simulation(
     State(state): State<Arc<AppState>>,
     Json(req): Json<SimulationRequest>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, StatusCode> {
+    let settings = req.scenario.settings();
+    *state.scenario.lock().unwrap() = settings;
+
+    let run_id = traffic_gen::begin_run(&state, settings, req.duration_minutes)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to record simulation run");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
     let deadline = chrono::Utc::now().timestamp() + (req.duration_minutes as i64 * 60);
     state.simulation_deadline.store(deadline, Ordering::Relaxed);
     state.simulation_running.store(true, Ordering::Relaxed);
     state.paused.store(false, Ordering::Relaxed);
-    info!(duration_minutes = req.duration_minutes, "Simulation started");
-    Json(serde_json::json!({
+    info!(duration_minutes = req.duration_minutes, scenario = ?req.scenario, "Simulation started");
+    Ok(Json(serde_json::json!({
         "running": true,
+        "run_id": run_id,
         "duration_minutes": req.duration_minutes,
-        "deadline_unix": deadline
-    }))
+        "deadline_unix": deadline,
+        "scenario": settings
+    })))
 }
 
 async fn stop_simulation(
@@ -278,6 +1178,7 @@ async fn stop_simulation(
     state.simulation_running.store(false, Ordering::Relaxed);
     state.paused.store(true, Ordering::Relaxed);
     state.simulation_deadline.store(0, Ordering::Relaxed);
+    traffic_gen::end_run(&state).await;
     info!("Simulation stopped");
     Json(serde_json::json!({"running": false}))
 }
@@ -295,19 +1196,172 @@ async fn simulation_status(
     Json(SimulationStatus {
         running,
         remaining_seconds: remaining,
+        backlog_throttled: state.traffic_throttled.load(Ordering::Relaxed),
+        scenario: *state.scenario.lock().unwrap(),
+        run_id: state.current_run.lock().unwrap().clone(),
     })
 }
 
+async fn inject_message(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<InjectRequest>,
+) -> Result<(StatusCode, Json<CrossChainMessage>), StatusCode> {
+    // The payload carries the description behind a u16 length
+    let description_len = req.description.as_ref().map_or(0, |d| d.len());
+    if req.amount == 0 || description_len > u16::MAX as usize {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let sender: ethers::types::Address = match req.sender.as_deref() {
+        Some(s) => s.parse().map_err(|_| StatusCode::BAD_REQUEST)?,
+        None => ethers::types::Address::zero(),
+    };
+
+    // Debug formatting, as for senders observed on Ethereum
+    let nonce = state_machine::inject_message(&state, &req, &format!("{:?}", sender))
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to inject message");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    let msg = db::get_message_by_nonce(&state.pool, nonce)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok((StatusCode::CREATED, Json(msg)))
+}
+
+/// Attempt one simulated attack, or all of them, and report what was caught.
+async fn attack(
+    State(state): State<Arc<AppState>>,
+    req: Option<Json<AttackRequest>>,
+) -> Result<Json<Vec<AttackOutcome>>, StatusCode> {
+    let kinds = match req.and_then(|Json(r)| r.kind) {
+        Some(kind) => vec![kind],
+        None => AttackKind::ALL.to_vec(),
+    };
+
+    let mut outcomes = Vec::with_capacity(kinds.len());
+    for kind in kinds {
+        let outcome = adversary::run_attack(&state, kind).await.map_err(|e| {
+            error!(%kind, error = %e, "Simulated attack failed to run");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+        outcomes.push(outcome);
+    }
+    Ok(Json(outcomes))
+}
+
+/// Run a benchmark; responds once every message has finished.
+async fn benchmark(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<BenchRequest>,
+) -> Result<Json<BenchReport>, StatusCode> {
+    let report = bench::run_benchmark(&state, req.n).await.map_err(|e| match e.downcast_ref::<BenchRefused>() {
+        Some(BenchRefused::InvalidSize) => StatusCode::BAD_REQUEST,
+        Some(BenchRefused::Busy(_)) => StatusCode::CONFLICT,
+        None => {
+            error!(error = %e, "Benchmark failed");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    })?;
+    Ok(Json(report))
+}
+
+/// Export messages and events to Parquet now, instead of waiting for
+/// `EXPORT_INTERVAL_HOURS`.
+async fn export_now(State(state): State<Arc<AppState>>) -> Result<Json<ExportSummary>, StatusCode> {
+    if state.export_running.load(Ordering::Relaxed) {
+        return Err(StatusCode::CONFLICT);
+    }
+    let summary = export::run_export(&state).await.map_err(|e| {
+        error!(error = %e, "Export failed");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(summary))
+}
+
+async fn list_exports(
+    State(state): State<Arc<AppState>>,
+    Query(q): Query<ExportsQuery>,
+) -> Result<Json<Vec<ExportSummary>>, StatusCode> {
+    db::get_exports(&state.pool, q.limit.clamp(1, 200))
+        .await
+        .map(Json)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// Notarize a completed day (default yesterday) now, instead of waiting for
+/// `NOTARY_INTERVAL_SECS`. A day whose root is already anchored is returned
+/// as it is.
+async fn notarize_now(
+    State(state): State<Arc<AppState>>,
+    Query(q): Query<NotarizeQuery>,
+) -> Result<Json<Notarization>, StatusCode> {
+    let today = chrono::Utc::now().date_naive();
+    let day = q.date.unwrap_or(today - chrono::Duration::days(1));
+    // Today's rows are still changing
+    if day >= today {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    if state.notary_running.load(Ordering::Relaxed) {
+        return Err(StatusCode::CONFLICT);
+    }
+    let notarization = notary::notarize(&state, day).await.map_err(|e| {
+        error!(%day, error = %e, "Notarization failed");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(notarization))
+}
+
+async fn list_notarizations(
+    State(state): State<Arc<AppState>>,
+    Query(q): Query<NotarizationsQuery>,
+) -> Result<Json<Vec<Notarization>>, StatusCode> {
+    db::list_notarizations(&state.pool, q.limit.clamp(1, 366))
+        .await
+        .map(Json)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// A day's notarization with its roots recomputed from the current rows, to
+/// check nothing changed since.
+async fn get_notarization(
+    State(state): State<Arc<AppState>>,
+    Path(day): Path<chrono::NaiveDate>,
+) -> Result<Json<NotarizationCheck>, StatusCode> {
+    let day = day.to_string();
+    let notarization = db::get_notarization(&state.pool, &day)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let current = notary::day_roots(&state, &day).await.map_err(|e| {
+        error!(%day, error = %e, "Failed to recompute notarization roots");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let admin_changes = db::get_admin_changes_on(&state.pool, &day)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(NotarizationCheck {
+        matches: current.root == notarization.root,
+        admin_changes,
+        notarization,
+        current,
+    }))
+}
+
 async fn clear_data(
     State(state): State<Arc<AppState>>,
 ) -> Result<impl IntoResponse, StatusCode> {
     // Stop simulation first
     state.simulation_running.store(false, Ordering::Relaxed);
     state.paused.store(true, Ordering::Relaxed);
+    traffic_gen::end_run(&state).await;
+    state.event_writer.flush().await;
 
     db::clear_all_data(&state.pool)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    state.cache.clear();
 
     info!("All demo data cleared");
     Ok(Json(serde_json::json!({"cleared": true})))
@@ -400,19 +1454,21 @@ RULES:
 - If mint happened but burn is missing: flag possible double-mint / unbacked token risk.
 - If Ethereum lock is present but neither settle nor refund is present: flag possible stuck-funds risk.
 - Compute ETH estimate as wei / 1e18 with 6 decimals (no fiat).
+- Base any gas/fee finding on "settlementGas" (recorded from the settlement receipt). If it is null, say gas data was not recorded rather than estimating.
 - If timestamps are missing or same, write "n/a" and say why."#;
 
 async fn analyze_transaction(
     State(state): State<Arc<AppState>>,
     Path(nonce): Path<u64>,
-) -> Result<impl IntoResponse, StatusCode> {
+    Query(query): Query<AnalyzeQuery>,
+) -> Result<Response, StatusCode> {
     let openai_key = std::env::var("OPENAI_API_KEY")
         .map_err(|_| {
             error!("OPENAI_API_KEY not set");
             StatusCode::SERVICE_UNAVAILABLE
         })?;
 
-    // Fetch transaction + events + proof
+    // Fetch transaction + events
     let msg = db::get_message_by_nonce(&state.pool, nonce)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
@@ -422,6 +1478,51 @@ async fn analyze_transaction(
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
+    if query.queued {
+        let Some(job) = state.analysis_queue.submit(nonce, query.format) else {
+            warn!(nonce, "Analysis queue full");
+            return Err(StatusCode::SERVICE_UNAVAILABLE);
+        };
+        let id = job.id;
+        tokio::spawn(async move {
+            let outcome = run_transaction_analysis(&state, &openai_key, &msg, &events, query.format, Some(id)).await;
+            if let Err(e) = &outcome {
+                error!(nonce, job = id, error = %e, "Queued analysis failed");
+            }
+            state.analysis_queue.finish(id, outcome.map_err(|e| e.to_string()));
+        });
+        return Ok((StatusCode::ACCEPTED, Json(job)).into_response());
+    }
+
+    let analysis = run_transaction_analysis(&state, &openai_key, &msg, &events, query.format, None)
+        .await
+        .map_err(|e| {
+            error!(nonce, error = %e, "Analysis failed");
+            if analysis::is_rate_limited(&e) {
+                StatusCode::TOO_MANY_REQUESTS
+            } else {
+                StatusCode::BAD_GATEWAY
+            }
+        })?;
+    Ok(Json(analysis).into_response())
+}
+
+/// Analyze one transaction in one of the `ANALYSIS_CONCURRENCY` slots,
+/// marking queued job `job` running once a slot is free.
+async fn run_transaction_analysis(
+    state: &Arc<AppState>,
+    openai_key: &str,
+    msg: &CrossChainMessage,
+    events: &[crate::event::LifecycleEvent],
+    format: AnalysisFormat,
+    job: Option<u64>,
+) -> anyhow::Result<serde_json::Value> {
+    let _slot = state.analysis_queue.slot().await;
+    if let Some(id) = job {
+        state.analysis_queue.start(id);
+    }
+    let nonce = msg.nonce;
+
     let proof = msg.proof_json.as_ref().and_then(|json| {
         serde_json::from_str::<crate::types::ProofBundle>(json).ok()
     });
@@ -436,7 +1537,7 @@ async fn analyze_transaction(
         "state": msg.state,
         "lifecycleStages": events.iter().map(|e| serde_json::json!({
             "stage": e.step,
-            "chain": e.actor,
+            "chain": e.actor.to_string(),
             "status": e.status,
             "timestamp": e.timestamp,
             "details": e.detail,
@@ -451,8 +1552,15 @@ async fn analyze_transaction(
         "references": {
             "ethLockTx": events.iter().find(|e| e.step == crate::event::Step::Locked).and_then(|e| e.detail.clone()),
             "ethSettleTx": msg.eth_settle_tx,
+            "ethRefundTx": msg.refund_tx,
             "solanaExecuteSig": msg.solana_signature,
         },
+        "settlementGas": msg.settle_gas_used.map(|gas_used| serde_json::json!({
+            "gasUsed": gas_used,
+            "effectiveGasPriceWei": msg.settle_gas_price_wei,
+            "blockNumber": msg.settle_block_number,
+            "costWei": msg.settle_cost_wei,
+        })),
         "counters": {
             "retries": msg.retry_count,
         },
@@ -460,111 +1568,312 @@ async fn analyze_transaction(
             "pending": msg.state == "observed" || msg.state == "persisted" || msg.state == "verified" || msg.state == "sent_to_solana" || msg.state == "executed",
             "failed": msg.state == "failed",
             "rollbackTriggered": msg.state == "rolled_back",
+            "refunded": msg.state == "refunded",
         },
     });
 
     let user_msg = format!("NOW ANALYZE THIS DATA:\n{}", serde_json::to_string_pretty(&tx_data).unwrap_or_default());
 
-    // Call OpenAI
-    let client = reqwest::Client::new();
-    let openai_res = client
-        .post("https://api.openai.com/v1/chat/completions")
-        .header("Authorization", format!("Bearer {}", openai_key))
-        .json(&serde_json::json!({
-            "model": "gpt-4o",
-            "messages": [
-                { "role": "system", "content": ANALYSIS_SYSTEM_PROMPT },
-                { "role": "user", "content": user_msg },
-            ],
-            "temperature": 0.3,
-            "max_tokens": 4000,
-        }))
-        .send()
+    let want_markdown = format != AnalysisFormat::Json;
+    let want_json = format != AnalysisFormat::Markdown;
+
+    let markdown_fut = async {
+        if want_markdown {
+            analysis::chat_completion(state, openai_key, "transaction", ANALYSIS_SYSTEM_PROMPT, &user_msg)
+                .await
+                .map(Some)
+        } else {
+            Ok(None)
+        }
+    };
+    let structured_fut = async {
+        if want_json {
+            analysis::structured_completion(state, openai_key, analysis::STRUCTURED_ANALYSIS_SYSTEM_PROMPT, &user_msg)
+                .await
+                .map(Some)
+        } else {
+            Ok(None)
+        }
+    };
+    let (markdown, structured) = tokio::join!(markdown_fut, structured_fut);
+    let (markdown, structured) = (markdown?, structured?);
+
+    // Add this transaction to the similar-incident index (best effort)
+    if let Err(e) = analysis::index_incident(state, openai_key, msg, events).await {
+        warn!(nonce, error = %e, "Failed to index incident embedding");
+    }
+
+    Ok(serde_json::json!({
+        "nonce": nonce,
+        "format": format,
+        "analysis": markdown,
+        "structured": structured,
+    }))
+}
+
+async fn analysis_job(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<u64>,
+) -> Result<Json<AnalysisJob>, StatusCode> {
+    state.analysis_queue.job(id).map(Json).ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn analyze_fleet(
+    State(state): State<Arc<AppState>>,
+    req: Option<Json<FleetAnalysisRequest>>,
+) -> Result<Json<FleetReport>, StatusCode> {
+    let openai_key = std::env::var("OPENAI_API_KEY")
+        .map_err(|_| {
+            error!("OPENAI_API_KEY not set");
+            StatusCode::SERVICE_UNAVAILABLE
+        })?;
+
+    let hours = req
+        .and_then(|Json(r)| r.hours)
+        .unwrap_or(state.config.fleet_report_window_hours);
+    if hours <= 0 {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let report = analysis::generate_fleet_report(&state, &openai_key, hours)
         .await
         .map_err(|e| {
-            error!(error = %e, "OpenAI request failed");
+            error!(error = %e, "Fleet analysis failed");
             StatusCode::BAD_GATEWAY
         })?;
 
-    if !openai_res.status().is_success() {
-        let status = openai_res.status();
-        let body = openai_res.text().await.unwrap_or_default();
-        error!(%status, %body, "OpenAI returned error");
-        return Err(StatusCode::BAD_GATEWAY);
-    }
+    Ok(Json(report))
+}
 
-    let body: serde_json::Value = openai_res.json().await.map_err(|e| {
-        error!(error = %e, "Failed to parse OpenAI response");
-        StatusCode::BAD_GATEWAY
-    })?;
+async fn analysis_costs(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<AnalysisCostQuery>,
+) -> Result<Json<AnalysisCostResponse>, StatusCode> {
+    let daily = db::get_analysis_costs(&state.pool, query.days.max(1))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    let analysis = body["choices"][0]["message"]["content"]
-        .as_str()
-        .unwrap_or("Analysis unavailable")
-        .to_string();
+    Ok(Json(AnalysisCostResponse {
+        days: query.days.max(1),
+        total_requests: daily.iter().map(|d| d.requests).sum(),
+        total_tokens: daily.iter().map(|d| d.prompt_tokens + d.completion_tokens).sum(),
+        total_cost_usd: daily.iter().fold(0.0, |acc, d| acc + d.cost_usd),
+        daily,
+    }))
+}
 
-    Ok(Json(serde_json::json!({
-        "nonce": nonce,
-        "analysis": analysis,
-    })))
+async fn list_fleet_reports(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<FleetReport>>, StatusCode> {
+    let reports = db::get_fleet_reports(&state.pool, 20)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(reports))
+}
+
+async fn get_fleet_report(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+) -> Result<Json<FleetReport>, StatusCode> {
+    let report = db::get_fleet_report(&state.pool, id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(report))
 }
 
 // ──────────────────────────────────────────────
 // WebSocket Handler
 // ──────────────────────────────────────────────
 
+/// Messages whose events a fresh WebSocket connection is sent.
+const WS_BACKFILL_MESSAGES: i64 = 100;
+
+/// Most events a resuming WebSocket connection is sent before going live.
+const WS_RESUME_LIMIT: usize = 5000;
+
+/// Events read and sent per backfill query.
+const WS_BACKFILL_CHUNK: i64 = 500;
+
+/// Longest batching window a WebSocket client can subscribe with.
+const WS_MAX_BATCH_MS: u64 = 1000;
+
+/// Most events coalesced into one batched frame.
+const WS_MAX_BATCH_EVENTS: usize = 500;
+
 async fn ws_handler(
     ws: WebSocketUpgrade,
     State(state): State<Arc<AppState>>,
-) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| handle_ws(socket, state))
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Query(query): Query<WsConnectQuery>,
+) -> Result<Response, StatusCode> {
+    let remote_addr = ws_auth::client_addr(
+        peer,
+        headers.get("x-forwarded-for").and_then(|v| v.to_str().ok()),
+        &state.config.trusted_proxies,
+    );
+
+    // A token that is present must be valid; without one, WS_UNAUTHENTICATED decides
+    let view = match &query.token {
+        Some(token) => match state.ws_tokens.verify(token, chrono::Utc::now()) {
+            Some(role) => role.view(),
+            None => {
+                warn!(%remote_addr, "WebSocket connection with an invalid or expired token");
+                return Err(StatusCode::UNAUTHORIZED);
+            }
+        },
+        None => match state.config.ws_unauthenticated {
+            UnauthenticatedWs::Full => WsView::Full,
+            UnauthenticatedWs::Redacted => WsView::Redacted,
+            UnauthenticatedWs::Deny => return Err(StatusCode::UNAUTHORIZED),
+        },
+    };
+    Ok(ws.on_upgrade(move |socket| handle_ws(socket, state, remote_addr, query.since_seq, view)))
+}
+
+/// Mint a short-lived `/ws` token for the API key (or `ADMIN_TOKEN`) given
+/// as the bearer token. The token carries the key's role.
+async fn mint_ws_token(State(state): State<Arc<AppState>>, headers: HeaderMap) -> Result<Json<WsToken>, StatusCode> {
+    if state.config.ws_api_keys.is_empty() && state.config.admin_token_sha256.is_none() {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    let key = headers
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+    let role = ws_auth::api_key_role(&state.config.ws_api_keys, state.config.admin_token_sha256, key)
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+    let token = state.ws_tokens.mint(role, chrono::Utc::now());
+    debug!(%role, expires_at = %token.expires_at, "WebSocket token minted");
+    Ok(Json(token))
 }
 
-async fn handle_ws(socket: WebSocket, state: Arc<AppState>) {
+/// Open WebSocket connections with their counters (admin only: it lists
+/// client addresses).
+async fn ws_stats(State(state): State<Arc<AppState>>, headers: HeaderMap) -> Result<Json<WsStatsReport>, StatusCode> {
+    require_admin(&state, &headers)?;
+    Ok(Json(state.ws_connections.snapshot()))
+}
+
+async fn handle_ws(socket: WebSocket, state: Arc<AppState>, remote_addr: String, since_seq: Option<u64>, view: WsView) {
     let (mut sender, mut receiver) = socket.split();
 
     // Subscribe to the event broadcast channel
     let mut event_rx = state.event_tx.subscribe();
 
-    info!("WebSocket client connected");
-
-    // Send existing events as initial state
-    if let Ok(messages) = db::get_all_messages(&state.pool).await {
-        for msg in messages.iter().take(100) {
-            if let Ok(events) = db::get_events_by_nonce(&state.pool, msg.nonce as u64).await {
-                for event in events {
-                    if let Ok(json) = serde_json::to_string(&event) {
-                        if sender.send(Message::Text(json)).await.is_err() {
-                            return;
-                        }
-                    }
+    // Registered until this handler returns
+    let guard = state.ws_connections.open(remote_addr.clone(), since_seq, view);
+    let connection = guard.connection.clone();
+    let id = connection.id();
+
+    info!(id, %remote_addr, ?since_seq, ?view, "WebSocket client connected");
+
+    // Each message carries its signature when a signing key is configured
+    let signing_key = state.config.event_signing_key.clone();
+
+    // Send existing events as initial state, in sequence order and one query
+    // per chunk: everything after `since_seq` when resuming, otherwise the
+    // events of the latest 100 messages
+    let mut cursor = since_seq.unwrap_or(0);
+    let mut backfilled = 0;
+    let mut truncated = false;
+    loop {
+        let chunk = match since_seq {
+            Some(_) => db::get_events_since(&state.pool, cursor, WS_BACKFILL_CHUNK).await,
+            None => db::get_recent_message_events(&state.pool, WS_BACKFILL_MESSAGES, cursor, WS_BACKFILL_CHUNK).await,
+        }
+        .unwrap_or_default();
+        for event in &chunk {
+            cursor = cursor.max(event.seq.unwrap_or(0));
+            if let Ok(json) = signing::to_wire_json(&ws_auth::for_view(event, view), signing_key.as_ref()) {
+                if sender.send(Message::Text(json)).await.is_err() {
+                    return;
                 }
+                connection.record_sent(1);
             }
         }
+        backfilled += chunk.len();
+        if (chunk.len() as i64) < WS_BACKFILL_CHUNK {
+            break;
+        }
+        if since_seq.is_some() && backfilled >= WS_RESUME_LIMIT {
+            truncated = true;
+            break;
+        }
+    }
+
+    // Tell a client resuming from too far back where its backfill stopped,
+    // rather than letting it jump to live events unawares
+    if truncated {
+        warn!(id, %remote_addr, last_seq = cursor, "WebSocket resume truncated");
+        let frame = serde_json::to_string(&WsServerMessage::Truncated { last_seq: cursor }).unwrap_or_default();
+        if sender.send(Message::Text(frame)).await.is_err() {
+            return;
+        }
     }
 
+    // When resuming, the backfill is a contiguous seq range, so events persisted
+    // during it that also arrive on the broadcast channel can be skipped
+    let last_sent = if since_seq.is_some() { cursor } else { 0 };
+
+    // Batching window, set by the client's subscribe message
+    let (batch_tx, batch_rx) = watch::channel(Duration::ZERO);
+
     // Forward broadcast events to the WebSocket client
+    let send_connection = connection.clone();
     let send_task = tokio::spawn(async move {
-        while let Ok(event) = event_rx.recv().await {
-            match serde_json::to_string(&event) {
-                Ok(json) => {
-                    if sender.send(Message::Text(json)).await.is_err() {
+        let connection = send_connection;
+        let key = signing_key.as_ref();
+        while let Some(json) = next_ws_event(&mut event_rx, &connection, last_sent, view, key).await {
+            let window = *batch_rx.borrow();
+            if window.is_zero() {
+                if sender.send(Message::Text(json)).await.is_err() {
+                    break;
+                }
+                connection.record_sent(1);
+                continue;
+            }
+
+            // Coalesce whatever else arrives within the window into one array
+            let deadline = Instant::now() + window;
+            let mut batch = vec![json];
+            let mut closed = false;
+            while batch.len() < WS_MAX_BATCH_EVENTS {
+                match tokio::time::timeout_at(deadline, next_ws_event(&mut event_rx, &connection, last_sent, view, key)).await {
+                    Ok(Some(json)) => batch.push(json),
+                    Ok(None) => {
+                        closed = true;
                         break;
                     }
+                    Err(_) => break,
                 }
-                Err(e) => {
-                    error!(error = %e, "Failed to serialize event");
-                }
             }
+            let count = batch.len() as u64;
+            let frame = format!("[{}]", batch.join(","));
+            if sender.send(Message::Text(frame)).await.is_err() || closed {
+                break;
+            }
+            connection.record_sent(count);
         }
     });
 
-    // Handle incoming messages (for future use, e.g., subscriptions)
+    // Handle subscribe messages from the client
     let recv_task = tokio::spawn(async move {
         while let Some(msg) = receiver.next().await {
             match msg {
+                Ok(Message::Text(text)) => match serde_json::from_str::<WsClientMessage>(&text) {
+                    Ok(WsClientMessage::Subscribe { batch_ms }) => {
+                        let batch_ms = batch_ms.min(WS_MAX_BATCH_MS);
+                        connection.set_batch_ms(batch_ms);
+                        let _ = batch_tx.send(Duration::from_millis(batch_ms));
+                        debug!(id, batch_ms, "WebSocket client subscribed");
+                    }
+                    Err(e) => debug!(id, error = %e, "Ignoring WebSocket client message"),
+                },
                 Ok(Message::Close(_)) => break,
-                Ok(_) => {} // Ignore other messages for now
+                Ok(_) => {}
                 Err(_) => break,
             }
         }
@@ -576,5 +1885,37 @@ async fn handle_ws(socket: WebSocket, state: Arc<AppState>) {
         _ = recv_task => {},
     }
 
-    info!("WebSocket client disconnected");
+    drop(guard);
+    info!(id, "WebSocket client disconnected");
+}
+
+/// Next live event for a WebSocket client as wire JSON, or `None` once the
+/// broadcast channel closes. Lag is recorded and skipped, as are events the
+/// resume backfill already sent.
+async fn next_ws_event(
+    event_rx: &mut broadcast::Receiver<crate::event::LifecycleEvent>,
+    connection: &WsConnection,
+    last_sent: u64,
+    view: WsView,
+    signing_key: Option<&signing::EventSigningKey>,
+) -> Option<String> {
+    loop {
+        let event = match event_rx.recv().await {
+            Ok(event) => event,
+            // A slow client skips what it missed and keeps the stream
+            Err(RecvError::Lagged(skipped)) => {
+                connection.record_lag(skipped);
+                warn!(id = connection.id(), skipped, "WebSocket client lagging, events dropped");
+                continue;
+            }
+            Err(RecvError::Closed) => return None,
+        };
+        if event.seq.is_some_and(|seq| seq <= last_sent) {
+            continue;
+        }
+        match signing::to_wire_json(&ws_auth::for_view(&event, view), signing_key) {
+            Ok(json) => return Some(json),
+            Err(e) => error!(error = %e, "Failed to serialize event"),
+        }
+    }
 }
@@ -0,0 +1,98 @@
+use hmac::{Hmac, Mac};
+use serde_json::Value;
+use sha2::Sha256;
+
+use crate::config::Secret;
+use crate::event::LifecycleEvent;
+
+/// Field that carries an event's signature on the wire. It sits beside the
+/// event fields and is not part of the event schema.
+pub const SIGNATURE_FIELD: &str = "signature";
+
+/// Shared secret for HMAC-SHA256 event signatures (`EVENT_SIGNING_KEY`).
+#[derive(Debug, Clone)]
+pub struct EventSigningKey(Secret);
+
+impl EventSigningKey {
+    pub fn new(secret: &str) -> Self {
+        Self(Secret(secret.to_string()))
+    }
+
+    /// Hex-encoded HMAC-SHA256 of `message`.
+    pub fn sign(&self, message: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.0 .0.as_bytes()).expect("HMAC accepts keys of any length");
+        mac.update(message.as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+}
+
+/// Canonical JSON of a value: object keys sorted, no whitespace.
+pub fn canonical_json(value: &Value) -> String {
+    serde_json::to_string(&sort_keys(value.clone())).expect("JSON values always serialize")
+}
+
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Object(entries.into_iter().map(|(k, v)| (k, sort_keys(v))).collect())
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(sort_keys).collect()),
+        other => other,
+    }
+}
+
+/// Serialize an event for delivery. With a key, the event is sent as its
+/// canonical JSON plus `signature`, the HMAC of that JSON without the field.
+pub fn to_wire_json(event: &LifecycleEvent, key: Option<&EventSigningKey>) -> serde_json::Result<String> {
+    let Some(key) = key else {
+        return serde_json::to_string(event);
+    };
+    let mut value = serde_json::to_value(event)?;
+    let signature = key.sign(&canonical_json(&value));
+    if let Value::Object(map) = &mut value {
+        map.insert(SIGNATURE_FIELD.to_string(), Value::String(signature));
+    }
+    Ok(canonical_json(&value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{Actor, Status, Step};
+
+    fn event() -> LifecycleEvent {
+        let mut event = LifecycleEvent::new("0xabc", 7, Actor::ethereum(), Step::Locked, Status::Success)
+            .with_detail("tx:0x01");
+        event.timestamp = "2026-10-16T12:00:00+00:00".into();
+        event.seq = Some(42);
+        event
+    }
+
+    /// What a consumer does: drop the field, re-canonicalize, compare.
+    #[test]
+    fn consumer_can_verify_signature() {
+        let key = EventSigningKey::new("shared-secret");
+        let wire = to_wire_json(&event(), Some(&key)).unwrap();
+
+        let mut received: Value = serde_json::from_str(&wire).unwrap();
+        let signature = received.as_object_mut().unwrap().remove(SIGNATURE_FIELD).unwrap();
+        assert_eq!(signature.as_str().unwrap(), key.sign(&canonical_json(&received)));
+
+        received["nonce"] = 8.into();
+        assert_ne!(signature.as_str().unwrap(), key.sign(&canonical_json(&received)));
+    }
+
+    #[test]
+    fn canonical_json_sorts_keys_without_whitespace() {
+        let value = serde_json::json!({ "b": 1, "a": { "d": [1, 2], "c": "x" } });
+        assert_eq!(canonical_json(&value), r#"{"a":{"c":"x","d":[1,2]},"b":1}"#);
+    }
+
+    #[test]
+    fn unsigned_events_are_unchanged() {
+        let wire = to_wire_json(&event(), None).unwrap();
+        assert_eq!(wire, serde_json::to_string(&event()).unwrap());
+    }
+}
@@ -0,0 +1,332 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::chaos::{faults, FaultTarget};
+use crate::config::Config;
+use crate::executor_error::ExecutorError;
+use crate::routing::Computation;
+
+/// Which Solana executor the relayer uses (`SOLANA_MODE`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SolanaMode {
+    /// In-process stub (`solana_sim`)
+    #[default]
+    Sim,
+    /// The reference program on a Solana cluster (`solana_rpc`)
+    Rpc,
+}
+
+impl std::fmt::Display for SolanaMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Sim => write!(f, "sim"),
+            Self::Rpc => write!(f, "rpc"),
+        }
+    }
+}
+
+impl std::str::FromStr for SolanaMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sim" => Ok(Self::Sim),
+            "rpc" => Ok(Self::Rpc),
+            other => anyhow::bail!("unknown SOLANA_MODE: {} (expected sim or rpc)", other),
+        }
+    }
+}
+
+/// An `ExecuteCrossChain` request for the destination program.
+#[derive(Debug, Clone)]
+pub struct ExecutionRequest {
+    pub nonce: u64,
+    /// Ethereum sender address
+    pub sender: [u8; 20],
+    pub amount: u64,
+    pub payload: Vec<u8>,
+    pub trace_id: [u8; 32],
+    pub computation: Computation,
+    /// Compute-unit price bid for faster inclusion under congestion
+    pub priority_fee_micro_lamports: u64,
+}
+
+/// Receipt written by the program, keyed by nonce
+/// (mirrors `ExecutionReceipt` in `/reference/solana-program/`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionReceipt {
+    pub nonce: u64,
+    pub result: u64,
+    pub sender: [u8; 20],
+    pub trace_id: [u8; 32],
+    /// Unix timestamp of execution
+    pub executed_at: i64,
+}
+
+/// Program-wide counters from the stats PDA
+/// (mirrors `ProgramStats` in `/reference/solana-program/`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProgramStats {
+    /// Executions that created a receipt; idempotent skips are not counted
+    pub total_executions: u64,
+    pub total_amount: u128,
+    pub last_execution_slot: u64,
+}
+
+/// A landed transaction and what it cost.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Confirmation {
+    pub slot: u64,
+    /// Base plus prioritization fee paid by the fee payer
+    pub fee_lamports: Option<u64>,
+    pub compute_units: Option<u64>,
+}
+
+/// Cluster-side view of the executor for GET /relayer/status.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ExecutorStatus {
+    pub slot: Option<u64>,
+    /// Fee payer address (base58); `None` when simulated
+    pub payer: Option<String>,
+    pub balance_lamports: Option<u64>,
+}
+
+/// Destination-side executor. Failures are typed (`ExecutorError`) so the
+/// state machine can tell transient from permanent ones. Implementations
+/// must agree on:
+///
+/// - `execute` is idempotent per nonce: re-executing succeeds and leaves the
+///   first receipt untouched.
+/// - `confirm` returns the slot and fee of a signature returned by `execute`
+///   once confirmed, `None` for signatures it does not know (or that did not
+///   confirm within `SOLANA_CONFIRM_TIMEOUT_MS`), and an error if the
+///   transaction failed.
+/// - `receipt` is `None` until the nonce has been executed, then returns the
+///   on-chain result.
+/// - `stats` is `None` until the first execution, then counts each executed
+///   nonce once.
+#[async_trait]
+pub trait SolanaExecutor: Send + Sync {
+    fn mode(&self) -> SolanaMode;
+
+    /// Fee payer address (base58); `None` when simulated.
+    fn payer(&self) -> Option<String>;
+
+    /// Submit the request; returns the transaction signature.
+    async fn execute(&self, req: &ExecutionRequest) -> Result<String, ExecutorError>;
+
+    /// Wait for a submitted transaction to be confirmed.
+    async fn confirm(&self, signature: &str) -> Result<Option<Confirmation>, ExecutorError>;
+
+    /// Read back the receipt for a nonce.
+    async fn receipt(&self, nonce: u64) -> Result<Option<ExecutionReceipt>, ExecutorError>;
+
+    /// Read the program-wide execution counters.
+    async fn stats(&self) -> Result<Option<ProgramStats>, ExecutorError>;
+
+    /// Short status line for /health/systems; errors mean unreachable.
+    async fn health(&self) -> Result<String>;
+
+    /// Current slot and fee payer balance.
+    async fn status(&self) -> Result<ExecutorStatus>;
+}
+
+/// Build the executor selected by `SOLANA_MODE`, behind the fault hook.
+pub fn from_config(cfg: &Config) -> Result<Arc<dyn SolanaExecutor>> {
+    let executor: Arc<dyn SolanaExecutor> = match cfg.solana_mode {
+        SolanaMode::Sim => Arc::new(crate::solana_sim::SimExecutor::new(
+            cfg.solana_sim,
+            std::time::Duration::from_millis(cfg.solana_confirm_timeout_ms),
+        )),
+        SolanaMode::Rpc => Arc::new(crate::solana_rpc::RpcExecutor::from_config(cfg)?),
+    };
+    Ok(Arc::new(FaultHooked(executor)))
+}
+
+/// Applies faults injected with POST /chaos/fault before every call to
+/// the wrapped executor.
+struct FaultHooked(Arc<dyn SolanaExecutor>);
+
+impl FaultHooked {
+    async fn check(&self) -> Result<(), ExecutorError> {
+        faults().check(FaultTarget::Solana).await
+    }
+}
+
+#[async_trait]
+impl SolanaExecutor for FaultHooked {
+    fn mode(&self) -> SolanaMode {
+        self.0.mode()
+    }
+
+    fn payer(&self) -> Option<String> {
+        self.0.payer()
+    }
+
+    async fn execute(&self, req: &ExecutionRequest) -> Result<String, ExecutorError> {
+        self.check().await?;
+        self.0.execute(req).await
+    }
+
+    async fn confirm(&self, signature: &str) -> Result<Option<Confirmation>, ExecutorError> {
+        self.check().await?;
+        self.0.confirm(signature).await
+    }
+
+    async fn receipt(&self, nonce: u64) -> Result<Option<ExecutionReceipt>, ExecutorError> {
+        self.check().await?;
+        self.0.receipt(nonce).await
+    }
+
+    async fn stats(&self) -> Result<Option<ProgramStats>, ExecutorError> {
+        self.check().await?;
+        self.0.stats().await
+    }
+
+    async fn health(&self) -> Result<String> {
+        self.check().await?;
+        self.0.health().await
+    }
+
+    async fn status(&self) -> Result<ExecutorStatus> {
+        self.check().await?;
+        self.0.status().await
+    }
+}
+
+/// A confirmed execution and its receipt.
+#[derive(Debug, Clone)]
+pub struct Execution {
+    pub signature: String,
+    pub confirmation: Confirmation,
+    pub receipt: ExecutionReceipt,
+}
+
+/// Execute, confirm and read the receipt back: the result the relayer settles with.
+pub async fn execute_and_read_back(
+    executor: &dyn SolanaExecutor,
+    req: &ExecutionRequest,
+) -> Result<Execution, ExecutorError> {
+    let signature = executor.execute(req).await?;
+    let Some(confirmation) = executor.confirm(&signature).await? else {
+        return Err(ExecutorError::Timeout(format!(
+            "Solana transaction {} was not confirmed",
+            signature
+        )));
+    };
+    let receipt = executor.receipt(req.nonce).await?.ok_or_else(|| {
+        ExecutorError::Unknown(format!("No execution receipt for nonce {} after confirmation", req.nonce))
+    })?;
+    Ok(Execution {
+        signature,
+        confirmation,
+        receipt,
+    })
+}
+
+/// Conformance suite: every executor must pass the same checks. The rpc
+/// variants need `solana-test-validator` with the reference program deployed
+/// and are run with
+/// `SOLANA_MODE=rpc SOLANA_PROGRAM_ID=... SOLANA_KEYPAIR_PATH=... cargo test -- --ignored`.
+#[cfg(test)]
+mod conformance {
+    use super::*;
+
+    fn request(nonce: u64, amount: u64) -> ExecutionRequest {
+        let mut trace_id = [0u8; 32];
+        trace_id[..8].copy_from_slice(&nonce.to_be_bytes());
+        ExecutionRequest {
+            nonce,
+            sender: [0xab; 20],
+            amount,
+            payload: b"conformance".to_vec(),
+            trace_id,
+            computation: Computation::Double,
+            priority_fee_micro_lamports: 0,
+        }
+    }
+
+    /// Nonces unique per test run so repeated runs against a live cluster
+    /// do not hit receipts from earlier runs.
+    fn fresh_nonce() -> u64 {
+        rand::random::<u32>() as u64 + (1 << 40)
+    }
+
+    async fn executes_and_reads_back(executor: &dyn SolanaExecutor) {
+        let req = request(fresh_nonce(), 21);
+        assert_eq!(executor.receipt(req.nonce).await.unwrap(), None);
+
+        let execution = execute_and_read_back(executor, &req).await.unwrap();
+        assert!(!execution.signature.is_empty());
+        // At least the base fee for the payer's signature
+        assert!(execution.confirmation.fee_lamports.unwrap() >= 5000);
+        let receipt = execution.receipt;
+        assert_eq!(receipt.nonce, req.nonce);
+        assert_eq!(receipt.result, 42);
+        assert_eq!(receipt.sender, req.sender);
+        assert_eq!(receipt.trace_id, req.trace_id);
+        assert!(receipt.executed_at > 0);
+    }
+
+    async fn execute_is_idempotent(executor: &dyn SolanaExecutor) {
+        let req = request(fresh_nonce(), 5);
+        let first = execute_and_read_back(executor, &req).await.unwrap().receipt;
+
+        // A replay with a different amount must not overwrite the receipt
+        let replay = ExecutionRequest { amount: 500, ..req.clone() };
+        let second = execute_and_read_back(executor, &replay).await.unwrap().receipt;
+        assert_eq!(first, second);
+        assert_eq!(second.result, 10);
+    }
+
+    async fn stats_count_new_executions(executor: &dyn SolanaExecutor) {
+        let before = executor.stats().await.unwrap().unwrap_or_default();
+        let req = request(fresh_nonce(), 8);
+        let execution = execute_and_read_back(executor, &req).await.unwrap();
+        let after = executor.stats().await.unwrap().unwrap();
+        assert_eq!(after.total_executions, before.total_executions + 1);
+        assert_eq!(after.total_amount, before.total_amount + 8);
+        assert_eq!(after.last_execution_slot, execution.confirmation.slot);
+
+        // An idempotent skip is not counted
+        execute_and_read_back(executor, &req).await.unwrap();
+        assert_eq!(executor.stats().await.unwrap().unwrap(), after);
+    }
+
+    async fn unknown_signature_is_unconfirmed(executor: &dyn SolanaExecutor) {
+        let unknown = bs58::encode([7u8; 64]).into_string();
+        assert_eq!(executor.confirm(&unknown).await.unwrap(), None);
+    }
+
+    async fn reports_health(executor: &dyn SolanaExecutor) {
+        assert!(!executor.health().await.unwrap().is_empty());
+        assert!(executor.status().await.unwrap().slot.is_some());
+    }
+
+    async fn run_suite(executor: &dyn SolanaExecutor) {
+        executes_and_reads_back(executor).await;
+        execute_is_idempotent(executor).await;
+        stats_count_new_executions(executor).await;
+        unknown_signature_is_unconfirmed(executor).await;
+        reports_health(executor).await;
+    }
+
+    #[tokio::test]
+    async fn sim_executor_conforms() {
+        let executor = crate::solana_sim::SimExecutor::default();
+        assert_eq!(executor.mode(), SolanaMode::Sim);
+        run_suite(&executor).await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs solana-test-validator with the reference program deployed"]
+    async fn rpc_executor_conforms() {
+        let cfg = Config::from_env().unwrap();
+        let executor = crate::solana_rpc::RpcExecutor::from_config(&cfg).unwrap();
+        assert_eq!(executor.mode(), SolanaMode::Rpc);
+        run_suite(&executor).await;
+    }
+}
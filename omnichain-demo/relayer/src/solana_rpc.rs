@@ -0,0 +1,405 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use base64::Engine;
+use ed25519_dalek::{Signer, SigningKey};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tokio::time::{sleep, Duration, Instant};
+use tracing::{info, warn};
+
+use crate::config::Config;
+use crate::executor_error::ExecutorError;
+use crate::routing::Computation;
+use crate::solana::{
+    Confirmation, ExecutionReceipt, ExecutionRequest, ExecutorStatus, ProgramStats, SolanaExecutor, SolanaMode,
+};
+
+/// Seed of the receipt PDA (`RECEIPT_SEED` in the reference program).
+const RECEIPT_SEED: &[u8] = b"receipt";
+
+/// Serialized `ExecutionReceipt` size (`ExecutionReceipt::SIZE`).
+const RECEIPT_SIZE: usize = 77;
+
+/// Seed of the singleton stats PDA (`STATS_SEED` in the reference program).
+const STATS_SEED: &[u8] = b"stats";
+
+/// Serialized `ProgramStats` size (`ProgramStats::SIZE`).
+const STATS_SIZE: usize = 33;
+
+/// The system program id (all zero bytes).
+const SYSTEM_PROGRAM: [u8; 32] = [0u8; 32];
+
+/// `ComputeBudget111111111111111111111111111111`
+const COMPUTE_BUDGET_PROGRAM: &str = "ComputeBudget111111111111111111111111111111";
+
+/// Solana client for the reference `cross-chain-executor` program (`SOLANA_MODE=rpc`).
+///
+/// Talks JSON-RPC directly and builds legacy transactions by hand, so the
+/// relayer does not pull in the Solana SDK. Accounts follow the program:
+/// payer (signer), receipt PDA `["receipt", nonce LE]`, system program,
+/// stats PDA `["stats"]`.
+pub struct RpcExecutor {
+    rpc_url: String,
+    program_id: [u8; 32],
+    payer: SigningKey,
+    confirm_timeout: Duration,
+    http: reqwest::Client,
+}
+
+impl RpcExecutor {
+    /// Requires `SOLANA_PROGRAM_ID` and a CLI-format keypair at `SOLANA_KEYPAIR_PATH`.
+    pub fn from_config(cfg: &Config) -> Result<Self> {
+        let program_id = cfg
+            .solana_program_id
+            .as_deref()
+            .context("SOLANA_PROGRAM_ID is required when SOLANA_MODE=rpc")?;
+        let program_id = decode_pubkey(program_id).context("invalid SOLANA_PROGRAM_ID")?;
+
+        let keypair_json = std::fs::read_to_string(&cfg.solana_keypair_path)
+            .with_context(|| format!("reading Solana keypair {}", cfg.solana_keypair_path))?;
+        let bytes: Vec<u8> = serde_json::from_str(&keypair_json).context("Solana keypair must be a JSON byte array")?;
+        let secret: [u8; 32] = bytes
+            .get(..32)
+            .and_then(|s| s.try_into().ok())
+            .context("Solana keypair is too short")?;
+
+        Ok(Self {
+            rpc_url: cfg.solana_rpc_url.clone(),
+            program_id,
+            payer: SigningKey::from_bytes(&secret),
+            confirm_timeout: Duration::from_millis(cfg.solana_confirm_timeout_ms),
+            http: reqwest::Client::new(),
+        })
+    }
+
+    async fn call(&self, method: &str, params: Value) -> Result<Value, ExecutorError> {
+        let res: Value = self
+            .http
+            .post(&self.rpc_url)
+            .json(&json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params}))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        if let Some(err) = res.get("error") {
+            return Err(classify_rpc_error(method, err));
+        }
+        Ok(res["result"].clone())
+    }
+
+    /// Fee and compute units from the transaction's metadata. A node that
+    /// cannot serve the transaction yet leaves them unset.
+    async fn confirmation(&self, signature: &str, slot: u64) -> Confirmation {
+        let meta = match self
+            .call(
+                "getTransaction",
+                json!([signature, {"encoding": "json", "commitment": "confirmed", "maxSupportedTransactionVersion": 0}]),
+            )
+            .await
+        {
+            Ok(tx) => tx["meta"].clone(),
+            Err(e) => {
+                warn!(%signature, error = %e, "Could not read Solana transaction fee");
+                Value::Null
+            }
+        };
+        Confirmation {
+            slot,
+            fee_lamports: meta["fee"].as_u64(),
+            compute_units: meta["computeUnitsConsumed"].as_u64(),
+        }
+    }
+
+    fn receipt_pda(&self, nonce: u64) -> [u8; 32] {
+        find_program_address(&[RECEIPT_SEED, &nonce.to_le_bytes()], &self.program_id)
+    }
+
+    fn stats_pda(&self) -> [u8; 32] {
+        find_program_address(&[STATS_SEED], &self.program_id)
+    }
+
+    /// Raw data of a program account; `None` if it does not exist.
+    async fn account_data(&self, address: [u8; 32]) -> Result<Option<Vec<u8>>, ExecutorError> {
+        let address = bs58::encode(address).into_string();
+        let account = self
+            .call(
+                "getAccountInfo",
+                json!([address, {"encoding": "base64", "commitment": "confirmed"}]),
+            )
+            .await?;
+        let value = &account["value"];
+        if value.is_null() {
+            return Ok(None);
+        }
+
+        let data = base64::engine::general_purpose::STANDARD
+            .decode(value["data"][0].as_str().unwrap_or_default())
+            .with_context(|| format!("invalid account data for {}", address))?;
+        Ok(Some(data))
+    }
+}
+
+#[async_trait]
+impl SolanaExecutor for RpcExecutor {
+    fn mode(&self) -> SolanaMode {
+        SolanaMode::Rpc
+    }
+
+    fn payer(&self) -> Option<String> {
+        Some(bs58::encode(self.payer.verifying_key().to_bytes()).into_string())
+    }
+
+    async fn execute(&self, req: &ExecutionRequest) -> Result<String, ExecutorError> {
+        // The reference program only implements `double`
+        if req.computation != Computation::Double {
+            return Err(ExecutorError::Reverted(format!(
+                "Solana program does not support computation '{}'",
+                req.computation
+            )));
+        }
+
+        let blockhash = self
+            .call("getLatestBlockhash", json!([{"commitment": "confirmed"}]))
+            .await?;
+        let blockhash = decode_pubkey(blockhash["value"]["blockhash"].as_str().unwrap_or_default())
+            .context("invalid blockhash")?;
+
+        let payer = self.payer.verifying_key().to_bytes();
+        let receipt = self.receipt_pda(req.nonce);
+        let stats = self.stats_pda();
+
+        // ExecuteCrossChain (borsh): variant, nonce, sender, amount, payload, trace_id
+        let mut data = vec![0u8];
+        data.extend_from_slice(&req.nonce.to_le_bytes());
+        data.extend_from_slice(&req.sender);
+        data.extend_from_slice(&req.amount.to_le_bytes());
+        data.extend_from_slice(&(req.payload.len() as u32).to_le_bytes());
+        data.extend_from_slice(&req.payload);
+        data.extend_from_slice(&req.trace_id);
+
+        // Legacy message: 1 signer, 0 readonly signed, then the writable PDAs
+        // and the readonly unsigned programs (system, executor, compute
+        // budget if bidding)
+        let mut keys = vec![payer, receipt, stats, SYSTEM_PROGRAM, self.program_id];
+        let mut instructions: Vec<(u8, Vec<u8>, Vec<u8>)> = vec![(4, vec![0, 1, 3, 2], data)];
+        if req.priority_fee_micro_lamports > 0 {
+            keys.push(decode_pubkey(COMPUTE_BUDGET_PROGRAM)?);
+            // SetComputeUnitPrice
+            let mut price = vec![3u8];
+            price.extend_from_slice(&req.priority_fee_micro_lamports.to_le_bytes());
+            instructions.insert(0, (5, vec![], price));
+        }
+
+        let mut message = vec![1u8, 0, (keys.len() - 3) as u8];
+        push_compact_len(&mut message, keys.len());
+        for key in &keys {
+            message.extend_from_slice(key);
+        }
+        message.extend_from_slice(&blockhash);
+        push_compact_len(&mut message, instructions.len());
+        for (program_index, accounts, data) in &instructions {
+            message.push(*program_index);
+            push_compact_len(&mut message, accounts.len());
+            message.extend_from_slice(accounts);
+            push_compact_len(&mut message, data.len());
+            message.extend_from_slice(data);
+        }
+
+        let signature = self.payer.sign(&message).to_bytes();
+        let mut tx = Vec::with_capacity(1 + 64 + message.len());
+        push_compact_len(&mut tx, 1);
+        tx.extend_from_slice(&signature);
+        tx.extend_from_slice(&message);
+
+        let encoded = base64::engine::general_purpose::STANDARD.encode(&tx);
+        let sig = self
+            .call(
+                "sendTransaction",
+                json!([encoded, {"encoding": "base64", "preflightCommitment": "confirmed"}]),
+            )
+            .await?;
+        let sig = sig.as_str().context("sendTransaction returned no signature")?.to_string();
+
+        info!(nonce = req.nonce, %sig, "Solana execution submitted");
+        Ok(sig)
+    }
+
+    async fn confirm(&self, signature: &str) -> Result<Option<Confirmation>, ExecutorError> {
+        let deadline = Instant::now() + self.confirm_timeout;
+        loop {
+            let statuses = self
+                .call(
+                    "getSignatureStatuses",
+                    json!([[signature], {"searchTransactionHistory": true}]),
+                )
+                .await?;
+            let status = &statuses["value"][0];
+
+            if !status.is_null() {
+                if !status["err"].is_null() {
+                    return Err(classify_tx_error(
+                        &status["err"],
+                        format!("Solana transaction {} failed: {}", signature, status["err"]),
+                    ));
+                }
+                if matches!(status["confirmationStatus"].as_str(), Some("confirmed" | "finalized")) {
+                    let Some(slot) = status["slot"].as_u64() else {
+                        return Ok(None);
+                    };
+                    return Ok(Some(self.confirmation(signature, slot).await));
+                }
+            }
+
+            if Instant::now() >= deadline {
+                // A null status means the cluster never saw the signature
+                if !status.is_null() {
+                    warn!(%signature, "Solana transaction not confirmed in time");
+                }
+                return Ok(None);
+            }
+            sleep(Duration::from_millis(500)).await;
+        }
+    }
+
+    async fn receipt(&self, nonce: u64) -> Result<Option<ExecutionReceipt>, ExecutorError> {
+        match self.account_data(self.receipt_pda(nonce)).await? {
+            Some(data) => Ok(decode_receipt(&data)?),
+            None => Ok(None),
+        }
+    }
+
+    async fn stats(&self) -> Result<Option<ProgramStats>, ExecutorError> {
+        match self.account_data(self.stats_pda()).await? {
+            Some(data) => Ok(decode_stats(&data)?),
+            None => Ok(None),
+        }
+    }
+
+    async fn health(&self) -> Result<String> {
+        let version = self.call("getVersion", json!([])).await?;
+        Ok(format!(
+            "RPC {} (solana-core {})",
+            self.rpc_url,
+            version["solana-core"].as_str().unwrap_or("unknown")
+        ))
+    }
+
+    async fn status(&self) -> Result<ExecutorStatus> {
+        let payer = SolanaExecutor::payer(self).unwrap_or_default();
+        let slot = self.call("getSlot", json!([{"commitment": "confirmed"}])).await?;
+        let balance = self
+            .call("getBalance", json!([payer, {"commitment": "confirmed"}]))
+            .await?;
+        Ok(ExecutorStatus {
+            slot: slot.as_u64(),
+            payer: Some(payer),
+            balance_lamports: balance["value"].as_u64(),
+        })
+    }
+}
+
+/// Map a JSON-RPC error (usually a failed preflight) to an `ExecutorError`.
+fn classify_rpc_error(method: &str, err: &Value) -> ExecutorError {
+    let message = format!("Solana RPC {} failed: {}", method, err["message"].as_str().unwrap_or("unknown error"));
+    if err["code"].as_i64() == Some(429) {
+        return ExecutorError::RateLimited(message);
+    }
+    match &err["data"]["err"] {
+        Value::Null => ExecutorError::Unknown(message),
+        tx_err => classify_tx_error(tx_err, message),
+    }
+}
+
+/// Map a `TransactionError` to an `ExecutorError`.
+fn classify_tx_error(tx_err: &Value, message: String) -> ExecutorError {
+    match tx_err.as_str() {
+        Some("AccountNotFound" | "InsufficientFundsForFee" | "InsufficientFundsForRent") => {
+            ExecutorError::InsufficientFunds(message)
+        }
+        // Expired before landing; a fresh blockhash may succeed
+        Some("BlockhashNotFound") => ExecutorError::Timeout(message),
+        Some("WouldExceedMaxBlockCostLimit" | "WouldExceedMaxAccountCostLimit") => ExecutorError::RateLimited(message),
+        _ if tx_err.get("InstructionError").is_some() => ExecutorError::Reverted(message),
+        _ => ExecutorError::Unknown(message),
+    }
+}
+
+fn decode_pubkey(s: &str) -> Result<[u8; 32]> {
+    let bytes = bs58::decode(s).into_vec()?;
+    bytes
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("expected 32 bytes"))
+}
+
+/// Solana's compact-u16 length prefix.
+fn push_compact_len(buf: &mut Vec<u8>, mut len: usize) {
+    loop {
+        let mut byte = (len & 0x7f) as u8;
+        len >>= 7;
+        if len == 0 {
+            buf.push(byte);
+            return;
+        }
+        byte |= 0x80;
+        buf.push(byte);
+    }
+}
+
+/// `Pubkey::find_program_address`: first bump (from 255 down) whose hash is off the curve.
+fn find_program_address(seeds: &[&[u8]], program_id: &[u8; 32]) -> [u8; 32] {
+    for bump in (0..=255u8).rev() {
+        let mut hasher = Sha256::new();
+        for seed in seeds {
+            hasher.update(seed);
+        }
+        hasher.update([bump]);
+        hasher.update(program_id);
+        hasher.update(b"ProgramDerivedAddress");
+        let hash: [u8; 32] = hasher.finalize().into();
+
+        let on_curve = curve25519_dalek::edwards::CompressedEdwardsY(hash)
+            .decompress()
+            .is_some();
+        if !on_curve {
+            return hash;
+        }
+    }
+    unreachable!("no viable program address bump")
+}
+
+/// Decode a borsh `ExecutionReceipt`; uninitialized accounts count as missing.
+fn decode_receipt(data: &[u8]) -> Result<Option<ExecutionReceipt>> {
+    if data.len() < RECEIPT_SIZE {
+        anyhow::bail!("receipt account is {} bytes, expected {}", data.len(), RECEIPT_SIZE);
+    }
+    if data[0] == 0 {
+        return Ok(None);
+    }
+
+    let u64_at = |i: usize| u64::from_le_bytes(data[i..i + 8].try_into().unwrap());
+    Ok(Some(ExecutionReceipt {
+        nonce: u64_at(1),
+        result: u64_at(9),
+        sender: data[17..37].try_into().unwrap(),
+        trace_id: data[37..69].try_into().unwrap(),
+        executed_at: u64_at(69) as i64,
+    }))
+}
+
+/// Decode a borsh `ProgramStats`; an uninitialized account counts as missing.
+fn decode_stats(data: &[u8]) -> Result<Option<ProgramStats>> {
+    if data.len() < STATS_SIZE {
+        anyhow::bail!("stats account is {} bytes, expected {}", data.len(), STATS_SIZE);
+    }
+    if data[0] == 0 {
+        return Ok(None);
+    }
+
+    Ok(Some(ProgramStats {
+        total_executions: u64::from_le_bytes(data[1..9].try_into().unwrap()),
+        total_amount: u128::from_le_bytes(data[9..25].try_into().unwrap()),
+        last_execution_slot: u64::from_le_bytes(data[25..33].try_into().unwrap()),
+    }))
+}
@@ -1,27 +1,438 @@
 use anyhow::Result;
-use tracing::info;
+use async_trait::async_trait;
+use rand::Rng;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use tokio::time::{sleep_until, Duration, Instant};
+use tracing::{info, warn};
 
-/// SIMULATION: Solana execution stub.
+use crate::executor_error::ExecutorError;
+use crate::routing::Computation;
+use crate::solana::{
+    Confirmation, ExecutionReceipt, ExecutionRequest, ExecutorStatus, ProgramStats, SolanaExecutor, SolanaMode,
+};
+
+/// Slots a blockhash stays valid for (Solana's `MAX_PROCESSING_AGE`).
+const MAX_PROCESSING_AGE: u64 = 150;
+
+/// Priority fee (micro-lamports per CU) at which half of the congestion is bypassed.
+const REFERENCE_PRIORITY_FEE: f64 = 10_000.0;
+
+/// Base fee per transaction signature.
+const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// Compute units the priority fee is charged on: the default limit, since
+/// the relayer does not set one.
+const COMPUTE_UNIT_LIMIT: u64 = 200_000;
+
+/// Compute units `ExecuteCrossChain` consumes, receipt creation included.
+const EXECUTE_COMPUTE_UNITS: u64 = 15_000;
+
+/// How long a landed or dropped transaction can still be looked up by
+/// signature; well past any confirmation timeout.
+const SIGNATURE_RETENTION: Duration = Duration::from_secs(600);
+
+/// Receipts kept; past this the oldest are forgotten, and re-executing one
+/// of their nonces would run again.
+const RECEIPT_CAPACITY: usize = 100_000;
+
+/// Distribution of the RPC round trip for submitting a transaction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LatencyDistribution {
+    /// `fixed:<ms>`
+    Fixed(f64),
+    /// `uniform:<min_ms>:<max_ms>`
+    Uniform(f64, f64),
+    /// `normal:<mean_ms>:<stddev_ms>` (clamped at 0)
+    Normal(f64, f64),
+    /// `exponential:<mean_ms>` — long-tailed
+    Exponential(f64),
+}
+
+impl LatencyDistribution {
+    pub fn sample(&self, rng: &mut impl Rng) -> Duration {
+        let ms = match *self {
+            Self::Fixed(ms) => ms,
+            Self::Uniform(min, max) if max > min => rng.gen_range(min..max),
+            Self::Uniform(min, _) => min,
+            Self::Normal(mean, stddev) => {
+                // Box–Muller
+                let (u1, u2): (f64, f64) = (rng.gen_range(f64::EPSILON..1.0), rng.gen());
+                mean + stddev * (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos()
+            }
+            Self::Exponential(mean) => -mean * rng.gen_range(f64::EPSILON..1.0).ln(),
+        };
+        Duration::from_secs_f64(ms.max(0.0) / 1000.0)
+    }
+}
+
+impl std::str::FromStr for LatencyDistribution {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split(':').collect();
+        let num = |i: usize| -> Result<f64> {
+            let v: f64 = parts
+                .get(i)
+                .ok_or_else(|| anyhow::anyhow!("latency distribution '{}' is missing a parameter", s))?
+                .parse()?;
+            Ok(v)
+        };
+        match parts[0] {
+            "fixed" => Ok(Self::Fixed(num(1)?)),
+            "uniform" => Ok(Self::Uniform(num(1)?, num(2)?)),
+            "normal" => Ok(Self::Normal(num(1)?, num(2)?)),
+            "exponential" => Ok(Self::Exponential(num(1)?)),
+            other => anyhow::bail!("unknown latency distribution: {} (expected fixed, uniform, normal or exponential)", other),
+        }
+    }
+}
+
+/// Cluster behaviour modelled by the simulator.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimModel {
+    /// Submission round trip
+    pub latency: LatencyDistribution,
+    /// Chance a submission fails with "Blockhash not found"
+    pub blockhash_expiry_rate: f64,
+    /// 0.0 = every transaction lands in the next slot; 1.0 = transactions
+    /// without a priority fee are almost never included
+    pub congestion: f64,
+    pub slot_ms: u64,
+}
+
+impl Default for SimModel {
+    /// An idle cluster: instant submission, next-slot inclusion, no errors.
+    fn default() -> Self {
+        Self {
+            latency: LatencyDistribution::Fixed(0.0),
+            blockhash_expiry_rate: 0.0,
+            congestion: 0.0,
+            slot_ms: 0,
+        }
+    }
+}
+
+impl SimModel {
+    /// Slots until inclusion. Each slot the transaction lands with probability
+    /// `1 - congestion × (1 - fee_share)`, where the priority fee buys a share
+    /// of the contended block space.
+    fn inclusion_slots(&self, priority_fee: u64, rng: &mut impl Rng) -> u64 {
+        let fee = priority_fee as f64;
+        let fee_share = fee / (fee + REFERENCE_PRIORITY_FEE);
+        let p = (1.0 - self.congestion.clamp(0.0, 1.0) * (1.0 - fee_share)).max(0.001);
+
+        let mut slots = 1;
+        while slots <= MAX_PROCESSING_AGE && !rng.gen_bool(p) {
+            slots += 1;
+        }
+        slots
+    }
+}
+
+/// A submitted transaction that lands once `confirm_at` passes.
+struct PendingTx {
+    confirmation: Confirmation,
+    confirm_at: Instant,
+    receipt: ExecutionReceipt,
+    amount: u64,
+}
+
+#[derive(Default)]
+struct Ledger {
+    receipts: HashMap<u64, ExecutionReceipt>,
+    /// Nonces of `receipts`, oldest first
+    receipt_order: VecDeque<u64>,
+    /// The program's stats PDA; `None` until the first execution lands
+    stats: Option<ProgramStats>,
+    pending: HashMap<String, PendingTx>,
+    /// Landed transactions, with when they landed
+    confirmed: HashMap<String, (Confirmation, Instant)>,
+    /// Submitted but never landing (blockhash expired in the queue), with
+    /// when they were submitted
+    dropped: HashMap<String, Instant>,
+}
+
+impl Ledger {
+    /// Land every pending transaction whose time has come, and forget
+    /// signatures older than `SIGNATURE_RETENTION`.
+    fn advance(&mut self, now: Instant) {
+        let due: Vec<String> = self
+            .pending
+            .iter()
+            .filter(|(_, tx)| tx.confirm_at <= now)
+            .map(|(sig, _)| sig.clone())
+            .collect();
+        for sig in due {
+            let tx = self.pending.remove(&sig).expect("due transaction");
+            // Idempotent skip: the first receipt for a nonce wins and is
+            // the only one counted
+            if let Entry::Vacant(entry) = self.receipts.entry(tx.receipt.nonce) {
+                self.receipt_order.push_back(tx.receipt.nonce);
+                entry.insert(tx.receipt);
+                let stats = self.stats.get_or_insert_with(ProgramStats::default);
+                stats.total_executions += 1;
+                stats.total_amount += tx.amount as u128;
+                stats.last_execution_slot = stats.last_execution_slot.max(tx.confirmation.slot);
+            }
+            self.confirmed.insert(sig, (tx.confirmation, tx.confirm_at));
+        }
+
+        let fresh = |at: &Instant| now.saturating_duration_since(*at) < SIGNATURE_RETENTION;
+        self.confirmed.retain(|_, (_, landed)| fresh(landed));
+        self.dropped.retain(|_, submitted| fresh(submitted));
+        while self.receipt_order.len() > RECEIPT_CAPACITY {
+            if let Some(nonce) = self.receipt_order.pop_front() {
+                self.receipts.remove(&nonce);
+            }
+        }
+    }
+}
+
+/// SIMULATION: Solana execution stub (`SOLANA_MODE=sim`).
 ///
-/// Computes the same deterministic result (amount × 2) that the real
-/// Solana program would produce.  See `/reference/solana-program/` for
+/// Computes the deterministic result selected by routing — by default
+/// amount × 2, the same as the real Solana program would produce.  See `/reference/solana-program/` for
 /// the full on-chain implementation (PDA receipt accounts, borsh
 /// serialization, idempotency checks, structured event logs).
 ///
-/// In a production system this function would:
-/// 1. Build a Borsh-serialized `ExecuteCrossChain` instruction
-/// 2. Derive the receipt PDA via `find_program_address`
-/// 3. Submit the transaction with `RpcClient::send_and_confirm_transaction`
-/// 4. Read back the `ExecutionReceipt` account for the on-chain result
-pub async fn execute_on_solana(
-    nonce: u64,
-    amount: u64,
-    trace_id: [u8; 32],
-) -> Result<(String, u64)> {
-    // Deterministic computation (matches the Solana program: amount * 2)
-    let result = amount.checked_mul(2).unwrap_or(u64::MAX);
-    let sig = format!("sim_{}_{}", nonce, hex::encode(&trace_id[..8]));
-
-    info!(nonce, %sig, result, "Solana execution simulated");
-    Ok((sig, result))
+/// Receipts are kept in memory, keyed by nonce like the program's receipt
+/// PDAs, so re-execution is an idempotent skip. A `SimModel` adds submission
+/// latency, blockhash expiry, a slot clock and congestion, so confirmation
+/// can be slow or time out. `solana_rpc::RpcExecutor` performs the same
+/// steps against a real cluster.
+pub struct SimExecutor {
+    model: SimModel,
+    confirm_timeout: Duration,
+    genesis: Instant,
+    ledger: Mutex<Ledger>,
+}
+
+impl Default for SimExecutor {
+    fn default() -> Self {
+        Self::new(SimModel::default(), Duration::from_secs(30))
+    }
+}
+
+impl SimExecutor {
+    pub fn new(model: SimModel, confirm_timeout: Duration) -> Self {
+        Self {
+            model,
+            confirm_timeout,
+            genesis: Instant::now(),
+            ledger: Mutex::new(Ledger::default()),
+        }
+    }
+
+    fn slot_at(&self, at: Instant) -> u64 {
+        let elapsed = at.saturating_duration_since(self.genesis).as_millis() as u64;
+        elapsed.checked_div(self.model.slot_ms).unwrap_or(0)
+    }
+}
+
+#[async_trait]
+impl SolanaExecutor for SimExecutor {
+    fn mode(&self) -> SolanaMode {
+        SolanaMode::Sim
+    }
+
+    fn payer(&self) -> Option<String> {
+        None
+    }
+
+    async fn execute(&self, req: &ExecutionRequest) -> Result<String, ExecutorError> {
+        // Deterministic computation (`Double` matches the Solana program: amount * 2)
+        let result = match req.computation {
+            Computation::Double => req
+                .amount
+                .checked_mul(2)
+                .ok_or_else(|| ExecutorError::Reverted("Solana program error: arithmetic overflow".into()))?,
+            Computation::Identity => req.amount,
+        };
+
+        let (latency, expired, slots) = {
+            let mut rng = rand::thread_rng();
+            (
+                self.model.latency.sample(&mut rng),
+                !crate::chaos::faults().suspended() && rng.gen_bool(self.model.blockhash_expiry_rate.clamp(0.0, 1.0)),
+                self.model.inclusion_slots(req.priority_fee_micro_lamports, &mut rng),
+            )
+        };
+        tokio::time::sleep(latency).await;
+
+        if expired {
+            warn!(nonce = req.nonce, "Simulated blockhash expiry");
+            return Err(ExecutorError::Timeout("Transaction simulation failed: Blockhash not found".into()));
+        }
+
+        let submitted = Instant::now();
+        let submit_slot = self.slot_at(submitted);
+        let sig = format!("sim_{}_{}_{}", req.nonce, hex::encode(&req.trace_id[..8]), submit_slot);
+
+        // Past MAX_PROCESSING_AGE the blockhash expires before the
+        // transaction lands: it is dropped and never confirms
+        if slots > MAX_PROCESSING_AGE {
+            warn!(nonce = req.nonce, %sig, "Simulated transaction dropped under congestion");
+            self.ledger.lock().unwrap().dropped.insert(sig.clone(), submitted);
+            return Ok(sig);
+        }
+
+        let receipt = ExecutionReceipt {
+            nonce: req.nonce,
+            result,
+            sender: req.sender,
+            trace_id: req.trace_id,
+            executed_at: chrono::Utc::now().timestamp(),
+        };
+        // Lands at the start of its inclusion slot
+        let slot = submit_slot + slots;
+        let confirm_at = self.genesis + Duration::from_millis(slot * self.model.slot_ms);
+        let confirmation = Confirmation {
+            slot,
+            fee_lamports: Some(
+                LAMPORTS_PER_SIGNATURE + (req.priority_fee_micro_lamports * COMPUTE_UNIT_LIMIT).div_ceil(1_000_000),
+            ),
+            compute_units: Some(EXECUTE_COMPUTE_UNITS),
+        };
+        self.ledger.lock().unwrap().pending.entry(sig.clone()).or_insert(PendingTx {
+            confirmation,
+            confirm_at,
+            receipt,
+            amount: req.amount,
+        });
+
+        info!(nonce = req.nonce, %sig, computation = %req.computation, result, slots, "Solana execution simulated");
+        Ok(sig)
+    }
+
+    async fn confirm(&self, signature: &str) -> Result<Option<Confirmation>, ExecutorError> {
+        let deadline = Instant::now() + self.confirm_timeout;
+        loop {
+            let wait_until = {
+                let mut ledger = self.ledger.lock().unwrap();
+                ledger.advance(Instant::now());
+                if let Some((confirmation, _)) = ledger.confirmed.get(signature) {
+                    return Ok(Some(*confirmation));
+                }
+                match ledger.pending.get(signature) {
+                    Some(tx) if tx.confirm_at <= deadline => tx.confirm_at,
+                    // Dropped, unknown, or landing after the timeout
+                    _ => break,
+                }
+            };
+            sleep_until(wait_until).await;
+        }
+
+        let in_flight = {
+            let ledger = self.ledger.lock().unwrap();
+            ledger.pending.contains_key(signature) || ledger.dropped.contains_key(signature)
+        };
+        if in_flight {
+            sleep_until(deadline).await;
+            warn!(%signature, "Solana transaction not confirmed in time");
+        }
+        Ok(None)
+    }
+
+    async fn receipt(&self, nonce: u64) -> Result<Option<ExecutionReceipt>, ExecutorError> {
+        let mut ledger = self.ledger.lock().unwrap();
+        ledger.advance(Instant::now());
+        Ok(ledger.receipts.get(&nonce).cloned())
+    }
+
+    async fn stats(&self) -> Result<Option<ProgramStats>, ExecutorError> {
+        let mut ledger = self.ledger.lock().unwrap();
+        ledger.advance(Instant::now());
+        Ok(ledger.stats)
+    }
+
+    async fn health(&self) -> Result<String> {
+        Ok(format!(
+            "Simulated (in-process), slot {}, congestion {:.0}%",
+            self.slot_at(Instant::now()),
+            self.model.congestion * 100.0
+        ))
+    }
+
+    async fn status(&self) -> Result<ExecutorStatus> {
+        // The simulated fee payer has no balance to report
+        Ok(ExecutorStatus {
+            slot: Some(self.slot_at(Instant::now())),
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solana::SolanaExecutor;
+
+    fn request(nonce: u64) -> ExecutionRequest {
+        ExecutionRequest {
+            nonce,
+            sender: [0xab; 20],
+            amount: 7,
+            payload: Vec::new(),
+            trace_id: [1; 32],
+            computation: Computation::Double,
+            priority_fee_micro_lamports: 0,
+        }
+    }
+
+    fn model(slot_ms: u64, blockhash_expiry_rate: f64) -> SimModel {
+        SimModel {
+            slot_ms,
+            blockhash_expiry_rate,
+            ..SimModel::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn confirms_in_the_next_slot() {
+        let executor = SimExecutor::new(model(10, 0.0), Duration::from_secs(1));
+        let submitted = executor.slot_at(Instant::now());
+        let sig = executor.execute(&request(1)).await.unwrap();
+        let confirmation = executor.confirm(&sig).await.unwrap().unwrap();
+        assert!(confirmation.slot > submitted);
+        assert!(confirmation.slot <= executor.slot_at(Instant::now()));
+        assert_eq!(executor.receipt(1).await.unwrap().unwrap().result, 14);
+    }
+
+    #[tokio::test]
+    async fn expired_blockhash_fails_as_a_timeout() {
+        let executor = SimExecutor::new(model(0, 1.0), Duration::from_secs(1));
+        let err = executor.execute(&request(2)).await.unwrap_err();
+        assert!(matches!(err, ExecutorError::Timeout(_)));
+        assert_eq!(executor.receipt(2).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn landing_after_the_timeout_is_unconfirmed() {
+        let executor = SimExecutor::new(model(60_000, 0.0), Duration::from_millis(20));
+        let sig = executor.execute(&request(3)).await.unwrap();
+        assert_eq!(executor.confirm(&sig).await.unwrap(), None);
+    }
+
+    #[test]
+    fn old_signatures_are_forgotten() {
+        let mut ledger = Ledger::default();
+        let now = Instant::now();
+        let confirmation = Confirmation {
+            slot: 1,
+            fee_lamports: None,
+            compute_units: None,
+        };
+        ledger.confirmed.insert("landed".into(), (confirmation, now));
+        ledger.dropped.insert("dropped".into(), now);
+
+        ledger.advance(now + SIGNATURE_RETENTION / 2);
+        assert!(ledger.confirmed.contains_key("landed"));
+        assert!(ledger.dropped.contains_key("dropped"));
+
+        ledger.advance(now + SIGNATURE_RETENTION);
+        assert!(ledger.confirmed.is_empty());
+        assert!(ledger.dropped.is_empty());
+    }
 }
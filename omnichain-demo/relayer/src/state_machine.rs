@@ -1,40 +1,75 @@
 use anyhow::Result;
+use payload::Payload;
 use rand::Rng;
+use std::collections::HashMap;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use tokio::time::{sleep, Duration};
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn};
 
+use crate::chaos::{self, ChaosStage, InjectedFailure};
+use crate::executor_error::{ExecutorError, FailureAction, MAX_DEFERS};
 use crate::config::Config;
 use crate::db;
+use crate::envelope::{MessageEnvelope, Route, RouteDecision, ETHEREUM_CHAIN_ID};
+use crate::routing::{self, Computation, PayloadHeader};
 use crate::eth;
+use crate::event_writer;
+use crate::integrity;
 use crate::event::{Actor, LifecycleEvent, Status, Step};
-use crate::solana_sim;
-use crate::types::{AppState, MessageState};
-use crate::verification;
+use crate::solana::{self, ExecutionRequest};
+use crate::types::{AppState, ChainConfig, InjectRequest, MessageState, ParsedFields};
+use crate::verification::{self, ProofRejection};
 
 const MAX_RETRIES: i32 = 1;
 
-/// Returns true ~10% of the time to simulate transient failures.
-fn should_simulate_failure() -> bool {
-    rand::thread_rng().gen_ratio(1, 10)
+/// First nonce given to injected messages, far above any escrow nonce.
+const INJECTED_NONCE_BASE: u64 = 1 << 48;
+
+/// Gas charged to simulated settlements priced by `GAS_SIMULATION`
+/// (about what settle() uses on Anvil).
+const SIMULATED_SETTLE_GAS: u64 = 61_000;
+
+/// Escrow lock window given to injected messages.
+const INJECTED_DEADLINE_SECS: i64 = 3600;
+
+/// Returns true at the active scenario's failure rate (~10% by default)
+/// to simulate transient failures.
+fn should_simulate_failure(state: &AppState) -> bool {
+    let pct = state.scenario.lock().unwrap().failure_rate_pct.min(100);
+    rand::thread_rng().gen_ratio(pct, 100)
 }
 
-/// Returns true ~50% of the time (coin flip for retry outcome).
-fn retry_also_fails() -> bool {
-    rand::thread_rng().gen_bool(0.5)
+/// Returns true at the active scenario's retry failure rate (~50% by default).
+fn retry_also_fails(state: &AppState) -> bool {
+    let pct = state.scenario.lock().unwrap().retry_failure_rate_pct.min(100);
+    rand::thread_rng().gen_ratio(pct, 100)
+}
+
+/// Whether an injected message was asked to fail at `stage`; `None` for
+/// messages observed on Ethereum, which fail at the scenario's rate instead.
+fn forced_failure(msg: &crate::types::CrossChainMessage, stage: ChaosStage) -> Option<bool> {
+    msg.behavior().map(|b| b.fails_at(stage))
 }
 
 /// Main processor loop: polls Ethereum for events and advances the state machine.
 pub async fn run_processor(state: Arc<AppState>, cfg: Config) -> Result<()> {
     info!("Starting state machine processor");
 
+    // Compare stored states with the chains before anything advances them
+    integrity::run_startup_check(&state).await;
+
     // Resume any in-flight messages from a previous run (crash-safe resume)
     resume_inflight(&state, &cfg).await?;
 
     let mut last_block: u64 = 0;
 
     loop {
+        // CHAOS: a requested crash; main restarts the task
+        if crate::chaos::faults().take_crash() {
+            panic!("Injected processor crash");
+        }
+
         // Check if paused
         if state.paused.load(Ordering::Relaxed) {
             sleep(Duration::from_millis(500)).await;
@@ -46,6 +81,7 @@ pub async fn run_processor(state: Arc<AppState>, cfg: Config) -> Result<()> {
         // 1. Poll Ethereum for new CrossChainRequest events
         match poll_ethereum(&state, &cfg, &mut last_block).await {
             Ok(count) => {
+                state.last_processed_block.store(last_block, Ordering::Relaxed);
                 if count > 0 {
                     info!(count, last_block, "Observed new cross-chain requests");
                 }
@@ -94,10 +130,13 @@ async fn resume_inflight(state: &Arc<AppState>, _cfg: &Config) -> Result<()> {
                 db::update_message_state(
                     &state.pool,
                     msg.nonce as u64,
+                    MessageState::SentToSolana,
                     MessageState::Executed,
                     None, None, None, None,
+                    "resume: Solana result already stored",
                 )
                 .await?;
+                state.cache.invalidate(msg.nonce as u64);
                 info!(nonce = msg.nonce, "Promoted SentToSolana → Executed on resume");
             }
         }
@@ -118,41 +157,72 @@ async fn poll_ethereum(
     }
 
     let from_block = if *last_block == 0 { 0 } else { *last_block + 1 };
-    let logs = eth::fetch_logs(&cfg.eth_rpc_url, &cfg.escrow_address, from_block).await?;
+    let logs = eth::fetch_logs(&cfg.eth_rpc_url, &cfg.escrow_address, from_block, &state.event_layout).await?;
+    let rules = db::get_routing_rules(&state.pool).await?;
 
     let mut count = 0;
     for log in &logs {
-        match eth::parse_log(log) {
+        match eth::parse_log(log, &state.event_layout) {
             Ok(event) => {
                 // Idempotency: skip if already in DB
                 if db::nonce_exists(&state.pool, event.nonce).await? {
                     continue;
                 }
 
-                let trace_id = format!("{:?}", event.trace_id);
-
-                // Try to extract a human-readable description from the payload
-                // Format: 16 bytes trace_id + 2 bytes desc_len (BE) + desc_bytes + random
-                let description = extract_description(&event.payload);
+                let fields = fields_from_event(&event);
+                let trace_id = fields.trace_id.clone();
+                let (header, body) = PayloadHeader::parse(&event.payload);
+                let codec = Payload::decode(body).map_or("undecodable".to_string(), |(_, codec)| codec.to_string());
+                let run_id = state.current_run.lock().unwrap().clone();
+
+                // Destination and computation come from the payload header + routing rules
+                let decision = routing::route(&rules, &header);
+                info!(
+                    nonce = event.nonce,
+                    payload_type = header.payload_type,
+                    payload_codec = %codec,
+                    dst_chain_id = decision.dst_chain_id,
+                    computation = %decision.computation,
+                    rule_id = ?decision.rule_id,
+                    "Routed payload"
+                );
+                let envelope = MessageEnvelope::new(
+                    ETHEREUM_CHAIN_ID,
+                    decision.dst_chain_id,
+                    event.nonce,
+                    event.payload.clone(),
+                );
 
                 // Persist to DB
-                db::insert_message(
+                let inserted = db::insert_message(
                     &state.pool,
-                    event.nonce,
+                    &envelope,
                     &trace_id,
-                    &format!("{:?}", event.sender),
-                    &event.amount.to_string(),
-                    &hex::encode(&event.payload),
-                    event.deadline.as_u64() as i64,
-                    description.as_deref(),
+                    &fields.sender,
+                    &fields.amount,
+                    fields.deadline,
+                    fields.description.as_deref(),
+                    run_id.as_deref(),
+                    fields.payload_type,
+                    decision.computation,
+                    None,
                 )
                 .await?;
+                match inserted {
+                    db::Inserted::New => state.cache.invalidate(event.nonce),
+                    db::Inserted::NonceExists => continue,
+                    db::Inserted::DuplicateTrace { existing_nonce } => {
+                        report_duplicate_trace(state, &event, &fields, existing_nonce).await?;
+                        continue;
+                    }
+                }
+                db::insert_raw_log(&state.pool, event.nonce, log).await?;
 
                 // Emit lifecycle events
                 let locked_event = LifecycleEvent::new(
                     &trace_id,
                     event.nonce,
-                    Actor::Ethereum,
+                    Actor::ethereum(),
                     Step::Locked,
                     Status::Success,
                 )
@@ -162,7 +232,7 @@ async fn poll_ethereum(
                 let observed_event = LifecycleEvent::new(
                     &trace_id,
                     event.nonce,
-                    Actor::Relayer,
+                    Actor::relayer(),
                     Step::Observed,
                     Status::Success,
                 )
@@ -173,13 +243,16 @@ async fn poll_ethereum(
                 db::update_message_state(
                     &state.pool,
                     event.nonce,
+                    MessageState::Observed,
                     MessageState::Persisted,
                     None,
                     None,
                     None,
                     None,
+                    "escrow event persisted",
                 )
                 .await?;
+                state.cache.invalidate(event.nonce);
 
                 count += 1;
             }
@@ -193,6 +266,162 @@ async fn poll_ethereum(
     Ok(count)
 }
 
+/// An escrow event reused a stored message's trace id: record it and warn
+/// instead of ingesting it. Only the first sighting of a nonce is reported.
+async fn report_duplicate_trace(
+    state: &Arc<AppState>,
+    event: &eth::CrossChainRequestEvent,
+    fields: &ParsedFields,
+    existing_nonce: u64,
+) -> Result<()> {
+    let tx_hash = format!("{:?}", event.tx_hash);
+    let recorded = db::record_trace_conflict(
+        &state.pool,
+        &fields.trace_id,
+        event.nonce,
+        existing_nonce,
+        &fields.sender,
+        &fields.amount,
+        Some(&tx_hash),
+        Some(event.block_number),
+    )
+    .await?;
+    if !recorded {
+        return Ok(());
+    }
+
+    warn!(
+        nonce = event.nonce,
+        existing_nonce,
+        trace_id = %fields.trace_id,
+        tx = %tx_hash,
+        "Escrow event reuses a stored trace id; not ingested"
+    );
+    let duplicate_event = LifecycleEvent::new(
+        &fields.trace_id,
+        event.nonce,
+        Actor::relayer(),
+        Step::DuplicateTrace,
+        Status::Failure,
+    )
+    .with_detail(format!(
+        "Trace id already used by nonce {}; tx:{} not ingested",
+        existing_nonce, tx_hash
+    ));
+    emit_and_persist(state, &duplicate_event).await
+}
+
+/// Message fields as stored from an escrow event.
+fn fields_from_event(event: &eth::CrossChainRequestEvent) -> ParsedFields {
+    // The description comes from the payload body, in whichever codec it was sent
+    let (header, body) = PayloadHeader::parse(&event.payload);
+    let description = Payload::decode(body)
+        .ok()
+        .map(|(payload, _)| payload.description)
+        .filter(|d| !d.is_empty());
+    ParsedFields {
+        trace_id: format!("{:?}", event.trace_id),
+        sender: format!("{:?}", event.sender),
+        amount: event.amount.to_string(),
+        payload: hex::encode(&event.payload),
+        deadline: event.deadline.as_u64() as i64,
+        description,
+        payload_type: header.payload_type,
+    }
+}
+
+/// Re-run parsing over a stored escrow log with the current event layout,
+/// for POST /transactions/:nonce/reparse. Fails if the log no longer parses
+/// or parses to another nonce.
+pub fn reparse_log(state: &AppState, nonce: u64, log: &ethers::types::Log) -> Result<ParsedFields> {
+    let event = eth::parse_log(log, &state.event_layout)?;
+    if event.nonce != nonce {
+        anyhow::bail!("stored log parses to nonce {}", event.nonce);
+    }
+    Ok(fields_from_event(&event))
+}
+
+/// SIMULATION: create a message for POST /control/inject without an
+/// Ethereum transaction. It starts in Observed like a polled message, minus
+/// the Locked step; no escrow backs it, so settlement is always simulated.
+/// Returns the nonce, allocated from `INJECTED_NONCE_BASE` up.
+pub async fn inject_message(state: &Arc<AppState>, req: &InjectRequest, sender: &str) -> Result<u64> {
+    let trace_bytes: [u8; 32] = rand::random();
+    let trace_id = format!("0x{}", hex::encode(trace_bytes));
+
+    // Same body as the traffic generator's, without the random data
+    let payload = Payload {
+        trace_id: trace_bytes[..16].try_into()?,
+        description: req.description.clone().unwrap_or_default(),
+        data: Vec::new(),
+    }
+    .encode(state.config.traffic_payload_codec);
+
+    let rules = db::get_routing_rules(&state.pool).await?;
+    let decision = routing::route(&rules, &PayloadHeader::default());
+    let run_id = state.current_run.lock().unwrap().clone();
+    let now = chrono::Utc::now().timestamp();
+    let deadline = if req.behavior.expired { now - 1 } else { now + INJECTED_DEADLINE_SECS };
+    let behavior_json = serde_json::to_string(&req.behavior)?;
+
+    // Retry on a nonce taken by a concurrent injection
+    let nonce = loop {
+        let nonce = db::next_nonce_from(&state.pool, INJECTED_NONCE_BASE).await?;
+        let envelope = MessageEnvelope::new(ETHEREUM_CHAIN_ID, decision.dst_chain_id, nonce, payload.clone());
+        let inserted = db::insert_message(
+            &state.pool,
+            &envelope,
+            &trace_id,
+            sender,
+            &req.amount.to_string(),
+            deadline,
+            req.description.as_deref().filter(|d| !d.is_empty()),
+            run_id.as_deref(),
+            0,
+            decision.computation,
+            Some(&behavior_json),
+        )
+        .await?;
+        match inserted {
+            db::Inserted::New => {
+                state.cache.invalidate(nonce);
+                break nonce;
+            }
+            db::Inserted::NonceExists => {}
+            db::Inserted::DuplicateTrace { existing_nonce } => {
+                anyhow::bail!("random trace id {} already used by nonce {}", trace_id, existing_nonce)
+            }
+        }
+    };
+
+    let observed_event = LifecycleEvent::new(
+        &trace_id,
+        nonce,
+        Actor::relayer(),
+        Step::Observed,
+        Status::Success,
+    )
+    .with_detail(format!("injected:{}", behavior_json));
+    emit_and_persist(state, &observed_event).await?;
+
+    db::update_message_state(
+        &state.pool,
+        nonce,
+        MessageState::Observed,
+        MessageState::Persisted,
+        None,
+        None,
+        None,
+        None,
+        "injected",
+    )
+    .await?;
+    state.cache.invalidate(nonce);
+
+    info!(nonce, %trace_id, behavior = %behavior_json, "Injected synthetic message");
+    Ok(nonce)
+}
+
 /// Process all pending messages through the state machine.
 async fn process_pending_messages(state: &Arc<AppState>, cfg: &Config) -> Result<()> {
     // Process each state in order
@@ -209,6 +438,16 @@ async fn process_state(
     current_state: MessageState,
 ) -> Result<()> {
     let messages = db::get_messages_by_state(&state.pool, current_state).await?;
+    if messages.is_empty() {
+        return Ok(());
+    }
+
+    // Chain registry snapshot used to dispatch this batch
+    let chains: HashMap<u32, ChainConfig> = db::get_chains(&state.pool)
+        .await?
+        .into_iter()
+        .map(|c| (c.chain_id, c))
+        .collect();
 
     for msg in messages {
         if state.paused.load(Ordering::Relaxed) {
@@ -217,65 +456,171 @@ async fn process_state(
 
         let nonce = msg.nonce as u64;
         let trace_id = &msg.trace_id;
-
-        if msg.retry_count >= MAX_RETRIES {
-            warn!(nonce, retries = msg.retry_count, "Max retries exceeded, rolling back");
-
-            // Emit rollback event
-            let rollback_event = LifecycleEvent::new(
-                trace_id,
-                nonce,
-                Actor::Relayer,
-                Step::Rollback,
-                Status::Failure,
-            )
-            .with_detail(format!(
-                "Rollback: {} failed after {} retry. Funds will be refunded.",
-                current_state, msg.retry_count
-            ));
-            emit_and_persist(state, &rollback_event).await?;
-
+        let actors = RouteActors::new(&chains, &msg);
+
+        // settle() reverts past the deadline; the sender reclaims the escrow instead
+        let now = chrono::Utc::now().timestamp();
+        if msg.deadline > 0 && now > msg.deadline {
+            warn!(nonce, deadline = msg.deadline, "Escrow deadline passed before settlement");
+            let reason = format!("Expired: deadline {} passed in state {}", msg.deadline, current_state);
+            state.deferrals.clear(nonce);
             db::update_message_state(
                 &state.pool,
                 nonce,
-                MessageState::RolledBack,
+                current_state,
+                MessageState::Failed,
                 None,
                 None,
                 None,
-                Some(&format!("Rolled back from {} after retry failure", current_state)),
+                Some(&reason),
+                &reason,
             )
             .await?;
-
-            let settled_event = LifecycleEvent::new(
+            state.cache.invalidate(nonce);
+            let expired_event = LifecycleEvent::new(
                 trace_id,
                 nonce,
-                Actor::Ethereum,
-                Step::Settled,
+                actors.src.clone(),
+                Step::Expired,
                 Status::Failure,
             )
-            .with_detail("Escrow refunded — rollback complete");
-            emit_and_persist(state, &settled_event).await?;
+            .with_detail(format!(
+                "Deadline passed {}s ago before settlement; escrow is reclaimable by the sender",
+                now - msg.deadline
+            ));
+            emit_and_persist(state, &expired_event).await?;
+            continue;
+        }
 
-            info!(nonce, from_state = %current_state, "Message rolled back, funds refunded");
+        let route = match Route::decide(&chains, msg.src_chain_id, msg.dst_chain_id) {
+            RouteDecision::Deliver(route) => route,
+            RouteDecision::Hold(reason) => {
+                debug!(nonce, %reason, "Route on hold, leaving message pending");
+                continue;
+            }
+            RouteDecision::Reject(reason) => {
+                warn!(nonce, %reason, "Unsupported route, failing message");
+                state.deferrals.clear(nonce);
+                db::update_message_state(
+                    &state.pool,
+                    nonce,
+                    current_state,
+                    MessageState::Failed,
+                    None,
+                    None,
+                    None,
+                    Some(&reason),
+                    &reason,
+                )
+                .await?;
+                state.cache.invalidate(nonce);
+                let failed_event = LifecycleEvent::new(
+                    trace_id,
+                    nonce,
+                    Actor::relayer(),
+                    step_for_state(current_state),
+                    Status::Failure,
+                )
+                .with_detail(reason);
+                emit_and_persist(state, &failed_event).await?;
+                continue;
+            }
+        };
+
+        if state.deferrals.waiting(nonce, current_state) {
+            continue;
+        }
+
+        if msg.retry_count >= MAX_RETRIES {
+            warn!(nonce, retries = msg.retry_count, "Max retries exceeded, rolling back");
+            rollback(
+                state,
+                &msg,
+                current_state,
+                format!(
+                    "Rollback: {} failed after {} retry. Funds will be refunded after the escrow deadline.",
+                    current_state, msg.retry_count
+                ),
+                format!("Rolled back from {} after retry failure", current_state),
+            )
+            .await?;
             continue;
         }
 
-        let result = match current_state {
-            MessageState::Persisted => advance_persisted_to_verified(state, cfg, &msg).await,
-            MessageState::Verified => advance_verified_to_sent(state, cfg, &msg).await,
-            MessageState::SentToSolana => advance_sent_to_executed(state, cfg, &msg).await,
-            MessageState::Executed => advance_executed_to_settled(state, cfg, &msg).await,
+        let result = match (route, current_state) {
+            (Route::EthereumToSolana, MessageState::Persisted) => {
+                advance_persisted_to_verified(state, cfg, &msg).await
+            }
+            (Route::EthereumToSolana, MessageState::Verified) => {
+                advance_verified_to_sent(state, cfg, &msg, &actors).await
+            }
+            (Route::EthereumToSolana, MessageState::SentToSolana) => {
+                advance_sent_to_executed(state, cfg, &msg).await
+            }
+            (Route::EthereumToSolana, MessageState::Executed) => {
+                advance_executed_to_settled(state, cfg, &msg, &actors).await
+            }
             _ => Ok(()),
         };
 
+        if result.is_ok() {
+            state.deferrals.clear(nonce);
+        }
         if let Err(e) = result {
-            warn!(nonce, error = %e, "State transition failed, will retry");
-            db::increment_retry(&state.pool, nonce).await?;
+            // Someone else moved the message; the next pass picks it up where it is
+            if let Some(conflict) = e.downcast_ref::<db::StateConflict>() {
+                warn!(nonce, %conflict, "Message changed state concurrently, skipping");
+                continue;
+            }
+            if let Some(stage) = ChaosStage::for_state(current_state) {
+                state.chaos_stats.record(stage, &e);
+            }
+
+            let failure = ExecutorError::classify(&e);
+            match failure.action() {
+                FailureAction::Rollback => {
+                    warn!(nonce, %route, error = %e, kind = failure.kind(), "Permanent failure, rolling back");
+                    rollback(
+                        state,
+                        &msg,
+                        current_state,
+                        format!(
+                            "Rollback: {} failed permanently ({}). Funds will be refunded after the escrow deadline.",
+                            current_state, e
+                        ),
+                        format!("Rolled back from {}: {}", current_state, failure),
+                    )
+                    .await?;
+                    continue;
+                }
+                FailureAction::Defer => {
+                    // Not the message's fault: back off without spending its budget,
+                    // until it has been deferred too often
+                    let defers = state.deferrals.defer(nonce, current_state);
+                    if defers > MAX_DEFERS {
+                        warn!(nonce, %route, error = %e, kind = failure.kind(), defers, "Deferred too often, spending a retry");
+                        let reason = format!("retry after {} deferrals: {}", defers - 1, failure);
+                        db::increment_retry(&state.pool, nonce, current_state, &reason).await?;
+                        state.cache.invalidate(nonce);
+                    } else {
+                        warn!(nonce, %route, error = %e, kind = failure.kind(), defers, "State transition deferred, will retry");
+                        // One Retry event per deferred state, not one per pass
+                        if defers > 1 {
+                            continue;
+                        }
+                    }
+                }
+                FailureAction::Retry => {
+                    warn!(nonce, %route, error = %e, kind = failure.kind(), "State transition failed, will retry");
+                    db::increment_retry(&state.pool, nonce, current_state, &format!("retry: {}", failure)).await?;
+                    state.cache.invalidate(nonce);
+                }
+            }
 
             let retry_event = LifecycleEvent::new(
                 trace_id,
                 nonce,
-                Actor::Relayer,
+                Actor::relayer(),
                 step_for_state(current_state),
                 Status::Retry,
             )
@@ -286,6 +631,79 @@ async fn process_state(
     Ok(())
 }
 
+/// Registry actors for the chains on a message's route.
+struct RouteActors {
+    src: Actor,
+    dst: Actor,
+}
+
+impl RouteActors {
+    fn new(chains: &HashMap<u32, ChainConfig>, msg: &crate::types::CrossChainMessage) -> Self {
+        let actor = |chain_id: u32| {
+            chains
+                .get(&chain_id)
+                .map(Actor::for_chain)
+                .unwrap_or_else(|| Actor::chain(format!("chain-{}", chain_id), chain_id))
+        };
+        Self {
+            src: actor(msg.src_chain_id),
+            dst: actor(msg.dst_chain_id),
+        }
+    }
+}
+
+/// Roll a message back. Its escrow is refunded later, once the deadline passes.
+async fn rollback(
+    state: &Arc<AppState>,
+    msg: &crate::types::CrossChainMessage,
+    from_state: MessageState,
+    detail: String,
+    reason: String,
+) -> Result<()> {
+    let nonce = msg.nonce as u64;
+    state.deferrals.clear(nonce);
+
+    // Emit rollback event
+    let rollback_event = LifecycleEvent::new(
+        &msg.trace_id,
+        nonce,
+        Actor::relayer(),
+        Step::Rollback,
+        Status::Failure,
+    )
+    .with_detail(detail);
+    emit_and_persist(state, &rollback_event).await?;
+
+    db::update_message_state(
+        &state.pool,
+        nonce,
+        from_state,
+        MessageState::RolledBack,
+        None,
+        None,
+        None,
+        Some(&reason),
+        &reason,
+    )
+    .await?;
+    state.cache.invalidate(nonce);
+
+    // Nothing moves on chain here: the escrow stays locked until the
+    // refunder (or the sender) returns it once its deadline has passed
+    let completed_event = LifecycleEvent::new(
+        &msg.trace_id,
+        nonce,
+        Actor::relayer(),
+        Step::Rollback,
+        Status::Success,
+    )
+    .with_detail("Rollback complete — escrow stays locked until refunded after its deadline");
+    emit_and_persist(state, &completed_event).await?;
+
+    info!(nonce, %from_state, "Message rolled back, escrow awaits refund");
+    Ok(())
+}
+
 /// Persisted → Verified: simulate light-client verification.
 async fn advance_persisted_to_verified(
     state: &Arc<AppState>,
@@ -294,28 +712,94 @@ async fn advance_persisted_to_verified(
 ) -> Result<()> {
     let nonce = msg.nonce as u64;
 
-    // SIMULATION: 10% chance of verification failure
-    if should_simulate_failure() {
+    // SIMULATION: chance (per scenario, default 10%) of verification failure;
+    // injected messages fail only where asked to
+    let forced = forced_failure(msg, ChaosStage::Verify);
+    if forced.unwrap_or_else(|| should_simulate_failure(state)) {
         let is_retry = msg.retry_count > 0;
-        if is_retry && retry_also_fails() {
+        if is_retry && (forced.is_some() || retry_also_fails(state)) {
             warn!(nonce, "Simulated verification failure on RETRY — will rollback");
-            anyhow::bail!("Simulated: light-client verification failed (retry)");
+            return Err(InjectedFailure(ExecutorError::Reverted("light-client verification failed (retry)".into())).into());
         } else if !is_retry {
             warn!(nonce, "Simulated verification failure — will retry");
-            anyhow::bail!("Simulated: light-client verification timeout");
+            return Err(InjectedFailure(ExecutorError::Timeout("light-client verification timeout".into())).into());
         }
     }
 
+    let Some((validator_index, validator_key, validator_address)) = state.validators.select(nonce)
+    else {
+        anyhow::bail!("No active validators available to sign the proof");
+    };
+
+    // SIMULATION: the configured validator occasionally signs a bad event root
+    let misbehaves = cfg.misbehaving_validator == Some(validator_index)
+        && !chaos::faults().suspended()
+        && rand::thread_rng().gen_bool(cfg.validator_misbehavior_rate.clamp(0.0, 1.0));
+
     // Generate and verify proof bundle with real ECDSA signature
-    let proof = verification::generate_proof_bundle(
-        nonce,
-        0, // We don't track block number in the message
-        &msg.trace_id,
-        msg.payload.as_bytes(),
-        &cfg.relayer_private_key,
-    )?;
+    let proof = if misbehaves {
+        verification::generate_misbehaving_proof_bundle(
+            nonce,
+            0,
+            &msg.trace_id,
+            msg.payload.as_bytes(),
+            validator_key,
+        )?
+    } else {
+        verification::generate_proof_bundle(
+            nonce,
+            0, // We don't track block number in the message
+            &msg.trace_id,
+            msg.payload.as_bytes(),
+            validator_key,
+        )?
+    };
+
+    let mismatch = match verification::check_proof(&proof, msg.payload.as_bytes(), &state.validators) {
+        Ok(()) => None,
+        Err(ProofRejection::EventRoot(mismatch)) => Some(mismatch),
+        Err(rejection) => anyhow::bail!(rejection.to_string()),
+    };
+    if let Some(mismatch) = mismatch {
+        let suspended = cfg.validator_auto_suspend && state.validators.suspend(&mismatch.signer);
+        // Auto-suspend never leaves the set without an active validator
+        let kept_as_last = cfg.validator_auto_suspend && !suspended && state.validators.is_active(&mismatch.signer);
+        warn!(
+            nonce,
+            validator = %mismatch.signer,
+            suspended,
+            kept_as_last,
+            "Validator signed an incorrect event root, proof rejected"
+        );
+
+        let event = LifecycleEvent::new(
+            &msg.trace_id,
+            nonce,
+            Actor::relayer(),
+            Step::ValidatorMisbehavior,
+            Status::Failure,
+        )
+        .with_detail(format!(
+            "validator:{} signed event_root:{} expected:{}{}",
+            mismatch.signer,
+            mismatch.signed,
+            mismatch.expected,
+            if suspended {
+                " (suspended)"
+            } else if kept_as_last {
+                " (not suspended: last active validator)"
+            } else {
+                ""
+            }
+        ));
+        emit_and_persist(state, &event).await?;
 
-    verification::verify_proof_bundle(&proof)?;
+        let reason = format!("Proof rejected: event root mismatch from validator {}", validator_address);
+        if misbehaves {
+            return Err(InjectedFailure(ExecutorError::Unknown(reason)).into());
+        }
+        anyhow::bail!(reason);
+    }
 
     // Store the proof bundle so the API returns stable hashes
     let proof_json = serde_json::to_string(&proof)?;
@@ -324,18 +808,21 @@ async fn advance_persisted_to_verified(
     db::update_message_state(
         &state.pool,
         nonce,
+        MessageState::Persisted,
         MessageState::Verified,
         None,
         None,
         None,
         None,
+        "proof verified",
     )
     .await?;
+    state.cache.invalidate(nonce);
 
     let event = LifecycleEvent::new(
         &msg.trace_id,
         nonce,
-        Actor::Relayer,
+        Actor::relayer(),
         Step::Verified,
         Status::Success,
     )
@@ -351,11 +838,17 @@ async fn advance_verified_to_sent(
     state: &Arc<AppState>,
     cfg: &Config,
     msg: &crate::types::CrossChainMessage,
+    actors: &RouteActors,
 ) -> Result<()> {
     let nonce = msg.nonce as u64;
 
     // Parse amount (stored as string in DB)
     let amount: u64 = msg.amount.parse().unwrap_or(0);
+    // Executing with a default would compute something nobody asked for
+    let computation: Computation = msg
+        .computation
+        .parse()
+        .map_err(|e| ExecutorError::Invalid(format!("stored computation: {}", e)))?;
 
     // Parse trace_id into [u8; 32]
     let trace_str = msg.trace_id.trim_start_matches("0x");
@@ -365,58 +858,84 @@ async fn advance_verified_to_sent(
         trace_bytes[..len].copy_from_slice(&bytes[..len]);
     }
 
-    // SIMULATION: 10% chance of Solana execution failure
-    if should_simulate_failure() {
+    // SIMULATION: chance (per scenario, default 10%) of Solana execution failure;
+    // injected messages fail only where asked to
+    let forced = forced_failure(msg, ChaosStage::Execute);
+    if forced.unwrap_or_else(|| should_simulate_failure(state)) {
         let is_retry = msg.retry_count > 0;
-        if is_retry && retry_also_fails() {
+        if is_retry && (forced.is_some() || retry_also_fails(state)) {
             warn!(nonce, "Simulated Solana execution failure on RETRY — will rollback");
-            anyhow::bail!("Simulated: Solana program execution reverted (retry)");
+            return Err(InjectedFailure(ExecutorError::Reverted("Solana program execution reverted (retry)".into())).into());
         } else if !is_retry {
             warn!(nonce, "Simulated Solana execution failure — will retry");
-            anyhow::bail!("Simulated: Solana transaction timeout");
+            return Err(InjectedFailure(ExecutorError::Timeout("Solana transaction timeout".into())).into());
         }
     }
 
-    let (sig, result) = solana_sim::execute_on_solana(nonce, amount, trace_bytes).await?;
+    let mut sender = [0u8; 20];
+    if let Ok(bytes) = hex::decode(msg.sender.trim_start_matches("0x")) {
+        let len = bytes.len().min(20);
+        sender[..len].copy_from_slice(&bytes[..len]);
+    }
+
+    let request = ExecutionRequest {
+        nonce,
+        sender,
+        amount,
+        payload: hex::decode(msg.payload.trim_start_matches("0x")).unwrap_or_default(),
+        trace_id: trace_bytes,
+        computation,
+        priority_fee_micro_lamports: cfg.solana_priority_fee_micro_lamports,
+    };
+    let execution = solana::execute_and_read_back(state.solana.as_ref(), &request).await?;
+    let (sig, confirmation, result) = (execution.signature, execution.confirmation, execution.receipt.result);
 
     db::update_message_state(
         &state.pool,
         nonce,
+        MessageState::Verified,
         MessageState::SentToSolana,
         Some(&result.to_string()),
         Some(&sig),
         None,
         None,
+        "sent to Solana",
     )
     .await?;
+    state.cache.invalidate(nonce);
 
-    let event = LifecycleEvent::new(
+    let mut event = LifecycleEvent::new(
         &msg.trace_id,
         nonce,
-        Actor::Relayer,
+        Actor::relayer(),
         Step::Executed,
         Status::Success,
     )
-    .with_detail(format!("solana_sig:{}, result:{}", sig, result));
+    .with_detail(format!("solana_sig:{}, result:{}, slot:{}", sig, result, confirmation.slot));
+    event.fee_lamports = confirmation.fee_lamports;
+    event.gas_used = confirmation.compute_units;
     emit_and_persist(state, &event).await?;
 
     // Immediately advance to Executed (since we got a response)
     db::update_message_state(
         &state.pool,
         nonce,
+        MessageState::SentToSolana,
         MessageState::Executed,
         None,
         None,
         None,
         None,
+        "Solana execution confirmed",
     )
     .await?;
+    state.cache.invalidate(nonce);
 
     // Emit minted event (simulated bridge receipt)
     let mint_event = LifecycleEvent::new(
         &msg.trace_id,
         nonce,
-        Actor::Solana,
+        actors.dst.clone(),
         Step::Minted,
         Status::Success,
     )
@@ -445,100 +964,141 @@ async fn advance_executed_to_settled(
     state: &Arc<AppState>,
     cfg: &Config,
     msg: &crate::types::CrossChainMessage,
+    actors: &RouteActors,
 ) -> Result<()> {
     let nonce = msg.nonce as u64;
+    ensure_settleable(msg)?;
+
+    // Hold the settlement while fees are above GAS_MAX_FEE_GWEI; it is
+    // picked up again on a later pass
+    if let Some(max_fee_gwei) = crate::gas::above_ceiling(state).await {
+        debug!(nonce, max_fee_gwei, "Fees above ceiling, settlement waits");
+        return Ok(());
+    }
 
     let result_str = msg.result.as_deref().unwrap_or("0");
     let result_value: u64 = result_str.parse().unwrap_or(0);
 
-    // Encode result as uint256 (32 bytes, big-endian)
-    let mut result_bytes = vec![0u8; 32];
-    result_bytes[24..32].copy_from_slice(&result_value.to_be_bytes());
+    let result_bytes = eth::encode_settlement_result(result_value);
 
     // Emit burned event (simulated bridge receipt burn before settlement)
     let burn_event = LifecycleEvent::new(
         &msg.trace_id,
         nonce,
-        Actor::Solana,
+        actors.dst.clone(),
         Step::Burned,
         Status::Success,
     )
     .with_detail("Simulated receipt token burned for settlement");
     emit_and_persist(state, &burn_event).await?;
 
-    // SIMULATION: 10% chance of settlement failure
-    if should_simulate_failure() {
+    // SIMULATION: chance (per scenario, default 10%) of settlement failure;
+    // injected messages fail only where asked to
+    let forced = forced_failure(msg, ChaosStage::Settle);
+    if forced.unwrap_or_else(|| should_simulate_failure(state)) {
         let is_retry = msg.retry_count > 0;
-        if is_retry && retry_also_fails() {
+        if is_retry && (forced.is_some() || retry_also_fails(state)) {
             warn!(nonce, "Simulated settlement failure on RETRY — will rollback");
-            anyhow::bail!("Simulated: Ethereum settlement reverted (retry)");
+            return Err(InjectedFailure(ExecutorError::Reverted("Ethereum settlement reverted (retry)".into())).into());
         } else if !is_retry {
             warn!(nonce, "Simulated settlement failure — will retry");
-            anyhow::bail!("Simulated: Ethereum gas estimation failed");
+            return Err(InjectedFailure(ExecutorError::Unknown("Ethereum gas estimation failed".into())).into());
         }
     }
 
-    // Sign the settlement
-    let signature = eth::sign_settlement(&cfg.relayer_private_key, nonce, &result_bytes)?;
-
-    // Call settle() on Ethereum
-    match eth::call_settle(
-        &cfg.eth_rpc_url,
-        &cfg.relayer_private_key,
-        &cfg.escrow_address,
-        nonce,
-        &result_bytes,
-        &signature,
-    )
-    .await
-    {
-        Ok(tx_hash) => {
-            db::update_message_state(
-                &state.pool,
-                nonce,
-                MessageState::Settled,
-                None,
-                None,
-                Some(&format!("{:?}", tx_hash)),
-                None,
-            )
-            .await?;
-
-            let event = LifecycleEvent::new(
+    // Injected messages have no escrow on Ethereum to settle
+    let settlement = if msg.behavior().is_some() {
+        None
+    } else {
+        // Sign the settlement
+        let signature = eth::sign_settlement(cfg.keyring.eth_settlement(), nonce, &result_bytes)?;
+
+        // Price the settlement from the gas oracle (recorded against the message)
+        let gas = crate::gas::reading_for_settlement(state, nonce).await;
+
+        // Call settle() on Ethereum
+        match eth::call_settle(
+            &cfg.eth_rpc_url,
+            cfg.keyring.eth_settlement(),
+            &cfg.escrow_address,
+            nonce,
+            &result_bytes,
+            &signature,
+            gas.as_ref(),
+        )
+        .await
+        {
+            Ok(settlement) => Some(settlement),
+            // A revert, a rate limit or an empty settlement wallet is not an
+            // unreachable node; leave those to the failure handling
+            Err(e) if ExecutorError::classify(&e).action() != FailureAction::Retry => return Err(e),
+            Err(e) => {
+                warn!(nonce, error = %e, "Settlement failed, simulating success for demo");
+                state.chaos_stats.record_real(ChaosStage::Settle);
+                None
+            }
+        }
+    };
+
+    match settlement {
+        Some(settlement) => {
+            let tx_hash = settlement.tx_hash;
+            let gas = db::SettlementGas {
+                gas_used: settlement.gas_used,
+                gas_price_wei: settlement.effective_gas_price.map(|p| p.to_string()),
+                block_number: settlement.block_number,
+                cost_wei: settlement.fee_wei.map(|f| f.to_string()),
+            };
+            db::record_settlement(&state.pool, nonce, &format!("{:?}", tx_hash), &gas, "escrow settled").await?;
+            state.cache.invalidate(nonce);
+
+            let mut event = LifecycleEvent::new(
                 &msg.trace_id,
                 nonce,
-                Actor::Ethereum,
+                actors.src.clone(),
                 Step::Settled,
                 Status::Success,
             )
             .with_detail(format!("tx:{:?}", tx_hash));
+            event.fee_wei = settlement.fee_wei.map(|f| f.to_string());
+            event.gas_used = settlement.gas_used;
             emit_and_persist(state, &event).await?;
 
             info!(nonce, %tx_hash, "Escrow settled on Ethereum");
         }
-        Err(e) => {
-            warn!(nonce, error = %e, "Settlement failed, simulating success for demo");
+        None => {
             // SIMULATION: In demo mode, if Ethereum is unreachable, simulate settlement
             let fake_tx = format!("0xsim_settle_{}", nonce);
-            db::update_message_state(
-                &state.pool,
-                nonce,
-                MessageState::Settled,
-                None,
-                None,
-                Some(&fake_tx),
-                None,
-            )
-            .await?;
-
-            let event = LifecycleEvent::new(
+            // Priced from the simulated fee market, when enabled and chaos is not suspended
+            let gas_price = match cfg.gas_simulation.filter(|_| !chaos::faults().suspended()) {
+                Some(_) => crate::gas::reading_for_settlement(state, nonce).await.map(|g| g.fees_wei().0),
+                None => None,
+            };
+            let fee_wei = gas_price.map(|p| p * SIMULATED_SETTLE_GAS);
+            let gas = match (gas_price, fee_wei) {
+                (Some(gas_price), Some(fee_wei)) => db::SettlementGas {
+                    gas_used: Some(SIMULATED_SETTLE_GAS),
+                    gas_price_wei: Some(gas_price.to_string()),
+                    block_number: None,
+                    cost_wei: Some(fee_wei.to_string()),
+                },
+                _ => db::SettlementGas::default(),
+            };
+            db::record_settlement(&state.pool, nonce, &fake_tx, &gas, "escrow settlement simulated").await?;
+            state.cache.invalidate(nonce);
+
+            let mut event = LifecycleEvent::new(
                 &msg.trace_id,
                 nonce,
-                Actor::Ethereum,
+                actors.src.clone(),
                 Step::Settled,
                 Status::Success,
             )
             .with_detail(format!("simulated_tx:{}", fake_tx));
+            if let Some(fee_wei) = fee_wei {
+                event.fee_wei = Some(fee_wei.to_string());
+                event.gas_used = Some(SIMULATED_SETTLE_GAS);
+            }
             emit_and_persist(state, &event).await?;
 
             info!(nonce, "Escrow settlement simulated");
@@ -548,43 +1108,35 @@ async fn advance_executed_to_settled(
     Ok(())
 }
 
+/// Refuse to settle a message twice: only an executed message with no
+/// settlement transaction on record may be settled.
+pub fn ensure_settleable(msg: &crate::types::CrossChainMessage) -> Result<()> {
+    if msg.state != MessageState::Executed.to_string() {
+        anyhow::bail!("message {} is {}, not executed", msg.nonce, msg.state);
+    }
+    if let Some(tx) = &msg.eth_settle_tx {
+        anyhow::bail!("message {} already has settlement {}", msg.nonce, tx);
+    }
+    Ok(())
+}
+
 /// Helper: emit event to broadcast channel and persist to DB.
-async fn emit_and_persist(
+pub async fn emit_and_persist(
     state: &Arc<AppState>,
     event: &LifecycleEvent,
 ) -> Result<()> {
-    // Persist to DB
-    db::insert_event(
-        &state.pool,
-        event.nonce,
-        &event.trace_id,
-        &format!("{:?}", event.actor).to_lowercase(),
-        &format!("{:?}", event.step).to_lowercase(),
-        &format!("{:?}", event.status).to_lowercase(),
-        event.detail.as_deref(),
-        &event.timestamp,
-    )
-    .await?;
+    // Batched: the event writer persists and broadcasts it
+    if state.config.event_write_interval_ms > 0 && state.event_writer.write(event.clone()) {
+        return Ok(());
+    }
 
-    // Broadcast to WebSocket subscribers (ignore if no receivers)
-    let _ = state.event_tx.send(event.clone());
+    // Persist to DB; the row id is the event's global sequence number
+    let persisted = db::insert_event(&state.pool, event).await?;
+    event_writer::publish(state, event.clone(), persisted);
 
     Ok(())
 }
 
-/// Extract a human-readable description from the payload if present.
-/// Payload format: 16 bytes trace_id + 2 bytes desc_len (BE) + desc_bytes + random
-fn extract_description(payload: &[u8]) -> Option<String> {
-    if payload.len() < 18 {
-        return None; // Not enough bytes for trace_id + length
-    }
-    let desc_len = u16::from_be_bytes([payload[16], payload[17]]) as usize;
-    if desc_len == 0 || payload.len() < 18 + desc_len {
-        return None;
-    }
-    std::str::from_utf8(&payload[18..18 + desc_len]).ok().map(String::from)
-}
-
 fn step_for_state(state: MessageState) -> Step {
     match state {
         MessageState::Observed | MessageState::Persisted => Step::Observed,
@@ -594,5 +1146,6 @@ fn step_for_state(state: MessageState) -> Step {
         MessageState::Settled => Step::Settled,
         MessageState::Failed => Step::Settled,
         MessageState::RolledBack => Step::Rollback,
+        MessageState::Refunded => Step::Refunded,
     }
 }
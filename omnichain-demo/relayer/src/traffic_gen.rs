@@ -1,6 +1,7 @@
 use anyhow::Result;
 use ethers::prelude::*;
 use ethers::signers::LocalWallet;
+use payload::{Codec, Payload};
 use rand::seq::SliceRandom;
 use rand::Rng;
 use std::str::FromStr;
@@ -10,8 +11,13 @@ use tokio::time::{sleep, Duration};
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
+use crate::chaos::ScenarioSettings;
+use crate::db;
 use crate::types::AppState;
 
+/// Maximum slow-down factor applied just below the pause threshold.
+const MAX_SLOWDOWN: u32 = 4;
+
 // Human-readable names for simulated users (mapped to Anvil accounts 1-9)
 const USER_NAMES: &[&str] = &[
     "Alice", "Bob", "Charlie", "Diana", "Eve", "Frank", "Grace", "Hank", "Ivy",
@@ -79,21 +85,95 @@ pub async fn run_traffic_generator(state: Arc<AppState>, rpc_url: String, escrow
                 info!("Simulation deadline reached, auto-stopping");
                 state.simulation_running.store(false, Ordering::Relaxed);
                 state.paused.store(true, Ordering::Relaxed);
+                end_run(&state).await;
                 continue;
             }
         }
 
+        // Back off while the relayer is behind
+        let threshold = state.config.traffic_backlog_threshold;
+        let pending = match db::count_pending(&state.pool).await {
+            Ok(n) => n,
+            Err(e) => {
+                warn!(error = %e, "Traffic generator: failed to read backlog");
+                0
+            }
+        };
+        let base = Duration::from_millis(state.scenario.lock().unwrap().tx_interval_ms);
+        let Some(interval) = throttled_interval(base, pending, threshold) else {
+            if !state.traffic_throttled.swap(true, Ordering::Relaxed) {
+                warn!(pending, threshold, "Relayer backlog over threshold, pausing traffic");
+            }
+            sleep(Duration::from_secs(1)).await;
+            continue;
+        };
+        if state.traffic_throttled.swap(false, Ordering::Relaxed) {
+            info!(pending, threshold, "Relayer backlog drained, resuming traffic");
+        }
+
         // Generate one transaction
-        if let Err(e) = send_one_transaction(&rpc_url, &escrow_address).await {
+        if let Err(e) = send_one_transaction(&rpc_url, &escrow_address, state.config.traffic_payload_codec).await {
             warn!(error = %e, "Traffic generator: failed to send transaction");
         }
 
-        // 1 transaction every 5 seconds
-        sleep(Duration::from_secs(5)).await;
+        sleep(interval).await;
+    }
+}
+
+/// Open a run record for a new simulation session, closing any still-active run.
+/// Messages observed from now on are tagged with the returned run id.
+pub async fn begin_run(
+    state: &AppState,
+    settings: ScenarioSettings,
+    duration_minutes: u64,
+) -> Result<String> {
+    end_run(state).await;
+
+    let run_id = Uuid::new_v4().to_string();
+    let mut parameters = serde_json::to_value(settings)?;
+    parameters["duration_minutes"] = duration_minutes.into();
+    db::insert_run(&state.pool, &run_id, &settings.scenario.to_string(), &parameters).await?;
+
+    *state.current_run.lock().unwrap() = Some(run_id.clone());
+    info!(%run_id, scenario = ?settings.scenario, "Simulation run started");
+    Ok(run_id)
+}
+
+/// Close the active run record, if any.
+pub async fn end_run(state: &AppState) {
+    let Some(run_id) = state.current_run.lock().unwrap().take() else {
+        return;
+    };
+    match db::end_run(&state.pool, &run_id).await {
+        Ok(()) => info!(%run_id, "Simulation run ended"),
+        Err(e) => warn!(%run_id, error = %e, "Failed to close simulation run"),
+    }
+}
+
+/// Pacing for the next transaction given the current backlog.
+///
+/// Below half the threshold traffic runs at the scenario's base rate; between
+/// half and the threshold the interval stretches linearly up to `MAX_SLOWDOWN`×;
+/// at or above the threshold returns `None` (pause). A threshold of 0 disables this.
+fn throttled_interval(base: Duration, pending: i64, threshold: i64) -> Option<Duration> {
+    if threshold <= 0 {
+        return Some(base);
     }
+    if pending >= threshold {
+        return None;
+    }
+
+    let slow_start = threshold / 2;
+    if pending <= slow_start {
+        return Some(base);
+    }
+
+    let span = (threshold - slow_start).max(1) as f64;
+    let factor = 1.0 + (pending - slow_start) as f64 / span * (MAX_SLOWDOWN - 1) as f64;
+    Some(base.mul_f64(factor))
 }
 
-async fn send_one_transaction(rpc_url: &str, escrow_address: &str) -> Result<()> {
+async fn send_one_transaction(rpc_url: &str, escrow_address: &str, codec: Codec) -> Result<()> {
     // Generate all random values upfront so rng doesn't live across await points
     let (wallet_idx, description, trace_id, amount, payload) = {
         let mut rng = rand::thread_rng();
@@ -104,10 +184,11 @@ async fn send_one_transaction(rpc_url: &str, escrow_address: &str) -> Result<()>
         let description = format!("{}'s payment to {} for {}", user_name, recipient_name, action);
         let trace_id = Uuid::new_v4();
         let amount: u64 = rng.gen_range(100_000..=1_000_000);
-        let payload = generate_payload(&mut rng, &trace_id, &description);
+        let payload = generate_payload(&mut rng, &trace_id, &description, codec);
         (wallet_idx, description, trace_id, amount, payload)
     };
 
+    crate::eth::fault_hook().await?;
     let provider = Provider::<Http>::try_from(rpc_url)?;
     let chain_id = provider.get_chainid().await?.as_u64();
 
@@ -155,15 +236,13 @@ async fn send_one_transaction(rpc_url: &str, escrow_address: &str) -> Result<()>
     Ok(())
 }
 
-fn generate_payload(rng: &mut impl Rng, trace_id: &Uuid, description: &str) -> Vec<u8> {
-    let mut payload = Vec::new();
-    payload.extend_from_slice(trace_id.as_bytes());
-    let desc_bytes = description.as_bytes();
-    payload.extend_from_slice(&(desc_bytes.len() as u16).to_be_bytes());
-    payload.extend_from_slice(desc_bytes);
-    let extra_len = rng.gen_range(4..=16);
-    let mut extra = vec![0u8; extra_len];
-    rng.fill(&mut extra[..]);
-    payload.extend_from_slice(&extra);
-    payload
+fn generate_payload(rng: &mut impl Rng, trace_id: &Uuid, description: &str, codec: Codec) -> Vec<u8> {
+    let mut data = vec![0u8; rng.gen_range(4..=16)];
+    rng.fill(&mut data[..]);
+    Payload {
+        trace_id: *trace_id.as_bytes(),
+        description: description.to_string(),
+        data,
+    }
+    .encode(codec)
 }
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use sqlx::SqlitePool;
-use std::sync::atomic::{AtomicBool, AtomicI64};
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering};
 use tokio::sync::broadcast;
 
 use crate::event::LifecycleEvent;
@@ -14,8 +14,71 @@ pub struct AppState {
     pub simulation_running: AtomicBool,
     /// Unix timestamp (seconds) when the simulation should auto-stop (0 = no deadline)
     pub simulation_deadline: AtomicI64,
+    /// Whether the traffic generator is currently held back by the relayer backlog
+    pub traffic_throttled: AtomicBool,
     /// Configuration snapshot for health checks
     pub config: crate::config::Config,
+    /// Keys allowed to sign proof bundles, with suspension state
+    pub validators: crate::verification::ValidatorSet,
+    /// Failure counters per stage, split into injected vs real
+    pub chaos_stats: crate::chaos::ChaosStats,
+    /// Traffic pacing and failure injection for the active demo scenario
+    pub scenario: std::sync::Mutex<crate::chaos::ScenarioSettings>,
+    /// Id of the active simulation run; new messages are tagged with it
+    pub current_run: std::sync::Mutex<Option<String>>,
+    /// Bumped on every replay start/stop; a playing replay exits once it changes
+    pub replay_generation: AtomicU64,
+    /// Escrow event ABI used to filter and decode logs
+    pub event_layout: crate::eth::EventLayout,
+    /// Latest gas reading (sampler or settlement)
+    pub gas: std::sync::Mutex<Option<crate::gas::GasReading>>,
+    /// Fee market process behind `GAS_SIMULATION`
+    pub fee_simulator: crate::gas::FeeSimulator,
+    /// Destination executor selected by `SOLANA_MODE`
+    pub solana: std::sync::Arc<dyn crate::solana::SolanaExecutor>,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    /// Last Ethereum block the processor has scanned for escrow events
+    pub last_processed_block: AtomicU64,
+    /// Result of the startup integrity check; None until it has run
+    pub integrity: std::sync::Mutex<Option<crate::integrity::IntegrityReport>>,
+    /// Newest messages and their events, for hot reads
+    pub cache: crate::cache::RecentCache,
+    /// POST /control/benchmark or --bench in progress
+    pub benchmark_running: AtomicBool,
+    /// Latest escrow balance snapshot
+    pub escrow: std::sync::Mutex<Option<crate::escrow::EscrowReading>>,
+    /// Open WebSocket connections, for GET /ws/stats
+    pub ws_connections: crate::ws_stats::WsConnections,
+    /// POST /control/export or a scheduled export in progress
+    pub export_running: AtomicBool,
+    /// Queue of the batched event writer
+    pub event_writer: crate::event_writer::EventWriter,
+    /// Concurrency, OpenAI rate limiting and queued jobs of AI analyses
+    pub analysis_queue: crate::analysis_queue::AnalysisQueue,
+    /// Mints and checks `/ws` tokens
+    pub ws_tokens: crate::ws_auth::WsTokenIssuer,
+    /// POST /control/notarize or a scheduled notarization in progress
+    pub notary_running: AtomicBool,
+    /// Messages backing off after a deferred failure
+    pub deferrals: crate::executor_error::Deferrals,
+}
+
+/// Holds one of the `*_running` flags of `AppState` while a task runs, and
+/// clears it when dropped. A task cancelled midway, such as the handler of
+/// a request whose client disconnected, therefore never leaves it set.
+pub struct RunningGuard<'a>(&'a AtomicBool);
+
+impl<'a> RunningGuard<'a> {
+    /// Set `flag`; None if it was already set.
+    pub fn acquire(flag: &'a AtomicBool) -> Option<Self> {
+        (!flag.swap(true, Ordering::SeqCst)).then_some(Self(flag))
+    }
+}
+
+impl Drop for RunningGuard<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
 }
 
 /// Relayer state machine states for a cross-chain message.
@@ -30,6 +93,8 @@ pub enum MessageState {
     Settled,
     Failed,
     RolledBack,
+    /// Expired escrow returned to the sender by the relayer's `refund()` call
+    Refunded,
 }
 
 impl std::fmt::Display for MessageState {
@@ -43,6 +108,7 @@ impl std::fmt::Display for MessageState {
             Self::Settled => write!(f, "settled"),
             Self::Failed => write!(f, "failed"),
             Self::RolledBack => write!(f, "rolled_back"),
+            Self::Refunded => write!(f, "refunded"),
         }
     }
 }
@@ -59,6 +125,7 @@ impl MessageState {
             "settled" => Self::Settled,
             "failed" => Self::Failed,
             "rolled_back" => Self::RolledBack,
+            "refunded" => Self::Refunded,
             _ => Self::Failed,
         }
     }
@@ -79,13 +146,45 @@ pub struct CrossChainMessage {
     pub result: Option<String>,
     pub solana_signature: Option<String>,
     pub eth_settle_tx: Option<String>,
+    /// refund() transaction of an expired escrow
+    pub refund_tx: Option<String>,
     pub proof_json: Option<String>,
     pub retry_count: i32,
     pub error_message: Option<String>,
+    /// Simulation run the message was observed during (None outside a run)
+    pub run_id: Option<String>,
+    /// Envelope source chain (see `envelope::ETHEREUM_CHAIN_ID` etc.)
+    pub src_chain_id: u32,
+    /// Envelope destination chain
+    pub dst_chain_id: u32,
+    /// Payload type from the routing header (0 when absent)
+    pub payload_type: u8,
+    /// Computation the destination executor performs (see `routing::Computation`)
+    pub computation: String,
+    /// `InjectBehavior` of a message created by POST /control/inject; None
+    /// for messages observed on Ethereum
+    pub behavior_json: Option<String>,
+    /// Gas used by the settlement transaction, from its receipt
+    pub settle_gas_used: Option<i64>,
+    /// Effective gas price of the settlement, in wei (decimal string)
+    pub settle_gas_price_wei: Option<String>,
+    /// Block the settlement was included in
+    pub settle_block_number: Option<i64>,
+    /// Total settlement cost, `settle_gas_used × settle_gas_price_wei`
+    pub settle_cost_wei: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
 
+impl CrossChainMessage {
+    /// Behavior flags if the message was injected rather than observed.
+    pub fn behavior(&self) -> Option<InjectBehavior> {
+        self.behavior_json
+            .as_deref()
+            .map(|json| serde_json::from_str(json).unwrap_or_default())
+    }
+}
+
 /// Simulated proof bundle for light-client verification.
 /// SIMULATION: These fields are structurally correct but contain fabricated data.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -96,9 +195,68 @@ pub struct ProofBundle {
     pub validator_signature: String,
     pub relayer_address: String,
     pub nonce: u64,
+    #[serde(default)]
     pub verified: bool,
 }
 
+/// Request body for POST /verify: a proof bundle plus, optionally, the
+/// event data it claims to cover (the message payload as returned by the API).
+#[derive(Debug, Deserialize)]
+pub struct VerifyRequest {
+    #[serde(flatten)]
+    pub proof: ProofBundle,
+    pub event_data: Option<String>,
+}
+
+/// Detailed outcome of auditing a proof bundle.
+#[derive(Debug, Serialize)]
+pub struct VerificationReport {
+    /// True only if every check that was run passed
+    pub valid: bool,
+    pub nonce: u64,
+    pub structural: Vec<VerificationCheck>,
+    pub recovered_signer: Option<String>,
+    pub claimed_signer: String,
+    pub signer_matches_claim: bool,
+    pub signature_error: Option<String>,
+    pub quorum: QuorumStatus,
+    pub merkle: MerkleCheck,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VerificationCheck {
+    pub name: String,
+    pub passed: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QuorumStatus {
+    pub required: usize,
+    pub valid_signatures: usize,
+    /// Recovered signer is in this relayer's validator set
+    pub known_validator: bool,
+    /// ... and is not suspended
+    pub active_validator: bool,
+    pub met: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MerkleCheck {
+    /// False when no event data was supplied
+    pub checked: bool,
+    pub event_root_matches: Option<bool>,
+    pub inclusion_proof_matches: Option<bool>,
+    pub expected_event_root: Option<String>,
+}
+
+/// Response for GET /chaos/stats
+#[derive(Debug, Serialize)]
+pub struct ChaosStatsResponse {
+    pub stages: Vec<crate::chaos::StageFailureStats>,
+    pub total_injected: u64,
+    pub total_real: u64,
+}
+
 /// API response types
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TransactionListResponse {
@@ -111,6 +269,298 @@ pub struct TransactionDetailResponse {
     pub transaction: CrossChainMessage,
     pub events: Vec<LifecycleEvent>,
     pub proof: Option<ProofBundle>,
+    /// Every state change, oldest first
+    pub transitions: Vec<StateTransition>,
+}
+
+/// One change of `messages.state` (`state_transitions` table).
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct StateTransition {
+    pub id: i64,
+    pub nonce: i64,
+    /// None for the initial `observed` state
+    pub from_state: Option<String>,
+    pub to_state: String,
+    pub reason: String,
+    /// False for an update that lost a race: the message had already left
+    /// the state it expected, so `to_state` was never reached
+    pub applied: bool,
+    pub at: String,
+}
+
+/// Response for GET /relayer/status: everything the ops header bar shows, in one call.
+#[derive(Debug, Serialize)]
+pub struct RelayerStatus {
+    pub started_at: String,
+    pub uptime_secs: i64,
+    pub ethereum: EthereumStatus,
+    pub solana: SolanaStatus,
+    /// Active signing key per purpose
+    pub keys: Vec<crate::keyring::SignerKey>,
+    /// Message count per state
+    pub backlog: std::collections::BTreeMap<String, i64>,
+    pub circuit_breakers: Vec<CircuitBreaker>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EthereumStatus {
+    /// Relayer (settlement signer) address
+    pub address: String,
+    /// Relayer balance in wei (as string to avoid overflow)
+    pub balance_wei: Option<String>,
+    pub block_height: Option<u64>,
+    pub last_processed_block: u64,
+    pub blocks_behind: Option<u64>,
+    /// Set when the RPC could not be reached
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SolanaStatus {
+    pub mode: crate::solana::SolanaMode,
+    #[serde(flatten)]
+    pub executor: crate::solana::ExecutorStatus,
+    /// Set when the cluster could not be reached
+    pub error: Option<String>,
+}
+
+/// Execution count and amount sum, as the program or the relayer sees them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ExecutionCounters {
+    pub executions: u64,
+    /// Decimal string: the sum can exceed what JSON numbers hold exactly
+    pub total_amount: String,
+}
+
+/// GET /solana/stats: the program's stats PDA next to the relayer's records.
+#[derive(Debug, Serialize)]
+pub struct SolanaStatsReport {
+    pub mode: crate::solana::SolanaMode,
+    /// `None` until the program has executed anything
+    pub on_chain: Option<ExecutionCounters>,
+    pub last_execution_slot: Option<u64>,
+    /// Messages with a Solana signature in the database
+    pub relayer: ExecutionCounters,
+    /// Relayer executions whose stored amount does not parse as a u64;
+    /// they count as executions but add nothing to `relayer.total_amount`
+    pub unparsable_amounts: u64,
+    /// Both counters agree and every relayer amount parsed
+    pub in_sync: bool,
+}
+
+/// Query parameters for GET /stats/daily
+#[derive(Debug, Deserialize)]
+pub struct DailyStatsQuery {
+    /// Days back from today, including today
+    #[serde(default = "default_daily_stats_days")]
+    pub days: i64,
+}
+
+fn default_daily_stats_days() -> i64 {
+    30
+}
+
+/// Totals of the messages observed on one UTC day. Amounts are wei, as
+/// decimal strings.
+#[derive(Debug, Clone, Serialize)]
+pub struct DailyStats {
+    pub day: String,
+    pub messages: u64,
+    pub locked_amount: String,
+    pub settled: u64,
+    pub settled_amount: String,
+    /// Rolled back or refunded after expiring: the escrow went back to the sender
+    pub refunded_amount: String,
+    /// Messages that were rolled back, including ones since refunded
+    pub rollbacks: u64,
+    /// First to last event of settled messages
+    pub avg_e2e_secs: Option<f64>,
+}
+
+/// GET /stats/daily, oldest day first. Days without messages are left out.
+#[derive(Debug, Serialize)]
+pub struct DailyStatsResponse {
+    pub days: i64,
+    pub daily: Vec<DailyStats>,
+}
+
+/// A switch that stops part of the pipeline. `open` means traffic is blocked.
+#[derive(Debug, Serialize)]
+pub struct CircuitBreaker {
+    /// `processor`, `traffic_backlog`, `validator:<address>` or `chain:<id>`
+    pub name: String,
+    pub open: bool,
+    pub detail: Option<String>,
+}
+
+/// Query for DELETE /transactions/:nonce
+#[derive(Debug, Deserialize)]
+pub struct PurgeQuery {
+    /// Recorded in the audit log
+    pub reason: Option<String>,
+}
+
+/// Response for DELETE /transactions/:nonce: what was removed.
+#[derive(Debug, Serialize)]
+pub struct PurgeSummary {
+    pub nonce: u64,
+    pub trace_id: String,
+    pub events: u64,
+    /// Stored incident analyses (embeddings)
+    pub analyses: u64,
+    pub gas_readings: u64,
+    pub raw_logs: u64,
+    pub state_transitions: u64,
+    /// Trace id collisions the message was either side of
+    pub trace_conflicts: u64,
+    /// Id of the `audit_log` entry recording the purge
+    pub audit_id: i64,
+}
+
+/// An `audit_log` entry: an admin action against one target.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEntry {
+    pub id: i64,
+    /// `reparse_transaction` or `purge_transaction`
+    pub action: String,
+    /// Nonce the action applied to
+    pub target: String,
+    pub detail: serde_json::Value,
+    pub created_at: String,
+}
+
+/// A message whose trace id was already taken, for GET /duplicates.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct TraceConflict {
+    pub id: i64,
+    pub trace_id: String,
+    /// Nonce of the message reusing the trace id
+    pub nonce: i64,
+    /// Nonce of the message that holds it
+    pub existing_nonce: i64,
+    pub sender: String,
+    pub amount: String,
+    /// Escrow transaction of the reusing message, when it came from a log
+    pub tx_hash: Option<String>,
+    pub block_number: Option<i64>,
+    /// False if both messages were stored before trace ids were unique
+    pub rejected: bool,
+    pub detected_at: String,
+}
+
+/// Query parameters for GET /duplicates
+#[derive(Debug, Deserialize)]
+pub struct DuplicatesQuery {
+    /// Maximum number of conflicts (default 100, capped at 1000)
+    pub limit: Option<i64>,
+}
+
+/// Message fields derived from an escrow log; what
+/// POST /transactions/:nonce/reparse compares and rewrites.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ParsedFields {
+    pub trace_id: String,
+    pub sender: String,
+    pub amount: String,
+    /// Hex, as stored in `messages.payload`
+    pub payload: String,
+    pub deadline: i64,
+    pub description: Option<String>,
+    pub payload_type: u8,
+}
+
+impl ParsedFields {
+    pub fn of(msg: &CrossChainMessage) -> Self {
+        Self {
+            trace_id: msg.trace_id.clone(),
+            sender: msg.sender.clone(),
+            amount: msg.amount.clone(),
+            payload: msg.payload.clone(),
+            deadline: msg.deadline,
+            description: msg.description.clone(),
+            payload_type: msg.payload_type,
+        }
+    }
+
+    /// Fields that differ in `other`.
+    pub fn changes_to(&self, other: &Self) -> Vec<FieldChange> {
+        let (before, after) = (serde_json::json!(self), serde_json::json!(other));
+        before
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter(|(field, value)| after[field.as_str()] != **value)
+            .map(|(field, value)| FieldChange {
+                field: field.clone(),
+                before: value.clone(),
+                after: after[field.as_str()].clone(),
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: serde_json::Value,
+    pub after: serde_json::Value,
+}
+
+/// Query for POST /transactions/:nonce/reparse
+#[derive(Debug, Deserialize)]
+pub struct ReparseQuery {
+    /// Report the changes without applying them
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Response for POST /transactions/:nonce/reparse.
+#[derive(Debug, Serialize)]
+pub struct ReparseResponse {
+    pub nonce: u64,
+    pub dry_run: bool,
+    pub changes: Vec<FieldChange>,
+    /// Id of the `audit_log` entry; None for a dry run or when nothing changed
+    pub audit_id: Option<i64>,
+    /// The message after the reparse
+    pub message: CrossChainMessage,
+}
+
+/// Request for POST /control/inject: a message that enters the pipeline
+/// without an Ethereum transaction.
+#[derive(Debug, Deserialize)]
+pub struct InjectRequest {
+    /// Ethereum sender address (default: the zero address)
+    pub sender: Option<String>,
+    pub amount: u64,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub behavior: InjectBehavior,
+}
+
+/// SIMULATION: edge cases an injected message forces. A forced failure
+/// fails the stage on every attempt, so the message is retried and then
+/// rolled back. Injected messages are exempt from the scenario's random
+/// failures.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InjectBehavior {
+    pub fail_verification: bool,
+    pub fail_execution: bool,
+    pub fail_settlement: bool,
+    /// Created with its escrow deadline already passed
+    pub expired: bool,
+}
+
+impl InjectBehavior {
+    pub fn fails_at(&self, stage: crate::chaos::ChaosStage) -> bool {
+        use crate::chaos::ChaosStage;
+        match stage {
+            ChaosStage::Verify => self.fail_verification,
+            ChaosStage::Execute => self.fail_execution,
+            ChaosStage::Settle => self.fail_settlement,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -127,6 +577,9 @@ pub struct SimulationRequest {
     /// Duration in minutes (default 60 = 1 hour)
     #[serde(default = "default_duration_minutes")]
     pub duration_minutes: u64,
+    /// Traffic + chaos preset (default `steady`)
+    #[serde(default)]
+    pub scenario: crate::chaos::Scenario,
 }
 
 fn default_duration_minutes() -> u64 {
@@ -137,6 +590,344 @@ fn default_duration_minutes() -> u64 {
 pub struct SimulationStatus {
     pub running: bool,
     pub remaining_seconds: i64,
+    pub backlog_throttled: bool,
+    pub scenario: crate::chaos::ScenarioSettings,
+    pub run_id: Option<String>,
+}
+
+/// Execution environment of a registered chain; selects the adapter used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChainKind {
+    Evm,
+    Solana,
+    Cosmos,
+}
+
+impl std::fmt::Display for ChainKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Evm => write!(f, "evm"),
+            Self::Solana => write!(f, "solana"),
+            Self::Cosmos => write!(f, "cosmos"),
+        }
+    }
+}
+
+/// A chain in the destination registry (`chains` table).
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainConfig {
+    pub chain_id: u32,
+    pub name: String,
+    pub kind: ChainKind,
+    pub rpc_url: Option<String>,
+    /// Adapter-specific executor settings (opaque JSON)
+    pub executor: serde_json::Value,
+    /// Disabled chains keep their messages pending until re-enabled
+    pub enabled: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Request body for POST /chains and PUT /chains/:id
+#[derive(Debug, Deserialize)]
+pub struct ChainUpsertRequest {
+    /// Required for POST; ignored for PUT (the path id wins)
+    pub chain_id: Option<u32>,
+    pub name: String,
+    pub kind: ChainKind,
+    pub rpc_url: Option<String>,
+    #[serde(default)]
+    pub executor: serde_json::Value,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// A simulation session: one start → stop (or deadline) of the traffic generator.
+#[derive(Debug, Clone, Serialize)]
+pub struct SimulationRun {
+    pub run_id: String,
+    pub scenario: String,
+    /// Resolved scenario settings and duration the run was started with
+    pub parameters: serde_json::Value,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+    pub message_count: i64,
+}
+
+/// Request body for POST /runs/:id/replay
+#[derive(Debug, Deserialize)]
+pub struct ReplayRequest {
+    /// Playback multiplier: 30 plays a 1-hour run in 2 minutes (default 1)
+    #[serde(default = "default_replay_speed")]
+    pub speed: f64,
+}
+
+impl Default for ReplayRequest {
+    fn default() -> Self {
+        Self {
+            speed: default_replay_speed(),
+        }
+    }
+}
+
+fn default_replay_speed() -> f64 {
+    1.0
+}
+
+/// Response for POST /runs/:id/replay
+#[derive(Debug, Serialize)]
+pub struct ReplaySummary {
+    pub run_id: String,
+    pub speed: f64,
+    pub events: usize,
+    pub original_duration_secs: f64,
+    pub playback_duration_secs: f64,
+}
+
+/// Response for GET /runs/:id/metrics
+#[derive(Debug, Serialize)]
+pub struct RunMetricsResponse {
+    pub run: SimulationRun,
+    pub metrics: MetricsResponse,
+}
+
+/// Query parameters for POST /analyze/:nonce
+#[derive(Debug, Deserialize)]
+pub struct AnalyzeQuery {
+    #[serde(default)]
+    pub format: AnalysisFormat,
+    /// Return 202 with a job to poll at GET /analyze/jobs/:id instead of waiting
+    #[serde(default)]
+    pub queued: bool,
+}
+
+/// Output format requested from the LLM.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnalysisFormat {
+    /// Human-readable markdown report (original behaviour)
+    #[default]
+    Markdown,
+    /// Validated `StructuredAnalysis` only
+    Json,
+    /// Both, generated concurrently
+    Both,
+}
+
+/// Machine-readable analysis result, validated server-side before it is returned.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StructuredAnalysis {
+    pub final_status: FinalStatus,
+    /// 0-100
+    pub confidence: u8,
+    pub badges: Vec<String>,
+    pub alerts: Vec<AnalysisAlert>,
+    pub metrics: AnalysisMetrics,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FinalStatus {
+    SettledSuccess,
+    SettledFailureRefunded,
+    Pending,
+    InconsistentState,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisAlert {
+    pub level: AlertLevel,
+    pub message: String,
+    pub trigger: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertLevel {
+    Info,
+    Warning,
+    Error,
+}
+
+/// Timing metrics in seconds; `None` when they cannot be derived from the timeline.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnalysisMetrics {
+    pub time_to_observe_secs: Option<f64>,
+    pub time_to_verify_secs: Option<f64>,
+    pub time_to_execute_secs: Option<f64>,
+    pub time_to_settle_secs: Option<f64>,
+    pub end_to_end_secs: Option<f64>,
+    pub retries: i64,
+}
+
+/// `?since_seq=` filter accepted wherever lifecycle events are served
+/// (GET /events, GET /transactions/:nonce, /ws).
+#[derive(Debug, Default, Deserialize)]
+pub struct EventsQuery {
+    /// Only return events with a sequence number greater than this
+    pub since_seq: Option<u64>,
+    /// Maximum number of events (GET /events only, default 500, capped at 5000)
+    pub limit: Option<i64>,
+}
+
+/// Query for the `/ws` upgrade
+#[derive(Debug, Deserialize)]
+pub struct WsConnectQuery {
+    /// Resume after this sequence number (see `EventsQuery`)
+    pub since_seq: Option<u64>,
+    /// Token from POST /ws/token
+    pub token: Option<String>,
+}
+
+/// Message a `/ws` client sends to the relayer.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WsClientMessage {
+    /// Coalesce live events arriving within `batch_ms` into one JSON array
+    /// frame; 0 (the default) sends each event as its own frame
+    Subscribe {
+        #[serde(default)]
+        batch_ms: u64,
+    },
+}
+
+/// Control frame the relayer sends a `/ws` client between events.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WsServerMessage {
+    /// The resume backfill stopped at its limit after `last_seq`, and live
+    /// events follow. The events in between are read from
+    /// GET /events?since_seq=<last_seq>.
+    Truncated { last_seq: u64 },
+}
+
+/// Query parameters for GET /transactions/:nonce/similar
+#[derive(Debug, Deserialize)]
+pub struct SimilarQuery {
+    #[serde(default = "default_similar_limit")]
+    pub limit: usize,
+}
+
+fn default_similar_limit() -> usize {
+    5
+}
+
+/// A previously analyzed transaction whose event summary resembles the query.
+#[derive(Debug, Serialize)]
+pub struct SimilarIncident {
+    pub nonce: i64,
+    /// Cosine similarity in [-1, 1]
+    pub similarity: f32,
+    pub state: Option<String>,
+    pub description: Option<String>,
+    pub summary: String,
+    pub indexed_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SimilarIncidentsResponse {
+    pub nonce: u64,
+    pub summary: String,
+    pub similar: Vec<SimilarIncident>,
+}
+
+/// Query parameters for GET /analyze/costs
+#[derive(Debug, Deserialize)]
+pub struct AnalysisCostQuery {
+    /// Look-back window in days (default 30)
+    #[serde(default = "default_cost_days")]
+    pub days: i64,
+}
+
+fn default_cost_days() -> i64 {
+    30
+}
+
+/// LLM usage for one model on one day.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct DailyAnalysisCost {
+    pub day: String,
+    pub model: String,
+    pub requests: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub cost_usd: f64,
+}
+
+/// Response for GET /analyze/costs
+#[derive(Debug, Serialize)]
+pub struct AnalysisCostResponse {
+    pub days: i64,
+    pub total_requests: i64,
+    pub total_tokens: i64,
+    pub total_cost_usd: f64,
+    pub daily: Vec<DailyAnalysisCost>,
+}
+
+/// Request body for POST /analyze/fleet
+#[derive(Debug, Deserialize)]
+pub struct FleetAnalysisRequest {
+    /// Look-back window in hours (default: FLEET_REPORT_WINDOW_HOURS)
+    pub hours: Option<i64>,
+}
+
+/// Aggregated fleet metrics for a time window, fed to the LLM and stored
+/// alongside the generated report.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FleetSummary {
+    pub window_hours: i64,
+    pub total: i64,
+    pub settled: i64,
+    pub rolled_back: i64,
+    pub failed: i64,
+    pub pending: i64,
+    pub total_retries: i64,
+    /// rolled_back / total (0.0 when there is no traffic)
+    pub rollback_rate: f64,
+    pub top_failure_reasons: Vec<FailureReason>,
+    pub latency_trend: Vec<LatencyBucket>,
+    pub sender_issues: Vec<SenderIssues>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct FailureReason {
+    pub reason: String,
+    pub count: i64,
+}
+
+/// Hourly end-to-end latency (first event → last event per message).
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct LatencyBucket {
+    pub hour: String,
+    pub messages: i64,
+    pub avg_e2e_secs: f64,
+    pub max_e2e_secs: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SenderIssues {
+    pub sender: String,
+    pub total: i64,
+    pub rolled_back: i64,
+    pub retries: i64,
+}
+
+/// A persisted fleet-level operations report.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FleetReport {
+    pub id: i64,
+    pub window_hours: i64,
+    pub summary: FleetSummary,
+    pub report: String,
+    pub model: String,
+    pub created_at: String,
 }
 
 /// Per-subsystem health status
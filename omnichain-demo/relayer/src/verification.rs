@@ -1,21 +1,25 @@
 use anyhow::Result;
 use ethers::signers::{LocalWallet, Signer};
 use ethers::types::H256;
+use serde::Serialize;
 use sha2::{Digest, Sha256};
-use tracing::info;
+use std::collections::HashSet;
+use std::sync::Mutex;
+use tracing::{error, info, warn};
 
-use crate::types::ProofBundle;
+use crate::config::Secret;
+use crate::types::{MerkleCheck, ProofBundle, QuorumStatus, VerificationCheck, VerificationReport};
 
-/// Semi-real verification model using ECDSA signatures.
-///
-/// Upgrade path from pure simulation:
-/// - Block header and event root are derived from real SHA-256 hashes of the data
-/// - Merkle inclusion proof nodes are deterministic (seeded by nonce), not random
-/// - Validator signature is a **real ECDSA signature** over keccak256(block_header || event_root || nonce)
-/// - Verification uses ecrecover to check the signer matches the relayer's address
-///
-/// This is the "Validator Signature" approach used by production bridges like
-/// early Wormhole and Ronin — real cryptography, one library call.
+// Semi-real verification model using ECDSA signatures.
+//
+// Upgrade path from pure simulation:
+// - Block header and event root are derived from real SHA-256 hashes of the data
+// - Merkle inclusion proof nodes are deterministic (seeded by nonce), not random
+// - Validator signature is a **real ECDSA signature** over keccak256(block_header || event_root || nonce)
+// - Verification uses ecrecover to check the signer matches the relayer's address
+//
+// This is the "Validator Signature" approach used by production bridges like
+// early Wormhole and Ronin — real cryptography, one library call.
 
 /// Generate a proof bundle with real ECDSA signature.
 pub fn generate_proof_bundle(
@@ -35,24 +39,10 @@ pub fn generate_proof_bundle(
     };
 
     // Deterministic event root from real event data
-    let event_root = {
-        let mut hasher = Sha256::new();
-        hasher.update(b"event_root:");
-        hasher.update(event_data);
-        hex::encode(hasher.finalize())
-    };
+    let event_root = compute_event_root(event_data);
 
     // Deterministic Merkle inclusion proof (3 sibling hashes, seeded by nonce)
-    let inclusion_proof: Vec<String> = (0..3)
-        .map(|i| {
-            let mut hasher = Sha256::new();
-            hasher.update(b"proof_node:");
-            hasher.update(i.to_string().as_bytes());
-            hasher.update(nonce.to_le_bytes());
-            hasher.update(event_data);
-            hex::encode(hasher.finalize())
-        })
-        .collect();
+    let inclusion_proof = compute_inclusion_proof(nonce, event_data);
 
     // REAL ECDSA: Sign keccak256(block_header || event_root || nonce) with relayer key
     let message = compute_signing_message(&block_header, &event_root, nonce);
@@ -80,6 +70,65 @@ pub fn generate_proof_bundle(
     })
 }
 
+/// SIMULATION: Generate a proof bundle as a misbehaving validator would —
+/// validly signed, but over an event root that does not match the event.
+pub fn generate_misbehaving_proof_bundle(
+    nonce: u64,
+    block_number: u64,
+    tx_hash: &str,
+    event_data: &[u8],
+    validator_private_key: &str,
+) -> Result<ProofBundle> {
+    let mut forged = event_data.to_vec();
+    forged.extend_from_slice(b":forged");
+    generate_proof_bundle(nonce, block_number, tx_hash, &forged, validator_private_key)
+}
+
+/// Deterministic event root: sha256("event_root:" || event_data), hex-encoded.
+pub fn compute_event_root(event_data: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(b"event_root:");
+    hasher.update(event_data);
+    hex::encode(hasher.finalize())
+}
+
+/// SIMULATION: Deterministic Merkle inclusion proof (3 sibling hashes, seeded by nonce).
+pub fn compute_inclusion_proof(nonce: u64, event_data: &[u8]) -> Vec<String> {
+    (0..3)
+        .map(|i| {
+            let mut hasher = Sha256::new();
+            hasher.update(b"proof_node:");
+            hasher.update(i.to_string().as_bytes());
+            hasher.update(nonce.to_le_bytes());
+            hasher.update(event_data);
+            hex::encode(hasher.finalize())
+        })
+        .collect()
+}
+
+/// A validator signed an event root that does not match the observed event.
+#[derive(Debug, Clone)]
+pub struct EventRootMismatch {
+    /// Address recovered from the (valid) signature — the culprit
+    pub signer: String,
+    pub expected: String,
+    pub signed: String,
+}
+
+/// Compare the proof's event root against the one recomputed from the event data.
+/// Call after `verify_proof_bundle`, so `relayer_address` is the proven signer.
+pub fn check_event_root(proof: &ProofBundle, event_data: &[u8]) -> Option<EventRootMismatch> {
+    let expected = compute_event_root(event_data);
+    if proof.event_root == expected {
+        return None;
+    }
+    Some(EventRootMismatch {
+        signer: proof.relayer_address.clone(),
+        expected,
+        signed: proof.event_root.clone(),
+    })
+}
+
 /// Verify a proof bundle using real ECDSA signature recovery.
 ///
 /// 1. Recompute the message hash from block_header, event_root, nonce
@@ -126,6 +175,253 @@ pub fn verify_proof_bundle(proof: &ProofBundle) -> Result<bool> {
     Ok(true)
 }
 
+/// Why the pipeline refused a proof bundle.
+#[derive(Debug)]
+pub enum ProofRejection {
+    /// Malformed, or not signed by the validator it claims
+    Invalid(anyhow::Error),
+    /// Signed by a validator that is unknown or suspended
+    InactiveValidator(String),
+    /// Validly signed, over the wrong event root
+    EventRoot(EventRootMismatch),
+}
+
+impl std::fmt::Display for ProofRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Invalid(e) => write!(f, "{}", e),
+            Self::InactiveValidator(address) => write!(f, "Proof signed by unknown or suspended validator {}", address),
+            Self::EventRoot(m) => write!(f, "validator {} signed event root {}, expected {}", m.signer, m.signed, m.expected),
+        }
+    }
+}
+
+/// The checks the state machine applies before accepting a proof for
+/// `event_data`, in order: `verify_proof_bundle`, an active signer, then
+/// `check_event_root`.
+pub fn check_proof(proof: &ProofBundle, event_data: &[u8], validators: &ValidatorSet) -> Result<(), ProofRejection> {
+    verify_proof_bundle(proof).map_err(ProofRejection::Invalid)?;
+    if !validators.is_active(&proof.relayer_address) {
+        return Err(ProofRejection::InactiveValidator(proof.relayer_address.clone()));
+    }
+    match check_event_root(proof, event_data) {
+        Some(mismatch) => Err(ProofRejection::EventRoot(mismatch)),
+        None => Ok(()),
+    }
+}
+
+/// Audit an arbitrary proof bundle without failing fast.
+///
+/// Unlike `verify_proof_bundle`, every check is run and reported so a third
+/// party can see exactly which part of an exported proof is wrong. The event
+/// root and inclusion proof are only checked when `event_data` is supplied.
+pub fn audit_proof_bundle(
+    proof: &ProofBundle,
+    event_data: Option<&[u8]>,
+    validators: &ValidatorSet,
+    quorum: usize,
+) -> VerificationReport {
+    let structural: Vec<VerificationCheck> = [
+        ("block_header_present", !proof.block_header.is_empty()),
+        ("event_root_present", !proof.event_root.is_empty()),
+        ("inclusion_proof_present", !proof.inclusion_proof.is_empty()),
+        ("validator_signature_present", !proof.validator_signature.is_empty()),
+        ("nonce_nonzero", proof.nonce != 0),
+    ]
+    .into_iter()
+    .map(|(name, passed)| VerificationCheck {
+        name: name.into(),
+        passed,
+    })
+    .collect();
+
+    // Signature recovery
+    let message = compute_signing_message(&proof.block_header, &proof.event_root, proof.nonce);
+    let (recovered_signer, signature_error) = match hex::decode(&proof.validator_signature)
+        .map_err(anyhow::Error::from)
+        .and_then(|bytes| Ok(ethers::types::Signature::try_from(bytes.as_slice())?))
+        .and_then(|sig| Ok(sig.recover(H256::from(message))?))
+    {
+        Ok(addr) => (Some(format!("{:?}", addr)), None),
+        Err(e) => (None, Some(e.to_string())),
+    };
+    let signer_matches_claim = recovered_signer
+        .as_deref()
+        .is_some_and(|s| s.eq_ignore_ascii_case(&proof.relayer_address));
+
+    // Quorum: each proof currently carries a single validator signature
+    let known_validator = recovered_signer
+        .as_deref()
+        .is_some_and(|s| validators.snapshot().iter().any(|v| v.address.eq_ignore_ascii_case(s)));
+    let active_validator = recovered_signer
+        .as_deref()
+        .is_some_and(|s| validators.is_active(s));
+    let valid_signatures = usize::from(signer_matches_claim && active_validator);
+    let quorum_status = QuorumStatus {
+        required: quorum,
+        valid_signatures,
+        known_validator,
+        active_validator,
+        met: valid_signatures >= quorum,
+    };
+
+    // Event root / inclusion proof against the supplied event data
+    let merkle = match event_data {
+        Some(data) => {
+            let expected_root = compute_event_root(data);
+            let expected_proof = compute_inclusion_proof(proof.nonce, data);
+            MerkleCheck {
+                checked: true,
+                event_root_matches: Some(expected_root == proof.event_root),
+                inclusion_proof_matches: Some(expected_proof == proof.inclusion_proof),
+                expected_event_root: Some(expected_root),
+            }
+        }
+        None => MerkleCheck {
+            checked: false,
+            event_root_matches: None,
+            inclusion_proof_matches: None,
+            expected_event_root: None,
+        },
+    };
+
+    let valid = structural.iter().all(|c| c.passed)
+        && signer_matches_claim
+        && quorum_status.met
+        && merkle.event_root_matches != Some(false)
+        && merkle.inclusion_proof_matches != Some(false);
+
+    VerificationReport {
+        valid,
+        nonce: proof.nonce,
+        structural,
+        recovered_signer,
+        claimed_signer: proof.relayer_address.clone(),
+        signer_matches_claim,
+        signature_error,
+        quorum: quorum_status,
+        merkle,
+    }
+}
+
+// ──────────────────────────────────────────────
+// Validator set
+// ──────────────────────────────────────────────
+
+/// Public view of a validator for the API.
+#[derive(Debug, Clone, Serialize)]
+pub struct ValidatorInfo {
+    pub index: usize,
+    pub address: String,
+    pub suspended: bool,
+}
+
+struct Validator {
+    private_key: String,
+    address: String,
+}
+
+/// The set of keys allowed to sign proof bundles.
+///
+/// SIMULATION: All validator keys live in this process. In a real bridge each
+/// validator would be an independent party and the set would be on-chain.
+pub struct ValidatorSet {
+    validators: Vec<Validator>,
+    suspended: Mutex<HashSet<String>>,
+}
+
+impl ValidatorSet {
+    pub fn from_keys(keys: &[Secret]) -> Result<Self> {
+        let validators = keys
+            .iter()
+            .map(|Secret(key)| {
+                let wallet: LocalWallet = key.parse()?;
+                Ok(Validator {
+                    private_key: key.clone(),
+                    address: format!("{:?}", wallet.address()),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if validators.is_empty() {
+            anyhow::bail!("Validator set is empty");
+        }
+        Ok(Self {
+            validators,
+            suspended: Mutex::new(HashSet::new()),
+        })
+    }
+
+    /// Pick the validator for a nonce: round-robin over the active set.
+    /// Returns `(index, private_key, address)`, or `None` if every validator is suspended.
+    pub fn select(&self, nonce: u64) -> Option<(usize, &str, &str)> {
+        let suspended = self.suspended.lock().unwrap();
+        let active: Vec<usize> = (0..self.validators.len())
+            .filter(|i| !suspended.contains(&self.validators[*i].address.to_lowercase()))
+            .collect();
+        if active.is_empty() {
+            return None;
+        }
+        let index = active[(nonce as usize) % active.len()];
+        let v = &self.validators[index];
+        Some((index, v.private_key.as_str(), v.address.as_str()))
+    }
+
+    /// Whether `address` is a member of the set and not suspended.
+    pub fn is_active(&self, address: &str) -> bool {
+        let address = address.to_lowercase();
+        self.validators.iter().any(|v| v.address.to_lowercase() == address)
+            && !self.suspended.lock().unwrap().contains(&address)
+    }
+
+    /// Suspend a validator. Returns false if it is unknown, already suspended,
+    /// or the last active one: with no validator left, no proof could be
+    /// signed and every message would stall until an operator reinstates one.
+    pub fn suspend(&self, address: &str) -> bool {
+        let address = address.to_lowercase();
+        if !self.validators.iter().any(|v| v.address.to_lowercase() == address) {
+            return false;
+        }
+        let mut suspended = self.suspended.lock().unwrap();
+        if suspended.contains(&address) {
+            return false;
+        }
+        let active = self
+            .validators
+            .iter()
+            .filter(|v| !suspended.contains(&v.address.to_lowercase()))
+            .count();
+        if active <= 1 {
+            error!(validator = %address, "Validator misbehaved but is the last active one, not suspending it");
+            return false;
+        }
+        suspended.insert(address.clone());
+        warn!(validator = %address, "Validator suspended");
+        true
+    }
+
+    /// Reinstate a suspended validator. Returns false if it was not suspended.
+    pub fn reinstate(&self, address: &str) -> bool {
+        let removed = self.suspended.lock().unwrap().remove(&address.to_lowercase());
+        if removed {
+            info!(validator = %address, "Validator reinstated");
+        }
+        removed
+    }
+
+    pub fn snapshot(&self) -> Vec<ValidatorInfo> {
+        let suspended = self.suspended.lock().unwrap();
+        self.validators
+            .iter()
+            .enumerate()
+            .map(|(index, v)| ValidatorInfo {
+                index,
+                address: v.address.clone(),
+                suspended: suspended.contains(&v.address.to_lowercase()),
+            })
+            .collect()
+    }
+}
+
 /// Compute the message to sign: keccak256(block_header || event_root || nonce)
 fn compute_signing_message(block_header: &str, event_root: &str, nonce: u64) -> [u8; 32] {
     let mut data = Vec::new();
@@ -0,0 +1,219 @@
+use anyhow::bail;
+use chrono::{DateTime, TimeZone, Utc};
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::net::{IpAddr, SocketAddr};
+
+use crate::event::LifecycleEvent;
+
+/// Role of an API key, carried by the WS tokens minted with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WsRole {
+    /// Full event stream
+    Operator,
+    /// Redacted event stream
+    Viewer,
+}
+
+impl WsRole {
+    pub fn view(self) -> WsView {
+        match self {
+            Self::Operator => WsView::Full,
+            Self::Viewer => WsView::Redacted,
+        }
+    }
+}
+
+impl std::fmt::Display for WsRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Operator => write!(f, "operator"),
+            Self::Viewer => write!(f, "viewer"),
+        }
+    }
+}
+
+impl std::str::FromStr for WsRole {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "operator" => Ok(Self::Operator),
+            "viewer" => Ok(Self::Viewer),
+            other => bail!("unknown WS role: {} (expected operator or viewer)", other),
+        }
+    }
+}
+
+/// What a `/ws` connection is sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WsView {
+    Full,
+    /// Events without their `detail` (transaction hashes, signatures,
+    /// error messages, injected behaviour)
+    Redacted,
+}
+
+/// How `/ws` treats connections without a token (`WS_UNAUTHENTICATED`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnauthenticatedWs {
+    Full,
+    Redacted,
+    /// Rejected with 401
+    Deny,
+}
+
+impl std::str::FromStr for UnauthenticatedWs {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "full" => Ok(Self::Full),
+            "redacted" => Ok(Self::Redacted),
+            "deny" => Ok(Self::Deny),
+            other => bail!("unknown WS_UNAUTHENTICATED: {} (expected full, redacted or deny)", other),
+        }
+    }
+}
+
+/// Response for POST /ws/token
+#[derive(Debug, Clone, Serialize)]
+pub struct WsToken {
+    /// Pass as `/ws?token=`
+    pub token: String,
+    pub role: WsRole,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Mints and checks short-lived `/ws` tokens. A token is
+/// `<role>.<expiry unix secs>.<HMAC-SHA256 of both>` under a secret drawn at
+/// startup, so nothing is stored and a restart invalidates every token.
+pub struct WsTokenIssuer {
+    secret: [u8; 32],
+    ttl_secs: i64,
+}
+
+impl WsTokenIssuer {
+    pub fn new(ttl_secs: i64) -> Self {
+        Self {
+            secret: rand::random(),
+            ttl_secs,
+        }
+    }
+
+    pub fn mint(&self, role: WsRole, now: DateTime<Utc>) -> WsToken {
+        let expires_at = now.timestamp() + self.ttl_secs;
+        let claims = format!("{}.{}", role, expires_at);
+        let signature = hex::encode(self.mac(&claims).finalize().into_bytes());
+        WsToken {
+            token: format!("{}.{}", claims, signature),
+            role,
+            expires_at: Utc.timestamp_opt(expires_at, 0).single().unwrap_or(now),
+        }
+    }
+
+    /// Role of a valid, unexpired token.
+    pub fn verify(&self, token: &str, now: DateTime<Utc>) -> Option<WsRole> {
+        let (claims, signature) = token.rsplit_once('.')?;
+        self.mac(claims).verify_slice(&hex::decode(signature).ok()?).ok()?;
+        let (role, expires_at) = claims.split_once('.')?;
+        if expires_at.parse::<i64>().ok()? <= now.timestamp() {
+            return None;
+        }
+        role.parse().ok()
+    }
+
+    fn mac(&self, claims: &str) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.secret).expect("HMAC accepts keys of any length");
+        mac.update(claims.as_bytes());
+        mac
+    }
+}
+
+/// Role of the API key presented as a bearer token, from `WS_API_KEYS`, or
+/// operator for `ADMIN_TOKEN`.
+pub fn api_key_role(
+    api_keys: &[(WsRole, [u8; 32])],
+    admin_token_sha256: Option<[u8; 32]>,
+    key: &str,
+) -> Option<WsRole> {
+    // Compare digests so response timing reveals nothing about the keys
+    let provided: [u8; 32] = Sha256::digest(key.as_bytes()).into();
+    if admin_token_sha256 == Some(provided) {
+        return Some(WsRole::Operator);
+    }
+    api_keys.iter().find(|(_, hash)| *hash == provided).map(|(role, _)| *role)
+}
+
+/// `event` as a connection with `view` may see it.
+pub fn for_view(event: &LifecycleEvent, view: WsView) -> std::borrow::Cow<'_, LifecycleEvent> {
+    match view {
+        WsView::Full => std::borrow::Cow::Borrowed(event),
+        WsView::Redacted => {
+            let mut event = event.clone();
+            event.detail = None;
+            std::borrow::Cow::Owned(event)
+        }
+    }
+}
+
+/// Address of a `/ws` client. `X-Forwarded-For` is only believed when the
+/// connection comes from one of `trusted_proxies`; the client is then the
+/// nearest hop that is not itself a trusted proxy.
+pub fn client_addr(peer: SocketAddr, forwarded_for: Option<&str>, trusted_proxies: &[IpAddr]) -> String {
+    let Some(forwarded_for) = forwarded_for.filter(|_| trusted_proxies.contains(&peer.ip())) else {
+        return peer.to_string();
+    };
+    let hops: Vec<&str> = forwarded_for.split(',').map(str::trim).filter(|h| !h.is_empty()).collect();
+    hops.iter()
+        .rev()
+        .find(|hop| !hop.parse().is_ok_and(|ip: IpAddr| trusted_proxies.contains(&ip)))
+        .or(hops.first())
+        .map_or_else(|| peer.to_string(), |hop| hop.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forwarded_for_is_only_believed_from_trusted_proxies() {
+        let proxy: IpAddr = "10.0.0.1".parse().unwrap();
+        let from_proxy = SocketAddr::new(proxy, 4000);
+        let from_client = "203.0.113.9:4000".parse().unwrap();
+        let trusted = [proxy, "10.0.0.2".parse().unwrap()];
+
+        assert_eq!(client_addr(from_client, Some("198.51.100.1"), &trusted), "203.0.113.9:4000");
+        assert_eq!(client_addr(from_proxy, Some("198.51.100.1"), &[]), "10.0.0.1:4000");
+        assert_eq!(client_addr(from_proxy, None, &trusted), "10.0.0.1:4000");
+        // A client cannot pass itself off by prepending hops
+        assert_eq!(
+            client_addr(from_proxy, Some("192.0.2.7, 198.51.100.1, 10.0.0.2"), &trusted),
+            "198.51.100.1"
+        );
+    }
+
+    #[test]
+    fn tokens_carry_their_role_until_they_expire() {
+        let issuer = WsTokenIssuer::new(60);
+        let now = Utc::now();
+        let token = issuer.mint(WsRole::Viewer, now);
+        assert_eq!(issuer.verify(&token.token, now), Some(WsRole::Viewer));
+        assert_eq!(issuer.verify(&token.token, now + chrono::Duration::seconds(61)), None);
+        // Another process's secret
+        assert_eq!(WsTokenIssuer::new(60).verify(&token.token, now), None);
+    }
+
+    #[test]
+    fn rejects_tampered_tokens() {
+        let issuer = WsTokenIssuer::new(60);
+        let now = Utc::now();
+        let token = issuer.mint(WsRole::Viewer, now).token;
+        let forged = token.replacen("viewer", "operator", 1);
+        assert_eq!(issuer.verify(&forged, now), None);
+        assert_eq!(issuer.verify("operator", now), None);
+    }
+}
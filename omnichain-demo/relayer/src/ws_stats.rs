@@ -0,0 +1,140 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::ws_auth::WsView;
+
+/// Open WebSocket connections and what each has been sent.
+#[derive(Debug, Default)]
+pub struct WsConnections {
+    next_id: AtomicU64,
+    open: Mutex<BTreeMap<u64, Arc<WsConnection>>>,
+}
+
+/// Counters of one connection, updated by its handler.
+#[derive(Debug)]
+pub struct WsConnection {
+    id: u64,
+    remote_addr: String,
+    connected_at: DateTime<Utc>,
+    since_seq: Option<u64>,
+    view: WsView,
+    events_sent: AtomicU64,
+    frames_sent: AtomicU64,
+    batch_ms: AtomicU64,
+    lag_events: AtomicU64,
+    events_dropped: AtomicU64,
+}
+
+/// Snapshot of one connection for GET /ws/stats.
+#[derive(Debug, Clone, Serialize)]
+pub struct WsConnectionStats {
+    pub id: u64,
+    /// Client address, from `X-Forwarded-For` behind a proxy
+    pub remote_addr: String,
+    pub connected_at: DateTime<Utc>,
+    /// The `since_seq` the client resumed from, its only filter
+    pub since_seq: Option<u64>,
+    /// Full or redacted event stream, from the connection's token
+    pub view: WsView,
+    /// Backfilled and live events
+    pub events_sent: u64,
+    /// WebSocket frames those events went out in
+    pub frames_sent: u64,
+    /// Batching window the client subscribed with, 0 when unbatched
+    pub batch_ms: u64,
+    /// Times the client fell behind the broadcast channel
+    pub lag_events: u64,
+    /// Live events skipped because of lag
+    pub events_dropped: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WsStatsReport {
+    pub active: usize,
+    pub connections: Vec<WsConnectionStats>,
+}
+
+/// Keeps a connection registered until dropped.
+pub struct WsConnectionGuard<'a> {
+    connections: &'a WsConnections,
+    pub connection: Arc<WsConnection>,
+}
+
+impl WsConnections {
+    pub fn open(&self, remote_addr: String, since_seq: Option<u64>, view: WsView) -> WsConnectionGuard<'_> {
+        let connection = Arc::new(WsConnection {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed) + 1,
+            remote_addr,
+            connected_at: Utc::now(),
+            since_seq,
+            view,
+            events_sent: AtomicU64::new(0),
+            frames_sent: AtomicU64::new(0),
+            batch_ms: AtomicU64::new(0),
+            lag_events: AtomicU64::new(0),
+            events_dropped: AtomicU64::new(0),
+        });
+        self.open.lock().unwrap().insert(connection.id, connection.clone());
+        WsConnectionGuard {
+            connections: self,
+            connection,
+        }
+    }
+
+    pub fn active(&self) -> usize {
+        self.open.lock().unwrap().len()
+    }
+
+    pub fn snapshot(&self) -> WsStatsReport {
+        let connections: Vec<_> = self.open.lock().unwrap().values().map(|c| c.stats()).collect();
+        WsStatsReport {
+            active: connections.len(),
+            connections,
+        }
+    }
+}
+
+impl Drop for WsConnectionGuard<'_> {
+    fn drop(&mut self) {
+        self.connections.open.lock().unwrap().remove(&self.connection.id);
+    }
+}
+
+impl WsConnection {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// One frame carrying `events` events.
+    pub fn record_sent(&self, events: u64) {
+        self.events_sent.fetch_add(events, Ordering::Relaxed);
+        self.frames_sent.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn set_batch_ms(&self, batch_ms: u64) {
+        self.batch_ms.store(batch_ms, Ordering::Relaxed);
+    }
+
+    pub fn record_lag(&self, skipped: u64) {
+        self.lag_events.fetch_add(1, Ordering::Relaxed);
+        self.events_dropped.fetch_add(skipped, Ordering::Relaxed);
+    }
+
+    fn stats(&self) -> WsConnectionStats {
+        WsConnectionStats {
+            id: self.id,
+            remote_addr: self.remote_addr.clone(),
+            connected_at: self.connected_at,
+            since_seq: self.since_seq,
+            view: self.view,
+            events_sent: self.events_sent.load(Ordering::Relaxed),
+            frames_sent: self.frames_sent.load(Ordering::Relaxed),
+            batch_ms: self.batch_ms.load(Ordering::Relaxed),
+            lag_events: self.lag_events.load(Ordering::Relaxed),
+            events_dropped: self.events_dropped.load(Ordering::Relaxed),
+        }
+    }
+}
@@ -1,14 +1,24 @@
+mod metrics;
+mod sweep;
+mod tui;
+mod wallets;
+
 use anyhow::Result;
 use clap::Parser;
 use ethers::prelude::*;
 use ethers::signers::LocalWallet;
+use payload::{Codec, Payload};
 use rand::Rng;
 use rand::seq::SliceRandom;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use tokio::time::{sleep, Duration};
 use tracing::{info, warn, error};
 use uuid::Uuid;
 
+use wallets::{Secret, WalletSource, ANVIL_CHAIN_ID, ANVIL_FAUCET, ANVIL_KEYS};
+
 /// Synthetic traffic generator for the omnichain demo.
 /// Submits randomized escrow requests to the Ethereum contract.
 #[derive(Parser, Debug)]
@@ -31,10 +41,28 @@ struct Args {
     #[arg(long, default_value = "0x5FbDB2315678afecb367f032d93F642f64180aa3")]
     escrow_address: String,
 
-    /// Number of simulated users (uses Anvil default accounts)
+    /// Number of simulated users (wallets derived or loaded)
     #[arg(long, default_value_t = 5)]
     users: usize,
 
+    /// BIP-39 mnemonic to derive the users' wallets from. Prefer the
+    /// environment variable: a flag value is visible in the process list
+    #[arg(long, env = "TRAFFIC_MNEMONIC", hide_env_values = true, conflicts_with = "keystore_dir")]
+    mnemonic: Option<Secret>,
+
+    /// First derivation index used with --mnemonic (0 is conventionally the relayer)
+    #[arg(long, default_value_t = 1, requires = "mnemonic")]
+    mnemonic_first_index: u32,
+
+    /// Directory of encrypted JSON keystores, one per user (loaded in file name order)
+    #[arg(long)]
+    keystore_dir: Option<std::path::PathBuf>,
+
+    /// Password for the keystores. Prefer the environment variable: a flag
+    /// value is visible in the process list
+    #[arg(long, env = "KEYSTORE_PASSWORD", hide_env_values = true)]
+    keystore_password: Option<Secret>,
+
     /// Minimum lock amount in wei
     #[arg(long, default_value_t = 100000)]
     min_amount: u64,
@@ -43,12 +71,32 @@ struct Args {
     #[arg(long, default_value_t = 1000000)]
     max_amount: u64,
 
+    /// Payload codec: "legacy" | "raw" | "cbor" | "protobuf"
+    #[arg(long, default_value = "raw")]
+    codec: Codec,
+
     /// Demo scenario: "steady" | "burst" | "failures"
     #[arg(long, default_value = "steady")]
     scenario: String,
+
+    /// Sweep mode: reclaim expired escrows and return user balances to the faucet, then exit
+    #[arg(long)]
+    sweep: bool,
+
+    /// Live terminal dashboard instead of log output
+    #[arg(long, conflicts_with = "sweep")]
+    tui: bool,
+
+    /// Serve OpenMetrics for Prometheus on this port (`GET /metrics`)
+    #[arg(long, conflicts_with = "sweep")]
+    metrics_port: Option<u16>,
+
+    /// Address that receives swept user balances (defaults to Anvil account #0,
+    /// required with --mnemonic or --keystore-dir)
+    #[arg(long)]
+    faucet_address: Option<String>,
 }
 
-// Anvil default private keys (accounts 1-9, account 0 is the relayer)
 // Human-readable names for simulated users (mapped to Anvil accounts 1-9)
 const USER_NAMES: &[&str] = &[
     "Alice", "Bob", "Charlie", "Diana", "Eve", "Frank", "Grace", "Hank", "Ivy",
@@ -88,48 +136,96 @@ const PAYMENT_ACTIONS: &[&str] = &[
     "window washing",
 ];
 
-const ANVIL_KEYS: &[&str] = &[
-    "59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d", // account 1
-    "5de4111afa1a4b94908f83103eb1f1706367c2e68ca870fc3fb9a804cdab365a", // account 2
-    "7c852118294e51e653712a81e05800f419141751be58f605c371e15141b007a6", // account 3
-    "47e179ec197488593b187f80a00eb0da91f1b9d0b13f8733639f19c30a34926a", // account 4
-    "8b3a350cf5c34c9194ca85829a2df0ec3153be0318b5e2d3348e872092edffba", // account 5
-    "92db14e403b83dfe3df233f83dfa3a0d7096f21ca9b0d6d6b8d88b2b4ec1564e", // account 6
-    "4bbbf85ce3377467afe5d46f804f221813b2bb87f24d81f60f1fcdbf7cbf4356", // account 7
-    "dbda1821b80551c9d65939329250298aa3472ba22feea921c0cf5d620ea67b97", // account 8
-    "2a871d0798f97d79848a013d4936a73bf4cc922c825d33c1cf7073dff6d409c6", // account 9
-];
-
 #[tokio::main]
 async fn main() -> Result<()> {
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "traffic_generator=info".into()),
-        )
-        .init();
-
     let args = Args::parse();
+
+    // The terminal UI owns the screen, so it replaces log output
+    if !args.tui {
+        tracing_subscriber::fmt()
+            .with_env_filter(
+                tracing_subscriber::EnvFilter::try_from_default_env()
+                    .unwrap_or_else(|_| "traffic_generator=info".into()),
+            )
+            .init();
+    }
     info!(?args, "Starting traffic generator");
 
     let interval = Duration::from_secs_f64(1.0 / args.rate);
-    let user_count = args.users.min(ANVIL_KEYS.len());
+
+    let source = if let Some(phrase) = &args.mnemonic {
+        WalletSource::Mnemonic {
+            phrase: phrase.clone(),
+            first_index: args.mnemonic_first_index,
+        }
+    } else if let Some(dir) = &args.keystore_dir {
+        let password = args
+            .keystore_password
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("--keystore-dir needs KEYSTORE_PASSWORD or --keystore-password"))?;
+        WalletSource::KeystoreDir { dir: dir.clone(), password }
+    } else {
+        WalletSource::Anvil
+    };
+
+    // Anvil account #0 is nobody's faucet outside the Anvil default keys
+    if args.sweep && args.faucet_address.is_none() && !matches!(source, WalletSource::Anvil) {
+        anyhow::bail!("--sweep with {} needs --faucet-address", source);
+    }
+
+    // Sweep every known Anvil account, not just the ones this run would use
+    let wanted = if args.sweep && matches!(source, WalletSource::Anvil) {
+        ANVIL_KEYS.len()
+    } else {
+        args.users
+    };
+
+    let wallets = source.load(wanted)?;
+    let user_count = wallets.len();
+    if user_count == 0 {
+        anyhow::bail!("no wallets loaded from {}", source);
+    }
+    if user_count < wanted {
+        warn!(wanted, loaded = user_count, %source, "Fewer wallets than requested users");
+    }
+    // Outside Anvil these accounts need funding before they can lock
+    for (idx, wallet) in wallets.iter().enumerate() {
+        info!(user = %user_name(idx), address = ?wallet.address(), "Loaded wallet");
+    }
 
     // Build signer clients for each simulated user
     let provider = Provider::<Http>::try_from(&args.rpc_url)?;
     let chain_id = provider.get_chainid().await?.as_u64();
-
-    let wallets: Vec<LocalWallet> = ANVIL_KEYS[..user_count]
-        .iter()
-        .map(|key| {
-            key.parse::<LocalWallet>()
-                .unwrap()
-                .with_chain_id(chain_id)
-        })
+    if matches!(source, WalletSource::Anvil) && chain_id != ANVIL_CHAIN_ID {
+        warn!(chain_id, "Using Anvil default keys on a non-Anvil chain; pass --mnemonic or --keystore-dir");
+    }
+    let wallets: Vec<LocalWallet> = wallets
+        .into_iter()
+        .map(|wallet| wallet.with_chain_id(chain_id))
         .collect();
 
+    if args.sweep {
+        let report = sweep::run_sweep(
+            &args.rpc_url,
+            &args.escrow_address,
+            &wallets,
+            args.faucet_address.as_deref().unwrap_or(ANVIL_FAUCET),
+        )
+        .await?;
+        info!(
+            reclaimed = report.reclaimed,
+            reclaimed_wei = %report.reclaimed_wei,
+            still_locked = report.still_locked,
+            foreign = report.foreign,
+            returned_wei = %report.returned_wei,
+            "Sweep complete"
+        );
+        return Ok(());
+    }
+
     info!(
         users = user_count,
+        %source,
         chain_id,
         rate = args.rate,
         scenario = %args.scenario,
@@ -138,10 +234,39 @@ async fn main() -> Result<()> {
 
     let contract_address = Address::from_str(&args.escrow_address)?;
 
+    let stats = Arc::new(Mutex::new(tui::RunStats::default()));
+    let stop = Arc::new(AtomicBool::new(false));
+    if let Some(port) = args.metrics_port {
+        metrics::serve(port, stats.clone(), args.rate).await?;
+    }
+    let monitor = if args.tui {
+        let users: Vec<tui::User> = wallets
+            .iter()
+            .enumerate()
+            .map(|(idx, wallet)| tui::User {
+                name: user_name(idx),
+                address: wallet.address(),
+            })
+            .collect();
+        let poller = tui::spawn_balance_poller(args.rpc_url.clone(), users.clone(), stats.clone(), stop.clone());
+        let info = tui::RunInfo {
+            scenario: args.scenario.clone(),
+            target_tps: args.rate,
+            chain_id,
+            users,
+        };
+        Some((tui::spawn(info, stats.clone(), stop.clone()), poller))
+    } else {
+        None
+    };
+
     let mut sent: u64 = 0;
     let mut rng = rand::thread_rng();
 
     loop {
+        if stop.load(Ordering::Relaxed) {
+            break;
+        }
         if args.count > 0 && sent >= args.count {
             info!(total = sent, "Reached target count, stopping");
             break;
@@ -154,11 +279,11 @@ async fn main() -> Result<()> {
         // Generate random payload with a human-readable description
         let trace_id = Uuid::new_v4();
         let amount = rng.gen_range(args.min_amount..=args.max_amount);
-        let user_name = USER_NAMES[wallet_idx];
+        let user_name = user_name(wallet_idx);
         let action = PAYMENT_ACTIONS.choose(&mut rng).unwrap();
         let recipient_name = USER_NAMES.choose(&mut rng).unwrap();
         let description = format!("{}'s payment to {} for {}", user_name, recipient_name, action);
-        let payload = generate_payload(&mut rng, &trace_id, &description);
+        let payload = generate_payload(&mut rng, &trace_id, &description, args.codec);
 
         // Apply scenario modifiers
         let effective_interval = match args.scenario.as_str() {
@@ -207,9 +332,16 @@ async fn main() -> Result<()> {
         match client.send_transaction(tx, None).await {
             Ok(pending) => {
                 let tx_hash = pending.tx_hash();
+                let seq = stats.lock().unwrap().submitted(wallet_idx, effective_amount, Some(tx_hash));
                 match pending.await {
                     Ok(Some(receipt)) => {
                         sent += 1;
+                        let outcome = if receipt.status == Some(0.into()) {
+                            tui::Outcome::Reverted
+                        } else {
+                            tui::Outcome::Confirmed
+                        };
+                        stats.lock().unwrap().finished(seq, outcome);
                         info!(
                             seq = sent,
                             %tx_hash,
@@ -221,14 +353,19 @@ async fn main() -> Result<()> {
                         );
                     }
                     Ok(None) => {
+                        stats.lock().unwrap().finished(seq, tui::Outcome::Dropped);
                         warn!(%tx_hash, "Transaction dropped");
                     }
                     Err(e) => {
+                        stats.lock().unwrap().finished(seq, tui::Outcome::Failed);
                         warn!(error = %e, "Transaction failed");
                     }
                 }
             }
             Err(e) => {
+                let mut stats = stats.lock().unwrap();
+                let seq = stats.submitted(wallet_idx, effective_amount, None);
+                stats.finished(seq, tui::Outcome::Failed);
                 error!(error = %e, "Failed to send transaction");
             }
         }
@@ -237,26 +374,33 @@ async fn main() -> Result<()> {
     }
 
     info!(total = sent, "Traffic generation complete");
+    stats.lock().unwrap().set_done();
+    // Keep the final numbers on screen until the operator quits
+    if let Some((ui, poller)) = monitor {
+        let _ = tokio::task::spawn_blocking(move || ui.join()).await;
+        poller.abort();
+    }
     Ok(())
 }
 
-/// Generate a randomized payload with embedded trace information and description.
-fn generate_payload(rng: &mut impl Rng, trace_id: &Uuid, description: &str) -> Vec<u8> {
-    let mut payload = Vec::new();
-
-    // Embed trace ID as first 16 bytes
-    payload.extend_from_slice(trace_id.as_bytes());
-
-    // Embed description length (2 bytes) + description bytes
-    let desc_bytes = description.as_bytes();
-    payload.extend_from_slice(&(desc_bytes.len() as u16).to_be_bytes());
-    payload.extend_from_slice(desc_bytes);
+/// Display name for user `idx`; users past the named ones are numbered.
+fn user_name(idx: usize) -> String {
+    USER_NAMES
+        .get(idx)
+        .map(|name| name.to_string())
+        .unwrap_or_else(|| format!("User {}", idx + 1))
+}
 
+/// Generate a randomized payload with embedded trace information and description.
+fn generate_payload(rng: &mut impl Rng, trace_id: &Uuid, description: &str, codec: Codec) -> Vec<u8> {
     // Add some random operation data (4-16 bytes)
-    let extra_len = rng.gen_range(4..=16);
-    let mut extra = vec![0u8; extra_len];
-    rng.fill(&mut extra[..]);
-    payload.extend_from_slice(&extra);
+    let mut data = vec![0u8; rng.gen_range(4..=16)];
+    rng.fill(&mut data[..]);
 
-    payload
+    Payload {
+        trace_id: *trace_id.as_bytes(),
+        description: description.to_string(),
+        data,
+    }
+    .encode(codec)
 }
@@ -0,0 +1,100 @@
+use anyhow::Result;
+use axum::{extract::State, http::header::CONTENT_TYPE, response::IntoResponse, routing::get, Router};
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{error, info};
+
+use crate::tui::RunStats;
+
+/// Upper bounds of the submission latency buckets, in seconds: instant
+/// mining on Anvil up to a slow public testnet.
+const LATENCY_BUCKETS: [f64; 10] = [0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 15.0, 30.0, 60.0];
+
+const OPENMETRICS_CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// Cumulative histogram of the time from submission to receipt.
+#[derive(Debug, Default)]
+pub struct LatencyHistogram {
+    /// Observations per bucket (not cumulative); the last is `+Inf`
+    counts: [u64; LATENCY_BUCKETS.len() + 1],
+    sum_secs: f64,
+}
+
+impl LatencyHistogram {
+    pub fn observe(&mut self, latency: Duration) {
+        let secs = latency.as_secs_f64();
+        let bucket = LATENCY_BUCKETS
+            .iter()
+            .position(|&le| secs <= le)
+            .unwrap_or(LATENCY_BUCKETS.len());
+        self.counts[bucket] += 1;
+        self.sum_secs += secs;
+    }
+}
+
+/// Serve `GET /metrics` on `port` until the process exits.
+pub async fn serve(port: u16, stats: Arc<Mutex<RunStats>>, target_tps: f64) -> Result<()> {
+    let listener = tokio::net::TcpListener::bind(("0.0.0.0", port)).await?;
+    let app = Router::new()
+        .route("/metrics", get(metrics))
+        .with_state((stats, target_tps));
+
+    info!(port, "Serving OpenMetrics on /metrics");
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, app).await {
+            error!(error = %e, "Metrics server stopped");
+        }
+    });
+    Ok(())
+}
+
+async fn metrics(State((stats, target_tps)): State<(Arc<Mutex<RunStats>>, f64)>) -> impl IntoResponse {
+    let body = render(&mut stats.lock().unwrap(), target_tps).unwrap_or_default();
+    ([(CONTENT_TYPE, OPENMETRICS_CONTENT_TYPE)], body)
+}
+
+/// Render the run's counters in the OpenMetrics text format.
+fn render(stats: &mut RunStats, target_tps: f64) -> Result<String> {
+    let mut out = String::new();
+
+    writeln!(out, "# TYPE traffic_generator_sent counter")?;
+    writeln!(out, "# HELP traffic_generator_sent Transactions submitted, including ones the node rejected.")?;
+    writeln!(out, "traffic_generator_sent_total {}", stats.sent())?;
+
+    writeln!(out, "# TYPE traffic_generator_transactions counter")?;
+    writeln!(out, "# HELP traffic_generator_transactions Finished transactions by outcome.")?;
+    for (outcome, count) in stats.outcomes() {
+        writeln!(out, "traffic_generator_transactions_total{{outcome=\"{}\"}} {}", outcome, count)?;
+    }
+
+    let histogram = stats.latency_histogram();
+    writeln!(out, "# TYPE traffic_generator_submission_latency_seconds histogram")?;
+    writeln!(
+        out,
+        "# HELP traffic_generator_submission_latency_seconds Time from submission to receipt of landed transactions."
+    )?;
+    let mut cumulative = 0;
+    for (i, count) in histogram.counts.iter().enumerate() {
+        cumulative += count;
+        let le = LATENCY_BUCKETS.get(i).map_or("+Inf".to_string(), |le| format!("{:?}", le));
+        writeln!(
+            out,
+            "traffic_generator_submission_latency_seconds_bucket{{le=\"{}\"}} {}",
+            le, cumulative
+        )?;
+    }
+    writeln!(out, "traffic_generator_submission_latency_seconds_sum {}", histogram.sum_secs)?;
+    writeln!(out, "traffic_generator_submission_latency_seconds_count {}", cumulative)?;
+
+    writeln!(out, "# TYPE traffic_generator_tps gauge")?;
+    writeln!(out, "# HELP traffic_generator_tps Transactions landed per second over the last 10 seconds.")?;
+    writeln!(out, "traffic_generator_tps {}", stats.current_tps())?;
+
+    writeln!(out, "# TYPE traffic_generator_target_tps gauge")?;
+    writeln!(out, "# HELP traffic_generator_target_tps The --rate target.")?;
+    writeln!(out, "traffic_generator_target_tps {}", target_tps)?;
+
+    writeln!(out, "# EOF")?;
+    Ok(out)
+}
@@ -0,0 +1,173 @@
+use anyhow::Result;
+use ethers::abi::{ParamType, Token};
+use ethers::prelude::*;
+use ethers::signers::LocalWallet;
+use std::str::FromStr;
+use tracing::{info, warn};
+
+/// Gas limit for a plain ETH transfer.
+const TRANSFER_GAS: u64 = 21_000;
+
+/// Summary of a sweep run, logged at the end.
+#[derive(Debug, Default)]
+pub struct SweepReport {
+    pub reclaimed: u64,
+    pub reclaimed_wei: U256,
+    pub still_locked: u64,
+    pub foreign: u64,
+    pub returned_wei: U256,
+}
+
+/// Post-demo cleanup: reclaim every expired escrow owned by one of our wallets,
+/// then return each wallet's remaining balance (minus transfer gas) to the faucet.
+///
+/// Escrows that are still inside their deadline cannot be reclaimed by the
+/// sender (the contract enforces `DeadlineNotReached`), so they are reported
+/// and left for the relayer to settle or for a later sweep.
+pub async fn run_sweep(
+    rpc_url: &str,
+    escrow_address: &str,
+    wallets: &[LocalWallet],
+    faucet_address: &str,
+) -> Result<SweepReport> {
+    let provider = Provider::<Http>::try_from(rpc_url)?;
+    let escrow = Address::from_str(escrow_address)?;
+    let faucet = Address::from_str(faucet_address)?;
+    let mut report = SweepReport::default();
+
+    // 1. Reclaim expired escrows
+    let latest_nonce = escrow_nonce(&provider, escrow).await?;
+    let now = provider
+        .get_block(BlockNumber::Latest)
+        .await?
+        .map(|b| b.timestamp)
+        .unwrap_or_default();
+
+    info!(latest_nonce, "Sweeping escrows");
+
+    for nonce in 1..=latest_nonce {
+        let (sender, amount, deadline, executed) = get_escrow(&provider, escrow, nonce).await?;
+        if executed || sender == Address::zero() {
+            continue;
+        }
+
+        let Some(wallet) = wallets.iter().find(|w| w.address() == sender) else {
+            report.foreign += 1;
+            continue;
+        };
+
+        if now < deadline {
+            report.still_locked += 1;
+            info!(
+                nonce,
+                remaining_secs = (deadline - now).as_u64(),
+                "Escrow still inside its deadline, leaving it for settlement"
+            );
+            continue;
+        }
+
+        let client = SignerMiddleware::new(provider.clone(), wallet.clone());
+        let selector = &ethers::utils::keccak256(b"reclaim(uint64)")[..4];
+        let encoded = ethers::abi::encode(&[Token::Uint(U256::from(nonce))]);
+        let mut calldata = selector.to_vec();
+        calldata.extend_from_slice(&encoded);
+
+        let tx = TransactionRequest::new()
+            .to(escrow)
+            .data(calldata)
+            .gas(100_000u64);
+
+        match client.send_transaction(tx, None).await {
+            Ok(pending) => match pending.await {
+                Ok(Some(receipt)) if receipt.status == Some(1u64.into()) => {
+                    report.reclaimed += 1;
+                    report.reclaimed_wei += amount;
+                    info!(nonce, %amount, tx_hash = ?receipt.transaction_hash, "Escrow reclaimed");
+                }
+                Ok(Some(receipt)) => {
+                    warn!(nonce, tx_hash = ?receipt.transaction_hash, "Reclaim reverted");
+                }
+                Ok(None) => warn!(nonce, "Reclaim transaction dropped"),
+                Err(e) => warn!(nonce, error = %e, "Reclaim failed"),
+            },
+            Err(e) => warn!(nonce, error = %e, "Failed to send reclaim"),
+        };
+    }
+
+    // 2. Return user balances to the faucet
+    let gas_price = provider.get_gas_price().await?;
+    let transfer_cost = gas_price * U256::from(TRANSFER_GAS);
+
+    for wallet in wallets {
+        let address = wallet.address();
+        if address == faucet {
+            continue;
+        }
+
+        let balance = provider.get_balance(address, None).await?;
+        if balance <= transfer_cost {
+            continue;
+        }
+        let value = balance - transfer_cost;
+
+        let client = SignerMiddleware::new(provider.clone(), wallet.clone());
+        let tx = TransactionRequest::new()
+            .to(faucet)
+            .value(value)
+            .gas(TRANSFER_GAS)
+            .gas_price(gas_price);
+
+        match client.send_transaction(tx, None).await {
+            Ok(pending) => match pending.await {
+                Ok(Some(_)) => {
+                    report.returned_wei += value;
+                    info!(?address, %value, "Balance returned to faucet");
+                }
+                Ok(None) => warn!(?address, "Faucet transfer dropped"),
+                Err(e) => warn!(?address, error = %e, "Faucet transfer failed"),
+            },
+            Err(e) => warn!(?address, error = %e, "Failed to send faucet transfer"),
+        };
+    }
+
+    Ok(report)
+}
+
+/// Read the escrow's current nonce counter (`nonce()` view).
+async fn escrow_nonce(provider: &Provider<Http>, escrow: Address) -> Result<u64> {
+    let selector = ethers::utils::keccak256(b"nonce()")[..4].to_vec();
+    let tx = TransactionRequest::new().to(escrow).data(selector);
+    let out = provider.call(&tx.into(), None).await?;
+    Ok(U256::from_big_endian(&out).as_u64())
+}
+
+/// Read `(sender, amount, deadline, executed)` for one escrow via `getEscrow(uint64)`.
+async fn get_escrow(
+    provider: &Provider<Http>,
+    escrow: Address,
+    nonce: u64,
+) -> Result<(Address, U256, U256, bool)> {
+    let selector = &ethers::utils::keccak256(b"getEscrow(uint64)")[..4];
+    let mut calldata = selector.to_vec();
+    calldata.extend_from_slice(&ethers::abi::encode(&[Token::Uint(U256::from(nonce))]));
+
+    let tx = TransactionRequest::new().to(escrow).data(calldata);
+    let out = provider.call(&tx.into(), None).await?;
+    let tokens = ethers::abi::decode(
+        &[
+            ParamType::Address,
+            ParamType::Uint(256),
+            ParamType::Uint(256),
+            ParamType::Bool,
+            ParamType::FixedBytes(32),
+            ParamType::Bytes,
+        ],
+        &out,
+    )?;
+
+    let sender = tokens[0].clone().into_address().unwrap_or_default();
+    let amount = tokens[1].clone().into_uint().unwrap_or_default();
+    let deadline = tokens[2].clone().into_uint().unwrap_or_default();
+    let executed = tokens[3].clone().into_bool().unwrap_or(false);
+    Ok((sender, amount, deadline, executed))
+}
@@ -0,0 +1,326 @@
+use ethers::prelude::*;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Cell, Paragraph, Row, Table};
+use ratatui::Frame;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::metrics::LatencyHistogram;
+
+/// Submissions kept for the table.
+const RECENT_CAP: usize = 200;
+
+/// Confirmation latencies kept for the percentiles.
+const LATENCY_CAP: usize = 1000;
+
+/// Window the current tps is measured over.
+const TPS_WINDOW: Duration = Duration::from_secs(10);
+
+/// How often user balances are re-read.
+const BALANCE_POLL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Pending,
+    Confirmed,
+    Reverted,
+    Dropped,
+    /// Rejected by the node, or lost while waiting for the receipt
+    Failed,
+}
+
+#[derive(Debug, Clone)]
+pub struct Submission {
+    pub seq: u64,
+    pub at: Instant,
+    pub user: usize,
+    pub amount: u64,
+    pub tx_hash: Option<H256>,
+    pub outcome: Outcome,
+    pub latency: Option<Duration>,
+}
+
+/// Counters and recent history of a run, written by the send loop and read
+/// by the terminal UI and the metrics endpoint.
+#[derive(Debug, Default)]
+pub struct RunStats {
+    /// Newest first
+    recent: VecDeque<Submission>,
+    next_seq: u64,
+    confirmed: u64,
+    reverted: u64,
+    dropped: u64,
+    failed: u64,
+    /// Landing times (confirmed or reverted) inside `TPS_WINDOW`
+    landed: VecDeque<Instant>,
+    latencies: VecDeque<Duration>,
+    /// Every landing's latency, for `--metrics-port`
+    latency_histogram: LatencyHistogram,
+    /// Per user, `None` until first read
+    balances: Vec<Option<U256>>,
+    done: bool,
+}
+
+impl RunStats {
+    /// Record a submission (`tx_hash` is None if the node rejected it);
+    /// returns its sequence number.
+    pub fn submitted(&mut self, user: usize, amount: u64, tx_hash: Option<H256>) -> u64 {
+        self.next_seq += 1;
+        self.recent.push_front(Submission {
+            seq: self.next_seq,
+            at: Instant::now(),
+            user,
+            amount,
+            tx_hash,
+            outcome: Outcome::Pending,
+            latency: None,
+        });
+        self.recent.truncate(RECENT_CAP);
+        self.next_seq
+    }
+
+    /// Record how submission `seq` ended.
+    pub fn finished(&mut self, seq: u64, outcome: Outcome) {
+        let now = Instant::now();
+        let latency = self.recent.iter_mut().find(|s| s.seq == seq).map(|s| {
+            s.outcome = outcome;
+            let latency = now - s.at;
+            s.latency = Some(latency);
+            latency
+        });
+
+        match outcome {
+            Outcome::Confirmed => self.confirmed += 1,
+            Outcome::Reverted => self.reverted += 1,
+            Outcome::Dropped => self.dropped += 1,
+            Outcome::Failed => self.failed += 1,
+            Outcome::Pending => {}
+        }
+        if matches!(outcome, Outcome::Confirmed | Outcome::Reverted) {
+            self.landed.push_back(now);
+            if let Some(latency) = latency {
+                self.latency_histogram.observe(latency);
+                self.latencies.push_back(latency);
+                if self.latencies.len() > LATENCY_CAP {
+                    self.latencies.pop_front();
+                }
+            }
+        }
+    }
+
+    pub fn set_done(&mut self) {
+        self.done = true;
+    }
+
+    /// Submissions so far, including ones the node rejected.
+    pub fn sent(&self) -> u64 {
+        self.next_seq
+    }
+
+    /// Finished submissions per outcome.
+    pub fn outcomes(&self) -> [(&'static str, u64); 4] {
+        [
+            ("confirmed", self.confirmed),
+            ("reverted", self.reverted),
+            ("dropped", self.dropped),
+            ("failed", self.failed),
+        ]
+    }
+
+    pub fn latency_histogram(&self) -> &LatencyHistogram {
+        &self.latency_histogram
+    }
+
+    /// Landings per second over the last `TPS_WINDOW`.
+    pub fn current_tps(&mut self) -> f64 {
+        let cutoff = Instant::now().checked_sub(TPS_WINDOW);
+        while let (Some(front), Some(cutoff)) = (self.landed.front(), cutoff) {
+            if *front >= cutoff {
+                break;
+            }
+            self.landed.pop_front();
+        }
+        self.landed.len() as f64 / TPS_WINDOW.as_secs_f64()
+    }
+
+    fn latency_percentile(&self, pct: usize) -> Option<Duration> {
+        let mut sorted: Vec<Duration> = self.latencies.iter().copied().collect();
+        sorted.sort();
+        let idx = (sorted.len() * pct / 100).min(sorted.len().checked_sub(1)?);
+        sorted.get(idx).copied()
+    }
+}
+
+/// A simulated user as shown in the balance table.
+#[derive(Debug, Clone)]
+pub struct User {
+    pub name: String,
+    pub address: Address,
+}
+
+/// Static facts about the run shown in the header.
+#[derive(Debug, Clone)]
+pub struct RunInfo {
+    pub scenario: String,
+    pub target_tps: f64,
+    pub chain_id: u64,
+    pub users: Vec<User>,
+}
+
+/// Re-read every user's balance until the run is stopped.
+pub fn spawn_balance_poller(
+    rpc_url: String,
+    users: Vec<User>,
+    stats: Arc<Mutex<RunStats>>,
+    stop: Arc<AtomicBool>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let Ok(provider) = Provider::<Http>::try_from(rpc_url.as_str()) else {
+            return;
+        };
+        while !stop.load(Ordering::Relaxed) {
+            for (idx, user) in users.iter().enumerate() {
+                let balance = provider.get_balance(user.address, None).await.ok();
+                let mut stats = stats.lock().unwrap();
+                if stats.balances.len() < users.len() {
+                    stats.balances.resize(users.len(), None);
+                }
+                stats.balances[idx] = balance;
+            }
+            tokio::time::sleep(BALANCE_POLL).await;
+        }
+    })
+}
+
+/// Draw the live view on its own thread until the operator quits (`q`,
+/// `Esc` or Ctrl-C), then set `stop`. The terminal is restored on exit.
+pub fn spawn(info: RunInfo, stats: Arc<Mutex<RunStats>>, stop: Arc<AtomicBool>) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || {
+        let started = Instant::now();
+        let mut terminal = ratatui::init();
+        loop {
+            let drawn = terminal.draw(|frame| {
+                let mut stats = stats.lock().unwrap();
+                draw(frame, &info, &mut stats, started.elapsed());
+            });
+            if drawn.is_err() {
+                break;
+            }
+
+            if event::poll(Duration::from_millis(250)).unwrap_or(false) {
+                if let Ok(Event::Key(key)) = event::read() {
+                    let ctrl_c = key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+                    if key.kind == KeyEventKind::Press && (ctrl_c || matches!(key.code, KeyCode::Char('q') | KeyCode::Esc)) {
+                        break;
+                    }
+                }
+            }
+        }
+        stop.store(true, Ordering::Relaxed);
+        ratatui::restore();
+    })
+}
+
+fn draw(frame: &mut Frame, info: &RunInfo, stats: &mut RunStats, elapsed: Duration) {
+    let [header, body, footer] =
+        Layout::vertical([Constraint::Length(4), Constraint::Min(5), Constraint::Length(1)]).areas(frame.area());
+    let [submissions, balances] =
+        Layout::horizontal([Constraint::Percentage(65), Constraint::Percentage(35)]).areas(body);
+
+    let tps = stats.current_tps();
+    let tps_style = if tps + f64::EPSILON < info.target_tps * 0.8 {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default().fg(Color::Green)
+    };
+    let ms = |d: Option<Duration>| d.map_or("-".to_string(), |d| format!("{}ms", d.as_millis()));
+    let summary = vec![
+        Line::from(vec![
+            Span::raw(format!("scenario {}  chain {}  elapsed {}s  ", info.scenario, info.chain_id, elapsed.as_secs())),
+            Span::styled(format!("tps {:.2}", tps), tps_style),
+            Span::raw(format!(" / target {:.2}", info.target_tps)),
+        ]),
+        Line::from(vec![
+            Span::raw(format!("confirmed {}  ", stats.confirmed)),
+            Span::styled(format!("reverted {}", stats.reverted), Style::default().fg(Color::Red)),
+            Span::raw(format!(
+                "  dropped {}  failed {}  latency p50 {}  p95 {}",
+                stats.dropped,
+                stats.failed,
+                ms(stats.latency_percentile(50)),
+                ms(stats.latency_percentile(95)),
+            )),
+        ]),
+    ];
+    frame.render_widget(Paragraph::new(summary).block(Block::bordered().title(" traffic-generator ")), header);
+
+    let now = Instant::now();
+    let rows = stats.recent.iter().map(|s| {
+        let (status, color) = match s.outcome {
+            Outcome::Pending => ("pending", Color::Yellow),
+            Outcome::Confirmed => ("confirmed", Color::Green),
+            Outcome::Reverted => ("reverted", Color::Red),
+            Outcome::Dropped => ("dropped", Color::Magenta),
+            Outcome::Failed => ("failed", Color::Red),
+        };
+        Row::new(vec![
+            Cell::from(format!("{}s", (now - s.at).as_secs())),
+            Cell::from(info.users.get(s.user).map_or("?", |u| u.name.as_str()).to_string()),
+            Cell::from(s.amount.to_string()),
+            Cell::from(s.tx_hash.map_or("-".to_string(), |h| short_hex(&format!("{:?}", h)))),
+            Cell::from(ms(s.latency)),
+            Cell::from(status).style(Style::default().fg(color)),
+        ])
+    });
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(6),
+            Constraint::Length(9),
+            Constraint::Length(10),
+            Constraint::Length(15),
+            Constraint::Length(9),
+            Constraint::Length(10),
+        ],
+    )
+    .header(
+        Row::new(vec!["age", "user", "amount", "tx", "latency", "status"])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+    )
+    .block(Block::bordered().title(" recent submissions "));
+    frame.render_widget(table, submissions);
+
+    let rows = info.users.iter().enumerate().map(|(idx, user)| {
+        let balance = stats
+            .balances
+            .get(idx)
+            .copied()
+            .flatten()
+            .map_or("-".to_string(), ethers::utils::format_ether);
+        Row::new(vec![
+            Cell::from(user.name.clone()),
+            Cell::from(short_hex(&format!("{:?}", user.address))),
+            Cell::from(balance),
+        ])
+    });
+    let table = Table::new(rows, [Constraint::Length(9), Constraint::Length(15), Constraint::Min(10)])
+        .header(Row::new(vec!["user", "address", "ETH"]).style(Style::default().add_modifier(Modifier::BOLD)))
+        .block(Block::bordered().title(" balances "));
+    frame.render_widget(table, balances);
+
+    let hint = if stats.done { "run complete — q to exit" } else { "q to stop" };
+    frame.render_widget(Paragraph::new(hint).style(Style::default().fg(Color::DarkGray)), footer);
+}
+
+/// `0x1234ab…cdef` for hashes and addresses.
+fn short_hex(s: &str) -> String {
+    if s.len() <= 14 {
+        return s.to_string();
+    }
+    format!("{}…{}", &s[..8], &s[s.len() - 4..])
+}
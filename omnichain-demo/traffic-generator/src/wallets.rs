@@ -0,0 +1,114 @@
+use anyhow::{bail, Context, Result};
+use ethers::signers::coins_bip39::English;
+use ethers::signers::{LocalWallet, MnemonicBuilder};
+use std::path::{Path, PathBuf};
+
+/// Anvil default private keys (accounts 1-9, account 0 is the relayer).
+/// Dev fallback when no mnemonic or keystore is given.
+pub const ANVIL_KEYS: &[&str] = &[
+    "59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d", // account 1
+    "5de4111afa1a4b94908f83103eb1f1706367c2e68ca870fc3fb9a804cdab365a", // account 2
+    "7c852118294e51e653712a81e05800f419141751be58f605c371e15141b007a6", // account 3
+    "47e179ec197488593b187f80a00eb0da91f1b9d0b13f8733639f19c30a34926a", // account 4
+    "8b3a350cf5c34c9194ca85829a2df0ec3153be0318b5e2d3348e872092edffba", // account 5
+    "92db14e403b83dfe3df233f83dfa3a0d7096f21ca9b0d6d6b8d88b2b4ec1564e", // account 6
+    "4bbbf85ce3377467afe5d46f804f221813b2bb87f24d81f60f1fcdbf7cbf4356", // account 7
+    "dbda1821b80551c9d65939329250298aa3472ba22feea921c0cf5d620ea67b97", // account 8
+    "2a871d0798f97d79848a013d4936a73bf4cc922c825d33c1cf7073dff6d409c6", // account 9
+];
+
+/// Anvil's chain id; the default keys are only funded there.
+pub const ANVIL_CHAIN_ID: u64 = 31337;
+
+/// Anvil account #0, the sweep's faucet when the users are Anvil's own accounts.
+pub const ANVIL_FAUCET: &str = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+
+/// A mnemonic or passphrase from the command line, redacted in Debug output.
+#[derive(Clone)]
+pub struct Secret(pub String);
+
+impl std::str::FromStr for Secret {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.to_string()))
+    }
+}
+
+impl std::fmt::Debug for Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<redacted>")
+    }
+}
+
+/// Where the simulated users' keys come from.
+#[derive(Debug, Clone)]
+pub enum WalletSource {
+    /// BIP-39 phrase; accounts `m/44'/60'/0'/0/{first_index}..`
+    Mnemonic { phrase: Secret, first_index: u32 },
+    /// Directory of encrypted JSON keystores (as written by `cast wallet new`
+    /// or geth), all sharing one password
+    KeystoreDir { dir: PathBuf, password: Secret },
+    Anvil,
+}
+
+impl WalletSource {
+    /// Up to `count` wallets. Mnemonics derive exactly `count`; keystore
+    /// directories and the Anvil set may hold fewer.
+    pub fn load(&self, count: usize) -> Result<Vec<LocalWallet>> {
+        match self {
+            Self::Mnemonic { phrase, first_index } => (0..count as u32)
+                .map(|i| {
+                    MnemonicBuilder::<English>::default()
+                        .phrase(phrase.0.as_str())
+                        .index(first_index + i)?
+                        .build()
+                        .context("deriving account from mnemonic")
+                })
+                .collect(),
+            Self::KeystoreDir { dir, password } => {
+                let paths = keystore_files(dir)?;
+                if paths.is_empty() {
+                    bail!("no keystore files in {}", dir.display());
+                }
+                paths
+                    .iter()
+                    .take(count)
+                    .map(|path| {
+                        LocalWallet::decrypt_keystore(path, &password.0)
+                            .with_context(|| format!("decrypting keystore {}", path.display()))
+                    })
+                    .collect()
+            }
+            Self::Anvil => ANVIL_KEYS
+                .iter()
+                .take(count)
+                .map(|key| Ok(key.parse::<LocalWallet>()?))
+                .collect(),
+        }
+    }
+}
+
+impl std::fmt::Display for WalletSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Mnemonic { first_index, .. } => write!(f, "mnemonic (from index {})", first_index),
+            Self::KeystoreDir { dir, .. } => write!(f, "keystore {}", dir.display()),
+            Self::Anvil => write!(f, "anvil defaults"),
+        }
+    }
+}
+
+/// Regular files in `dir`, sorted by name so account order is stable across runs.
+fn keystore_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    for entry in std::fs::read_dir(dir).with_context(|| format!("reading keystore dir {}", dir.display()))? {
+        let entry = entry?;
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if entry.file_type()?.is_file() && !hidden {
+            paths.push(entry.path());
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}